@@ -0,0 +1,501 @@
+use std::ascii::{AsciiExt};
+use std::error::{Error};
+use std::net::{IpAddr};
+use std::str;
+use std::sync::{Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use openssl::crypto::hash::{Type};
+use openssl::nid::{Nid};
+use openssl::ssl::{SslContext, SSL_VERIFY_PEER};
+use openssl::x509::{X509, X509StoreContext};
+use url::{Url, UrlParser};
+
+use {SimpleError, SOAPError, SOAPResult};
+use forum::{GenericQuery, TargetType};
+use forum::fetcher::{DescriptionFetcher, Hop};
+use forum::trust::{TrustPolicy, Verification};
+use forum::service::{GenericService, ServiceType};
+use ssdp::{FieldPair};
+use xml;
+
+const URN_PREFIX:      &'static str = "urn:";
+const HTTPS_SCHEME:    &'static str = "https";
+const WILDCARD_PREFIX: &'static str = "*.";
+
+const URL_BASE_ELEMENT:     &'static str = "URLBase";
+const DEVICE_ELEMENT:       &'static str = "device";
+const DEVICE_LIST_ELEMENT:  &'static str = "deviceList";
+const UDN_ELEMENT:          &'static str = "UDN";
+const SERVICE_LIST_ELEMENT: &'static str = "serviceList";
+const SERVICE_ELEMENT:      &'static str = "service";
+const SERVICE_TYPE_ELEMENT: &'static str = "serviceType";
+const SERVICE_ID_ELEMENT:   &'static str = "serviceId";
+const CONTROL_URL_ELEMENT:  &'static str = "controlURL";
+const EVENT_URL_ELEMENT:    &'static str = "eventSubURL";
+
+/// Fetch the device description for the given query and locate a service
+/// compatible with the given service type on the device that was queried.
+///
+/// This is a blocking operation.
+pub fn find_service(query: &GenericQuery, service: &ServiceType) -> SOAPResult<GenericService> {
+    let (description, location) = try!(fetch_description(query));
+    
+    locate_service(&description[..], &location, query.udn(), service)
+}
+
+/// Fetch the device description for the given query and locate all services
+/// compatible with the given service type on the device that was queried as
+/// well as on any of its embedded devices.
+///
+/// This is a blocking operation.
+pub fn find_services(query: &GenericQuery, service: &ServiceType) -> SOAPResult<Vec<GenericService>> {
+    let (description, location) = try!(fetch_description(query));
+    
+    locate_services(&description[..], &location, query.udn(), service, true)
+}
+
+/// Retrieve the description document for the given query, preferring the
+/// secure location when one was advertised.
+///
+/// Queries that were not given a fetcher use a fresh one, so nothing is cached
+/// for them. Cached copies are only used if they were verified in a way the
+/// trust policy accepts.
+///
+/// Returns the document along with the location it was retrieved from.
+fn fetch_description(query: &GenericQuery) -> SOAPResult<(String, Url)> {
+    let default_fetcher = DescriptionFetcher::new();
+    let fetcher = query.fetcher().unwrap_or(&default_fetcher);
+    let default_policy = TrustPolicy::new();
+    let policy = query.trust_policy().unwrap_or(&default_policy);
+    let udn = String::from_utf8_lossy(query.udn());
+    
+    if let Some(config_id) = query.config_id() {
+        for verification in policy.accepts(&udn[..]).iter() {
+            if let Some(n) = fetcher.cached(&udn[..], config_id, verification) {
+                return Ok(n)
+            }
+        }
+    }
+    
+    let (description, location, verification) = try!(fetch_trusted(query, policy, fetcher, &udn[..]));
+    if let Some(config_id) = query.config_id() {
+        fetcher.store(&udn[..], config_id, verification, description.clone(), location.clone());
+    }
+    
+    Ok((description, location))
+}
+
+/// Retrieve the description document from the secure location if possible,
+/// falling back to the regular location only if the trust policy allows it.
+///
+/// Returns the document, the location it was retrieved from and how it was
+/// verified.
+fn fetch_trusted(query: &GenericQuery, policy: &TrustPolicy, fetcher: &DescriptionFetcher, udn: &str)
+    -> SOAPResult<(String, Url, Verification)> {
+    let pinned = policy.pinned(udn);
+    
+    let secure_error = match query.secure_location() {
+        Some(n) => match fetch_secure(fetcher, n, pinned) {
+            Ok((description, location)) => {
+                let verification = match pinned {
+                    Some(n) => Verification::Pinned(n.to_vec()),
+                    None    => Verification::System
+                };
+                
+                return Ok((description, location, verification))
+            },
+            Err(e) => e
+        },
+        None => SOAPError::InvalidResponse("Device Did Not Advertise A Secure Location")
+    };
+    
+    if policy.allows_fallback(udn) {
+        let (description, location) = try!(fetcher.fetch(query.location()));
+        
+        Ok((description, location, Verification::Unverified))
+    } else {
+        Err(secure_error)
+    }
+}
+
+/// Retrieve the description document at the given https location.
+///
+/// If a fingerprint is supplied, the device must present the certificate with
+/// that fingerprint, otherwise the certificate must be trusted by the system
+/// and issued for the host being requested. Every request, including those
+/// made to follow redirects, is verified on its own.
+fn fetch_secure(fetcher: &DescriptionFetcher, location: &Url, pinned: Option<&[u8]>)
+    -> SOAPResult<(String, Url)> {
+    if location.scheme != HTTPS_SCHEME {
+        return Err(SOAPError::Other(Box::new(SimpleError::new("Secure Location Does Not Use HTTPS"))
+            as Box<Error>))
+    }
+    
+    fetcher.fetch_with(location, |url, mut client| {
+        match pinned {
+            Some(n) => {
+                let matched = Arc::new(AtomicBool::new(false));
+                
+                let pin = (n.to_vec(), matched.clone());
+                client.set_ssl_verifier(Box::new(move |ctx: &mut SslContext| {
+                    ctx.set_verify_with_data(SSL_VERIFY_PEER, verify_pinned, pin.clone());
+                }));
+                
+                // Only Trust The Response If The Pinned Certificate Was Actually Presented
+                Ok(Hop::verified_by(client, matched))
+            },
+            None => {
+                let host = try!(url.serialize_host().ok_or(SOAPError::Other(Box::new(
+                    SimpleError::new("Secure Location Has No Host")) as Box<Error>)));
+                let matched = Arc::new(AtomicBool::new(false));
+                
+                let expected = (host, matched.clone());
+                client.set_ssl_verifier(Box::new(move |ctx: &mut SslContext| {
+                    // Without The System Certificates Nothing Verifies, So This Fails Closed
+                    let _ = ctx.set_default_verify_paths();
+                    ctx.set_verify_with_data(SSL_VERIFY_PEER, verify_host, expected.clone());
+                }));
+                
+                // Only Trust The Response If The Certificate Was Issued For The Host
+                Ok(Hop::verified_by(client, matched))
+            }
+        }
+    })
+}
+
+/// Verify a certificate in the chain presented by a device against a pinned
+/// fingerprint, recording whether or not the pinned certificate was seen.
+fn verify_pinned(preverify_ok: bool, x509_ctx: &X509StoreContext, pin: &(Vec<u8>, Arc<AtomicBool>))
+    -> bool {
+    let &(ref fingerprint, ref matched) = pin;
+    
+    let is_pinned = x509_ctx.get_current_cert().and_then(|n| n.fingerprint(Type::SHA256))
+        .map(|n| &n[..] == &fingerprint[..]).unwrap_or(false);
+    if is_pinned {
+        matched.store(true, Ordering::SeqCst);
+    }
+    
+    preverify_ok || is_pinned
+}
+
+/// Verify a certificate in the chain presented by a device against the trusted
+/// system certificates, requiring the certificate of the device itself to be
+/// issued for the expected host.
+fn verify_host(preverify_ok: bool, x509_ctx: &X509StoreContext, expected: &(String, Arc<AtomicBool>))
+    -> bool {
+    let &(ref host, ref matched) = expected;
+    
+    // Only The Certificate Of The Device Itself Names The Host
+    if !preverify_ok || x509_ctx.error_depth() != 0 {
+        return preverify_ok
+    }
+    
+    let is_host = x509_ctx.get_current_cert().map_or(false, |n| certificate_matches(&n, &host[..]));
+    if is_host {
+        matched.store(true, Ordering::SeqCst);
+    }
+    
+    is_host
+}
+
+/// Returns true if the certificate was issued for the given host.
+///
+/// Subject alternative names take precedence, the common name is only checked
+/// if the certificate has no DNS names.
+fn certificate_matches(cert: &X509, host: &str) -> bool {
+    let mut dns_names = Vec::new();
+    let mut ip_addresses = Vec::new();
+    
+    if let Some(names) = cert.subject_alt_names() {
+        for name in names.iter() {
+            dns_names.extend(name.dnsname().map(|n| n.to_owned()));
+            ip_addresses.extend(name.ipaddress().map(|n| n.to_vec()));
+        }
+    }
+    if dns_names.is_empty() {
+        dns_names.extend(cert.subject_name().text_by_nid(Nid::CN).map(|n| n.to_string()));
+    }
+    
+    host_matches(host, &dns_names[..], &ip_addresses[..])
+}
+
+/// Returns true if the host, which may be an IP address, is covered by the
+/// given DNS names or IP addresses taken from a certificate.
+fn host_matches(host: &str, dns_names: &[String], ip_addresses: &[Vec<u8>]) -> bool {
+    match host.trim_matches(|c| c == '[' || c == ']').parse::<IpAddr>() {
+        Ok(IpAddr::V4(n)) => ip_addresses.iter().any(|ip| &ip[..] == &n.octets()[..]),
+        Ok(IpAddr::V6(n)) => ip_addresses.iter().any(|ip| &ip[..] == &n.octets()[..]),
+        Err(_) => dns_names.iter().any(|name| dns_name_matches(&name[..], host))
+    }
+}
+
+/// Returns true if the DNS name, which may hold a wildcard for its leftmost
+/// label, matches the host without regard to case.
+fn dns_name_matches(name: &str, host: &str) -> bool {
+    let (name, host) = (name.trim_right_matches('.'), host.trim_right_matches('.'));
+    
+    if name.starts_with(WILDCARD_PREFIX) {
+        match host.find('.') {
+            Some(n) if n > 0 => host[n + 1..].eq_ignore_ascii_case(&name[WILDCARD_PREFIX.len()..]),
+            _ => false
+        }
+    } else {
+        name.eq_ignore_ascii_case(host)
+    }
+}
+
+/// Locate the first compatible service on the device within an already
+/// retrieved description document.
+fn locate_service(description: &str, location: &Url, udn: &[u8], service: &ServiceType)
+    -> SOAPResult<GenericService> {
+    let mut services = try!(locate_services(description, location, udn, service, false));
+    
+    if services.is_empty() {
+        Err(SOAPError::MissingService(service.urn()))
+    } else {
+        Ok(services.remove(0))
+    }
+}
+
+/// Locate all compatible services on the device, and optionally its embedded
+/// devices, within an already retrieved description document.
+fn locate_services(description: &str, location: &Url, udn: &[u8], service: &ServiceType,
+                   embedded: bool) -> SOAPResult<Vec<GenericService>> {
+    let udn = try!(str::from_utf8(udn).map_err(|_| SOAPError::MissingService(service.urn())));
+    let device = try!(find_device(description, udn).ok_or(SOAPError::MissingService(service.urn())));
+    
+    // UPnP 1.0 Devices May Specify A Base Url For All Relative Urls
+    let base_url = match xml::element(description, URL_BASE_ELEMENT) {
+        Some(n) => try!(Url::parse(n.trim()).map_err(|_|
+            SOAPError::InvalidResponse("Description Has An Invalid URLBase")
+        )),
+        None => location.clone()
+    };
+    
+    let mut services = Vec::new();
+    try!(collect_services(device, &base_url, service, embedded, &mut services));
+    
+    Ok(services)
+}
+
+/// Collect all compatible services found on the given device, and optionally
+/// its embedded devices.
+fn collect_services(device: &str, base_url: &Url, service: &ServiceType, embedded: bool,
+                    services: &mut Vec<GenericService>) -> SOAPResult<()> {
+    // Embedded Devices Come After The Service List Of The Device
+    let (own_device, device_list) = match xml::element_start(device, DEVICE_LIST_ELEMENT) {
+        Some(n) => (&device[..n], xml::element(&device[n..], DEVICE_LIST_ELEMENT)),
+        None    => (device, None)
+    };
+    
+    if let Some(service_list) = xml::element(own_device, SERVICE_LIST_ELEMENT) {
+        for service_desc in xml::Elements::new(service_list, SERVICE_ELEMENT) {
+            let service_type = match xml::element(service_desc, SERVICE_TYPE_ELEMENT) {
+                Some(n) => n.trim(),
+                None    => continue
+            };
+            
+            let advertised = match urn_as_service(service_type) {
+                Some(n) => n,
+                None    => continue
+            };
+            
+            if service.is_compatible(&advertised) {
+                let control_url = try!(join_url(base_url, service_desc, CONTROL_URL_ELEMENT));
+                let event_url = try!(join_url(base_url, service_desc, EVENT_URL_ELEMENT));
+                let service_id = xml::element(service_desc, SERVICE_ID_ELEMENT).unwrap_or("").trim();
+                
+                services.push(GenericService::new(service_type.to_owned(), service_id.to_owned(),
+                                                  advertised.version(), control_url, event_url));
+            }
+        }
+    }
+    
+    if let (true, Some(device_list)) = (embedded, device_list) {
+        for nested_device in xml::Elements::new(device_list, DEVICE_ELEMENT) {
+            try!(collect_services(nested_device, base_url, service, embedded, services));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Find the contents of the device element with the given udn, searching
+/// embedded devices as well.
+fn find_device<'a>(doc: &'a str, udn: &str) -> Option<&'a str> {
+    for device in xml::Elements::new(doc, DEVICE_ELEMENT) {
+        match xml::element(device, UDN_ELEMENT) {
+            Some(n) if n.trim() == udn => return Some(device),
+            _ => ()
+        };
+        
+        if let Some(n) = xml::element(device, DEVICE_LIST_ELEMENT) {
+            if let Some(n) = find_device(n, udn) {
+                return Some(n)
+            }
+        }
+    }
+    
+    None
+}
+
+/// Convert a urn found in a description into a ServiceType.
+///
+/// Urns outside of the UPnP Forum schema come back as vendor service types.
+/// Returns None if the urn is not a service type at all.
+fn urn_as_service(urn: &str) -> Option<ServiceType> {
+    if !urn.starts_with(URN_PREFIX) {
+        return None
+    }
+    let field = FieldPair::URN(urn[URN_PREFIX.len()..].as_bytes().to_vec());
+    
+    match TargetType::new(&field) {
+        Ok(TargetType::Service(n)) => Some(n),
+        _ => None
+    }
+}
+
+/// Resolve the url found in the given element against the base url.
+fn join_url(base_url: &Url, service_desc: &str, element: &'static str) -> SOAPResult<Url> {
+    let relative = try!(xml::element(service_desc, element).ok_or(
+        SOAPError::InvalidResponse("Service Description Is Missing A Url")
+    ));
+    
+    UrlParser::new().base_url(base_url).parse(xml::unescape(relative.trim()).trim()).map_err(|_|
+        SOAPError::InvalidResponse("Service Description Has An Invalid Url")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use url::{Url};
+    
+    use forum::service::{ServiceType};
+    use version::{Version};
+    
+    const DESCRIPTION: &'static str = "<?xml version=\"1.0\"?>\
+        <root xmlns=\"urn:schemas-upnp-org:device-1-0\"><device>\
+        <deviceType>urn:schemas-upnp-org:device:DimmableLight:1</deviceType>\
+        <UDN>uuid:light-root</UDN><serviceList>\
+        <service><serviceType>urn:schemas-upnp-org:service:SwitchPower:1</serviceType>\
+        <controlURL>/switch/control</controlURL><eventSubURL>/switch/event</eventSubURL></service>\
+        <service><serviceType>urn:schemas-upnp-org:service:Dimming:1</serviceType>\
+        <serviceId>urn:upnp-org:serviceId:Dimming.0001</serviceId>\
+        <controlURL>dim/control</controlURL><eventSubURL>dim/event</eventSubURL></service>\
+        <service><serviceType>urn:schemas-example-com:service:Scene:1</serviceType>\
+        <controlURL>/scene/control</controlURL><eventSubURL>/scene/event</eventSubURL></service>\
+        </serviceList><deviceList><device><UDN>uuid:light-embedded</UDN><serviceList>\
+        <service><serviceType>urn:schemas-upnp-org:service:SwitchPower:2</serviceType>\
+        <controlURL>/embedded/control</controlURL><eventSubURL>/embedded/event</eventSubURL></service>\
+        </serviceList></device></deviceList></device></root>";
+    
+    fn location() -> Url {
+        Url::parse("http://192.168.1.5:49152/desc/root.xml").unwrap()
+    }
+    
+    #[test]
+    fn positive_root_service() {
+        let service = super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                                            &ServiceType::SwitchPower(Version::V1)).unwrap();
+        
+        assert_eq!(service.control_url().serialize(), "http://192.168.1.5:49152/switch/control");
+        assert_eq!(service.version(), Version::V1);
+    }
+    
+    #[test]
+    fn positive_relative_url() {
+        let service = super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                                            &ServiceType::Dimming(Version::V1)).unwrap();
+        
+        assert_eq!(service.event_url().serialize(), "http://192.168.1.5:49152/desc/dim/event");
+    }
+    
+    #[test]
+    fn positive_vendor_service() {
+        let service_type = ServiceType::Vendor("schemas-example-com".to_string(), "Scene".to_string(),
+                                               Version::V1);
+        let service = super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                                            &service_type).unwrap();
+        
+        assert_eq!(service.control_url().serialize(), "http://192.168.1.5:49152/scene/control");
+    }
+    
+    #[test]
+    fn positive_service_id() {
+        let dimming = super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                                            &ServiceType::Dimming(Version::V1)).unwrap();
+        let switch = super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                                           &ServiceType::SwitchPower(Version::V1)).unwrap();
+        
+        assert_eq!(dimming.service_id(), "urn:upnp-org:serviceId:Dimming.0001");
+        assert_eq!(switch.service_id(), "");
+    }
+    
+    #[test]
+    fn positive_embedded_service() {
+        let service = super::locate_service(DESCRIPTION, &location(), b"uuid:light-embedded",
+                                            &ServiceType::SwitchPower(Version::V1)).unwrap();
+        
+        assert_eq!(service.service_type(), "urn:schemas-upnp-org:service:SwitchPower:2");
+        assert_eq!(service.version(), Version::V2);
+    }
+    
+    #[test]
+    fn positive_embedded_services() {
+        let services = super::locate_services(DESCRIPTION, &location(), b"uuid:light-root",
+                                              &ServiceType::SwitchPower(Version::V1), true).unwrap();
+        
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[1].control_url().serialize(), "http://192.168.1.5:49152/embedded/control");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_service_on_other_device() {
+        super::locate_service(DESCRIPTION, &location(), b"uuid:light-embedded",
+                              &ServiceType::Dimming(Version::V1)).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_newer_version_required() {
+        super::locate_service(DESCRIPTION, &location(), b"uuid:light-root",
+                              &ServiceType::SwitchPower(Version::V2)).unwrap();
+    }
+    
+    #[test]
+    fn positive_host_matches() {
+        let dns_names = vec!["Device.Example.com".to_string(), "*.lan.example.com".to_string()];
+        let ip_addresses = vec![vec![192, 168, 1, 5]];
+        
+        assert!(super::host_matches("device.example.com", &dns_names[..], &ip_addresses[..]));
+        assert!(super::host_matches("light.lan.example.com", &dns_names[..], &ip_addresses[..]));
+        assert!(super::host_matches("192.168.1.5", &dns_names[..], &ip_addresses[..]));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_certificate_for_wrong_host() {
+        let dns_names = vec!["other.example.com".to_string()];
+        
+        assert!(super::host_matches("device.example.com", &dns_names[..], &[]));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_certificate_for_wrong_ip() {
+        let dns_names = vec!["192.168.1.5".to_string()];
+        let ip_addresses = vec![vec![192, 168, 1, 6]];
+        
+        assert!(super::host_matches("192.168.1.5", &dns_names[..], &ip_addresses[..]));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_wildcard_spans_labels() {
+        let dns_names = vec!["*.example.com".to_string()];
+        
+        assert!(super::host_matches("light.lan.example.com", &dns_names[..], &[]));
+    }
+}
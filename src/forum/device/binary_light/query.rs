@@ -1,5 +1,8 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::switch_power::{SwitchPowerClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +19,15 @@ impl<'a> BinaryLightQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the SwitchPower service of the light.
+    ///
+    /// This is a blocking operation.
+    pub fn switch_power(&self) -> SOAPResult<SwitchPowerClient> {
+        let service = try!(self.query.service(&ServiceType::SwitchPower(Version::V1)));
+        
+        Ok(SwitchPowerClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for BinaryLightQuery<'a> {
@@ -1,5 +1,9 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::dimming::{DimmingClient};
+use forum::service::switch_power::{SwitchPowerClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +20,24 @@ impl<'a> DimmableLightQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the SwitchPower service of the light.
+    ///
+    /// This is a blocking operation.
+    pub fn switch_power(&self) -> SOAPResult<SwitchPowerClient> {
+        let service = try!(self.query.service(&ServiceType::SwitchPower(Version::V1)));
+        
+        Ok(SwitchPowerClient::new(service))
+    }
+    
+    /// Returns a client for the Dimming service of the light.
+    ///
+    /// This is a blocking operation.
+    pub fn dimming(&self) -> SOAPResult<DimmingClient> {
+        let service = try!(self.query.service(&ServiceType::Dimming(Version::V1)));
+        
+        Ok(DimmingClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for DimmableLightQuery<'a> {
@@ -1,5 +1,12 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{GenericService, ServiceType};
+use forum::service::fan_operating_mode::{FanOperatingModeClient};
+use forum::service::fan_speed::{FanSpeedClient};
+use forum::service::temperature_sensor::{TemperatureSensorClient};
+use forum::service::temperature_setpoint::{TemperatureSetpointClient};
+use forum::service::user_operating_mode::{UserOperatingModeClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,10 +23,57 @@ impl<'a> HVACSystemQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns clients for every HVAC_UserOperatingMode service of the system.
+    ///
+    /// Services of embedded zone thermostat devices are included. This is a
+    /// blocking operation.
+    pub fn user_operating_modes(&self) -> SOAPResult<Vec<UserOperatingModeClient>> {
+        self.clients(ServiceType::UserOperatingMode(Version::V1), UserOperatingModeClient::new)
+    }
+    
+    /// Returns clients for every TemperatureSensor service of the system.
+    ///
+    /// Services of embedded zone thermostat devices are included. This is a
+    /// blocking operation.
+    pub fn temperature_sensors(&self) -> SOAPResult<Vec<TemperatureSensorClient>> {
+        self.clients(ServiceType::TemperatureSensor(Version::V1), TemperatureSensorClient::new)
+    }
+    
+    /// Returns clients for every TemperatureSetpoint service of the system.
+    ///
+    /// Services of embedded zone thermostat devices are included. This is a
+    /// blocking operation.
+    pub fn temperature_setpoints(&self) -> SOAPResult<Vec<TemperatureSetpointClient>> {
+        self.clients(ServiceType::TemperatureSetpoint(Version::V1), TemperatureSetpointClient::new)
+    }
+    
+    /// Returns clients for every HVAC_FanOperatingMode service of the system.
+    ///
+    /// Services of embedded zone thermostat devices are included. This is a
+    /// blocking operation.
+    pub fn fan_operating_modes(&self) -> SOAPResult<Vec<FanOperatingModeClient>> {
+        self.clients(ServiceType::FanOperatingMode(Version::V1), FanOperatingModeClient::new)
+    }
+    
+    /// Returns clients for every FanSpeed service of the system.
+    ///
+    /// Services of embedded zone thermostat devices are included. This is a
+    /// blocking operation.
+    pub fn fan_speeds(&self) -> SOAPResult<Vec<FanSpeedClient>> {
+        self.clients(ServiceType::FanSpeed(Version::V1), FanSpeedClient::new)
+    }
+    
+    fn clients<F, T>(&self, service: ServiceType, client: F) -> SOAPResult<Vec<T>>
+        where F: Fn(GenericService) -> T {
+        let services = try!(self.query.services(&service));
+        
+        Ok(services.into_iter().map(client).collect())
+    }
 }
 
 impl<'a> TypedQuery for HVACSystemQuery<'a> {
     fn version(&self) -> Version {
         self.dev_type.version()
     }
-}
\ No newline at end of file
+}
@@ -1,25 +1,73 @@
+use {SOAPError, SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::device_protection::{DeviceProtectionClient};
+use forum::service::wan_ip_connection::{WANIPConnectionClient};
+use soap::session::{SecureSession};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct InternetGatewayQuery<'a> {
     query:    GenericQuery<'a>,
-    dev_type: DeviceType
+    dev_type: DeviceType,
+    session:  Option<SecureSession>
 }
 
 impl<'a> InternetGatewayQuery<'a> {
     pub fn new(query: GenericQuery<'a>, dev_type: DeviceType) -> InternetGatewayQuery<'a> {
-        InternetGatewayQuery{ query: query, dev_type: dev_type }
+        InternetGatewayQuery{ query: query, dev_type: dev_type, session: None }
+    }
+    
+    /// Send the calls of every protected service client returned by the query
+    /// over the given secure session.
+    ///
+    /// The session should be the one the DeviceProtection client logged in
+    /// over, so that the roles granted by the login apply.
+    pub fn with_session(mut self, session: SecureSession) -> InternetGatewayQuery<'a> {
+        self.session = Some(session);
+        self
     }
     
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the DeviceProtection service of the gateway.
+    ///
+    /// The client uses the session of the query, if set, or a new one otherwise.
+    /// This is a blocking operation.
+    pub fn device_protection(&self) -> SOAPResult<DeviceProtectionClient> {
+        let service = try!(self.query.service(&ServiceType::DeviceProtection(Version::V1)));
+        
+        Ok(match self.session {
+            Some(ref session) => DeviceProtectionClient::with_session(service, session.clone()),
+            None              => DeviceProtectionClient::new(service)
+        })
+    }
+    
+    /// Returns a client for the WANIPConnection service of the gateway.
+    ///
+    /// The service lives on a WANConnectionDevice embedded within the gateway.
+    /// This is a blocking operation.
+    pub fn wan_ip_connection(&self) -> SOAPResult<WANIPConnectionClient> {
+        let mut services = try!(self.query.services(&ServiceType::WANIPConnection(Version::V1)));
+        
+        if services.is_empty() {
+            Err(SOAPError::MissingService(ServiceType::WANIPConnection(Version::V1).urn()))
+        } else {
+            let service = services.remove(0);
+            
+            Ok(WANIPConnectionClient::new(match self.session {
+                Some(ref session) => service.with_session(session.clone()),
+                None              => service
+            }))
+        }
+    }
 }
 
 impl<'a> TypedQuery for InternetGatewayQuery<'a> {
     fn version(&self) -> Version {
         self.dev_type.version()
     }
-}
\ No newline at end of file
+}
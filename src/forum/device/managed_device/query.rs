@@ -1,5 +1,9 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::basic_management::{BasicManagementClient};
+use forum::service::configuration_management::{ConfigurationManagementClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,10 +20,28 @@ impl<'a> ManagedDeviceQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the ConfigurationManagement service of the device.
+    ///
+    /// This is a blocking operation.
+    pub fn configuration_management(&self) -> SOAPResult<ConfigurationManagementClient> {
+        let service = try!(self.query.service(&ServiceType::ConfigurationManagement(Version::V1)));
+        
+        Ok(ConfigurationManagementClient::new(service))
+    }
+    
+    /// Returns a client for the BasicManagement service of the device.
+    ///
+    /// This is a blocking operation.
+    pub fn basic_management(&self) -> SOAPResult<BasicManagementClient> {
+        let service = try!(self.query.service(&ServiceType::BasicManagement(Version::V1)));
+        
+        Ok(BasicManagementClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for ManagedDeviceQuery<'a> {
     fn version(&self) -> Version {
         self.dev_type.version()
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,8 @@
+use {SOAPError, SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::print::{PrintClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +19,20 @@ impl<'a> PrinterQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the print service of the printer.
+    ///
+    /// PrintEnhanced is preferred over PrintBasic when the printer offers
+    /// both. This is a blocking operation.
+    pub fn print(&self) -> SOAPResult<PrintClient> {
+        let service = match self.query.service(&ServiceType::PrintEnhanced(Version::V1)) {
+            Ok(n) => n,
+            Err(SOAPError::MissingService(_)) => try!(self.query.service(&ServiceType::PrintBasic(Version::V1))),
+            Err(e) => return Err(e)
+        };
+        
+        Ok(PrintClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for PrinterQuery<'a> {
@@ -1,5 +1,8 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::scan::{ScanClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +19,15 @@ impl<'a> ScannerQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the ScanService of the scanner.
+    ///
+    /// This is a blocking operation.
+    pub fn scan(&self) -> SOAPResult<ScanClient> {
+        let service = try!(self.query.service(&ServiceType::Scan(Version::V1)));
+        
+        Ok(ScanClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for ScannerQuery<'a> {
@@ -1,5 +1,9 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::camera_image::{MotionImageClient, StillImageClient};
+use forum::service::camera_settings::{CameraSettingsClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +20,33 @@ impl<'a> SecurityCameraQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the DigitalSecurityCameraSettings service of the camera.
+    ///
+    /// This is a blocking operation.
+    pub fn settings(&self) -> SOAPResult<CameraSettingsClient> {
+        let service = try!(self.query.service(&ServiceType::CameraSettings(Version::V1)));
+        
+        Ok(CameraSettingsClient::new(service))
+    }
+    
+    /// Returns a client for the DigitalSecurityCameraMotionImage service of the camera.
+    ///
+    /// This is a blocking operation.
+    pub fn motion_image(&self) -> SOAPResult<MotionImageClient> {
+        let service = try!(self.query.service(&ServiceType::MotionImage(Version::V1)));
+        
+        Ok(MotionImageClient::new(service))
+    }
+    
+    /// Returns a client for the DigitalSecurityCameraStillImage service of the camera.
+    ///
+    /// This is a blocking operation.
+    pub fn still_image(&self) -> SOAPResult<StillImageClient> {
+        let service = try!(self.query.service(&ServiceType::StillImage(Version::V1)));
+        
+        Ok(StillImageClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for SecurityCameraQuery<'a> {
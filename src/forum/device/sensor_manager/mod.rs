@@ -1,3 +1,5 @@
 mod query;
+mod sensor;
 
-pub use self::query::SensorManagerQuery;
\ No newline at end of file
+pub use self::query::SensorManagerQuery;
+pub use self::sensor::{DataItem, Sensor, SensorCollection, SensorUrn};
@@ -1,5 +1,12 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::device::sensor_manager::{SensorCollection};
+use forum::device::sensor_manager::sensor::{self, SENSOR_COLLECTIONS_PATH};
+use forum::service::{ServiceType};
+use forum::service::configuration_management::{ConfigurationManagementClient};
+use forum::service::data_store::{DataStoreClient};
+use forum::service::sensor_transport::{SensorTransportClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +23,35 @@ impl<'a> SensorManagerQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the SensorTransportGeneric service of the sensor manager.
+    ///
+    /// This is a blocking operation.
+    pub fn sensor_transport(&self) -> SOAPResult<SensorTransportClient> {
+        let service = try!(self.query.service(&ServiceType::SensorTransportGeneric(Version::V1)));
+        
+        Ok(SensorTransportClient::new(service))
+    }
+    
+    /// Returns a client for the DataStore service of the sensor manager.
+    ///
+    /// This is a blocking operation.
+    pub fn data_store(&self) -> SOAPResult<DataStoreClient> {
+        let service = try!(self.query.service(&ServiceType::DataStore(Version::V1)));
+        
+        Ok(DataStoreClient::new(service))
+    }
+    
+    /// Returns the sensor collections, and the sensors within them, that are
+    /// configured on the sensor manager.
+    ///
+    /// This is a blocking operation.
+    pub fn sensor_collections(&self) -> SOAPResult<Vec<SensorCollection>> {
+        let service = try!(self.query.service(&ServiceType::ConfigurationManagement(Version::V1)));
+        let parameters = try!(ConfigurationManagementClient::new(service).get_values(&[SENSOR_COLLECTIONS_PATH]));
+        
+        Ok(sensor::from_parameters(&parameters))
+    }
 }
 
 impl<'a> TypedQuery for SensorManagerQuery<'a> {
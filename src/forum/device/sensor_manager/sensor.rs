@@ -0,0 +1,295 @@
+use std::borrow::{ToOwned};
+
+use forum::service::configuration_management::{ParameterValue};
+use soap::value;
+
+/// Partial path of the sensor collections within the data model of a sensor manager.
+pub const SENSOR_COLLECTIONS_PATH: &'static str = "/UPnP/SensorMgt/SensorCollections/";
+
+const PATH_SEP: char = '/';
+
+const SENSORS_NODE:     &'static str = "Sensors";
+const SENSOR_URNS_NODE: &'static str = "SensorURNs";
+const DATA_ITEMS_NODE:  &'static str = "DataItems";
+
+const COLLECTION_ID_PARAM:   &'static str = "CollectionID";
+const COLLECTION_TYPE_PARAM: &'static str = "CollectionType";
+const COLLECTION_NAME_PARAM: &'static str = "CollectionFriendlyName";
+const SENSOR_ID_PARAM:       &'static str = "SensorID";
+const SENSOR_TYPE_PARAM:     &'static str = "SensorType";
+const SENSOR_EVENTS_PARAM:   &'static str = "SensorEventsEnable";
+const SENSOR_URN_PARAM:      &'static str = "SensorURN";
+const ITEM_NAME_PARAM:       &'static str = "Name";
+const ITEM_TYPE_PARAM:       &'static str = "Type";
+const ITEM_ENCODING_PARAM:   &'static str = "Encoding";
+const ITEM_DESC_PARAM:       &'static str = "Description";
+
+const COLLECTION_PARAMS: [&'static str; 3] = [COLLECTION_ID_PARAM, COLLECTION_TYPE_PARAM,
+                                              COLLECTION_NAME_PARAM];
+const SENSOR_PARAMS:     [&'static str; 3] = [SENSOR_ID_PARAM, SENSOR_TYPE_PARAM, SENSOR_EVENTS_PARAM];
+const URN_PARAMS:        [&'static str; 1] = [SENSOR_URN_PARAM];
+const ITEM_PARAMS:       [&'static str; 4] = [ITEM_NAME_PARAM, ITEM_TYPE_PARAM, ITEM_ENCODING_PARAM,
+                                              ITEM_DESC_PARAM];
+
+const SENSOR_NODES: [&'static str; 1] = [SENSORS_NODE];
+const URN_NODES:    [&'static str; 2] = [SENSORS_NODE, SENSOR_URNS_NODE];
+const ITEM_NODES:   [&'static str; 3] = [SENSORS_NODE, SENSOR_URNS_NODE, DATA_ITEMS_NODE];
+
+/// Value that a sensor reports or accepts through one of its urns.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct DataItem {
+    pub name:        String,
+    /// Data type of the item, such as uda:float.
+    pub item_type:   String,
+    pub encoding:    String,
+    pub description: String
+}
+
+/// Urn through which the data items of a sensor are transported.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct SensorUrn {
+    pub urn:        String,
+    pub data_items: Vec<DataItem>
+}
+
+/// Sensor within a sensor collection.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Sensor {
+    /// Identifier used to refer to the sensor in SensorTransportGeneric actions.
+    pub id:             String,
+    pub sensor_type:    String,
+    pub events_enabled: bool,
+    pub urns:           Vec<SensorUrn>
+}
+
+/// Group of sensors exposed by a sensor manager, typically one physical device.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct SensorCollection {
+    pub id:              String,
+    pub collection_type: String,
+    pub friendly_name:   String,
+    pub sensors:         Vec<Sensor>
+}
+
+#[derive(Default)]
+struct CollectionEntry {
+    collection: SensorCollection,
+    sensors:    Vec<(u32, SensorEntry)>
+}
+
+#[derive(Default)]
+struct SensorEntry {
+    sensor: Sensor,
+    urns:   Vec<(u32, UrnEntry)>
+}
+
+#[derive(Default)]
+struct UrnEntry {
+    urn:   SensorUrn,
+    items: Vec<(u32, DataItem)>
+}
+
+/// Build the sensor collections described by the given data model parameters.
+///
+/// Parameters outside of the sensor collections, or that are not understood,
+/// are ignored. Collections, sensors, urns and data items are ordered by their
+/// instance numbers.
+pub fn from_parameters(parameters: &[ParameterValue]) -> Vec<SensorCollection> {
+    let mut collections: Vec<(u32, CollectionEntry)> = Vec::new();
+    
+    for parameter in parameters.iter() {
+        if !parameter.path.starts_with(SENSOR_COLLECTIONS_PATH) {
+            continue
+        }
+        let segments = parameter.path[SENSOR_COLLECTIONS_PATH.len()..].split(PATH_SEP).collect::<Vec<&str>>();
+        
+        // Entries Are Only Created For Parameters Of The Model, Never For Unknown Paths
+        if !is_known(&segments) {
+            continue
+        }
+        if let Some(collection) = instance_of(&mut collections, &segments, 0) {
+            apply_collection(collection, &segments[1..], &parameter.value[..]);
+        }
+    }
+    
+    sorted(collections).into_iter().map(|mut entry| {
+        entry.collection.sensors = sorted(entry.sensors).into_iter().map(|mut entry| {
+            entry.sensor.urns = sorted(entry.urns).into_iter().map(|mut entry| {
+                entry.urn.data_items = sorted(entry.items);
+                entry.urn
+            }).collect();
+            entry.sensor
+        }).collect();
+        entry.collection
+    }).collect()
+}
+
+fn apply_collection(entry: &mut CollectionEntry, segments: &[&str], value: &str) {
+    match segments.len() {
+        1 => match segments[0] {
+            COLLECTION_ID_PARAM   => entry.collection.id = value.to_owned(),
+            COLLECTION_TYPE_PARAM => entry.collection.collection_type = value.to_owned(),
+            COLLECTION_NAME_PARAM => entry.collection.friendly_name = value.to_owned(),
+            _ => ()
+        },
+        n if n > 2 && segments[0] == SENSORS_NODE => {
+            if let Some(sensor) = instance_of(&mut entry.sensors, segments, 1) {
+                apply_sensor(sensor, &segments[2..], value);
+            }
+        },
+        _ => ()
+    }
+}
+
+fn apply_sensor(entry: &mut SensorEntry, segments: &[&str], value: &str) {
+    match segments.len() {
+        1 => match segments[0] {
+            SENSOR_ID_PARAM     => entry.sensor.id = value.to_owned(),
+            SENSOR_TYPE_PARAM   => entry.sensor.sensor_type = value.to_owned(),
+            SENSOR_EVENTS_PARAM => entry.sensor.events_enabled = value::to_bool(SENSOR_EVENTS_PARAM, value).unwrap_or(false),
+            _ => ()
+        },
+        n if n > 2 && segments[0] == SENSOR_URNS_NODE => {
+            if let Some(urn) = instance_of(&mut entry.urns, segments, 1) {
+                apply_urn(urn, &segments[2..], value);
+            }
+        },
+        _ => ()
+    }
+}
+
+fn apply_urn(entry: &mut UrnEntry, segments: &[&str], value: &str) {
+    match segments.len() {
+        1 if segments[0] == SENSOR_URN_PARAM => entry.urn.urn = value.to_owned(),
+        3 if segments[0] == DATA_ITEMS_NODE => {
+            if let Some(item) = instance_of(&mut entry.items, segments, 1) {
+                match segments[2] {
+                    ITEM_NAME_PARAM     => item.name = value.to_owned(),
+                    ITEM_TYPE_PARAM     => item.item_type = value.to_owned(),
+                    ITEM_ENCODING_PARAM => item.encoding = value.to_owned(),
+                    ITEM_DESC_PARAM     => item.description = value.to_owned(),
+                    _ => ()
+                }
+            }
+        },
+        _ => ()
+    }
+}
+
+/// Returns true if the segments, relative to the sensor collections, name one
+/// of the parameters of the sensor model.
+///
+/// Instance numbers alternate with node names, ending in the parameter name.
+fn is_known(segments: &[&str]) -> bool {
+    let (nodes, params) = match segments.len() {
+        2 => (&[][..], &COLLECTION_PARAMS[..]),
+        4 => (&SENSOR_NODES[..], &SENSOR_PARAMS[..]),
+        6 => (&URN_NODES[..], &URN_PARAMS[..]),
+        8 => (&ITEM_NODES[..], &ITEM_PARAMS[..]),
+        _ => return false
+    };
+    let last = segments.len() - 1;
+    
+    for (index, segment) in segments[..last].iter().enumerate() {
+        let valid = if index % 2 == 0 {
+            segment.parse::<u32>().is_ok()
+        } else {
+            *segment == nodes[index / 2]
+        };
+        
+        if !valid {
+            return false
+        }
+    }
+    
+    params.contains(&segments[last])
+}
+
+/// Returns the entry for the instance number found at the given segment,
+/// creating it if it does not exist yet.
+fn instance_of<'a, T>(entries: &'a mut Vec<(u32, T)>, segments: &[&str], index: usize) -> Option<&'a mut T>
+    where T: Default {
+    let instance = match segments.get(index).and_then(|n| n.parse::<u32>().ok()) {
+        Some(n) => n,
+        None    => return None
+    };
+    
+    let position = match entries.iter().position(|&(n, _)| n == instance) {
+        Some(n) => n,
+        None    => {
+            entries.push((instance, T::default()));
+            entries.len() - 1
+        }
+    };
+    
+    Some(&mut entries[position].1)
+}
+
+fn sorted<T>(mut entries: Vec<(u32, T)>) -> Vec<T> {
+    entries.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+    
+    entries.into_iter().map(|(_, n)| n).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use forum::service::configuration_management::{ParameterValue};
+    
+    fn parameter(path: &str, value: &str) -> ParameterValue {
+        ParameterValue{ path: format!("/UPnP/SensorMgt/SensorCollections/{}", path), value: value.to_string() }
+    }
+    
+    #[test]
+    fn positive_sensor_model() {
+        let parameters = vec![
+            parameter("2/CollectionID", "hub-b"),
+            parameter("1/CollectionID", "hub-a"),
+            parameter("1/CollectionFriendlyName", "Kitchen"),
+            parameter("1/Sensors/1/SensorID", "temp-1"),
+            parameter("1/Sensors/1/SensorEventsEnable", "1"),
+            parameter("1/Sensors/1/SensorURNs/1/SensorURN", "urn:upnp-org:smgt-surn:temp"),
+            parameter("1/Sensors/1/SensorURNs/1/DataItems/2/Name", "Unit"),
+            parameter("1/Sensors/1/SensorURNs/1/DataItems/1/Name", "Temp"),
+            parameter("1/Sensors/1/SensorURNs/1/DataItems/1/Type", "uda:float")
+        ];
+        let collections = super::from_parameters(&parameters);
+        
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].id, "hub-a");
+        assert_eq!(collections[0].friendly_name, "Kitchen");
+        assert_eq!(collections[1].id, "hub-b");
+        
+        let sensor = &collections[0].sensors[0];
+        assert_eq!(sensor.id, "temp-1");
+        assert!(sensor.events_enabled);
+        assert_eq!(sensor.urns[0].urn, "urn:upnp-org:smgt-surn:temp");
+        assert_eq!(sensor.urns[0].data_items[0].name, "Temp");
+        assert_eq!(sensor.urns[0].data_items[0].item_type, "uda:float");
+        assert_eq!(sensor.urns[0].data_items[1].name, "Unit");
+    }
+    
+    #[test]
+    fn positive_ignore_unknown_parameters() {
+        let parameters = vec![
+            ParameterValue{ path: "/UPnP/DeviceInfo/FriendlyName".to_string(), value: "Hub".to_string() },
+            parameter("x/CollectionID", "bad-instance"),
+            parameter("1/Vendor/Thing", "ignored")
+        ];
+        let collections = super::from_parameters(&parameters);
+        
+        assert!(collections.is_empty());
+    }
+    
+    #[test]
+    fn positive_ignore_unknown_sensor_parameters() {
+        let parameters = vec![
+            parameter("1/CollectionID", "hub-a"),
+            parameter("1/Sensors/1/Vendor", "ignored"),
+            parameter("1/Sensors/2/SensorURNs/1/Vendor", "ignored")
+        ];
+        let collections = super::from_parameters(&parameters);
+        
+        assert_eq!(collections.len(), 1);
+        assert!(collections[0].sensors.is_empty());
+    }
+}
@@ -1,7 +1,19 @@
+use std::ascii::{AsciiExt};
+
+use {SOAPError, SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{GenericService, ServiceType};
+use forum::service::dimming::{DimmingClient};
+use forum::service::switch_power::{SwitchPowerClient};
 use version::{Version};
 
+const SERVICE_ID_SEPARATOR: char = ':';
+
+/// Service id fragments identifying the Dimming service that controls the
+/// slat angle of a blind.
+const SLAT_ANGLE_ID_MARKERS: [&'static str; 3] = ["slat", "tilt", "angle"];
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct SolarBlindQuery<'a> {
     query:    GenericQuery<'a>,
@@ -16,10 +28,112 @@ impl<'a> SolarBlindQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the SwitchPower service of the blind.
+    ///
+    /// This is a blocking operation.
+    pub fn switch_power(&self) -> SOAPResult<SwitchPowerClient> {
+        let service = try!(self.query.service(&ServiceType::SwitchPower(Version::V1)));
+        
+        Ok(SwitchPowerClient::new(service))
+    }
+    
+    /// Returns a client for the Dimming service that controls the position of
+    /// the blind, where the load level is the percentage that the blind is closed.
+    ///
+    /// This is a blocking operation.
+    pub fn position(&self) -> SOAPResult<DimmingClient> {
+        let services = try!(self.query.services(&ServiceType::Dimming(Version::V1)));
+        
+        match split_dimming(services).0 {
+            Some(n) => Ok(DimmingClient::new(n)),
+            None    => Err(SOAPError::MissingService(ServiceType::Dimming(Version::V1).urn()))
+        }
+    }
+    
+    /// Returns a client for the Dimming service that controls the slat angle of
+    /// the blind, if the blind has adjustable slats.
+    ///
+    /// This is a blocking operation.
+    pub fn slat_angle(&self) -> SOAPResult<Option<DimmingClient>> {
+        let services = try!(self.query.services(&ServiceType::Dimming(Version::V1)));
+        
+        Ok(split_dimming(services).1.map(DimmingClient::new))
+    }
 }
 
 impl<'a> TypedQuery for SolarBlindQuery<'a> {
     fn version(&self) -> Version {
         self.dev_type.version()
     }
-}
\ No newline at end of file
+}
+
+/// Split the Dimming services of a blind into its position and slat angle services.
+///
+/// The slat angle service is identified by its service id, and blinds that use
+/// generic service ids are expected to list the position service first.
+fn split_dimming(mut services: Vec<GenericService>) -> (Option<GenericService>, Option<GenericService>) {
+    let slat_index = services.iter().position(|n| is_slat_angle(n.service_id()));
+    
+    match slat_index {
+        Some(index) => {
+            let slat = services.remove(index);
+            
+            (services.into_iter().next(), Some(slat))
+        },
+        None => {
+            let mut services = services.into_iter();
+            
+            (services.next(), services.next())
+        }
+    }
+}
+
+/// Returns true if the last part of the service id, as in the "SlatAngle" of
+/// "urn:upnp-org:serviceId:SlatAngle", names the slat angle.
+fn is_slat_angle(service_id: &str) -> bool {
+    let id = service_id.rsplit(SERVICE_ID_SEPARATOR).next().unwrap_or(service_id).to_ascii_lowercase();
+    
+    SLAT_ANGLE_ID_MARKERS.iter().any(|n| id.contains(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use url::{Url};
+    
+    use forum::service::{GenericService};
+    use version::{Version};
+    
+    fn dimming(service_id: &str) -> GenericService {
+        let url = Url::parse("http://192.168.1.5/control").unwrap();
+        
+        GenericService::new("urn:schemas-upnp-org:service:Dimming:1".to_string(), service_id.to_string(),
+                            Version::V1, url.clone(), url)
+    }
+    
+    #[test]
+    fn positive_slat_angle_by_id() {
+        let services = vec![dimming("urn:upnp-org:serviceId:SlatAngle"), dimming("urn:upnp-org:serviceId:Position")];
+        let (position, slat) = super::split_dimming(services);
+        
+        assert_eq!(position.unwrap().service_id(), "urn:upnp-org:serviceId:Position");
+        assert_eq!(slat.unwrap().service_id(), "urn:upnp-org:serviceId:SlatAngle");
+    }
+    
+    #[test]
+    fn positive_slat_angle_by_order() {
+        let services = vec![dimming("urn:upnp-org:serviceId:Dimming.0001"), dimming("urn:upnp-org:serviceId:Dimming.0002")];
+        let (position, slat) = super::split_dimming(services);
+        
+        assert_eq!(position.unwrap().service_id(), "urn:upnp-org:serviceId:Dimming.0001");
+        assert_eq!(slat.unwrap().service_id(), "urn:upnp-org:serviceId:Dimming.0002");
+    }
+    
+    #[test]
+    fn positive_no_slats() {
+        let (position, slat) = super::split_dimming(vec![dimming("urn:upnp-org:serviceId:Dimming.0001")]);
+        
+        assert!(position.is_some());
+        assert!(slat.is_none());
+    }
+}
@@ -1,5 +1,11 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::address_book::{AddressBookClient};
+use forum::service::call_management::{CallManagementClient};
+use forum::service::messaging::{MessagingClient};
+use forum::service::presence::{PresenceClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,6 +22,42 @@ impl<'a> TelephonyServerQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the CallManagement service of the telephony server.
+    ///
+    /// This is a blocking operation.
+    pub fn call_management(&self) -> SOAPResult<CallManagementClient> {
+        let service = try!(self.query.service(&ServiceType::CallManagement(Version::V1)));
+        
+        Ok(CallManagementClient::new(service))
+    }
+    
+    /// Returns a client for the Messaging service of the telephony server.
+    ///
+    /// This is a blocking operation.
+    pub fn messaging(&self) -> SOAPResult<MessagingClient> {
+        let service = try!(self.query.service(&ServiceType::Messaging(Version::V1)));
+        
+        Ok(MessagingClient::new(service))
+    }
+    
+    /// Returns a client for the PresenceService of the telephony server.
+    ///
+    /// This is a blocking operation.
+    pub fn presence(&self) -> SOAPResult<PresenceClient> {
+        let service = try!(self.query.service(&ServiceType::Presence(Version::V1)));
+        
+        Ok(PresenceClient::new(service))
+    }
+    
+    /// Returns a client for the AddressBook service of the telephony server.
+    ///
+    /// This is a blocking operation.
+    pub fn address_book(&self) -> SOAPResult<AddressBookClient> {
+        let service = try!(self.query.service(&ServiceType::AddressBook(Version::V1)));
+        
+        Ok(AddressBookClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for TelephonyServerQuery<'a> {
@@ -93,6 +93,35 @@ impl DeviceType {
             DeviceType::Unimplemented(_, n) => n
         }
     }
+    
+    /// Returns the device type name as it appears in a urn.
+    pub fn name(&self) -> &str {
+        match *self {
+            DeviceType::BasicDevice(_)          => BASIC_DEVICE_NAME,
+            DeviceType::MediaServer(_)          => MEDIA_SERVER_NAME,
+            DeviceType::MediaRenderer(_)        => MEDIA_RENDERER_NAME,
+            DeviceType::ManagedDevice(_)        => MANAGED_DEVICE_NAME,
+            DeviceType::SolarBlind(_)           => SOLAR_BLIND_NAME,
+            DeviceType::SecurityCamera(_)       => SECURITY_CAMERA_NAME,
+            DeviceType::HVACSystem(_)           => HVAC_SYSTEM_NAME,
+            DeviceType::BinaryLight(_)          => BINARY_LIGHT_NAME,
+            DeviceType::DimmableLight(_)        => DIMMABLE_LIGHT_NAME,
+            DeviceType::InternetGateway(_)      => INTERNET_GATEWAY_NAME,
+            DeviceType::WirelessAP(_)           => WIRELESS_AP_NAME,
+            DeviceType::Printer(_)              => PRINTER_NAME,
+            DeviceType::Scanner(_)              => SCANNER_NAME,
+            DeviceType::SensorManager(_)        => SENSOR_MANAGER_NAME,
+            DeviceType::TelephonyClient(_)      => TELEPHONY_CLIENT_NAME,
+            DeviceType::TelephonyServer(_)      => TELEPHONY_SERVER_NAME,
+            DeviceType::Unimplemented(ref n, _) => &n[..]
+        }
+    }
+    
+    /// Returns the full urn identifying this device type.
+    pub fn urn(&self) -> String {
+        format!("urn:{}:{}:{}:{}", forum::UPNP_SCHEMA_VALUE, forum::URN_DEVICE_VALUE,
+                self.name(), self.version().to_u8())
+    }
 }
 
 /// Match the type to a vendor name.
@@ -139,4 +168,11 @@ mod tests {
         
         assert_eq!(device_type, DeviceType::Unimplemented("Blargenfargen".to_string(), Version::V1));
     }
+    
+    #[test]
+    fn positive_urn() {
+        let device_type = DeviceType::InternetGateway(Version::V2);
+        
+        assert_eq!(device_type.urn(), "urn:schemas-upnp-org:device:InternetGatewayDevice:2");
+    }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
+use {SOAPResult};
 use forum::{GenericQuery, TypedQuery};
 use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::wlan_configuration::{WLANConfigurationClient};
 use version::{Version};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -16,10 +19,19 @@ impl<'a> WirelessAPQuery<'a> {
     pub fn uuid(&self) -> &[u8] {
         self.query.uuid()
     }
+    
+    /// Returns a client for the WLANConfiguration service of the access point.
+    ///
+    /// This is a blocking operation.
+    pub fn wlan_configuration(&self) -> SOAPResult<WLANConfigurationClient> {
+        let service = try!(self.query.service(&ServiceType::WLANConfiguration(Version::V1)));
+        
+        Ok(WLANConfigurationClient::new(service))
+    }
 }
 
 impl<'a> TypedQuery for WirelessAPQuery<'a> {
     fn version(&self) -> Version {
         self.dev_type.version()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,468 @@
+//! Retrieval of device description documents.
+//!
+//! Devices on a network are often slow or misbehaving, so every request is
+//! bounded by socket timeouts and every fetch by a size limit. Descriptions
+//! are cached by (UDN, CONFIGID) since a device must change its CONFIGID
+//! whenever its description changes, along with how the description was
+//! verified so that a stricter trust policy never picks up a weaker copy.
+//! Only the latest CONFIGID of each device is kept.
+
+use std::cmp;
+use std::collections::{HashMap};
+use std::error::{Error};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io::{Read};
+use std::str;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration as StdDuration};
+
+use flate2::read::{GzDecoder};
+use hyper::{Client};
+use hyper::client::{RedirectPolicy};
+use hyper::header::{Headers};
+use time::{Duration};
+use url::{Url, UrlParser};
+
+use {SOAPError, SOAPResult};
+use forum::trust::{Verification};
+use net::connector::{TimeoutConnector};
+
+/// Time allowed for establishing a connection to a device.
+pub const DEFAULT_CONNECT_TIMEOUT_MS: i64 = 5000;
+/// Time allowed for sending a request to a device.
+pub const DEFAULT_WRITE_TIMEOUT_MS: i64 = 5000;
+/// Time allowed between consecutive reads of the response.
+pub const DEFAULT_READ_TIMEOUT_MS: i64 = 5000;
+/// Largest description accepted, after decompression.
+pub const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+/// Most redirects followed before giving up.
+pub const DEFAULT_MAX_REDIRECTS: u8 = 5;
+/// Most redirects that a fetcher can be configured to follow.
+pub const MAX_REDIRECTS_LIMIT: u8 = 10;
+
+const ACCEPT_ENCODING_HEADER:  &'static str = "ACCEPT-ENCODING";
+const CONTENT_ENCODING_HEADER: &'static str = "CONTENT-ENCODING";
+const LOCATION_HEADER:         &'static str = "LOCATION";
+const GZIP_ENCODING:           &'static str = "gzip";
+const HTTPS_SCHEME:            &'static str = "https";
+
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// Fetches description documents on behalf of any number of queries, caching
+/// them by device.
+///
+/// Fetchers are compared by identity since each one owns its own cache.
+pub struct DescriptionFetcher {
+    connect_timeout: Duration,
+    write_timeout:   Duration,
+    read_timeout:    Duration,
+    max_size:        usize,
+    max_redirects:   u8,
+    cache:           Mutex<HashMap<(String, u32, Verification), (String, Url)>>
+}
+
+impl DescriptionFetcher {
+    /// Create a new DescriptionFetcher with default limits and an empty cache.
+    pub fn new() -> DescriptionFetcher {
+        DescriptionFetcher{ connect_timeout: Duration::milliseconds(DEFAULT_CONNECT_TIMEOUT_MS),
+            write_timeout: Duration::milliseconds(DEFAULT_WRITE_TIMEOUT_MS),
+            read_timeout: Duration::milliseconds(DEFAULT_READ_TIMEOUT_MS), max_size: DEFAULT_MAX_SIZE,
+            max_redirects: DEFAULT_MAX_REDIRECTS, cache: Mutex::new(HashMap::new()) }
+    }
+    
+    /// Set the time allowed for establishing a connection to a device.
+    pub fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.connect_timeout = timeout;
+    }
+    
+    /// Set the time allowed for sending a request to a device.
+    pub fn set_write_timeout(&mut self, timeout: Duration) {
+        self.write_timeout = timeout;
+    }
+    
+    /// Set the time allowed between consecutive reads of the response, which
+    /// includes waiting for the response headers.
+    pub fn set_read_timeout(&mut self, timeout: Duration) {
+        self.read_timeout = timeout;
+    }
+    
+    /// Set the largest description, in bytes, that will be accepted.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+    
+    /// Set the most redirects that will be followed for a single fetch, up to
+    /// MAX_REDIRECTS_LIMIT.
+    pub fn set_max_redirects(&mut self, max_redirects: u8) {
+        self.max_redirects = cmp::min(max_redirects, MAX_REDIRECTS_LIMIT);
+    }
+    
+    /// Returns the cached description of the device with the given udn and
+    /// config id that was verified as given, along with the location it was
+    /// retrieved from.
+    pub fn cached(&self, udn: &str, config_id: u32, verification: &Verification) -> Option<(String, Url)> {
+        let cache = self.cache.lock().unwrap();
+        
+        cache.get(&(udn.to_string(), config_id, verification.clone())).cloned()
+    }
+    
+    /// Cache the description of the device with the given udn and config id
+    /// that was verified as given.
+    ///
+    /// Descriptions cached for any other config id of the device are removed.
+    pub fn store(&self, udn: &str, config_id: u32, verification: Verification, description: String,
+        location: Url) {
+        let mut cache = self.cache.lock().unwrap();
+        
+        let stale = cache.keys().filter(|&&(ref n, id, _)| n == udn && id != config_id).cloned()
+            .collect::<Vec<_>>();
+        for key in stale {
+            cache.remove(&key);
+        }
+        cache.insert((udn.to_string(), config_id, verification), (description, location));
+    }
+    
+    /// Remove all cached descriptions of the device with the given udn.
+    pub fn invalidate(&self, udn: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        
+        let stale = cache.keys().filter(|&&(ref n, _, _)| n == udn).cloned().collect::<Vec<_>>();
+        for key in stale {
+            cache.remove(&key);
+        }
+    }
+    
+    /// Remove all cached descriptions.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+    
+    /// Retrieve the document at the given location.
+    ///
+    /// Returns the document along with the location it was retrieved from after
+    /// following any redirects. This is a blocking operation.
+    pub fn fetch(&self, location: &Url) -> SOAPResult<(String, Url)> {
+        self.fetch_with(location, |_, client| Ok(Hop::new(client)))
+    }
+    
+    /// Retrieve the document at the given location using clients customized
+    /// by the given closure, for example to verify certificates.
+    ///
+    /// The closure is called with the location of every request, including
+    /// those made to follow redirects, along with a client that already applies
+    /// the timeouts of the fetcher, so that each one is held to the same rules.
+    /// Redirects from https to plain http are never followed.
+    ///
+    /// This is a blocking operation.
+    pub fn fetch_with<F>(&self, location: &Url, connect: F) -> SOAPResult<(String, Url)>
+        where F: Fn(&Url, Client) -> SOAPResult<Hop> {
+        let (document, location) = try!(fetch_document(location.clone(), self.max_size, self.max_redirects,
+            |url| {
+                let mut client = Client::with_connector(TimeoutConnector::new(
+                    to_std_duration(self.connect_timeout)));
+                client.set_write_timeout(Some(to_std_duration(self.write_timeout)));
+                client.set_read_timeout(Some(to_std_duration(self.read_timeout)));
+                
+                connect(url, client)
+            }
+        ));
+        let document = try!(String::from_utf8(document).map_err(|_|
+            SOAPError::InvalidResponse("Description Is Not Valid UTF-8")
+        ));
+        
+        Ok((document, location))
+    }
+}
+
+impl PartialEq for DescriptionFetcher {
+    fn eq(&self, other: &DescriptionFetcher) -> bool {
+        self as *const DescriptionFetcher == other as *const DescriptionFetcher
+    }
+}
+
+impl Eq for DescriptionFetcher {}
+
+impl Hash for DescriptionFetcher {
+    fn hash<H>(&self, state: &mut H) where H: Hasher {
+        (self as *const DescriptionFetcher as usize).hash(state);
+    }
+}
+
+impl Debug for DescriptionFetcher {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("DescriptionFetcher {{ connect_timeout: {:?}, write_timeout: {:?}, \
+            read_timeout: {:?}, max_size: {}, max_redirects: {} }}", self.connect_timeout, self.write_timeout,
+            self.read_timeout, self.max_size, self.max_redirects))
+    }
+}
+
+/// Client used for a single request of a fetch.
+pub struct Hop {
+    client:   Client,
+    verified: Option<Arc<AtomicBool>>
+}
+
+impl Hop {
+    /// Create a new Hop whose response is trusted as is.
+    pub fn new(client: Client) -> Hop {
+        Hop{ client: client, verified: None }
+    }
+    
+    /// Create a new Hop whose response is only trusted if the flag was set by
+    /// the time the response headers arrived, typically by a certificate
+    /// verifier installed on the client.
+    pub fn verified_by(client: Client, verified: Arc<AtomicBool>) -> Hop {
+        Hop{ client: client, verified: Some(verified) }
+    }
+}
+
+/// Error that occurred while fetching a document.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct FetchError {
+    desc: String
+}
+
+impl FetchError {
+    fn new(desc: &str) -> FetchError {
+        FetchError{ desc: desc.to_string() }
+    }
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str(&self.desc[..])
+    }
+}
+
+impl Error for FetchError {
+    fn description(&self) -> &str {
+        &self.desc[..]
+    }
+}
+
+fn to_soap_error(error: FetchError) -> SOAPError {
+    SOAPError::Other(Box::new(error) as Box<Error>)
+}
+
+/// Convert a Duration into the form expected for socket timeouts.
+///
+/// Zero is not a valid socket timeout, so anything shorter is rounded up to a
+/// single millisecond.
+fn to_std_duration(duration: Duration) -> StdDuration {
+    StdDuration::from_millis(cmp::max(duration.num_milliseconds(), 1) as u64)
+}
+
+/// Retrieve the document at the given location, following redirects and
+/// decoding compressed bodies.
+///
+/// Every request is made with a new Hop from the given closure.
+fn fetch_document<F>(mut location: Url, max_size: usize, max_redirects: u8, connect: F)
+    -> SOAPResult<(Vec<u8>, Url)> where F: Fn(&Url) -> SOAPResult<Hop> {
+    let mut redirects = 0;
+    loop {
+        let mut hop = try!(connect(&location));
+        
+        // Redirects Are Followed Here So They Can Be Counted And Checked
+        hop.client.set_redirect_policy(RedirectPolicy::FollowNone);
+        
+        let mut headers = Headers::new();
+        headers.set_raw(ACCEPT_ENCODING_HEADER, vec![GZIP_ENCODING.as_bytes().to_vec()]);
+        
+        let mut response = try!(hop.client.get(location.clone()).headers(headers).send().map_err(|e|
+            to_soap_error(FetchError::new(e.description()))
+        ));
+        
+        if let Some(ref verified) = hop.verified {
+            if !verified.load(Ordering::SeqCst) {
+                return Err(to_soap_error(FetchError::new("Connection Could Not Be Verified")))
+            }
+        }
+        
+        let code = response.status.to_u16();
+        if code >= 300 && code < 400 {
+            if redirects >= max_redirects {
+                return Err(to_soap_error(FetchError::new("Too Many Redirects")))
+            }
+            redirects += 1;
+            
+            let target = try!(header_value(&response.headers, LOCATION_HEADER).ok_or(
+                to_soap_error(FetchError::new("Redirect Is Missing A Location"))
+            ));
+            location = try!(redirect_target(&location, &target[..]).map_err(to_soap_error));
+            
+            continue
+        } else if code != 200 {
+            return Err(to_soap_error(FetchError{ desc: format!("Response Code: {}", code) }))
+        }
+        
+        let is_gzip = header_value(&response.headers, CONTENT_ENCODING_HEADER)
+            .map(|n| n.trim().to_lowercase() == GZIP_ENCODING).unwrap_or(false);
+        
+        let body = try!(read_body(&mut response, max_size).map_err(to_soap_error));
+        if !is_gzip {
+            return Ok((body, location))
+        }
+        
+        let document = try!(decode_gzip(&body[..], max_size).map_err(to_soap_error));
+        return Ok((document, location))
+    }
+}
+
+/// Returns the first value of the given header as a string.
+fn header_value(headers: &Headers, name: &str) -> Option<String> {
+    headers.get_raw(name).and_then(|n| n.first()).and_then(|n| str::from_utf8(&n[..]).ok())
+        .map(|n| n.to_string())
+}
+
+/// Resolve the location of a redirect, which may be relative, against the
+/// location that was redirected from.
+///
+/// Fails if the redirect would go from https to plain http.
+fn redirect_target(location: &Url, target: &str) -> Result<Url, FetchError> {
+    let target = try!(UrlParser::new().base_url(location).parse(target.trim()).map_err(|_|
+        FetchError::new("Redirect Has An Invalid Location")
+    ));
+    
+    if location.scheme == HTTPS_SCHEME && target.scheme != HTTPS_SCHEME {
+        Err(FetchError::new("Redirect Leaves HTTPS"))
+    } else {
+        Ok(target)
+    }
+}
+
+/// Read the body of a response, failing if it exceeds the size limit.
+fn read_body<R>(reader: &mut R, max_size: usize) -> Result<Vec<u8>, FetchError> where R: Read {
+    let mut body = Vec::new();
+    let mut buffer = [0u8; READ_BUFFER_SIZE];
+    
+    loop {
+        let count = try!(reader.read(&mut buffer).map_err(|e| FetchError::new(e.description())));
+        if count == 0 {
+            return Ok(body)
+        }
+        
+        if body.len() + count > max_size {
+            return Err(FetchError::new("Description Exceeds The Size Limit"))
+        }
+        body.push_all(&buffer[..count]);
+    }
+}
+
+/// Decompress a gzip encoded body, failing if it exceeds the size limit.
+fn decode_gzip(body: &[u8], max_size: usize) -> Result<Vec<u8>, FetchError> {
+    let decoder = try!(GzDecoder::new(body).map_err(|e| FetchError::new(e.description())));
+    
+    // Read One Byte Past The Limit So That Exceeding It Can Be Detected
+    let mut decoded = Vec::new();
+    try!(decoder.take(max_size as u64 + 1).read_to_end(&mut decoded).map_err(|e|
+        FetchError::new(e.description())
+    ));
+    
+    if decoded.len() > max_size {
+        Err(FetchError::new("Description Exceeds The Size Limit"))
+    } else {
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::{Url};
+    
+    use forum::trust::{Verification};
+    use super::{DescriptionFetcher};
+    
+    const GZIP_BODY: [u8; 33] = [0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xFF, 0xB3, 0x29,
+        0xCA, 0xCF, 0x2F, 0xB1, 0x4B, 0xAF, 0xCA, 0x2C, 0xB0, 0xD1, 0x07, 0x33, 0x01, 0x46, 0xDB, 0xAA,
+        0x02, 0x11, 0x00, 0x00, 0x00];
+    
+    fn location() -> Url {
+        Url::parse("http://192.168.1.5:49152/desc/root.xml").unwrap()
+    }
+    
+    #[test]
+    fn positive_cache_by_config_id() {
+        let fetcher = DescriptionFetcher::new();
+        fetcher.store("uuid:device", 7, Verification::Unverified, "<root/>".to_string(), location());
+        
+        assert_eq!(fetcher.cached("uuid:device", 7, &Verification::Unverified).unwrap().0, "<root/>");
+        assert!(fetcher.cached("uuid:device", 8, &Verification::Unverified).is_none());
+        assert!(fetcher.cached("uuid:other", 7, &Verification::Unverified).is_none());
+    }
+    
+    #[test]
+    fn positive_cache_by_verification() {
+        let fetcher = DescriptionFetcher::new();
+        fetcher.store("uuid:device", 7, Verification::Unverified, "<root/>".to_string(), location());
+        
+        assert!(fetcher.cached("uuid:device", 7, &Verification::System).is_none());
+        assert!(fetcher.cached("uuid:device", 7, &Verification::Pinned(vec![1, 2, 3])).is_none());
+    }
+    
+    #[test]
+    fn positive_invalidate() {
+        let fetcher = DescriptionFetcher::new();
+        fetcher.store("uuid:device", 7, Verification::System, "<root/>".to_string(), location());
+        fetcher.store("uuid:other", 7, Verification::System, "<root/>".to_string(), location());
+        fetcher.invalidate("uuid:device");
+        
+        assert!(fetcher.cached("uuid:device", 7, &Verification::System).is_none());
+        assert!(fetcher.cached("uuid:other", 7, &Verification::System).is_some());
+    }
+    
+    #[test]
+    fn positive_store_evicts_other_config_ids() {
+        let fetcher = DescriptionFetcher::new();
+        fetcher.store("uuid:device", 7, Verification::System, "<root/>".to_string(), location());
+        fetcher.store("uuid:device", 7, Verification::Unverified, "<root/>".to_string(), location());
+        fetcher.store("uuid:other", 7, Verification::System, "<root/>".to_string(), location());
+        fetcher.store("uuid:device", 8, Verification::System, "<root/>".to_string(), location());
+        
+        assert!(fetcher.cached("uuid:device", 7, &Verification::System).is_none());
+        assert!(fetcher.cached("uuid:device", 7, &Verification::Unverified).is_none());
+        assert!(fetcher.cached("uuid:device", 8, &Verification::System).is_some());
+        assert!(fetcher.cached("uuid:other", 7, &Verification::System).is_some());
+    }
+    
+    #[test]
+    fn positive_relative_redirect() {
+        let target = super::redirect_target(&location(), "../other/root.xml").unwrap();
+        
+        assert_eq!(target.serialize(), "http://192.168.1.5:49152/other/root.xml");
+    }
+    
+    #[test]
+    fn positive_max_redirects_limit() {
+        let mut fetcher = DescriptionFetcher::new();
+        fetcher.set_max_redirects(200);
+        
+        assert_eq!(fetcher.max_redirects, super::MAX_REDIRECTS_LIMIT);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_https_downgrade() {
+        let secure = Url::parse("https://192.168.1.5:49153/desc/root.xml").unwrap();
+        
+        super::redirect_target(&secure, "http://192.168.1.5:49152/desc/root.xml").unwrap();
+    }
+    
+    #[test]
+    fn positive_decode_gzip() {
+        assert_eq!(super::decode_gzip(&GZIP_BODY[..], 1024).unwrap(), b"<root>gzip</root>".to_vec());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_decode_gzip_too_large() {
+        super::decode_gzip(&GZIP_BODY[..], 8).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_body_too_large() {
+        super::read_body(&mut &b"<root>description</root>"[..], 8).unwrap();
+    }
+}
@@ -13,10 +13,13 @@ use forum::device::{DeviceType};
 use forum::service::{ServiceType};
 use version::{Version};
 
+mod description;
 mod query;
 
 pub mod device;
+pub mod fetcher;
 pub mod service;
+pub mod trust;
 
 pub use forum::query::{GenericQuery, QueryType, TypedQuery};
 
@@ -1,7 +1,11 @@
 use url::{Url};
 
-use forum::{TargetType};
+use {SOAPResult};
+use forum::{description, TargetType};
+use forum::fetcher::{DescriptionFetcher};
+use forum::trust::{TrustPolicy};
 use forum::device::{DeviceQuery};
+use forum::service::{GenericService, ServiceType};
 use version::{Version};
 
 /// Offset Of UUID Inside A UDN
@@ -15,19 +19,104 @@ pub trait TypedQuery {
 /// Query containing no type information about what device it is querying.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct GenericQuery<'a> {
-    url: &'a Url,
-    udn: &'a [u8]
+    url:        &'a Url,
+    secure_url: Option<&'a Url>,
+    udn:        &'a [u8],
+    config_id:  Option<u32>,
+    policy:     Option<&'a TrustPolicy>,
+    fetcher:    Option<&'a DescriptionFetcher>
 }
 
 impl<'a> GenericQuery<'a> {
     pub fn new(url: &'a Url, udn: &'a [u8]) -> GenericQuery<'a> {
-        GenericQuery{ url: url, udn: udn }
+        GenericQuery{ url: url, secure_url: None, udn: udn, config_id: None, policy: None, fetcher: None }
+    }
+    
+    /// Set the secure location advertised by a UPnP 2.0 device.
+    ///
+    /// The description will be fetched from the secure location in preference
+    /// to the regular location.
+    pub fn with_secure_location(mut self, secure_url: &'a Url) -> GenericQuery<'a> {
+        self.secure_url = Some(secure_url);
+        
+        self
+    }
+    
+    /// Set the CONFIGID advertised by a UPnP 1.1 or later device.
+    ///
+    /// Descriptions are only cached for devices with a known config id.
+    pub fn with_config_id(mut self, config_id: u32) -> GenericQuery<'a> {
+        self.config_id = Some(config_id);
+        
+        self
+    }
+    
+    /// Set the fetcher used to retrieve, and cache, the description of the device.
+    ///
+    /// Queries sharing a fetcher share its cache, whereas queries without one
+    /// fetch the description every time.
+    pub fn with_fetcher(mut self, fetcher: &'a DescriptionFetcher) -> GenericQuery<'a> {
+        self.fetcher = Some(fetcher);
+        
+        self
+    }
+    
+    /// Set the policy used to verify the certificate of the device.
+    pub fn with_trust_policy(mut self, policy: &'a TrustPolicy) -> GenericQuery<'a> {
+        self.policy = Some(policy);
+        
+        self
     }
     
     pub fn uuid(&self) -> &[u8] {
         &self.udn[UDN_UUID_OFFSET..]
     }
     
+    /// Returns the full udn of the device, including the uuid prefix.
+    pub fn udn(&self) -> &[u8] {
+        self.udn
+    }
+    
+    /// Returns the location of the root device description.
+    pub fn location(&self) -> &Url {
+        self.url
+    }
+    
+    /// Returns the secure location of the root device description, if any.
+    pub fn secure_location(&self) -> Option<&Url> {
+        self.secure_url
+    }
+    
+    /// Returns the CONFIGID advertised by the device, if any.
+    pub fn config_id(&self) -> Option<u32> {
+        self.config_id
+    }
+    
+    /// Returns the fetcher used to retrieve the description of the device, if any.
+    pub fn fetcher(&self) -> Option<&DescriptionFetcher> {
+        self.fetcher
+    }
+    
+    /// Returns the policy used to verify the certificate of the device, if any.
+    pub fn trust_policy(&self) -> Option<&TrustPolicy> {
+        self.policy
+    }
+    
+    /// Locate a service compatible with the given service type on the device.
+    ///
+    /// This is a blocking operation.
+    pub fn service(&self, service: &ServiceType) -> SOAPResult<GenericService> {
+        description::find_service(self, service)
+    }
+    
+    /// Locate all services compatible with the given service type on the device
+    /// and any of its embedded devices.
+    ///
+    /// This is a blocking operation.
+    pub fn services(&self, service: &ServiceType) -> SOAPResult<Vec<GenericService>> {
+        description::find_services(self, service)
+    }
+    
     /*pub fn query(&self) -> Result<Device> {
         // TODO: Fill In
         Ok(()) 
@@ -0,0 +1,60 @@
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::address_book::{Contact};
+
+const GET_CONTACTS_ACTION:   &'static str = "GetContacts";
+const ADD_CONTACT_ACTION:    &'static str = "AddContact";
+const DELETE_CONTACT_ACTION: &'static str = "DeleteContact";
+
+const CONTACT_FILTER_ARG: &'static str = "ContactFilter";
+const CONTACTS_ARG:       &'static str = "Contacts";
+const CONTACT_INFO_ARG:   &'static str = "ContactInfo";
+const CONTACT_ID_ARG:     &'static str = "ContactID";
+
+/// Filter that matches every contact.
+const ALL_CONTACTS_FILTER: &'static str = "*";
+
+/// Client for an AddressBook service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AddressBookClient {
+    service: GenericService
+}
+
+impl AddressBookClient {
+    pub fn new(service: GenericService) -> AddressBookClient {
+        AddressBookClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns every contact in the address book.
+    pub fn contacts(&self) -> SOAPResult<Vec<Contact>> {
+        self.find_contacts(ALL_CONTACTS_FILTER)
+    }
+    
+    /// Returns the contacts matching the given filter.
+    pub fn find_contacts(&self, filter: &str) -> SOAPResult<Vec<Contact>> {
+        let response = try!(self.service.send_action(GET_CONTACTS_ACTION, &[(CONTACT_FILTER_ARG, filter)]));
+        
+        Contact::from_value(CONTACTS_ARG, &try!(response.argument(CONTACTS_ARG))[..])
+    }
+    
+    /// Add the given contact to the address book.
+    ///
+    /// Returns the id assigned to the contact.
+    pub fn add_contact(&self, contact: &Contact) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(ADD_CONTACT_ACTION, &[(CONTACT_INFO_ARG, &contact.to_value()[..])]));
+        
+        response.argument(CONTACT_ID_ARG)
+    }
+    
+    /// Remove the contact with the given id from the address book.
+    pub fn delete_contact(&self, contact_id: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(DELETE_CONTACT_ACTION, &[(CONTACT_ID_ARG, contact_id)]));
+        
+        Ok(())
+    }
+}
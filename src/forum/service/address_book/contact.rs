@@ -0,0 +1,96 @@
+use {SOAPError, SOAPResult};
+use xml;
+
+const ADDRESS_BOOK_NAMESPACE: &'static str = "urn:schemas-upnp-org:phone:addressbook";
+
+const CONTACT_ELEMENT:      &'static str = "contact";
+const CONTACT_ID_ELEMENT:   &'static str = "contactID";
+const NAME_ELEMENT:         &'static str = "name";
+const PHONE_NUMBER_ELEMENT: &'static str = "telephoneNumber";
+const EMAIL_ELEMENT:        &'static str = "email";
+
+/// Entry within an address book.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Contact {
+    /// Identifier assigned by the address book, empty for new contacts.
+    pub contact_id:    String,
+    pub name:          String,
+    pub phone_numbers: Vec<String>,
+    pub emails:        Vec<String>
+}
+
+impl Contact {
+    /// Parse the contacts out of a document containing contact elements.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Vec<Contact>> {
+        xml::Elements::new(doc, CONTACT_ELEMENT).map(|contact| {
+            let contact_id = match xml::element(contact, CONTACT_ID_ELEMENT) {
+                Some(n) => xml::unescape(n.trim()),
+                None    => return Err(SOAPError::InvalidArgument(name, "Contact Has No Contact Id"))
+            };
+            let list = |element: &str| {
+                xml::Elements::new(contact, element).map(|n| xml::unescape(n.trim())).collect::<Vec<String>>()
+            };
+            
+            Ok(Contact{
+                contact_id:    contact_id,
+                name:          xml::element(contact, NAME_ELEMENT).map(|n| xml::unescape(n.trim()))
+                                   .unwrap_or(String::new()),
+                phone_numbers: list(PHONE_NUMBER_ELEMENT),
+                emails:        list(EMAIL_ELEMENT)
+            })
+        }).collect()
+    }
+    
+    /// Returns the contact document sent to the service.
+    ///
+    /// The contact id is left out when empty so that the address book assigns one.
+    pub fn to_value(&self) -> String {
+        let mut doc = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><{} xmlns=\"{}\">",
+                              CONTACT_ELEMENT, ADDRESS_BOOK_NAMESPACE);
+        
+        if !self.contact_id.is_empty() {
+            push_element(&mut doc, CONTACT_ID_ELEMENT, &self.contact_id[..]);
+        }
+        push_element(&mut doc, NAME_ELEMENT, &self.name[..]);
+        for number in self.phone_numbers.iter() {
+            push_element(&mut doc, PHONE_NUMBER_ELEMENT, &number[..]);
+        }
+        for email in self.emails.iter() {
+            push_element(&mut doc, EMAIL_ELEMENT, &email[..]);
+        }
+        doc.push_str(&format!("</{}>", CONTACT_ELEMENT)[..]);
+        
+        doc
+    }
+}
+
+fn push_element(doc: &mut String, element: &str, value: &str) {
+    doc.push_str(&format!("<{0}>{1}</{0}>", element, xml::escape(value))[..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Contact};
+    
+    #[test]
+    fn positive_contact_round_trip() {
+        let contact = Contact{ contact_id: "c7".to_string(), name: "Bob & Co".to_string(),
+            phone_numbers: vec!["+15551234".to_string(), "+15554321".to_string()],
+            emails: vec!["bob@example.com".to_string()] };
+        
+        assert_eq!(Contact::from_value("Contacts", &contact.to_value()[..]).unwrap(), vec![contact]);
+    }
+    
+    #[test]
+    fn positive_new_contact_without_id() {
+        let contact = Contact{ name: "Alice".to_string(), ..Contact::default() };
+        
+        assert!(!contact.to_value().contains("contactID"));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_contact_without_id() {
+        Contact::from_value("Contacts", "<contacts><contact><name>Eve</name></contact></contacts>").unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod contact;
+
+pub use self::client::{AddressBookClient};
+pub use self::contact::{Contact};
@@ -0,0 +1,215 @@
+use std::borrow::{ToOwned};
+use std::error::{Error};
+use std::io::{Read};
+
+use hyper::{Client};
+use hyper::status::{StatusCode};
+use url::{Url};
+
+use {SOAPError, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::basic_management::{DiagnosticStatus, NSLookupAnswer, NSLookupRequest, NSLookupResult,
+    PingRequest, PingResult, TestState, TracerouteRequest, TracerouteResult};
+use soap::{ActionResponse};
+use soap::value;
+
+const REBOOT_ACTION:                &'static str = "Reboot";
+const GET_DEVICE_STATUS_ACTION:     &'static str = "GetDeviceStatus";
+const GET_LOG_URIS_ACTION:          &'static str = "GetLogURIs";
+const START_PING_ACTION:            &'static str = "StartPing";
+const GET_PING_RESULT_ACTION:       &'static str = "GetPingResult";
+const START_NS_LOOKUP_ACTION:       &'static str = "StartNSLookup";
+const GET_NS_LOOKUP_RESULT_ACTION:  &'static str = "GetNSLookupResult";
+const START_TRACEROUTE_ACTION:      &'static str = "StartTraceroute";
+const GET_TRACEROUTE_RESULT_ACTION: &'static str = "GetTracerouteResult";
+const GET_TEST_INFO_ACTION:         &'static str = "GetTestInfo";
+const CANCEL_TEST_ACTION:           &'static str = "CancelTest";
+
+const DEVICE_STATUS_ARG:         &'static str = "DeviceStatus";
+const LOG_URIS_ARG:              &'static str = "LogURIs";
+const TEST_ID_ARG:               &'static str = "TestID";
+const STATE_ARG:                 &'static str = "State";
+const HOST_ARG:                  &'static str = "Host";
+const HOST_NAME_ARG:             &'static str = "HostName";
+const DNS_SERVER_ARG:            &'static str = "DNSServer";
+const NUMBER_OF_REPETITIONS_ARG: &'static str = "NumberOfRepetitions";
+const TIMEOUT_ARG:               &'static str = "Timeout";
+const DATA_BLOCK_SIZE_ARG:       &'static str = "DataBlockSize";
+const DSCP_ARG:                  &'static str = "DSCP";
+const MAX_HOP_COUNT_ARG:         &'static str = "MaxHopCount";
+const STATUS_ARG:                &'static str = "Status";
+const ADDITIONAL_INFO_ARG:       &'static str = "AdditionalInfo";
+const SUCCESS_COUNT_ARG:         &'static str = "SuccessCount";
+const FAILURE_COUNT_ARG:         &'static str = "FailureCount";
+const AVERAGE_RESPONSE_ARG:      &'static str = "AverageResponseTime";
+const MINIMUM_RESPONSE_ARG:      &'static str = "MinimumResponseTime";
+const MAXIMUM_RESPONSE_ARG:      &'static str = "MaximumResponseTime";
+const RESULT_ARG:                &'static str = "Result";
+const RESPONSE_TIME_ARG:         &'static str = "ResponseTime";
+const HOP_HOSTS_ARG:             &'static str = "HopHosts";
+
+/// Client for a BasicManagement service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BasicManagementClient {
+    service: GenericService
+}
+
+impl BasicManagementClient {
+    pub fn new(service: GenericService) -> BasicManagementClient {
+        BasicManagementClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Reboot the device.
+    pub fn reboot(&self) -> SOAPResult<()> {
+        try!(self.service.send_action(REBOOT_ACTION, &[]));
+        
+        Ok(())
+    }
+    
+    /// Returns the status reported by the device, such as ChangesApplied or
+    /// RebootRequired.
+    pub fn device_status(&self) -> SOAPResult<Vec<String>> {
+        let response = try!(self.service.send_action(GET_DEVICE_STATUS_ACTION, &[]));
+        
+        Ok(value::to_list(&try!(response.argument(DEVICE_STATUS_ARG))[..]).into_iter()
+            .map(|n| n.to_owned()).collect())
+    }
+    
+    /// Returns the locations of the logs that the device makes available.
+    pub fn log_uris(&self) -> SOAPResult<Vec<Url>> {
+        let response = try!(self.service.send_action(GET_LOG_URIS_ACTION, &[]));
+        let uris = try!(response.argument(LOG_URIS_ARG));
+        
+        value::to_list(&uris[..]).into_iter().map(|n| value::to_url(LOG_URIS_ARG, n)).collect()
+    }
+    
+    /// Retrieve the contents of a log.
+    ///
+    /// This is a blocking operation.
+    pub fn fetch_log(&self, uri: &Url) -> SOAPResult<Vec<u8>> {
+        let mut client = Client::new();
+        let mut response = try!(client.get(uri.clone()).send().map_err(|e|
+            SOAPError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        if response.status != StatusCode::Ok {
+            return Err(SOAPError::ResponseCode(response.status.to_u16()))
+        }
+        
+        let mut log = Vec::new();
+        try!(response.read_to_end(&mut log).map_err(|e|
+            SOAPError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        Ok(log)
+    }
+    
+    /// Start a ping test on the device, returning the id of the test.
+    pub fn start_ping(&self, request: &PingRequest) -> SOAPResult<u32> {
+        let response = try!(self.service.send_action(START_PING_ACTION, &[
+            (HOST_ARG,                  &request.host[..]),
+            (NUMBER_OF_REPETITIONS_ARG, &request.repetitions.to_string()[..]),
+            (TIMEOUT_ARG,               &request.timeout_ms.to_string()[..]),
+            (DATA_BLOCK_SIZE_ARG,       &request.data_block_size.to_string()[..]),
+            (DSCP_ARG,                  &request.dscp.to_string()[..])
+        ]));
+        
+        value::to_number(TEST_ID_ARG, &try!(response.argument(TEST_ID_ARG))[..])
+    }
+    
+    /// Returns the results of a completed ping test.
+    pub fn ping_result(&self, test_id: u32) -> SOAPResult<PingResult> {
+        let response = try!(self.service.send_action(GET_PING_RESULT_ACTION,
+            &[(TEST_ID_ARG, &test_id.to_string()[..])]));
+        
+        Ok(PingResult{
+            status:           try!(status(&response)),
+            additional_info:  try!(response.argument(ADDITIONAL_INFO_ARG)),
+            success_count:    try!(number(&response, SUCCESS_COUNT_ARG)),
+            failure_count:    try!(number(&response, FAILURE_COUNT_ARG)),
+            average_response: try!(number(&response, AVERAGE_RESPONSE_ARG)),
+            minimum_response: try!(number(&response, MINIMUM_RESPONSE_ARG)),
+            maximum_response: try!(number(&response, MAXIMUM_RESPONSE_ARG))
+        })
+    }
+    
+    /// Start a name lookup test on the device, returning the id of the test.
+    pub fn start_ns_lookup(&self, request: &NSLookupRequest) -> SOAPResult<u32> {
+        let response = try!(self.service.send_action(START_NS_LOOKUP_ACTION, &[
+            (HOST_NAME_ARG,             &request.host_name[..]),
+            (DNS_SERVER_ARG,            &request.dns_server[..]),
+            (NUMBER_OF_REPETITIONS_ARG, &request.repetitions.to_string()[..]),
+            (TIMEOUT_ARG,               &request.timeout_ms.to_string()[..])
+        ]));
+        
+        value::to_number(TEST_ID_ARG, &try!(response.argument(TEST_ID_ARG))[..])
+    }
+    
+    /// Returns the results of a completed name lookup test.
+    pub fn ns_lookup_result(&self, test_id: u32) -> SOAPResult<NSLookupResult> {
+        let response = try!(self.service.send_action(GET_NS_LOOKUP_RESULT_ACTION,
+            &[(TEST_ID_ARG, &test_id.to_string()[..])]));
+        
+        Ok(NSLookupResult{
+            status:          try!(status(&response)),
+            additional_info: try!(response.argument(ADDITIONAL_INFO_ARG)),
+            success_count:   try!(number(&response, SUCCESS_COUNT_ARG)),
+            answers:         try!(NSLookupAnswer::from_value(RESULT_ARG, &try!(response.argument(RESULT_ARG))[..]))
+        })
+    }
+    
+    /// Start a traceroute test on the device, returning the id of the test.
+    pub fn start_traceroute(&self, request: &TracerouteRequest) -> SOAPResult<u32> {
+        let response = try!(self.service.send_action(START_TRACEROUTE_ACTION, &[
+            (HOST_ARG,            &request.host[..]),
+            (TIMEOUT_ARG,         &request.timeout_ms.to_string()[..]),
+            (DATA_BLOCK_SIZE_ARG, &request.data_block_size.to_string()[..]),
+            (MAX_HOP_COUNT_ARG,   &request.max_hop_count.to_string()[..]),
+            (DSCP_ARG,            &request.dscp.to_string()[..])
+        ]));
+        
+        value::to_number(TEST_ID_ARG, &try!(response.argument(TEST_ID_ARG))[..])
+    }
+    
+    /// Returns the results of a completed traceroute test.
+    pub fn traceroute_result(&self, test_id: u32) -> SOAPResult<TracerouteResult> {
+        let response = try!(self.service.send_action(GET_TRACEROUTE_RESULT_ACTION,
+            &[(TEST_ID_ARG, &test_id.to_string()[..])]));
+        let hop_hosts = try!(response.argument(HOP_HOSTS_ARG));
+        
+        Ok(TracerouteResult{
+            status:           try!(status(&response)),
+            additional_info:  try!(response.argument(ADDITIONAL_INFO_ARG)),
+            response_time_ms: try!(number(&response, RESPONSE_TIME_ARG)),
+            hop_hosts:        value::to_list(&hop_hosts[..]).into_iter().map(|n| n.to_owned()).collect()
+        })
+    }
+    
+    /// Returns the current state of the given test.
+    pub fn test_state(&self, test_id: u32) -> SOAPResult<TestState> {
+        let response = try!(self.service.send_action(GET_TEST_INFO_ACTION,
+            &[(TEST_ID_ARG, &test_id.to_string()[..])]));
+        
+        Ok(TestState::from_value(&try!(response.argument(STATE_ARG))[..]))
+    }
+    
+    /// Cancel the given test if it has not yet completed.
+    pub fn cancel_test(&self, test_id: u32) -> SOAPResult<()> {
+        try!(self.service.send_action(CANCEL_TEST_ACTION, &[(TEST_ID_ARG, &test_id.to_string()[..])]));
+        
+        Ok(())
+    }
+}
+
+fn status(response: &ActionResponse) -> SOAPResult<DiagnosticStatus> {
+    response.argument(STATUS_ARG).map(|n| DiagnosticStatus::from_value(&n[..]))
+}
+
+fn number(response: &ActionResponse, name: &'static str) -> SOAPResult<u32> {
+    value::to_number(name, &try!(response.argument(name))[..])
+}
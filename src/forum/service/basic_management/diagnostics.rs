@@ -0,0 +1,233 @@
+use std::borrow::{ToOwned};
+
+use {SOAPResult};
+use soap::value;
+use xml;
+
+const SUCCESS_VALUE:      &'static str = "Success";
+const ERROR_VALUE_PREFIX: &'static str = "Error_";
+
+const REQUESTED_VALUE:   &'static str = "Requested";
+const IN_PROGRESS_VALUE: &'static str = "InProgress";
+const CANCELED_VALUE:    &'static str = "Canceled";
+const COMPLETED_VALUE:   &'static str = "Completed";
+
+const ANSWER_ELEMENT:        &'static str = "Result";
+const STATUS_ELEMENT:        &'static str = "Status";
+const HOST_NAME_ELEMENT:     &'static str = "HostNameReturned";
+const IP_ADDRESSES_ELEMENT:  &'static str = "IPAddresses";
+const DNS_SERVER_ELEMENT:    &'static str = "DNSServerIP";
+const RESPONSE_TIME_ELEMENT: &'static str = "ResponseTime";
+
+const DEFAULT_REPETITIONS:      u32 = 4;
+const DEFAULT_TIMEOUT_MS:       u32 = 1000;
+const DEFAULT_DATA_BLOCK_SIZE:  u16 = 32;
+const DEFAULT_MAX_HOP_COUNT:    u8  = 30;
+const DEFAULT_TRACE_TIMEOUT_MS: u32 = 5000;
+
+/// State of a diagnostic test on a device.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TestState {
+    Requested,
+    InProgress,
+    Canceled,
+    Completed,
+    /// Vendor defined test state.
+    Other(String)
+}
+
+impl TestState {
+    /// Convert the text value of a test state to a TestState.
+    pub fn from_value(value: &str) -> TestState {
+        match value.trim() {
+            REQUESTED_VALUE   => TestState::Requested,
+            IN_PROGRESS_VALUE => TestState::InProgress,
+            CANCELED_VALUE    => TestState::Canceled,
+            COMPLETED_VALUE   => TestState::Completed,
+            n => TestState::Other(n.to_owned())
+        }
+    }
+}
+
+/// Outcome of a diagnostic test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DiagnosticStatus {
+    Success,
+    /// Test failed for the given reason, such as CannotResolveHostName.
+    Error(String)
+}
+
+impl DiagnosticStatus {
+    /// Convert the text value of a test status to a DiagnosticStatus.
+    pub fn from_value(value: &str) -> DiagnosticStatus {
+        let value = value.trim();
+        
+        if value == SUCCESS_VALUE {
+            DiagnosticStatus::Success
+        } else if value.starts_with(ERROR_VALUE_PREFIX) {
+            DiagnosticStatus::Error(value[ERROR_VALUE_PREFIX.len()..].to_owned())
+        } else {
+            DiagnosticStatus::Error(value.to_owned())
+        }
+    }
+}
+
+/// Parameters of a ping test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PingRequest {
+    pub host:            String,
+    pub repetitions:     u32,
+    /// Time to wait for each response, in milliseconds.
+    pub timeout_ms:      u32,
+    pub data_block_size: u16,
+    pub dscp:            u8
+}
+
+impl PingRequest {
+    /// Create a new PingRequest for the given host with common defaults.
+    pub fn new(host: &str) -> PingRequest {
+        PingRequest{ host: host.to_owned(), repetitions: DEFAULT_REPETITIONS, timeout_ms: DEFAULT_TIMEOUT_MS,
+            data_block_size: DEFAULT_DATA_BLOCK_SIZE, dscp: 0 }
+    }
+}
+
+/// Results of a ping test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PingResult {
+    pub status:           DiagnosticStatus,
+    pub additional_info:  String,
+    pub success_count:    u32,
+    pub failure_count:    u32,
+    /// Response times, in milliseconds.
+    pub average_response: u32,
+    pub minimum_response: u32,
+    pub maximum_response: u32
+}
+
+/// Parameters of a name lookup test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NSLookupRequest {
+    pub host_name:   String,
+    /// Server to query, or empty to use the default server of the device.
+    pub dns_server:  String,
+    pub repetitions: u32,
+    /// Time to wait for each response, in milliseconds.
+    pub timeout_ms:  u32
+}
+
+impl NSLookupRequest {
+    /// Create a new NSLookupRequest for the given host name with common defaults.
+    pub fn new(host_name: &str) -> NSLookupRequest {
+        NSLookupRequest{ host_name: host_name.to_owned(), dns_server: String::new(), repetitions: 1,
+            timeout_ms: DEFAULT_TIMEOUT_MS }
+    }
+}
+
+/// Answer received for a single repetition of a name lookup test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NSLookupAnswer {
+    pub status:           DiagnosticStatus,
+    pub host_name:        String,
+    pub ip_addresses:     Vec<String>,
+    pub dns_server:       String,
+    /// Response time, in milliseconds.
+    pub response_time_ms: u32
+}
+
+impl NSLookupAnswer {
+    /// Parse the answers out of a name lookup result document.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Vec<NSLookupAnswer>> {
+        xml::Elements::new(doc, ANSWER_ELEMENT).map(|answer| {
+            let field = |element: &str| xml::element(answer, element).map(|n| xml::unescape(n.trim()))
+                .unwrap_or(String::new());
+            let response_time = field(RESPONSE_TIME_ELEMENT);
+            
+            Ok(NSLookupAnswer{
+                status:           DiagnosticStatus::from_value(&field(STATUS_ELEMENT)[..]),
+                host_name:        field(HOST_NAME_ELEMENT),
+                ip_addresses:     value::to_list(&field(IP_ADDRESSES_ELEMENT)[..]).into_iter()
+                                      .map(|n| n.to_owned()).collect(),
+                dns_server:       field(DNS_SERVER_ELEMENT),
+                response_time_ms: if response_time.is_empty() { 0 } else {
+                    try!(value::to_number(name, &response_time[..]))
+                }
+            })
+        }).collect()
+    }
+}
+
+/// Results of a name lookup test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NSLookupResult {
+    pub status:          DiagnosticStatus,
+    pub additional_info: String,
+    pub success_count:   u32,
+    pub answers:         Vec<NSLookupAnswer>
+}
+
+/// Parameters of a traceroute test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TracerouteRequest {
+    pub host:            String,
+    /// Time to wait for each response, in milliseconds.
+    pub timeout_ms:      u32,
+    pub data_block_size: u16,
+    pub max_hop_count:   u8,
+    pub dscp:            u8
+}
+
+impl TracerouteRequest {
+    /// Create a new TracerouteRequest for the given host with common defaults.
+    pub fn new(host: &str) -> TracerouteRequest {
+        TracerouteRequest{ host: host.to_owned(), timeout_ms: DEFAULT_TRACE_TIMEOUT_MS,
+            data_block_size: DEFAULT_DATA_BLOCK_SIZE, max_hop_count: DEFAULT_MAX_HOP_COUNT, dscp: 0 }
+    }
+}
+
+/// Results of a traceroute test.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TracerouteResult {
+    pub status:           DiagnosticStatus,
+    pub additional_info:  String,
+    /// Response time of the final hop, in milliseconds.
+    pub response_time_ms: u32,
+    /// Hosts along the route, in order.
+    pub hop_hosts:        Vec<String>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiagnosticStatus, NSLookupAnswer, TestState};
+    
+    #[test]
+    fn positive_diagnostic_status() {
+        assert_eq!(DiagnosticStatus::from_value("Success"), DiagnosticStatus::Success);
+        assert_eq!(DiagnosticStatus::from_value("Error_CannotResolveHostName"),
+                   DiagnosticStatus::Error("CannotResolveHostName".to_string()));
+    }
+    
+    #[test]
+    fn positive_test_state() {
+        assert_eq!(TestState::from_value("InProgress"), TestState::InProgress);
+    }
+    
+    #[test]
+    fn positive_nslookup_answers() {
+        let doc = "<bms:NSLookupResult><Result><Status>Success</Status><HostNameReturned>example.com</HostNameReturned>\
+                   <IPAddresses>93.184.216.34,93.184.216.35</IPAddresses><DNSServerIP>192.168.1.1</DNSServerIP>\
+                   <ResponseTime>12</ResponseTime></Result></bms:NSLookupResult>";
+        let answers = NSLookupAnswer::from_value("Result", doc).unwrap();
+        
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0].ip_addresses, vec!["93.184.216.34".to_string(), "93.184.216.35".to_string()]);
+        assert_eq!(answers[0].response_time_ms, 12);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_nslookup_response_time() {
+        let doc = "<Result><Status>Success</Status><ResponseTime>fast</ResponseTime></Result>";
+        
+        NSLookupAnswer::from_value("Result", doc).unwrap();
+    }
+}
@@ -0,0 +1,6 @@
+mod client;
+mod diagnostics;
+
+pub use self::client::{BasicManagementClient};
+pub use self::diagnostics::{DiagnosticStatus, NSLookupAnswer, NSLookupRequest, NSLookupResult, PingRequest,
+    PingResult, TestState, TracerouteRequest, TracerouteResult};
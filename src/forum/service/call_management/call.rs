@@ -0,0 +1,98 @@
+use std::borrow::{ToOwned};
+
+use {SOAPError, SOAPResult};
+use xml;
+
+const CALL_INFO_ELEMENT:    &'static str = "callInfo";
+const CALL_ID_ELEMENT:      &'static str = "callID";
+const CALL_STATUS_ELEMENT:  &'static str = "callStatus";
+const REMOTE_PARTY_ELEMENT: &'static str = "remoteParty";
+
+const IDLE_VALUE:         &'static str = "Idle";
+const DIALING_VALUE:      &'static str = "Dialing";
+const RINGING_VALUE:      &'static str = "Ringing";
+const TALKING_VALUE:      &'static str = "Talking";
+const ON_HOLD_VALUE:      &'static str = "OnHold";
+const DISCONNECTED_VALUE: &'static str = "Disconnected";
+
+/// Progress of a call.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CallStatus {
+    Idle,
+    /// Outgoing call that has not been answered yet.
+    Dialing,
+    /// Incoming call that has not been answered yet.
+    Ringing,
+    Talking,
+    OnHold,
+    Disconnected,
+    /// Vendor defined call status.
+    Other(String)
+}
+
+impl CallStatus {
+    /// Convert the text value of a call status to a CallStatus.
+    pub fn from_value(value: &str) -> CallStatus {
+        match value.trim() {
+            IDLE_VALUE         => CallStatus::Idle,
+            DIALING_VALUE      => CallStatus::Dialing,
+            RINGING_VALUE      => CallStatus::Ringing,
+            TALKING_VALUE      => CallStatus::Talking,
+            ON_HOLD_VALUE      => CallStatus::OnHold,
+            DISCONNECTED_VALUE => CallStatus::Disconnected,
+            n => CallStatus::Other(n.to_owned())
+        }
+    }
+}
+
+/// Information about a call on a telephony server.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CallInfo {
+    pub call_id:      String,
+    pub status:       CallStatus,
+    /// Identifier of the other party of the call, such as a phone number.
+    pub remote_party: String
+}
+
+impl CallInfo {
+    /// Parse the calls out of a document containing callInfo elements.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Vec<CallInfo>> {
+        xml::Elements::new(doc, CALL_INFO_ELEMENT).map(|call| {
+            let call_id = match xml::element(call, CALL_ID_ELEMENT) {
+                Some(n) => xml::unescape(n.trim()),
+                None    => return Err(SOAPError::InvalidArgument(name, "Call Info Has No Call Id"))
+            };
+            let status = xml::element(call, CALL_STATUS_ELEMENT).map(|n| CallStatus::from_value(&xml::unescape(n)[..]));
+            let remote_party = xml::element(call, REMOTE_PARTY_ELEMENT).map(|n| xml::unescape(n.trim()));
+            
+            Ok(CallInfo{ call_id: call_id, status: status.unwrap_or(CallStatus::Idle),
+                remote_party: remote_party.unwrap_or(String::new()) })
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CallInfo, CallStatus};
+    
+    #[test]
+    fn positive_call_info() {
+        let doc = "<cm:callInfoList><callInfo><callID>12</callID><callStatus>Ringing</callStatus>\
+                   <remoteParty>tel:+15551234</remoteParty></callInfo></cm:callInfoList>";
+        let calls = CallInfo::from_value("CallInfo", doc).unwrap();
+        
+        assert_eq!(calls, vec![CallInfo{ call_id: "12".to_string(), status: CallStatus::Ringing,
+            remote_party: "tel:+15551234".to_string() }]);
+    }
+    
+    #[test]
+    fn positive_vendor_status() {
+        assert_eq!(CallStatus::from_value("Parked"), CallStatus::Other("Parked".to_string()));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_call_id() {
+        CallInfo::from_value("CallInfo", "<callInfo><callStatus>Talking</callStatus></callInfo>").unwrap();
+    }
+}
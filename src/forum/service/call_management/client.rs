@@ -0,0 +1,111 @@
+use {GENAResult, SOAPError, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::call_management::{CallInfo};
+use gena::{PropertySet, Subscription};
+
+const INITIATE_CALL_ACTION: &'static str = "InitiateCall";
+const ACCEPT_CALL_ACTION:   &'static str = "AcceptCall";
+const STOP_CALL_ACTION:     &'static str = "StopCall";
+const GET_CALL_INFO_ACTION: &'static str = "GetCallInfo";
+
+const CALLEE_ID_ARG: &'static str = "CalleeID";
+const CALL_ID_ARG:   &'static str = "CallID";
+const CALL_INFO_ARG: &'static str = "CallInfo";
+
+const CALL_INFO_VARIABLE: &'static str = "CallInfo";
+
+/// Evented state variables of a CallManagement service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CallManagementEvent {
+    /// Calls whose status has changed.
+    CallInfo(Vec<CallInfo>)
+}
+
+/// Client for a CallManagement service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CallManagementClient {
+    service: GenericService
+}
+
+impl CallManagementClient {
+    pub fn new(service: GenericService) -> CallManagementClient {
+        CallManagementClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Place a call to the given callee, such as a phone number or sip uri.
+    ///
+    /// Returns the id of the new call.
+    pub fn initiate_call(&self, callee: &str) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(INITIATE_CALL_ACTION, &[(CALLEE_ID_ARG, callee)]));
+        
+        response.argument(CALL_ID_ARG)
+    }
+    
+    /// Answer the given incoming call.
+    pub fn accept_call(&self, call_id: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(ACCEPT_CALL_ACTION, &[(CALL_ID_ARG, call_id)]));
+        
+        Ok(())
+    }
+    
+    /// End, or reject, the given call.
+    pub fn stop_call(&self, call_id: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(STOP_CALL_ACTION, &[(CALL_ID_ARG, call_id)]));
+        
+        Ok(())
+    }
+    
+    /// Returns information about the given call.
+    pub fn call_info(&self, call_id: &str) -> SOAPResult<CallInfo> {
+        let response = try!(self.service.send_action(GET_CALL_INFO_ACTION, &[(CALL_ID_ARG, call_id)]));
+        let calls = try!(CallInfo::from_value(CALL_INFO_ARG, &try!(response.argument(CALL_INFO_ARG))[..]));
+        
+        calls.into_iter().next().ok_or(SOAPError::InvalidArgument(CALL_INFO_ARG, "Call Info Is Empty"))
+    }
+    
+    /// Subscribe to changes in the status of calls.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(CallManagementEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+///
+/// Evented values hold escaped documents, which are unescaped by the property set.
+fn to_events(property_set: &PropertySet) -> Vec<CallManagementEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            CALL_INFO_VARIABLE => CallInfo::from_value(CALL_INFO_VARIABLE, &value[..]).ok()
+                .map(|n| CallManagementEvent::CallInfo(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use forum::service::call_management::{CallInfo, CallStatus};
+    use super::{CallManagementEvent};
+    
+    #[test]
+    fn positive_call_info_event() {
+        let body = "<e:propertyset><e:property><CallInfo>&lt;callInfo&gt;&lt;callID&gt;3&lt;/callID&gt;\
+                    &lt;callStatus&gt;Talking&lt;/callStatus&gt;&lt;/callInfo&gt;</CallInfo></e:property>\
+                    </e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![CallManagementEvent::CallInfo(vec![CallInfo{
+            call_id: "3".to_string(), status: CallStatus::Talking, remote_party: String::new() }])]);
+    }
+}
@@ -0,0 +1,5 @@
+mod call;
+mod client;
+
+pub use self::call::{CallInfo, CallStatus};
+pub use self::client::{CallManagementClient, CallManagementEvent};
@@ -0,0 +1,66 @@
+//! Actions shared by the MotionImage and StillImage services.
+
+use std::borrow::{ToOwned};
+
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::camera_image::{Resolution};
+use soap::value;
+
+const GET_AVAILABLE_ENCODINGS_ACTION:   &'static str = "GetAvailableEncodings";
+const GET_DEFAULT_ENCODING_ACTION:      &'static str = "GetDefaultEncoding";
+const SET_DEFAULT_ENCODING_ACTION:      &'static str = "SetDefaultEncoding";
+const GET_AVAILABLE_RESOLUTIONS_ACTION: &'static str = "GetAvailableResolutions";
+const GET_DEFAULT_RESOLUTION_ACTION:    &'static str = "GetDefaultResolution";
+const SET_DEFAULT_RESOLUTION_ACTION:    &'static str = "SetDefaultResolution";
+
+const RET_AVAILABLE_ENCODINGS_ARG:   &'static str = "RetAvailableEncodings";
+const RET_AVAILABLE_RESOLUTIONS_ARG: &'static str = "RetAvailableResolutions";
+const RET_ENCODING_ARG:              &'static str = "RetEncoding";
+const RET_RESOLUTION_ARG:            &'static str = "RetResolution";
+
+/// Argument used to request an encoding.
+pub const REQ_ENCODING_ARG:   &'static str = "ReqEncoding";
+/// Argument used to request a resolution.
+pub const REQ_RESOLUTION_ARG: &'static str = "ReqResolution";
+
+pub fn available_encodings(service: &GenericService) -> SOAPResult<Vec<String>> {
+    let response = try!(service.send_action(GET_AVAILABLE_ENCODINGS_ACTION, &[]));
+    let encodings = try!(response.argument(RET_AVAILABLE_ENCODINGS_ARG));
+    
+    Ok(value::to_list(&encodings[..]).into_iter().map(|n| n.to_owned()).collect())
+}
+
+pub fn default_encoding(service: &GenericService) -> SOAPResult<String> {
+    let response = try!(service.send_action(GET_DEFAULT_ENCODING_ACTION, &[]));
+    
+    response.argument(RET_ENCODING_ARG)
+}
+
+pub fn set_default_encoding(service: &GenericService, encoding: &str) -> SOAPResult<()> {
+    try!(service.send_action(SET_DEFAULT_ENCODING_ACTION, &[(REQ_ENCODING_ARG, encoding)]));
+    
+    Ok(())
+}
+
+pub fn available_resolutions(service: &GenericService) -> SOAPResult<Vec<Resolution>> {
+    let response = try!(service.send_action(GET_AVAILABLE_RESOLUTIONS_ACTION, &[]));
+    let resolutions = try!(response.argument(RET_AVAILABLE_RESOLUTIONS_ARG));
+    
+    value::to_list(&resolutions[..]).into_iter().map(|n| {
+        Resolution::from_value(RET_AVAILABLE_RESOLUTIONS_ARG, n)
+    }).collect()
+}
+
+pub fn default_resolution(service: &GenericService) -> SOAPResult<Resolution> {
+    let response = try!(service.send_action(GET_DEFAULT_RESOLUTION_ACTION, &[]));
+    
+    Resolution::from_value(RET_RESOLUTION_ARG, &try!(response.argument(RET_RESOLUTION_ARG))[..])
+}
+
+pub fn set_default_resolution(service: &GenericService, resolution: &Resolution) -> SOAPResult<()> {
+    try!(service.send_action(SET_DEFAULT_RESOLUTION_ACTION,
+                             &[(REQ_RESOLUTION_ARG, &resolution.to_string()[..])]));
+    
+    Ok(())
+}
@@ -0,0 +1,8 @@
+mod common;
+mod motion;
+mod resolution;
+mod still;
+
+pub use self::motion::{MotionImageClient, VideoRequest};
+pub use self::resolution::{Resolution};
+pub use self::still::{ImageRequest, StillImageClient};
@@ -0,0 +1,102 @@
+use url::{Url};
+
+use {SOAPResult};
+use forum::service::{optional, GenericService};
+use forum::service::camera_image::{Resolution};
+use forum::service::camera_image::common;
+use soap::value;
+
+const GET_VIDEO_URL_ACTION:         &'static str = "GetVideoURL";
+const GET_DEFAULT_VIDEO_URL_ACTION: &'static str = "GetDefaultVideoURL";
+
+const REQ_COMPRESSION_ARG:       &'static str = "ReqCompression";
+const REQ_MAX_BANDWIDTH_ARG:     &'static str = "ReqMaxBandwidth";
+const REQ_TARGET_FRAME_RATE_ARG: &'static str = "ReqTargetFrameRate";
+const RET_VIDEO_URL_ARG:         &'static str = "RetVideoURL";
+
+/// Parameters of a video stream to request from a camera.
+///
+/// Values left as None are sent empty, which leaves the choice of setting
+/// up to the camera.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct VideoRequest {
+    /// Encoding of the stream, as returned by available_encodings.
+    pub encoding:          Option<String>,
+    pub compression:       Option<String>,
+    pub resolution:        Option<Resolution>,
+    /// Maximum bandwidth of the stream, in bits per second.
+    pub max_bandwidth:     Option<u32>,
+    /// Frame rate of the stream, in frames per second.
+    pub target_frame_rate: Option<u32>
+}
+
+/// Client for a DigitalSecurityCameraMotionImage service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MotionImageClient {
+    service: GenericService
+}
+
+impl MotionImageClient {
+    pub fn new(service: GenericService) -> MotionImageClient {
+        MotionImageClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the encodings, such as MPEG4, that video can be streamed in.
+    pub fn available_encodings(&self) -> SOAPResult<Vec<String>> {
+        common::available_encodings(&self.service)
+    }
+    
+    /// Returns the encoding used for the default video stream.
+    pub fn default_encoding(&self) -> SOAPResult<String> {
+        common::default_encoding(&self.service)
+    }
+    
+    /// Set the encoding used for the default video stream.
+    pub fn set_default_encoding(&self, encoding: &str) -> SOAPResult<()> {
+        common::set_default_encoding(&self.service, encoding)
+    }
+    
+    /// Returns the resolutions that video can be streamed at.
+    pub fn available_resolutions(&self) -> SOAPResult<Vec<Resolution>> {
+        common::available_resolutions(&self.service)
+    }
+    
+    /// Returns the resolution used for the default video stream.
+    pub fn default_resolution(&self) -> SOAPResult<Resolution> {
+        common::default_resolution(&self.service)
+    }
+    
+    /// Set the resolution used for the default video stream.
+    pub fn set_default_resolution(&self, resolution: &Resolution) -> SOAPResult<()> {
+        common::set_default_resolution(&self.service, resolution)
+    }
+    
+    /// Returns the url of the default video stream.
+    pub fn default_video_url(&self) -> SOAPResult<Url> {
+        let response = try!(self.service.send_action(GET_DEFAULT_VIDEO_URL_ACTION, &[]));
+        
+        value::to_url(RET_VIDEO_URL_ARG, &try!(response.argument(RET_VIDEO_URL_ARG))[..])
+    }
+    
+    /// Returns the url of a video stream matching the given request.
+    pub fn video_url(&self, request: &VideoRequest) -> SOAPResult<Url> {
+        let resolution = request.resolution.map_or(String::new(), |n| n.to_string());
+        let bandwidth = request.max_bandwidth.map_or(String::new(), |n| n.to_string());
+        let frame_rate = request.target_frame_rate.map_or(String::new(), |n| n.to_string());
+        
+        let response = try!(self.service.send_action(GET_VIDEO_URL_ACTION, &[
+            (common::REQ_ENCODING_ARG,   optional(&request.encoding)),
+            (REQ_COMPRESSION_ARG,        optional(&request.compression)),
+            (common::REQ_RESOLUTION_ARG, &resolution[..]),
+            (REQ_MAX_BANDWIDTH_ARG,      &bandwidth[..]),
+            (REQ_TARGET_FRAME_RATE_ARG,  &frame_rate[..])
+        ]));
+        
+        value::to_url(RET_VIDEO_URL_ARG, &try!(response.argument(RET_VIDEO_URL_ARG))[..])
+    }
+}
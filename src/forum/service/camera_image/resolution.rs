@@ -0,0 +1,61 @@
+use std::fmt::{Display, Formatter, Result};
+
+use {SOAPError, SOAPResult};
+
+const RESOLUTION_SEP: char = 'x';
+
+/// Dimensions of an image, in pixels.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Resolution {
+    pub width:  u32,
+    pub height: u32
+}
+
+impl Resolution {
+    pub fn new(width: u32, height: u32) -> Resolution {
+        Resolution{ width: width, height: height }
+    }
+    
+    /// Convert a resolution value, such as 640x480, to a Resolution.
+    pub fn from_value(name: &'static str, value: &str) -> SOAPResult<Resolution> {
+        let mut dimensions = value.trim().splitn(2, |c: char| c == RESOLUTION_SEP || c == 'X');
+        
+        let width = dimensions.next().and_then(|n| n.trim().parse::<u32>().ok());
+        let height = dimensions.next().and_then(|n| n.trim().parse::<u32>().ok());
+        
+        match (width, height) {
+            (Some(w), Some(h)) => Ok(Resolution::new(w, h)),
+            _ => Err(SOAPError::InvalidArgument(name, "Value Is Not A Valid Resolution"))
+        }
+    }
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}{}{}", self.width, RESOLUTION_SEP, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Resolution};
+    
+    #[test]
+    fn positive_resolution_round_trip() {
+        let resolution = Resolution::from_value("Res", "640x480").unwrap();
+        
+        assert_eq!(resolution, Resolution::new(640, 480));
+        assert_eq!(resolution.to_string(), "640x480");
+    }
+    
+    #[test]
+    fn positive_upper_case_separator() {
+        assert_eq!(Resolution::from_value("Res", " 1280X720 ").unwrap(), Resolution::new(1280, 720));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_height() {
+        Resolution::from_value("Res", "640").unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+use url::{Url};
+
+use {SOAPResult};
+use forum::service::{optional, GenericService};
+use forum::service::camera_image::{Resolution};
+use forum::service::camera_image::common;
+use soap::value;
+
+const GET_IMAGE_URL_ACTION:         &'static str = "GetImageURL";
+const GET_DEFAULT_IMAGE_URL_ACTION: &'static str = "GetDefaultImageURL";
+
+const REQ_COMPRESSION_ARG: &'static str = "ReqCompression";
+const RET_IMAGE_URL_ARG:   &'static str = "RetImageURL";
+
+/// Parameters of a snapshot to request from a camera.
+///
+/// Values left as None are sent empty, which leaves the choice of setting
+/// up to the camera.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ImageRequest {
+    /// Encoding of the image, as returned by available_encodings.
+    pub encoding:    Option<String>,
+    pub compression: Option<String>,
+    pub resolution:  Option<Resolution>
+}
+
+/// Client for a DigitalSecurityCameraStillImage service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct StillImageClient {
+    service: GenericService
+}
+
+impl StillImageClient {
+    pub fn new(service: GenericService) -> StillImageClient {
+        StillImageClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the encodings, such as JPEG, that snapshots can be taken in.
+    pub fn available_encodings(&self) -> SOAPResult<Vec<String>> {
+        common::available_encodings(&self.service)
+    }
+    
+    /// Returns the encoding used for default snapshots.
+    pub fn default_encoding(&self) -> SOAPResult<String> {
+        common::default_encoding(&self.service)
+    }
+    
+    /// Set the encoding used for default snapshots.
+    pub fn set_default_encoding(&self, encoding: &str) -> SOAPResult<()> {
+        common::set_default_encoding(&self.service, encoding)
+    }
+    
+    /// Returns the resolutions that snapshots can be taken at.
+    pub fn available_resolutions(&self) -> SOAPResult<Vec<Resolution>> {
+        common::available_resolutions(&self.service)
+    }
+    
+    /// Returns the resolution used for default snapshots.
+    pub fn default_resolution(&self) -> SOAPResult<Resolution> {
+        common::default_resolution(&self.service)
+    }
+    
+    /// Set the resolution used for default snapshots.
+    pub fn set_default_resolution(&self, resolution: &Resolution) -> SOAPResult<()> {
+        common::set_default_resolution(&self.service, resolution)
+    }
+    
+    /// Returns the url of a default snapshot.
+    pub fn default_image_url(&self) -> SOAPResult<Url> {
+        let response = try!(self.service.send_action(GET_DEFAULT_IMAGE_URL_ACTION, &[]));
+        
+        value::to_url(RET_IMAGE_URL_ARG, &try!(response.argument(RET_IMAGE_URL_ARG))[..])
+    }
+    
+    /// Returns the url of a snapshot matching the given request.
+    pub fn image_url(&self, request: &ImageRequest) -> SOAPResult<Url> {
+        let resolution = request.resolution.map_or(String::new(), |n| n.to_string());
+        
+        let response = try!(self.service.send_action(GET_IMAGE_URL_ACTION, &[
+            (common::REQ_ENCODING_ARG,   optional(&request.encoding)),
+            (REQ_COMPRESSION_ARG,        optional(&request.compression)),
+            (common::REQ_RESOLUTION_ARG, &resolution[..])
+        ]));
+        
+        value::to_url(RET_IMAGE_URL_ARG, &try!(response.argument(RET_IMAGE_URL_ARG))[..])
+    }
+}
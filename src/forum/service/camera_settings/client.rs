@@ -0,0 +1,242 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+/// Minimum brightness, contrast or color saturation level.
+pub const PICTURE_LEVEL_MIN: u8 = 0;
+
+/// Maximum brightness, contrast or color saturation level.
+pub const PICTURE_LEVEL_MAX: u8 = 100;
+
+const GET_AUTOMATIC_WHITE_BALANCE_ACTION: &'static str = "GetAutomaticWhiteBalance";
+const SET_AUTOMATIC_WHITE_BALANCE_ACTION: &'static str = "SetAutomaticWhiteBalance";
+const GET_FIXED_WHITE_BALANCE_ACTION:     &'static str = "GetFixedWhiteBalance";
+const SET_FIXED_WHITE_BALANCE_ACTION:     &'static str = "SetFixedWhiteBalance";
+const GET_AVAILABLE_ROTATIONS_ACTION:     &'static str = "GetAvailableRotations";
+const GET_DEFAULT_ROTATION_ACTION:        &'static str = "GetDefaultRotation";
+const SET_DEFAULT_ROTATION_ACTION:        &'static str = "SetDefaultRotation";
+const GET_BRIGHTNESS_ACTION:              &'static str = "GetBrightness";
+const SET_BRIGHTNESS_ACTION:              &'static str = "SetBrightness";
+const GET_CONTRAST_ACTION:                &'static str = "GetContrast";
+const SET_CONTRAST_ACTION:                &'static str = "SetContrast";
+const GET_COLOR_SATURATION_ACTION:        &'static str = "GetColorSaturation";
+const SET_COLOR_SATURATION_ACTION:        &'static str = "SetColorSaturation";
+
+const NEW_AUTOMATIC_WHITE_BALANCE_ARG: &'static str = "NewAutomaticWhiteBalance";
+const RET_AUTOMATIC_WHITE_BALANCE_ARG: &'static str = "RetAutomaticWhiteBalance";
+const NEW_FIXED_WHITE_BALANCE_ARG:     &'static str = "NewFixedWhiteBalance";
+const RET_FIXED_WHITE_BALANCE_ARG:     &'static str = "RetFixedWhiteBalance";
+const RET_AVAILABLE_ROTATIONS_ARG:     &'static str = "RetAvailableRotations";
+const NEW_ROTATION_ARG:                &'static str = "NewRotation";
+const RET_ROTATION_ARG:                &'static str = "RetRotation";
+const NEW_BRIGHTNESS_ARG:              &'static str = "NewBrightness";
+const RET_BRIGHTNESS_ARG:              &'static str = "RetBrightness";
+const NEW_CONTRAST_ARG:                &'static str = "NewContrast";
+const RET_CONTRAST_ARG:                &'static str = "RetContrast";
+const NEW_COLOR_SATURATION_ARG:        &'static str = "NewColorSaturation";
+const RET_COLOR_SATURATION_ARG:        &'static str = "RetColorSaturation";
+
+const AUTOMATIC_WHITE_BALANCE_VARIABLE: &'static str = "AutomaticWhiteBalance";
+const FIXED_WHITE_BALANCE_VARIABLE:     &'static str = "FixedWhiteBalance";
+const DEFAULT_ROTATION_VARIABLE:        &'static str = "DefaultRotation";
+const BRIGHTNESS_VARIABLE:              &'static str = "Brightness";
+const CONTRAST_VARIABLE:                &'static str = "Contrast";
+const COLOR_SATURATION_VARIABLE:        &'static str = "ColorSaturation";
+
+/// Evented state variables of a DigitalSecurityCameraSettings service.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum CameraSettingsEvent {
+    AutomaticWhiteBalance(bool),
+    /// Color temperature, in Kelvin, used when automatic white balance is off.
+    FixedWhiteBalance(u32),
+    /// Rotation of the picture, in degrees.
+    DefaultRotation(u16),
+    Brightness(u8),
+    Contrast(u8),
+    ColorSaturation(u8)
+}
+
+/// Client for a DigitalSecurityCameraSettings service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CameraSettingsClient {
+    service: GenericService
+}
+
+impl CameraSettingsClient {
+    pub fn new(service: GenericService) -> CameraSettingsClient {
+        CameraSettingsClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns whether or not the camera adjusts white balance automatically.
+    pub fn automatic_white_balance(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_AUTOMATIC_WHITE_BALANCE_ACTION, &[]));
+        
+        value::to_bool(RET_AUTOMATIC_WHITE_BALANCE_ARG, &try!(response.argument(RET_AUTOMATIC_WHITE_BALANCE_ARG))[..])
+    }
+    
+    /// Set whether or not the camera adjusts white balance automatically.
+    pub fn set_automatic_white_balance(&self, automatic: bool) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_AUTOMATIC_WHITE_BALANCE_ACTION,
+                                      &[(NEW_AUTOMATIC_WHITE_BALANCE_ARG, value::from_bool(automatic))]));
+        
+        Ok(())
+    }
+    
+    /// Returns the color temperature, in Kelvin, used when automatic white
+    /// balance is off.
+    pub fn fixed_white_balance(&self) -> SOAPResult<u32> {
+        let response = try!(self.service.send_action(GET_FIXED_WHITE_BALANCE_ACTION, &[]));
+        
+        value::to_number(RET_FIXED_WHITE_BALANCE_ARG, &try!(response.argument(RET_FIXED_WHITE_BALANCE_ARG))[..])
+    }
+    
+    /// Set the color temperature, in Kelvin, used when automatic white balance
+    /// is off.
+    pub fn set_fixed_white_balance(&self, kelvin: u32) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_FIXED_WHITE_BALANCE_ACTION,
+                                      &[(NEW_FIXED_WHITE_BALANCE_ARG, &kelvin.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the rotations, in degrees, that the camera supports.
+    pub fn available_rotations(&self) -> SOAPResult<Vec<u16>> {
+        let response = try!(self.service.send_action(GET_AVAILABLE_ROTATIONS_ACTION, &[]));
+        let rotations = try!(response.argument(RET_AVAILABLE_ROTATIONS_ARG));
+        
+        value::to_list(&rotations[..]).into_iter().map(|n| {
+            value::to_number(RET_AVAILABLE_ROTATIONS_ARG, n)
+        }).collect()
+    }
+    
+    /// Returns the rotation, in degrees, applied to the picture.
+    pub fn default_rotation(&self) -> SOAPResult<u16> {
+        let response = try!(self.service.send_action(GET_DEFAULT_ROTATION_ACTION, &[]));
+        
+        value::to_number(RET_ROTATION_ARG, &try!(response.argument(RET_ROTATION_ARG))[..])
+    }
+    
+    /// Set the rotation, in degrees, applied to the picture.
+    pub fn set_default_rotation(&self, degrees: u16) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_DEFAULT_ROTATION_ACTION, &[(NEW_ROTATION_ARG, &degrees.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the brightness of the picture.
+    pub fn brightness(&self) -> SOAPResult<u8> {
+        self.get_level(GET_BRIGHTNESS_ACTION, RET_BRIGHTNESS_ARG)
+    }
+    
+    /// Set the brightness of the picture.
+    pub fn set_brightness(&self, level: u8) -> SOAPResult<()> {
+        self.set_level(SET_BRIGHTNESS_ACTION, NEW_BRIGHTNESS_ARG, level)
+    }
+    
+    /// Returns the contrast of the picture.
+    pub fn contrast(&self) -> SOAPResult<u8> {
+        self.get_level(GET_CONTRAST_ACTION, RET_CONTRAST_ARG)
+    }
+    
+    /// Set the contrast of the picture.
+    pub fn set_contrast(&self, level: u8) -> SOAPResult<()> {
+        self.set_level(SET_CONTRAST_ACTION, NEW_CONTRAST_ARG, level)
+    }
+    
+    /// Returns the color saturation of the picture.
+    pub fn color_saturation(&self) -> SOAPResult<u8> {
+        self.get_level(GET_COLOR_SATURATION_ACTION, RET_COLOR_SATURATION_ARG)
+    }
+    
+    /// Set the color saturation of the picture.
+    pub fn set_color_saturation(&self, level: u8) -> SOAPResult<()> {
+        self.set_level(SET_COLOR_SATURATION_ACTION, NEW_COLOR_SATURATION_ARG, level)
+    }
+    
+    /// Subscribe to changes in the settings of the camera.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(CameraSettingsEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+    
+    fn get_level(&self, action: &str, arg: &'static str) -> SOAPResult<u8> {
+        let response = try!(self.service.send_action(action, &[]));
+        
+        value::to_ranged(arg, &try!(response.argument(arg))[..], PICTURE_LEVEL_MIN, PICTURE_LEVEL_MAX)
+    }
+    
+    fn set_level(&self, action: &str, arg: &'static str, level: u8) -> SOAPResult<()> {
+        let level_value = level.to_string();
+        try!(value::to_ranged(arg, &level_value[..], PICTURE_LEVEL_MIN, PICTURE_LEVEL_MAX));
+        
+        try!(self.service.send_action(action, &[(arg, &level_value[..])]));
+        
+        Ok(())
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<CameraSettingsEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        let name = &name[..];
+        let value = &value[..];
+        
+        match name {
+            AUTOMATIC_WHITE_BALANCE_VARIABLE => value::to_bool(AUTOMATIC_WHITE_BALANCE_VARIABLE, value).ok()
+                .map(|n| CameraSettingsEvent::AutomaticWhiteBalance(n)),
+            FIXED_WHITE_BALANCE_VARIABLE => value::to_number(FIXED_WHITE_BALANCE_VARIABLE, value).ok()
+                .map(|n| CameraSettingsEvent::FixedWhiteBalance(n)),
+            DEFAULT_ROTATION_VARIABLE => value::to_number(DEFAULT_ROTATION_VARIABLE, value).ok()
+                .map(|n| CameraSettingsEvent::DefaultRotation(n)),
+            BRIGHTNESS_VARIABLE => to_level(BRIGHTNESS_VARIABLE, value).map(|n| CameraSettingsEvent::Brightness(n)),
+            CONTRAST_VARIABLE => to_level(CONTRAST_VARIABLE, value).map(|n| CameraSettingsEvent::Contrast(n)),
+            COLOR_SATURATION_VARIABLE => to_level(COLOR_SATURATION_VARIABLE, value)
+                .map(|n| CameraSettingsEvent::ColorSaturation(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+fn to_level(name: &'static str, value: &str) -> Option<u8> {
+    value::to_ranged(name, value, PICTURE_LEVEL_MIN, PICTURE_LEVEL_MAX).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{CameraSettingsEvent};
+    
+    #[test]
+    fn positive_settings_events() {
+        let body = "<e:propertyset><e:property><AutomaticWhiteBalance>0</AutomaticWhiteBalance></e:property>\
+                    <e:property><DefaultRotation>180</DefaultRotation></e:property>\
+                    <e:property><Brightness>40</Brightness></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![
+            CameraSettingsEvent::AutomaticWhiteBalance(false),
+            CameraSettingsEvent::DefaultRotation(180),
+            CameraSettingsEvent::Brightness(40)
+        ]);
+    }
+    
+    #[test]
+    fn positive_ignore_out_of_range_level() {
+        let body = "<e:propertyset><e:property><Contrast>250</Contrast></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert!(super::to_events(&property_set).is_empty());
+    }
+}
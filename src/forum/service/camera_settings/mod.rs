@@ -0,0 +1,3 @@
+mod client;
+
+pub use self::client::{CameraSettingsClient, CameraSettingsEvent, PICTURE_LEVEL_MIN, PICTURE_LEVEL_MAX};
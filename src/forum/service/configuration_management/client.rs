@@ -0,0 +1,70 @@
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::configuration_management::{ParameterValue};
+use forum::service::configuration_management::parameter;
+
+/// Search depth that returns every node beneath the starting node.
+pub const UNLIMITED_SEARCH_DEPTH: u32 = 0;
+
+const GET_VALUES_ACTION:               &'static str = "GetValues";
+const SET_VALUES_ACTION:               &'static str = "SetValues";
+const GET_SUPPORTED_PARAMETERS_ACTION: &'static str = "GetSupportedParameters";
+
+const PARAMETERS_ARG:           &'static str = "Parameters";
+const PARAMETER_VALUE_LIST_ARG: &'static str = "ParameterValueList";
+const STATUS_ARG:               &'static str = "Status";
+const STARTING_NODE_ARG:        &'static str = "StartingNode";
+const SEARCH_DEPTH_ARG:         &'static str = "SearchDepth";
+const RESULT_ARG:               &'static str = "Result";
+
+const CHANGES_APPLIED_VALUE: &'static str = "ChangesApplied";
+
+/// Client for a ConfigurationManagement service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ConfigurationManagementClient {
+    service: GenericService
+}
+
+impl ConfigurationManagementClient {
+    pub fn new(service: GenericService) -> ConfigurationManagementClient {
+        ConfigurationManagementClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the values of the parameters at the given paths.
+    ///
+    /// Paths ending in a slash are partial paths and return the values of every
+    /// parameter beneath them.
+    pub fn get_values(&self, paths: &[&str]) -> SOAPResult<Vec<ParameterValue>> {
+        let paths = parameter::format_paths(paths);
+        let response = try!(self.service.send_action(GET_VALUES_ACTION, &[(PARAMETERS_ARG, &paths[..])]));
+        
+        parameter::parse_values(PARAMETER_VALUE_LIST_ARG, &try!(response.argument(PARAMETER_VALUE_LIST_ARG))[..])
+    }
+    
+    /// Set the values of the given parameters.
+    ///
+    /// Returns true if the changes took effect immediately, or false if the
+    /// device will apply them later, such as after a reboot.
+    pub fn set_values(&self, values: &[ParameterValue]) -> SOAPResult<bool> {
+        let values = parameter::format_values(values);
+        let response = try!(self.service.send_action(SET_VALUES_ACTION, &[(PARAMETER_VALUE_LIST_ARG, &values[..])]));
+        
+        Ok(try!(response.argument(STATUS_ARG)).trim() == CHANGES_APPLIED_VALUE)
+    }
+    
+    /// Returns the paths of the nodes that the device supports beneath the
+    /// given starting node, up to depth levels down.
+    pub fn supported_parameters(&self, starting_node: &str, depth: u32) -> SOAPResult<Vec<String>> {
+        let response = try!(self.service.send_action(GET_SUPPORTED_PARAMETERS_ACTION, &[
+            (STARTING_NODE_ARG, starting_node),
+            (SEARCH_DEPTH_ARG,  &depth.to_string()[..])
+        ]));
+        
+        parameter::parse_paths(RESULT_ARG, &try!(response.argument(RESULT_ARG))[..])
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod parameter;
+
+pub use self::client::{ConfigurationManagementClient, UNLIMITED_SEARCH_DEPTH};
+pub use self::parameter::{ParameterValue};
@@ -0,0 +1,122 @@
+use {SOAPError, SOAPResult};
+use xml;
+
+const CMS_NAMESPACE: &'static str = "urn:schemas-upnp-org:dm:cms";
+
+const CONTENT_PATH_LIST_ELEMENT:    &'static str = "ContentPathList";
+const CONTENT_PATH_ELEMENT:         &'static str = "ContentPath";
+const PARAMETER_VALUE_LIST_ELEMENT: &'static str = "ParameterValueList";
+const PARAMETER_ELEMENT:            &'static str = "Parameter";
+const PARAMETER_PATH_ELEMENT:       &'static str = "ParameterPath";
+const VALUE_ELEMENT:                &'static str = "Value";
+const STRUCTURE_PATH_LIST_ELEMENT:  &'static str = "StructurePathList";
+const STRUCTURE_PATH_ELEMENT:       &'static str = "StructurePath";
+
+/// Value of a parameter within the data model of a device.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ParameterValue {
+    /// Full path of the parameter, such as /UPnP/DeviceInfo/FriendlyName.
+    pub path:  String,
+    pub value: String
+}
+
+/// Format the given paths as a ContentPathList document.
+pub fn format_paths(paths: &[&str]) -> String {
+    format_list(CONTENT_PATH_LIST_ELEMENT, paths.iter().map(|n| {
+        format!("<{0}>{1}</{0}>", CONTENT_PATH_ELEMENT, xml::escape(n))
+    }))
+}
+
+/// Format the given values as a ParameterValueList document.
+pub fn format_values(values: &[ParameterValue]) -> String {
+    format_list(PARAMETER_VALUE_LIST_ELEMENT, values.iter().map(|n| {
+        format!("<{0}><{1}>{2}</{1}><{3}>{4}</{3}></{0}>", PARAMETER_ELEMENT,
+                PARAMETER_PATH_ELEMENT, xml::escape(&n.path[..]), VALUE_ELEMENT, xml::escape(&n.value[..]))
+    }))
+}
+
+/// Parse the paths out of a StructurePathList document.
+pub fn parse_paths(name: &'static str, doc: &str) -> SOAPResult<Vec<String>> {
+    match xml::element(doc, STRUCTURE_PATH_LIST_ELEMENT) {
+        Some(n) => Ok(xml::Elements::new(n, STRUCTURE_PATH_ELEMENT).map(|n| xml::unescape(n.trim())).collect()),
+        None    => Err(SOAPError::InvalidArgument(name, "Value Is Not A StructurePathList Document"))
+    }
+}
+
+/// Parse the values out of a ParameterValueList document.
+pub fn parse_values(name: &'static str, doc: &str) -> SOAPResult<Vec<ParameterValue>> {
+    let values = match xml::element(doc, PARAMETER_VALUE_LIST_ELEMENT) {
+        Some(n) => n,
+        None    => return Err(SOAPError::InvalidArgument(name, "Value Is Not A ParameterValueList Document"))
+    };
+    
+    xml::Elements::new(values, PARAMETER_ELEMENT).map(|parameter| {
+        match (xml::element(parameter, PARAMETER_PATH_ELEMENT), xml::element(parameter, VALUE_ELEMENT)) {
+            (Some(path), Some(value)) => Ok(ParameterValue{ path: xml::unescape(path.trim()),
+                value: xml::unescape(value) }),
+            _ => Err(SOAPError::InvalidArgument(name, "Parameter Is Missing A Path Or Value"))
+        }
+    }).collect()
+}
+
+/// Wrap the given entries in a cms list element.
+fn format_list<I>(element: &str, entries: I) -> String where I: Iterator<Item=String> {
+    let mut doc = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><cms:{} xmlns:cms=\"{}\">",
+                          element, CMS_NAMESPACE);
+    
+    for entry in entries {
+        doc.push_str(&entry[..]);
+    }
+    doc.push_str(&format!("</cms:{}>", element)[..]);
+    
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParameterValue};
+    
+    #[test]
+    fn positive_format_paths() {
+        let doc = super::format_paths(&["/UPnP/SensorMgt/"]);
+        
+        assert!(doc.contains("<cms:ContentPathList xmlns:cms=\"urn:schemas-upnp-org:dm:cms\">\
+                              <ContentPath>/UPnP/SensorMgt/</ContentPath></cms:ContentPathList>"));
+    }
+    
+    #[test]
+    fn positive_parse_values() {
+        let doc = "<cms:ParameterValueList xmlns:cms=\"urn:schemas-upnp-org:dm:cms\">\
+                   <Parameter><ParameterPath>/UPnP/DeviceInfo/FriendlyName</ParameterPath>\
+                   <Value>Hall &amp; Stairs</Value></Parameter></cms:ParameterValueList>";
+        
+        assert_eq!(super::parse_values("Values", doc).unwrap(), vec![ParameterValue{
+            path: "/UPnP/DeviceInfo/FriendlyName".to_string(), value: "Hall & Stairs".to_string() }]);
+    }
+    
+    #[test]
+    fn positive_values_round_trip() {
+        let values = vec![ParameterValue{ path: "/UPnP/DeviceInfo/FriendlyName".to_string(),
+            value: "<Lab>".to_string() }];
+        
+        assert_eq!(super::parse_values("Values", &super::format_values(&values)[..]).unwrap(), values);
+    }
+    
+    #[test]
+    fn positive_parse_paths() {
+        let doc = "<cms:StructurePathList xmlns:cms=\"urn:schemas-upnp-org:dm:cms\">\
+                   <StructurePath>/UPnP/DeviceInfo/</StructurePath>\
+                   <StructurePath>/UPnP/DeviceInfo/FriendlyName</StructurePath></cms:StructurePathList>";
+        
+        assert_eq!(super::parse_paths("Paths", doc).unwrap(),
+                   vec!["/UPnP/DeviceInfo/".to_string(), "/UPnP/DeviceInfo/FriendlyName".to_string()]);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_parameter_without_value() {
+        let doc = "<ParameterValueList><Parameter><ParameterPath>/a</ParameterPath></Parameter></ParameterValueList>";
+        
+        super::parse_values("Values", doc).unwrap();
+    }
+}
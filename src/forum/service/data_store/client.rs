@@ -0,0 +1,55 @@
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::data_store::{self, DataRecord, DataStoreInfo, DataTable};
+
+const GET_DATA_STORE_INFO_ACTION: &'static str = "GetDataStoreInfo";
+const GET_TABLE_RECORDS_ACTION:   &'static str = "GetTableRecords";
+
+const DATA_STORE_INFO_ARG:    &'static str = "DataStoreInfo";
+const DATA_TABLE_ID_ARG:      &'static str = "DataTableID";
+const DATA_RECORD_FILTER_ARG: &'static str = "DataRecordFilter";
+const DATA_RECORD_START_ARG:  &'static str = "DataRecordStart";
+const DATA_RECORD_COUNT_ARG:  &'static str = "DataRecordCount";
+const DATA_RECORDS_ARG:       &'static str = "DataRecords";
+
+/// Client for a DataStore service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataStoreClient {
+    service: GenericService
+}
+
+impl DataStoreClient {
+    pub fn new(service: GenericService) -> DataStoreClient {
+        DataStoreClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns a description of the tables held by the data store.
+    pub fn data_store_info(&self) -> SOAPResult<DataStoreInfo> {
+        let response = try!(self.service.send_action(GET_DATA_STORE_INFO_ACTION, &[]));
+        
+        DataStoreInfo::from_value(DATA_STORE_INFO_ARG, &try!(response.argument(DATA_STORE_INFO_ARG))[..])
+    }
+    
+    /// Returns the tables held by the data store.
+    pub fn tables(&self) -> SOAPResult<Vec<DataTable>> {
+        self.data_store_info().map(|n| n.tables)
+    }
+    
+    /// Returns up to count records of the given table, beginning with the
+    /// record at index start.
+    pub fn table_records(&self, table_id: &str, start: u32, count: u32) -> SOAPResult<Vec<DataRecord>> {
+        let response = try!(self.service.send_action(GET_TABLE_RECORDS_ACTION, &[
+            (DATA_TABLE_ID_ARG,      table_id),
+            (DATA_RECORD_FILTER_ARG, ""),
+            (DATA_RECORD_START_ARG,  &start.to_string()[..]),
+            (DATA_RECORD_COUNT_ARG,  &count.to_string()[..])
+        ]));
+        
+        data_store::parse_records(DATA_RECORDS_ARG, &try!(response.argument(DATA_RECORDS_ARG))[..])
+    }
+}
@@ -0,0 +1,89 @@
+use {SOAPError, SOAPResult};
+use xml;
+
+const DATA_STORE_INFO_ELEMENT: &'static str = "DataStoreInfo";
+const DATA_TABLE_ELEMENT:      &'static str = "datatable";
+const FIELD_ELEMENT:           &'static str = "field";
+
+const ID_ATTRIBUTE:   &'static str = "id";
+const NAME_ATTRIBUTE: &'static str = "name";
+const TYPE_ATTRIBUTE: &'static str = "type";
+
+/// Field that records within a data table may contain.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataTableField {
+    pub name:       String,
+    /// Data type of the field, such as xsd:float, if one was given.
+    pub field_type: Option<String>
+}
+
+/// Table of records within a data store.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataTable {
+    /// Identifier used to refer to the table in actions.
+    pub id:     String,
+    pub name:   String,
+    pub fields: Vec<DataTableField>
+}
+
+/// Description of the tables held by a data store.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataStoreInfo {
+    pub tables: Vec<DataTable>
+}
+
+impl DataStoreInfo {
+    /// Parse a DataStoreInfo document.
+    ///
+    /// Tables are expected as datatable elements carrying id and name
+    /// attributes, each listing their fields as field elements.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<DataStoreInfo> {
+        let info = match xml::element(doc, DATA_STORE_INFO_ELEMENT) {
+            Some(n) => n,
+            None    => return Err(SOAPError::InvalidArgument(name, "Value Is Not A DataStoreInfo Document"))
+        };
+        
+        let mut tables = Vec::new();
+        for table in xml::Nodes::new(info, DATA_TABLE_ELEMENT) {
+            let id = match table.attribute(ID_ATTRIBUTE) {
+                Some(n) => n,
+                None    => return Err(SOAPError::InvalidArgument(name, "Data Table Has No Id"))
+            };
+            let fields = xml::Nodes::new(table.content(), FIELD_ELEMENT).filter_map(|field| {
+                field.attribute(NAME_ATTRIBUTE).map(|n| {
+                    DataTableField{ name: n, field_type: field.attribute(TYPE_ATTRIBUTE) }
+                })
+            }).collect();
+            
+            tables.push(DataTable{ name: table.attribute(NAME_ATTRIBUTE).unwrap_or(id.clone()),
+                id: id, fields: fields });
+        }
+        
+        Ok(DataStoreInfo{ tables: tables })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataStoreInfo};
+    
+    #[test]
+    fn positive_data_store_info() {
+        let doc = "<DataStoreInfo><datatable id=\"1\" name=\"Readings\">\
+                   <field name=\"Temp\" type=\"xsd:float\"/><field name=\"Time\"/></datatable>\
+                   <datatable id=\"2\"></datatable></DataStoreInfo>";
+        let info = DataStoreInfo::from_value("Info", doc).unwrap();
+        
+        assert_eq!(info.tables.len(), 2);
+        assert_eq!(info.tables[0].name, "Readings");
+        assert_eq!(info.tables[0].fields[0].field_type, Some("xsd:float".to_string()));
+        assert_eq!(info.tables[0].fields[1].field_type, None);
+        assert_eq!(info.tables[1].name, "2");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_table_without_id() {
+        DataStoreInfo::from_value("Info", "<DataStoreInfo><datatable name=\"x\"/></DataStoreInfo>").unwrap();
+    }
+}
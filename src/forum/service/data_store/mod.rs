@@ -0,0 +1,7 @@
+mod client;
+mod info;
+mod record;
+
+pub use self::client::{DataStoreClient};
+pub use self::info::{DataStoreInfo, DataTable, DataTableField};
+pub use self::record::{DataField, DataRecord, format_records, parse_records};
@@ -0,0 +1,140 @@
+use std::borrow::{ToOwned};
+
+use {SOAPError, SOAPResult};
+use xml;
+
+const DATA_RECORDS_NAMESPACE: &'static str = "urn:schemas-upnp-org:ds:drecs";
+
+const DATA_RECORDS_ELEMENT: &'static str = "DataRecords";
+const DATA_RECORD_ELEMENT:  &'static str = "datarecord";
+const FIELD_ELEMENT:        &'static str = "field";
+
+const NAME_ATTRIBUTE:     &'static str = "name";
+const ENCODING_ATTRIBUTE: &'static str = "encoding";
+
+/// Single named value within a data record.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataField {
+    pub name:     String,
+    pub value:    String,
+    /// Encoding of the value, such as ascii, if one was given.
+    pub encoding: Option<String>
+}
+
+impl DataField {
+    pub fn new(name: &str, value: &str) -> DataField {
+        DataField{ name: name.to_owned(), value: value.to_owned(), encoding: None }
+    }
+}
+
+/// Record read from a sensor or a data table.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DataRecord {
+    pub fields: Vec<DataField>
+}
+
+impl DataRecord {
+    pub fn new(fields: Vec<DataField>) -> DataRecord {
+        DataRecord{ fields: fields }
+    }
+    
+    /// Returns the value of the first field with the given name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|n| n.name == name).map(|n| &n.value[..])
+    }
+}
+
+/// Parse the records out of a DataRecords document.
+///
+/// An empty document is treated as containing no records.
+pub fn parse_records(name: &'static str, doc: &str) -> SOAPResult<Vec<DataRecord>> {
+    if doc.trim().is_empty() {
+        return Ok(Vec::new())
+    }
+    
+    let records = match xml::element(doc, DATA_RECORDS_ELEMENT) {
+        Some(n) => n,
+        None    => return Err(SOAPError::InvalidArgument(name, "Value Is Not A DataRecords Document"))
+    };
+    
+    let mut parsed = Vec::new();
+    for record in xml::Elements::new(records, DATA_RECORD_ELEMENT) {
+        let mut fields = Vec::new();
+        
+        for field in xml::Nodes::new(record, FIELD_ELEMENT) {
+            let field_name = match field.attribute(NAME_ATTRIBUTE) {
+                Some(n) => n,
+                None    => return Err(SOAPError::InvalidArgument(name, "Data Record Field Has No Name"))
+            };
+            
+            fields.push(DataField{ name: field_name, value: xml::unescape(field.content()),
+                encoding: field.attribute(ENCODING_ATTRIBUTE) });
+        }
+        
+        parsed.push(DataRecord::new(fields));
+    }
+    
+    Ok(parsed)
+}
+
+/// Format the given records as a DataRecords document.
+pub fn format_records(records: &[DataRecord]) -> String {
+    let mut doc = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><{} xmlns=\"{}\">",
+                          DATA_RECORDS_ELEMENT, DATA_RECORDS_NAMESPACE);
+    
+    for record in records.iter() {
+        doc.push_str(&format!("<{}>", DATA_RECORD_ELEMENT)[..]);
+        
+        for field in record.fields.iter() {
+            let encoding = match field.encoding {
+                Some(ref n) => format!(" {}=\"{}\"", ENCODING_ATTRIBUTE, xml::escape(&n[..])),
+                None        => String::new()
+            };
+            
+            doc.push_str(&format!("<{0} {1}=\"{2}\"{3}>{4}</{0}>", FIELD_ELEMENT, NAME_ATTRIBUTE,
+                xml::escape(&field.name[..]), encoding, xml::escape(&field.value[..]))[..]);
+        }
+        
+        doc.push_str(&format!("</{}>", DATA_RECORD_ELEMENT)[..]);
+    }
+    doc.push_str(&format!("</{}>", DATA_RECORDS_ELEMENT)[..]);
+    
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataField, DataRecord};
+    
+    #[test]
+    fn positive_parse_records() {
+        let doc = "<?xml version=\"1.0\"?><DataRecords xmlns=\"urn:schemas-upnp-org:ds:drecs\">\
+                   <datarecord><field name=\"ClientID\">gateway</field>\
+                   <field name=\"Temp\" encoding=\"ascii\">21.5</field></datarecord>\
+                   <datarecord><field name=\"Temp\">22.0</field></datarecord></DataRecords>";
+        let records = super::parse_records("Records", doc).unwrap();
+        
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("Temp").unwrap(), "21.5");
+        assert_eq!(records[0].fields[1].encoding, Some("ascii".to_string()));
+        assert_eq!(records[1].get("ClientID"), None);
+    }
+    
+    #[test]
+    fn positive_records_round_trip() {
+        let records = vec![DataRecord::new(vec![DataField::new("Note", "<open & shut>")])];
+        
+        assert_eq!(super::parse_records("Records", &super::format_records(&records)[..]).unwrap(), records);
+    }
+    
+    #[test]
+    fn positive_empty_document() {
+        assert!(super::parse_records("Records", "  ").unwrap().is_empty());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_unnamed_field() {
+        super::parse_records("Records", "<DataRecords><datarecord><field>1</field></datarecord></DataRecords>").unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+use rustc_serialize::base64::{FromBase64, ToBase64, STANDARD};
+
+use {SOAPError, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::device_protection::{ActionRoles, LoginChallenge, SupportedProtocols};
+use forum::service::device_protection::roles;
+use soap::{ActionResponse};
+use soap::session::{SecureSession};
+
+/// Introduction protocol based on Wi-Fi Protected Setup.
+pub const WPS_PROTOCOL:   &'static str = "WPS";
+/// Login protocol based on a PKCS #5 derived password key.
+pub const PKCS5_PROTOCOL: &'static str = "PKCS5";
+
+const SEND_SETUP_MESSAGE_ACTION:       &'static str = "SendSetupMessage";
+const GET_SUPPORTED_PROTOCOLS_ACTION:  &'static str = "GetSupportedProtocols";
+const GET_USER_LOGIN_CHALLENGE_ACTION: &'static str = "GetUserLoginChallenge";
+const USER_LOGIN_ACTION:               &'static str = "UserLogin";
+const USER_LOGOUT_ACTION:              &'static str = "UserLogout";
+const GET_ASSIGNED_ROLES_ACTION:       &'static str = "GetAssignedRoles";
+const GET_ROLES_FOR_ACTION_ACTION:     &'static str = "GetRolesForAction";
+
+const PROTOCOL_TYPE_ARG:        &'static str = "ProtocolType";
+const IN_MESSAGE_ARG:           &'static str = "InMessage";
+const OUT_MESSAGE_ARG:          &'static str = "OutMessage";
+const PROTOCOL_LIST_ARG:        &'static str = "ProtocolList";
+const NAME_ARG:                 &'static str = "Name";
+const SALT_ARG:                 &'static str = "Salt";
+const CHALLENGE_ARG:            &'static str = "Challenge";
+const AUTHENTICATOR_ARG:        &'static str = "Authenticator";
+const ROLE_LIST_ARG:            &'static str = "RoleList";
+const RESTRICTED_ROLE_LIST_ARG: &'static str = "RestrictedRoleList";
+const DEVICE_UDN_ARG:           &'static str = "DeviceUDN";
+const SERVICE_ID_ARG:           &'static str = "ServiceId";
+const ACTION_NAME_ARG:          &'static str = "ActionName";
+
+/// Client for a DeviceProtection service.
+///
+/// Devices associate introductions and logins with the secure connection the
+/// control point is using, so the protected services of the device must be
+/// reached over that same connection for the granted roles to apply. Every
+/// call is sent over the session of the client, which other protected services
+/// are attached to through `GenericService::with_session`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DeviceProtectionClient {
+    service: GenericService,
+    session: SecureSession
+}
+
+impl DeviceProtectionClient {
+    /// Create a new DeviceProtectionClient, opening a new secure session for
+    /// the service unless it already has one.
+    pub fn new(service: GenericService) -> DeviceProtectionClient {
+        let session = service.session().cloned().unwrap_or_else(SecureSession::new);
+        
+        DeviceProtectionClient::with_session(service, session)
+    }
+    
+    /// Create a new DeviceProtectionClient that sends every call over the given
+    /// secure session, such as one presenting a client certificate.
+    pub fn with_session(service: GenericService, session: SecureSession) -> DeviceProtectionClient {
+        DeviceProtectionClient{ service: service.with_session(session.clone()), session: session }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the secure session that every call is sent over.
+    pub fn session(&self) -> &SecureSession {
+        &self.session
+    }
+    
+    /// Returns the introduction and login protocols supported by the device.
+    pub fn supported_protocols(&self) -> SOAPResult<SupportedProtocols> {
+        let response = try!(self.service.send_action(GET_SUPPORTED_PROTOCOLS_ACTION, &[]));
+        
+        Ok(SupportedProtocols::from_value(&try!(response.argument(PROTOCOL_LIST_ARG))[..]))
+    }
+    
+    /// Send a single message of an introduction protocol, such as one of the
+    /// WPS registration messages, returning the reply of the device.
+    pub fn send_setup_message(&self, protocol: &str, message: &[u8]) -> SOAPResult<Vec<u8>> {
+        let message = message.to_base64(STANDARD);
+        let response = try!(self.service.send_action(SEND_SETUP_MESSAGE_ACTION, &[
+            (PROTOCOL_TYPE_ARG, protocol),
+            (IN_MESSAGE_ARG,    &message[..])
+        ]));
+        
+        binary(&response, OUT_MESSAGE_ARG)
+    }
+    
+    /// Request a challenge for logging in as the given user.
+    pub fn login_challenge(&self, name: &str) -> SOAPResult<LoginChallenge> {
+        let response = try!(self.service.send_action(GET_USER_LOGIN_CHALLENGE_ACTION, &[
+            (PROTOCOL_TYPE_ARG, PKCS5_PROTOCOL),
+            (NAME_ARG,          name)
+        ]));
+        
+        Ok(LoginChallenge{ salt: try!(binary(&response, SALT_ARG)),
+            challenge: try!(binary(&response, CHALLENGE_ARG)) })
+    }
+    
+    /// Answer a challenge previously handed out by the device.
+    pub fn user_login(&self, challenge: &LoginChallenge, authenticator: &[u8]) -> SOAPResult<()> {
+        let challenge = challenge.challenge.to_base64(STANDARD);
+        let authenticator = authenticator.to_base64(STANDARD);
+        
+        try!(self.service.send_action(USER_LOGIN_ACTION, &[
+            (PROTOCOL_TYPE_ARG, PKCS5_PROTOCOL),
+            (CHALLENGE_ARG,     &challenge[..]),
+            (AUTHENTICATOR_ARG, &authenticator[..])
+        ]));
+        
+        Ok(())
+    }
+    
+    /// Log in as the given user, performing the whole challenge/response exchange.
+    pub fn login(&self, name: &str, password: &str) -> SOAPResult<()> {
+        let challenge = try!(self.login_challenge(name));
+        let authenticator = challenge.authenticator(password);
+        
+        self.user_login(&challenge, &authenticator[..])
+    }
+    
+    /// Log out the currently logged in user.
+    pub fn logout(&self) -> SOAPResult<()> {
+        try!(self.service.send_action(USER_LOGOUT_ACTION, &[]));
+        
+        Ok(())
+    }
+    
+    /// Returns the roles currently granted to this control point.
+    pub fn assigned_roles(&self) -> SOAPResult<Vec<String>> {
+        let response = try!(self.service.send_action(GET_ASSIGNED_ROLES_ACTION, &[]));
+        
+        Ok(roles::to_roles(&try!(response.argument(ROLE_LIST_ARG))[..]))
+    }
+    
+    /// Returns the roles permitted to invoke the given action of a service on
+    /// the device with the given udn.
+    pub fn roles_for_action(&self, device_udn: &str, service_id: &str, action: &str) -> SOAPResult<ActionRoles> {
+        let response = try!(self.service.send_action(GET_ROLES_FOR_ACTION_ACTION, &[
+            (DEVICE_UDN_ARG,  device_udn),
+            (SERVICE_ID_ARG,  service_id),
+            (ACTION_NAME_ARG, action)
+        ]));
+        
+        Ok(ActionRoles{ roles: roles::to_roles(&try!(response.argument(ROLE_LIST_ARG))[..]),
+            restricted_roles: roles::to_roles(&try!(response.argument(RESTRICTED_ROLE_LIST_ARG))[..]) })
+    }
+}
+
+fn binary(response: &ActionResponse, name: &'static str) -> SOAPResult<Vec<u8>> {
+    try!(response.argument(name)).trim().from_base64().map_err(|_|
+        SOAPError::InvalidArgument(name, "Value Is Not Valid Base64")
+    )
+}
@@ -0,0 +1,128 @@
+//! Implements the PKCS5 challenge/response login of DeviceProtection.
+//!
+//! The password never leaves the control point. Instead, a key is derived
+//! from the password and the salt held by the device, and combined with a
+//! one time challenge to produce the authenticator sent to the device.
+
+use std::iter;
+
+use openssl::crypto::hash::{self, Type};
+
+/// Number of PBKDF2 iterations used to derive the stored key.
+const STORED_ITERATIONS: u32 = 5000;
+/// Length, in bytes, of the derived stored key.
+const STORED_LENGTH: usize = 16;
+/// Length, in bytes, of the authenticator sent to the device.
+const AUTHENTICATOR_LENGTH: usize = 20;
+
+const SHA256_BLOCK_SIZE:  usize = 64;
+const SHA256_DIGEST_SIZE: usize = 32;
+
+const HMAC_INNER_PAD: u8 = 0x36;
+const HMAC_OUTER_PAD: u8 = 0x5C;
+
+/// Salt and challenge handed out by a device for a login attempt.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LoginChallenge {
+    pub salt:      Vec<u8>,
+    pub challenge: Vec<u8>
+}
+
+impl LoginChallenge {
+    /// Returns the authenticator proving knowledge of the given password.
+    pub fn authenticator(&self, password: &str) -> Vec<u8> {
+        let stored = stored_key(password.as_bytes(), &self.salt[..]);
+        
+        let mut data = stored;
+        data.push_all(&self.challenge[..]);
+        
+        let mut digest = sha256(&data[..]);
+        digest.truncate(AUTHENTICATOR_LENGTH);
+        
+        digest
+    }
+}
+
+/// Derive the key that the device stores in place of the password.
+fn stored_key(password: &[u8], salt: &[u8]) -> Vec<u8> {
+    pbkdf2_sha256(password, salt, STORED_ITERATIONS, STORED_LENGTH)
+}
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    hash::hash(Type::SHA256, data)
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut key_block = if key.len() > SHA256_BLOCK_SIZE { sha256(key) } else { key.to_vec() };
+    let padding = SHA256_BLOCK_SIZE - key_block.len();
+    key_block.extend(iter::repeat(0).take(padding));
+    
+    let mut inner = key_block.iter().map(|n| n ^ HMAC_INNER_PAD).collect::<Vec<u8>>();
+    inner.push_all(data);
+    
+    let mut outer = key_block.iter().map(|n| n ^ HMAC_OUTER_PAD).collect::<Vec<u8>>();
+    outer.push_all(&sha256(&inner[..])[..]);
+    
+    sha256(&outer[..])
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Vec<u8> {
+    let mut derived = Vec::with_capacity(length + SHA256_DIGEST_SIZE);
+    let mut block_index = 1u32;
+    
+    while derived.len() < length {
+        let mut first = salt.to_vec();
+        first.push_all(&[(block_index >> 24) as u8, (block_index >> 16) as u8,
+            (block_index >> 8) as u8, block_index as u8]);
+        
+        let mut last = hmac_sha256(password, &first[..]);
+        let mut block = last.clone();
+        
+        for _ in 1..iterations {
+            last = hmac_sha256(password, &last[..]);
+            
+            for (b, l) in block.iter_mut().zip(last.iter()) {
+                *b ^= *l;
+            }
+        }
+        
+        derived.push_all(&block[..]);
+        block_index += 1;
+    }
+    derived.truncate(length);
+    
+    derived
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoginChallenge};
+    
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|n| format!("{:02x}", n)).collect()
+    }
+    
+    #[test]
+    fn positive_hmac_sha256() {
+        let mac = super::hmac_sha256(b"key", b"The quick brown fox jumps over the lazy dog");
+        
+        assert_eq!(to_hex(&mac[..]), "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+    }
+    
+    #[test]
+    fn positive_pbkdf2_sha256() {
+        assert_eq!(to_hex(&super::pbkdf2_sha256(b"password", b"salt", 1, 16)[..]),
+                   "120fb6cffcf8b32c43e7225256c4f837");
+        assert_eq!(to_hex(&super::pbkdf2_sha256(b"password", b"salt", 2, 16)[..]),
+                   "ae4d0c95af6b46d32d0adff928f06dd0");
+    }
+    
+    #[test]
+    fn positive_authenticator() {
+        let challenge = LoginChallenge{ salt: b"0123456789abcdef".to_vec(), challenge: b"challenge".to_vec() };
+        
+        assert_eq!(to_hex(&super::stored_key(b"secret", &challenge.salt[..])[..]),
+                   "aa0c6d0ef96879fa8bc1e008dccf2936");
+        assert_eq!(to_hex(&challenge.authenticator("secret")[..]), "a90e7b9c3a20eebd65107328ff73dc13f304ee8e");
+    }
+}
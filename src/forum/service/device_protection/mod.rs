@@ -0,0 +1,7 @@
+mod client;
+mod login;
+mod roles;
+
+pub use self::client::{DeviceProtectionClient, PKCS5_PROTOCOL, WPS_PROTOCOL};
+pub use self::login::{LoginChallenge};
+pub use self::roles::{ActionRoles, SupportedProtocols};
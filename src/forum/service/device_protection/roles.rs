@@ -0,0 +1,63 @@
+use std::borrow::{ToOwned};
+
+use xml;
+
+const INTRODUCTION_ELEMENT: &'static str = "Introduction";
+const LOGIN_ELEMENT:        &'static str = "Login";
+const NAME_ELEMENT:         &'static str = "Name";
+
+/// Protocols that a device supports for introducing and logging in control points.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SupportedProtocols {
+    /// Protocols used to establish trust with a new control point, such as WPS.
+    pub introduction: Vec<String>,
+    /// Protocols used to log in as a user, such as PKCS5.
+    pub login:        Vec<String>
+}
+
+impl SupportedProtocols {
+    /// Parse the SupportedProtocols document returned by the device.
+    pub fn from_value(doc: &str) -> SupportedProtocols {
+        SupportedProtocols{ introduction: protocol_names(doc, INTRODUCTION_ELEMENT),
+            login: protocol_names(doc, LOGIN_ELEMENT) }
+    }
+}
+
+fn protocol_names(doc: &str, kind: &str) -> Vec<String> {
+    xml::Elements::new(doc, kind).filter_map(|n| xml::element(n, NAME_ELEMENT))
+        .map(|n| xml::unescape(n.trim())).collect()
+}
+
+/// Roles that are permitted to invoke an action.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionRoles {
+    /// Roles permitted to invoke the action on any of its arguments.
+    pub roles:            Vec<String>,
+    /// Roles permitted to invoke the action on arguments they own.
+    pub restricted_roles: Vec<String>
+}
+
+/// Split a space separated list of role names.
+pub fn to_roles(value: &str) -> Vec<String> {
+    value.split(' ').map(|n| n.trim()).filter(|n| !n.is_empty()).map(|n| n.to_owned()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SupportedProtocols};
+    
+    #[test]
+    fn positive_supported_protocols() {
+        let doc = "<SupportedProtocols><Introduction><Name>WPS</Name></Introduction>\
+                   <Login><Name>PKCS5</Name></Login></SupportedProtocols>";
+        let protocols = SupportedProtocols::from_value(doc);
+        
+        assert_eq!(protocols.introduction, vec!["WPS".to_string()]);
+        assert_eq!(protocols.login, vec!["PKCS5".to_string()]);
+    }
+    
+    #[test]
+    fn positive_roles() {
+        assert_eq!(super::to_roles(" Public  Admin "), vec!["Public".to_string(), "Admin".to_string()]);
+    }
+}
@@ -0,0 +1,275 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+/// Minimum load level, in percent.
+pub const LOAD_LEVEL_MIN: u8 = 0;
+
+/// Maximum load level, in percent.
+pub const LOAD_LEVEL_MAX: u8 = 100;
+
+const SET_LOAD_LEVEL_TARGET_ACTION: &'static str = "SetLoadLevelTarget";
+const GET_LOAD_LEVEL_TARGET_ACTION: &'static str = "GetLoadLevelTarget";
+const GET_LOAD_LEVEL_STATUS_ACTION: &'static str = "GetLoadLevelStatus";
+const SET_STEP_DELTA_ACTION:        &'static str = "SetStepDelta";
+const GET_STEP_DELTA_ACTION:        &'static str = "GetStepDelta";
+const STEP_UP_ACTION:               &'static str = "StepUp";
+const STEP_DOWN_ACTION:             &'static str = "StepDown";
+const SET_RAMP_RATE_ACTION:         &'static str = "SetRampRate";
+const GET_RAMP_RATE_ACTION:         &'static str = "GetRampRate";
+const START_RAMP_UP_ACTION:         &'static str = "StartRampUp";
+const START_RAMP_DOWN_ACTION:       &'static str = "StartRampDown";
+const START_RAMP_TO_LEVEL_ACTION:   &'static str = "StartRampToLevel";
+const STOP_RAMP_ACTION:             &'static str = "StopRamp";
+const PAUSE_RAMP_ACTION:            &'static str = "PauseRamp";
+const RESUME_RAMP_ACTION:           &'static str = "ResumeRamp";
+const GET_IS_RAMPING_ACTION:        &'static str = "GetIsRamping";
+const GET_RAMP_PAUSED_ACTION:       &'static str = "GetRampPaused";
+const GET_RAMP_TIME_ACTION:         &'static str = "GetRampTime";
+
+const NEW_LOAD_LEVEL_TARGET_ARG: &'static str = "newLoadlevelTarget";
+const GET_LOAD_LEVEL_TARGET_ARG: &'static str = "GetLoadlevelTarget";
+const RET_LOAD_LEVEL_STATUS_ARG: &'static str = "retLoadlevelStatus";
+const NEW_STEP_DELTA_ARG:        &'static str = "newStepDelta";
+const OUT_STEP_DELTA_ARG:        &'static str = "OutStepDelta";
+const NEW_RAMP_RATE_ARG:         &'static str = "newRampRate";
+const RET_RAMP_RATE_ARG:         &'static str = "retRampRate";
+const NEW_RAMP_TIME_ARG:         &'static str = "newRampTime";
+const RET_RAMP_TIME_ARG:         &'static str = "retRampTime";
+const RET_IS_RAMPING_ARG:        &'static str = "retIsRamping";
+const RET_RAMP_PAUSED_ARG:       &'static str = "retRampPaused";
+
+const LOAD_LEVEL_STATUS_VARIABLE: &'static str = "LoadLevelStatus";
+const STEP_DELTA_VARIABLE:        &'static str = "StepDelta";
+const RAMP_RATE_VARIABLE:         &'static str = "RampRate";
+const IS_RAMPING_VARIABLE:        &'static str = "IsRamping";
+const RAMP_PAUSED_VARIABLE:       &'static str = "RampPaused";
+
+/// Evented state variables of a Dimming service.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DimmingEvent {
+    /// Current load level, in percent.
+    LoadLevelStatus(u8),
+    /// Amount the load level changes for each step, in percent.
+    StepDelta(u8),
+    /// Rate at which the load level changes when ramping, in percent per second.
+    RampRate(u8),
+    /// Whether or not the load level is currently ramping.
+    IsRamping(bool),
+    /// Whether or not the current ramp is paused.
+    RampPaused(bool)
+}
+
+/// Client for a Dimming service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DimmingClient {
+    service: GenericService
+}
+
+impl DimmingClient {
+    pub fn new(service: GenericService) -> DimmingClient {
+        DimmingClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Set the load level that the device should be at, in percent.
+    pub fn set_load_level_target(&self, level: u8) -> SOAPResult<()> {
+        self.set_percent(SET_LOAD_LEVEL_TARGET_ACTION, NEW_LOAD_LEVEL_TARGET_ARG, level)
+    }
+    
+    /// Returns the load level that the device was last asked to be at.
+    pub fn load_level_target(&self) -> SOAPResult<u8> {
+        self.get_percent(GET_LOAD_LEVEL_TARGET_ACTION, GET_LOAD_LEVEL_TARGET_ARG)
+    }
+    
+    /// Returns the current load level of the device.
+    pub fn load_level_status(&self) -> SOAPResult<u8> {
+        self.get_percent(GET_LOAD_LEVEL_STATUS_ACTION, RET_LOAD_LEVEL_STATUS_ARG)
+    }
+    
+    /// Set the amount that the load level changes for each step.
+    pub fn set_step_delta(&self, delta: u8) -> SOAPResult<()> {
+        self.set_percent(SET_STEP_DELTA_ACTION, NEW_STEP_DELTA_ARG, delta)
+    }
+    
+    /// Returns the amount that the load level changes for each step.
+    pub fn step_delta(&self) -> SOAPResult<u8> {
+        self.get_percent(GET_STEP_DELTA_ACTION, OUT_STEP_DELTA_ARG)
+    }
+    
+    /// Increase the load level by the step delta.
+    pub fn step_up(&self) -> SOAPResult<()> {
+        self.send_empty(STEP_UP_ACTION)
+    }
+    
+    /// Decrease the load level by the step delta.
+    pub fn step_down(&self) -> SOAPResult<()> {
+        self.send_empty(STEP_DOWN_ACTION)
+    }
+    
+    /// Set the rate at which the load level changes when ramping.
+    pub fn set_ramp_rate(&self, rate: u8) -> SOAPResult<()> {
+        self.set_percent(SET_RAMP_RATE_ACTION, NEW_RAMP_RATE_ARG, rate)
+    }
+    
+    /// Returns the rate at which the load level changes when ramping.
+    pub fn ramp_rate(&self) -> SOAPResult<u8> {
+        self.get_percent(GET_RAMP_RATE_ACTION, RET_RAMP_RATE_ARG)
+    }
+    
+    /// Start ramping the load level up at the ramp rate.
+    pub fn start_ramp_up(&self) -> SOAPResult<()> {
+        self.send_empty(START_RAMP_UP_ACTION)
+    }
+    
+    /// Start ramping the load level down at the ramp rate.
+    pub fn start_ramp_down(&self) -> SOAPResult<()> {
+        self.send_empty(START_RAMP_DOWN_ACTION)
+    }
+    
+    /// Start ramping the load level to the given level over the given number
+    /// of milliseconds.
+    pub fn start_ramp_to_level(&self, level: u8, ramp_time_ms: u32) -> SOAPResult<()> {
+        try!(check_percent(NEW_LOAD_LEVEL_TARGET_ARG, level));
+        
+        try!(self.service.send_action(START_RAMP_TO_LEVEL_ACTION,
+            &[(NEW_LOAD_LEVEL_TARGET_ARG, &level.to_string()[..]),
+              (NEW_RAMP_TIME_ARG, &ramp_time_ms.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Stop any ramp that is in progress.
+    pub fn stop_ramp(&self) -> SOAPResult<()> {
+        self.send_empty(STOP_RAMP_ACTION)
+    }
+    
+    /// Pause the ramp that is in progress.
+    pub fn pause_ramp(&self) -> SOAPResult<()> {
+        self.send_empty(PAUSE_RAMP_ACTION)
+    }
+    
+    /// Resume a ramp that was paused.
+    pub fn resume_ramp(&self) -> SOAPResult<()> {
+        self.send_empty(RESUME_RAMP_ACTION)
+    }
+    
+    /// Returns whether or not the load level is currently ramping.
+    pub fn is_ramping(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_IS_RAMPING_ACTION, &[]));
+        
+        value::to_bool(RET_IS_RAMPING_ARG, &try!(response.argument(RET_IS_RAMPING_ARG))[..])
+    }
+    
+    /// Returns whether or not the current ramp is paused.
+    pub fn ramp_paused(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_RAMP_PAUSED_ACTION, &[]));
+        
+        value::to_bool(RET_RAMP_PAUSED_ARG, &try!(response.argument(RET_RAMP_PAUSED_ARG))[..])
+    }
+    
+    /// Returns the number of milliseconds remaining in the current ramp.
+    pub fn ramp_time(&self) -> SOAPResult<u32> {
+        let response = try!(self.service.send_action(GET_RAMP_TIME_ACTION, &[]));
+        
+        value::to_number(RET_RAMP_TIME_ARG, &try!(response.argument(RET_RAMP_TIME_ARG))[..])
+    }
+    
+    /// Subscribe to changes in the load level and ramping state of the device.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(DimmingEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+    
+    fn send_empty(&self, action: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(action, &[]));
+        
+        Ok(())
+    }
+    
+    fn set_percent(&self, action: &str, arg: &'static str, percent: u8) -> SOAPResult<()> {
+        try!(check_percent(arg, percent));
+        
+        try!(self.service.send_action(action, &[(arg, &percent.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    fn get_percent(&self, action: &str, arg: &'static str) -> SOAPResult<u8> {
+        let response = try!(self.service.send_action(action, &[]));
+        
+        value::to_ranged(arg, &try!(response.argument(arg))[..], LOAD_LEVEL_MIN, LOAD_LEVEL_MAX)
+    }
+}
+
+/// Returns an error if the percent is above the maximum load level.
+fn check_percent(arg: &'static str, percent: u8) -> SOAPResult<()> {
+    value::to_ranged(arg, &percent.to_string()[..], LOAD_LEVEL_MIN, LOAD_LEVEL_MAX).map(|_| ())
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<DimmingEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        let value = &value[..];
+        
+        match &name[..] {
+            LOAD_LEVEL_STATUS_VARIABLE => value::to_ranged(LOAD_LEVEL_STATUS_VARIABLE, value,
+                LOAD_LEVEL_MIN, LOAD_LEVEL_MAX).ok().map(|n| DimmingEvent::LoadLevelStatus(n)),
+            STEP_DELTA_VARIABLE => value::to_number(STEP_DELTA_VARIABLE, value).ok()
+                .map(|n| DimmingEvent::StepDelta(n)),
+            RAMP_RATE_VARIABLE => value::to_number(RAMP_RATE_VARIABLE, value).ok()
+                .map(|n| DimmingEvent::RampRate(n)),
+            IS_RAMPING_VARIABLE => value::to_bool(IS_RAMPING_VARIABLE, value).ok()
+                .map(|n| DimmingEvent::IsRamping(n)),
+            RAMP_PAUSED_VARIABLE => value::to_bool(RAMP_PAUSED_VARIABLE, value).ok()
+                .map(|n| DimmingEvent::RampPaused(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{DimmingEvent};
+    
+    #[test]
+    fn positive_load_level_events() {
+        let body = "<e:propertyset><e:property><LoadLevelStatus>40</LoadLevelStatus></e:property>\
+                    <e:property><IsRamping>1</IsRamping></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![DimmingEvent::LoadLevelStatus(40), DimmingEvent::IsRamping(true)]);
+    }
+    
+    #[test]
+    fn positive_max_percent() {
+        super::check_percent("Arg", 100).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_percent_overflow() {
+        super::check_percent("Arg", 101).unwrap();
+    }
+    
+    #[test]
+    fn negative_load_level_out_of_range() {
+        let body = "<e:propertyset><e:property><LoadLevelStatus>150</LoadLevelStatus></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert!(super::to_events(&property_set).is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use std::borrow::{ToOwned};
+
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+
+const SET_MODE_ACTION:       &'static str = "SetMode";
+const GET_MODE_ACTION:       &'static str = "GetMode";
+const GET_FAN_STATUS_ACTION: &'static str = "GetFanStatus";
+const GET_NAME_ACTION:       &'static str = "GetName";
+const SET_NAME_ACTION:       &'static str = "SetName";
+
+const NEW_MODE_ARG:       &'static str = "NewMode";
+const CURRENT_MODE_ARG:   &'static str = "CurrentMode";
+const CURRENT_STATUS_ARG: &'static str = "CurrentStatus";
+const CURRENT_NAME_ARG:   &'static str = "CurrentName";
+const NEW_NAME_ARG:       &'static str = "NewName";
+
+const MODE_VARIABLE:       &'static str = "Mode";
+const FAN_STATUS_VARIABLE: &'static str = "FanStatus";
+const NAME_VARIABLE:       &'static str = "Name";
+
+const AUTO_VALUE:          &'static str = "Auto";
+const CONTINUOUS_ON_VALUE: &'static str = "ContinuousOn";
+const PERIODIC_ON_VALUE:   &'static str = "PeriodicOn";
+
+const FAN_ON_VALUE: &'static str = "On";
+
+/// Operating modes of a fan.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FanMode {
+    /// Fan runs only when heating or cooling.
+    Auto,
+    /// Fan runs continuously.
+    ContinuousOn,
+    /// Fan runs periodically.
+    PeriodicOn,
+    /// Vendor defined fan mode.
+    Other(String)
+}
+
+impl FanMode {
+    /// Convert the text value of an argument or state variable to a FanMode.
+    pub fn from_value(value: &str) -> FanMode {
+        match value.trim() {
+            AUTO_VALUE          => FanMode::Auto,
+            CONTINUOUS_ON_VALUE => FanMode::ContinuousOn,
+            PERIODIC_ON_VALUE   => FanMode::PeriodicOn,
+            n => FanMode::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns the text value used when sending the mode to a service.
+    pub fn as_value(&self) -> &str {
+        match *self {
+            FanMode::Auto         => AUTO_VALUE,
+            FanMode::ContinuousOn => CONTINUOUS_ON_VALUE,
+            FanMode::PeriodicOn   => PERIODIC_ON_VALUE,
+            FanMode::Other(ref n) => &n[..]
+        }
+    }
+}
+
+/// Evented state variables of an HVAC_FanOperatingMode service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FanOperatingModeEvent {
+    /// Operating mode of the fan.
+    Mode(FanMode),
+    /// Whether or not the fan is currently running.
+    FanStatus(bool),
+    /// User assigned name of the service.
+    Name(String)
+}
+
+/// Client for an HVAC_FanOperatingMode service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FanOperatingModeClient {
+    service: GenericService
+}
+
+impl FanOperatingModeClient {
+    pub fn new(service: GenericService) -> FanOperatingModeClient {
+        FanOperatingModeClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Set the operating mode of the fan.
+    pub fn set_mode(&self, mode: &FanMode) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_MODE_ACTION, &[(NEW_MODE_ARG, mode.as_value())]));
+        
+        Ok(())
+    }
+    
+    /// Returns the operating mode of the fan.
+    pub fn mode(&self) -> SOAPResult<FanMode> {
+        let response = try!(self.service.send_action(GET_MODE_ACTION, &[]));
+        
+        Ok(FanMode::from_value(&try!(response.argument(CURRENT_MODE_ARG))[..]))
+    }
+    
+    /// Returns whether or not the fan is currently running.
+    pub fn fan_status(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_FAN_STATUS_ACTION, &[]));
+        
+        Ok(is_fan_on(&try!(response.argument(CURRENT_STATUS_ARG))[..]))
+    }
+    
+    /// Returns the user assigned name of the service.
+    pub fn name(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_NAME_ACTION, &[]));
+        
+        response.argument(CURRENT_NAME_ARG)
+    }
+    
+    /// Set the user assigned name of the service.
+    pub fn set_name(&self, name: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_NAME_ACTION, &[(NEW_NAME_ARG, name)]));
+        
+        Ok(())
+    }
+    
+    /// Subscribe to changes in the operating mode and status of the fan.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(FanOperatingModeEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Returns true if the fan status value denotes a running fan.
+///
+/// Some devices report variants such as OnHigh or OnLow, which are treated as
+/// running as well.
+fn is_fan_on(value: &str) -> bool {
+    value.trim().starts_with(FAN_ON_VALUE)
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<FanOperatingModeEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            MODE_VARIABLE       => Some(FanOperatingModeEvent::Mode(FanMode::from_value(&value[..]))),
+            FAN_STATUS_VARIABLE => Some(FanOperatingModeEvent::FanStatus(is_fan_on(&value[..]))),
+            NAME_VARIABLE       => Some(FanOperatingModeEvent::Name(value.clone())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{FanMode, FanOperatingModeEvent};
+    
+    #[test]
+    fn positive_fan_events() {
+        let body = "<e:propertyset><e:property><Mode>ContinuousOn</Mode></e:property>\
+                    <e:property><FanStatus>OnHigh</FanStatus></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![FanOperatingModeEvent::Mode(FanMode::ContinuousOn),
+                        FanOperatingModeEvent::FanStatus(true)]);
+    }
+    
+    #[test]
+    fn positive_fan_off() {
+        assert!(!super::is_fan_on("Off"));
+    }
+}
@@ -0,0 +1,108 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+/// Minimum fan speed, in percent.
+pub const FAN_SPEED_MIN: u8 = 0;
+
+/// Maximum fan speed, in percent.
+pub const FAN_SPEED_MAX: u8 = 100;
+
+const SET_FAN_SPEED_ACTION:        &'static str = "SetFanSpeed";
+const GET_FAN_SPEED_ACTION:        &'static str = "GetFanSpeed";
+const GET_FAN_SPEED_TARGET_ACTION: &'static str = "GetFanSpeedTarget";
+
+const NEW_FAN_SPEED_TARGET_ARG:     &'static str = "NewFanSpeedTarget";
+const CURRENT_FAN_SPEED_STATUS_ARG: &'static str = "CurrentFanSpeedStatus";
+const CURRENT_FAN_SPEED_TARGET_ARG: &'static str = "CurrentFanSpeedTarget";
+
+const FAN_SPEED_STATUS_VARIABLE: &'static str = "FanSpeedStatus";
+
+/// Evented state variables of a FanSpeed service.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FanSpeedEvent {
+    /// Current speed of the fan, in percent.
+    FanSpeedStatus(u8)
+}
+
+/// Client for a FanSpeed service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FanSpeedClient {
+    service: GenericService
+}
+
+impl FanSpeedClient {
+    pub fn new(service: GenericService) -> FanSpeedClient {
+        FanSpeedClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Set the speed that the fan should run at, in percent.
+    pub fn set_fan_speed(&self, speed: u8) -> SOAPResult<()> {
+        let speed_value = speed.to_string();
+        try!(value::to_ranged(NEW_FAN_SPEED_TARGET_ARG, &speed_value[..], FAN_SPEED_MIN, FAN_SPEED_MAX));
+        
+        try!(self.service.send_action(SET_FAN_SPEED_ACTION,
+                                      &[(NEW_FAN_SPEED_TARGET_ARG, &speed_value[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the current speed of the fan.
+    pub fn fan_speed(&self) -> SOAPResult<u8> {
+        let response = try!(self.service.send_action(GET_FAN_SPEED_ACTION, &[]));
+        
+        value::to_ranged(CURRENT_FAN_SPEED_STATUS_ARG, &try!(response.argument(CURRENT_FAN_SPEED_STATUS_ARG))[..],
+                         FAN_SPEED_MIN, FAN_SPEED_MAX)
+    }
+    
+    /// Returns the speed that the fan was last asked to run at.
+    pub fn fan_speed_target(&self) -> SOAPResult<u8> {
+        let response = try!(self.service.send_action(GET_FAN_SPEED_TARGET_ACTION, &[]));
+        
+        value::to_ranged(CURRENT_FAN_SPEED_TARGET_ARG, &try!(response.argument(CURRENT_FAN_SPEED_TARGET_ARG))[..],
+                         FAN_SPEED_MIN, FAN_SPEED_MAX)
+    }
+    
+    /// Subscribe to changes in the speed of the fan.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(FanSpeedEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<FanSpeedEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            FAN_SPEED_STATUS_VARIABLE => value::to_ranged(FAN_SPEED_STATUS_VARIABLE, &value[..],
+                FAN_SPEED_MIN, FAN_SPEED_MAX).ok().map(|n| FanSpeedEvent::FanSpeedStatus(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{FanSpeedEvent};
+    
+    #[test]
+    fn positive_fan_speed_event() {
+        let body = "<e:propertyset><e:property><FanSpeedStatus>75</FanSpeedStatus></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![FanSpeedEvent::FanSpeedStatus(75)]);
+    }
+}
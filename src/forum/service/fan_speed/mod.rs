@@ -0,0 +1,3 @@
+mod client;
+
+pub use self::client::{FanSpeedClient, FanSpeedEvent, FAN_SPEED_MIN, FAN_SPEED_MAX};
@@ -1,5 +1,91 @@
-trait GenericService {
-    fn send_action(name: &str, params: &[(&str, &str)]) -> SOAPError<()>;
+use url::{Url};
+
+use {GENAResult, SOAPResult};
+use gena::{self, PropertySet, Subscription};
+use gena::header::{Timeout};
+use soap::{self, ActionResponse};
+use soap::session::{SecureSession};
+use version::{Version};
+
+/// Service that has been located within the description of a device.
+///
+/// Provides untyped access to the actions and evented state variables of the
+/// service, which typed service clients are built on top of.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GenericService {
+    service_type: String,
+    service_id:   String,
+    version:      Version,
+    control_url:  Url,
+    event_url:    Url,
+    session:      Option<SecureSession>
+}
+
+impl GenericService {
+    /// Create a new GenericService from the advertised service type urn and id
+    /// and the absolute control and event urls of the service.
+    pub fn new(service_type: String, service_id: String, version: Version, control_url: Url,
+        event_url: Url) -> GenericService {
+        GenericService{ service_type: service_type, service_id: service_id, version: version,
+            control_url: control_url, event_url: event_url, session: None }
+    }
     
+    /// Send every action of the service over the given secure session.
+    ///
+    /// Used for services protected by DeviceProtection, whose actions are only
+    /// permitted over the session the control point logged in with.
+    pub fn with_session(mut self, session: SecureSession) -> GenericService {
+        self.session = Some(session);
+        self
+    }
     
-}
\ No newline at end of file
+    /// Returns the service type urn as advertised by the device.
+    pub fn service_type(&self) -> &str {
+        &self.service_type[..]
+    }
+    
+    /// Returns the service id as advertised by the device.
+    ///
+    /// Used to tell apart multiple services of the same type on one device.
+    pub fn service_id(&self) -> &str {
+        &self.service_id[..]
+    }
+    
+    /// Returns the version of the service as advertised by the device.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    
+    pub fn control_url(&self) -> &Url {
+        &self.control_url
+    }
+    
+    pub fn event_url(&self) -> &Url {
+        &self.event_url
+    }
+    
+    /// Returns the secure session that actions are sent over, if any.
+    pub fn session(&self) -> Option<&SecureSession> {
+        self.session.as_ref()
+    }
+    
+    /// Invoke the given action on the service.
+    ///
+    /// This is a blocking operation.
+    pub fn send_action(&self, action: &str, args: &[(&str, &str)]) -> SOAPResult<ActionResponse> {
+        match self.session {
+            Some(ref session) => session.send_action(&self.control_url, &self.service_type[..], action, args),
+            None => soap::send_action(&self.control_url, &self.service_type[..], action, args)
+        }
+    }
+    
+    /// Subscribe to the evented state variables of the service.
+    ///
+    /// This is a blocking operation.
+    pub fn subscribe<F>(&self, handler: F) -> GENAResult<Subscription>
+        where F: FnMut(PropertySet) + Send + 'static {
+        let timeout = Timeout::Seconds(gena::DEFAULT_TIMEOUT_SECONDS);
+        
+        Subscription::new(&self.event_url, timeout, handler)
+    }
+}
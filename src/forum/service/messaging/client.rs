@@ -0,0 +1,93 @@
+use std::borrow::{ToOwned};
+
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::messaging::{Message, OutgoingMessage};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+const SEND_MESSAGE_ACTION: &'static str = "SendMessage";
+const READ_MESSAGE_ACTION: &'static str = "ReadMessage";
+
+const MESSAGE_TO_SEND_ARG:   &'static str = "MessageToSend";
+const MESSAGE_ID_ARG:        &'static str = "MessageID";
+const MESSAGE_REQUESTED_ARG: &'static str = "MessageRequested";
+
+const NEW_MESSAGE_VARIABLE: &'static str = "NewMessage";
+
+/// Evented state variables of a Messaging service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MessagingEvent {
+    /// Ids of messages that have been received.
+    NewMessage(Vec<String>)
+}
+
+/// Client for a Messaging service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MessagingClient {
+    service: GenericService
+}
+
+impl MessagingClient {
+    pub fn new(service: GenericService) -> MessagingClient {
+        MessagingClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Send the given message.
+    ///
+    /// Returns the id of the sent message.
+    pub fn send_message(&self, message: &OutgoingMessage) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(SEND_MESSAGE_ACTION,
+                                                     &[(MESSAGE_TO_SEND_ARG, &message.to_value()[..])]));
+        
+        response.argument(MESSAGE_ID_ARG)
+    }
+    
+    /// Returns the message with the given id.
+    pub fn read_message(&self, message_id: &str) -> SOAPResult<Message> {
+        let response = try!(self.service.send_action(READ_MESSAGE_ACTION, &[(MESSAGE_ID_ARG, message_id)]));
+        
+        Message::from_value(MESSAGE_REQUESTED_ARG, &try!(response.argument(MESSAGE_REQUESTED_ARG))[..])
+    }
+    
+    /// Subscribe to notifications of received messages.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(MessagingEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<MessagingEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            NEW_MESSAGE_VARIABLE => Some(MessagingEvent::NewMessage(
+                value::to_list(&value[..]).into_iter().map(|n| n.to_owned()).collect())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{MessagingEvent};
+    
+    #[test]
+    fn positive_new_message_event() {
+        let body = "<e:propertyset><e:property><NewMessage>m1,m2</NewMessage></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![MessagingEvent::NewMessage(vec!["m1".to_string(), "m2".to_string()])]);
+    }
+}
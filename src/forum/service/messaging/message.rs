@@ -0,0 +1,118 @@
+use std::borrow::{ToOwned};
+
+use {SOAPError, SOAPResult};
+use xml;
+
+const MESSAGING_NAMESPACE: &'static str = "urn:schemas-upnp-org:phone:messaging";
+
+const MESSAGE_ELEMENT:      &'static str = "message";
+const MESSAGE_ID_ELEMENT:   &'static str = "messageID";
+const FROM_ELEMENT:         &'static str = "from";
+const TO_ELEMENT:           &'static str = "to";
+const SUBJECT_ELEMENT:      &'static str = "subject";
+const CONTENT_TYPE_ELEMENT: &'static str = "contentType";
+const BODY_ELEMENT:         &'static str = "body";
+
+const DEFAULT_CONTENT_TYPE: &'static str = "text/plain";
+
+/// Message to be sent through a telephony server.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OutgoingMessage {
+    /// Recipients of the message, such as phone numbers or sip uris.
+    pub to:           Vec<String>,
+    pub subject:      String,
+    pub body:         String,
+    /// MIME type of the body.
+    pub content_type: String
+}
+
+impl OutgoingMessage {
+    /// Create a new plain text message for the given recipients.
+    pub fn new(to: Vec<String>, subject: &str, body: &str) -> OutgoingMessage {
+        OutgoingMessage{ to: to, subject: subject.to_owned(), body: body.to_owned(),
+            content_type: DEFAULT_CONTENT_TYPE.to_owned() }
+    }
+    
+    /// Returns the message document sent to the service.
+    pub fn to_value(&self) -> String {
+        let mut doc = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><{} xmlns=\"{}\">",
+                              MESSAGE_ELEMENT, MESSAGING_NAMESPACE);
+        
+        for recipient in self.to.iter() {
+            push_element(&mut doc, TO_ELEMENT, &recipient[..]);
+        }
+        push_element(&mut doc, SUBJECT_ELEMENT, &self.subject[..]);
+        push_element(&mut doc, CONTENT_TYPE_ELEMENT, &self.content_type[..]);
+        push_element(&mut doc, BODY_ELEMENT, &self.body[..]);
+        doc.push_str(&format!("</{}>", MESSAGE_ELEMENT)[..]);
+        
+        doc
+    }
+}
+
+/// Message stored on a telephony server.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Message {
+    pub message_id:   String,
+    pub from:         String,
+    pub to:           Vec<String>,
+    pub subject:      String,
+    pub body:         String,
+    pub content_type: String
+}
+
+impl Message {
+    /// Parse a message document returned by the service.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Message> {
+        let message = match xml::element(doc, MESSAGE_ELEMENT) {
+            Some(n) => n,
+            None    => return Err(SOAPError::InvalidArgument(name, "Value Is Not A Message Document"))
+        };
+        let field = |element: &str| xml::element(message, element).map(|n| xml::unescape(n)).unwrap_or(String::new());
+        
+        Ok(Message{
+            message_id:   field(MESSAGE_ID_ELEMENT).trim().to_owned(),
+            from:         field(FROM_ELEMENT).trim().to_owned(),
+            to:           xml::Elements::new(message, TO_ELEMENT).map(|n| xml::unescape(n.trim())).collect(),
+            subject:      field(SUBJECT_ELEMENT),
+            body:         field(BODY_ELEMENT),
+            content_type: field(CONTENT_TYPE_ELEMENT).trim().to_owned()
+        })
+    }
+}
+
+fn push_element(doc: &mut String, element: &str, value: &str) {
+    doc.push_str(&format!("<{0}>{1}</{0}>", element, xml::escape(value))[..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, OutgoingMessage};
+    
+    #[test]
+    fn positive_outgoing_message() {
+        let message = OutgoingMessage::new(vec!["tel:+15551234".to_string()], "Door", "Front door <open>");
+        let doc = message.to_value();
+        
+        assert!(doc.contains("<to>tel:+15551234</to><subject>Door</subject><contentType>text/plain</contentType>\
+                              <body>Front door &lt;open&gt;</body>"));
+    }
+    
+    #[test]
+    fn positive_read_message() {
+        let doc = "<message><messageID>m1</messageID><from>tel:+15559876</from><to>tel:+15551234</to>\
+                   <to>tel:+15554321</to><subject>Hi</subject><body>Call me &amp; soon</body></message>";
+        let message = Message::from_value("Message", doc).unwrap();
+        
+        assert_eq!(message.message_id, "m1");
+        assert_eq!(message.to.len(), 2);
+        assert_eq!(message.body, "Call me & soon");
+        assert_eq!(message.content_type, "");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_not_a_message() {
+        Message::from_value("Message", "<contact/>").unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod message;
+
+pub use self::client::{MessagingClient, MessagingEvent};
+pub use self::message::{Message, OutgoingMessage};
@@ -1,13 +1,34 @@
-use version::{Version};
+mod generic_service;
+mod typing;
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
-pub enum ServiceType {
-    PlaceHolder
-}
+pub mod address_book;
+pub mod basic_management;
+pub mod call_management;
+pub mod camera_image;
+pub mod camera_settings;
+pub mod configuration_management;
+pub mod data_store;
+pub mod device_protection;
+pub mod dimming;
+pub mod fan_operating_mode;
+pub mod fan_speed;
+pub mod messaging;
+pub mod presence;
+pub mod print;
+pub mod scan;
+pub mod sensor_transport;
+pub mod switch_power;
+pub mod temperature_sensor;
+pub mod temperature_setpoint;
+pub mod user_operating_mode;
+pub mod wan_ip_connection;
+pub mod wlan_configuration;
+
+pub use forum::service::generic_service::GenericService;
+pub use forum::service::typing::ServiceType;
 
-impl ServiceType {
-    /// Create a new ServiceType from the given values.
-    pub fn new(_: &str, _: &str, _: Version) -> ServiceType {
-        ServiceType::PlaceHolder
-    }
-}
\ No newline at end of file
+/// Returns the value to send for an optional action argument, which is empty
+/// when the argument is left out.
+fn optional(value: &Option<String>) -> &str {
+    value.as_ref().map(|n| &n[..]).unwrap_or("")
+}
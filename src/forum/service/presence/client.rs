@@ -0,0 +1,92 @@
+use {GENAResult, SOAPError, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::presence::{Presence};
+use gena::{PropertySet, Subscription};
+
+const UPDATE_PRESENCE_ACTION:      &'static str = "UpdatePresence";
+const GET_CONTACT_PRESENCE_ACTION: &'static str = "GetPresenceOfContact";
+
+const PRESENCE_INFO_ARG:    &'static str = "PresenceInfo";
+const CONTACT_ID_ARG:       &'static str = "ContactID";
+const CONTACT_PRESENCE_ARG: &'static str = "ContactPresence";
+
+const PRESENCE_UPDATE_VARIABLE: &'static str = "PresenceUpdate";
+
+/// Evented state variables of a PresenceService.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PresenceEvent {
+    /// Contacts whose presence has changed.
+    PresenceUpdate(Vec<Presence>)
+}
+
+/// Client for a PresenceService.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PresenceClient {
+    service: GenericService
+}
+
+impl PresenceClient {
+    pub fn new(service: GenericService) -> PresenceClient {
+        PresenceClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Publish the presence of the local user.
+    pub fn update_presence(&self, presence: &Presence) -> SOAPResult<()> {
+        try!(self.service.send_action(UPDATE_PRESENCE_ACTION, &[(PRESENCE_INFO_ARG, &presence.to_value()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the presence of the given contact.
+    pub fn contact_presence(&self, contact_id: &str) -> SOAPResult<Presence> {
+        let response = try!(self.service.send_action(GET_CONTACT_PRESENCE_ACTION, &[(CONTACT_ID_ARG, contact_id)]));
+        let presences = try!(Presence::from_value(CONTACT_PRESENCE_ARG,
+                                                  &try!(response.argument(CONTACT_PRESENCE_ARG))[..]));
+        
+        presences.into_iter().next().ok_or(SOAPError::InvalidArgument(CONTACT_PRESENCE_ARG, "Presence Is Empty"))
+    }
+    
+    /// Subscribe to changes in the presence of contacts.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(PresenceEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<PresenceEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            PRESENCE_UPDATE_VARIABLE => Presence::from_value(PRESENCE_UPDATE_VARIABLE, &value[..]).ok()
+                .map(|n| PresenceEvent::PresenceUpdate(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use forum::service::presence::{Presence, PresenceStatus};
+    use super::{PresenceEvent};
+    
+    #[test]
+    fn positive_presence_event() {
+        let body = "<e:propertyset><e:property><PresenceUpdate>&lt;presence&gt;&lt;contactID&gt;bob&lt;/contactID&gt;\
+                    &lt;presenceStatus&gt;Away&lt;/presenceStatus&gt;&lt;/presence&gt;</PresenceUpdate></e:property>\
+                    </e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![PresenceEvent::PresenceUpdate(vec![Presence{
+            contact_id: "bob".to_string(), status: PresenceStatus::Away, note: String::new() }])]);
+    }
+}
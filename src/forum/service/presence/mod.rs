@@ -0,0 +1,5 @@
+mod client;
+mod status;
+
+pub use self::client::{PresenceClient, PresenceEvent};
+pub use self::status::{Presence, PresenceStatus};
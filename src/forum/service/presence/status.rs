@@ -0,0 +1,104 @@
+use std::borrow::{ToOwned};
+
+use {SOAPError, SOAPResult};
+use xml;
+
+const PRESENCE_NAMESPACE: &'static str = "urn:schemas-upnp-org:phone:presence";
+
+const PRESENCE_ELEMENT:        &'static str = "presence";
+const CONTACT_ID_ELEMENT:      &'static str = "contactID";
+const PRESENCE_STATUS_ELEMENT: &'static str = "presenceStatus";
+const PRESENCE_NOTE_ELEMENT:   &'static str = "presenceNote";
+
+const AVAILABLE_VALUE: &'static str = "Available";
+const BUSY_VALUE:      &'static str = "Busy";
+const AWAY_VALUE:      &'static str = "Away";
+const OFFLINE_VALUE:   &'static str = "Offline";
+
+/// Availability of a contact.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PresenceStatus {
+    Available,
+    Busy,
+    Away,
+    Offline,
+    /// Vendor defined presence status.
+    Other(String)
+}
+
+impl PresenceStatus {
+    /// Convert the text value of a presence status to a PresenceStatus.
+    pub fn from_value(value: &str) -> PresenceStatus {
+        match value.trim() {
+            AVAILABLE_VALUE => PresenceStatus::Available,
+            BUSY_VALUE      => PresenceStatus::Busy,
+            AWAY_VALUE      => PresenceStatus::Away,
+            OFFLINE_VALUE   => PresenceStatus::Offline,
+            n => PresenceStatus::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns the text value used when sending the status to a service.
+    pub fn as_value(&self) -> &str {
+        match *self {
+            PresenceStatus::Available    => AVAILABLE_VALUE,
+            PresenceStatus::Busy         => BUSY_VALUE,
+            PresenceStatus::Away         => AWAY_VALUE,
+            PresenceStatus::Offline      => OFFLINE_VALUE,
+            PresenceStatus::Other(ref n) => &n[..]
+        }
+    }
+}
+
+/// Presence of a single contact.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Presence {
+    pub contact_id: String,
+    pub status:     PresenceStatus,
+    /// Free form note that accompanies the status.
+    pub note:       String
+}
+
+impl Presence {
+    /// Parse the presences out of a document containing presence elements.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Vec<Presence>> {
+        xml::Elements::new(doc, PRESENCE_ELEMENT).map(|presence| {
+            let status = match xml::element(presence, PRESENCE_STATUS_ELEMENT) {
+                Some(n) => PresenceStatus::from_value(&xml::unescape(n)[..]),
+                None    => return Err(SOAPError::InvalidArgument(name, "Presence Has No Status"))
+            };
+            let field = |element: &str| xml::element(presence, element).map(|n| xml::unescape(n.trim()));
+            
+            Ok(Presence{ contact_id: field(CONTACT_ID_ELEMENT).unwrap_or(String::new()), status: status,
+                note: field(PRESENCE_NOTE_ELEMENT).unwrap_or(String::new()) })
+        }).collect()
+    }
+    
+    /// Returns the presence document sent to the service.
+    pub fn to_value(&self) -> String {
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><{0} xmlns=\"{1}\"><{2}>{3}</{2}><{4}>{5}</{4}>\
+                 <{6}>{7}</{6}></{0}>", PRESENCE_ELEMENT, PRESENCE_NAMESPACE,
+                CONTACT_ID_ELEMENT, xml::escape(&self.contact_id[..]),
+                PRESENCE_STATUS_ELEMENT, xml::escape(self.status.as_value()),
+                PRESENCE_NOTE_ELEMENT, xml::escape(&self.note[..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Presence, PresenceStatus};
+    
+    #[test]
+    fn positive_presence_round_trip() {
+        let presence = Presence{ contact_id: "alice".to_string(), status: PresenceStatus::Busy,
+            note: "In a meeting & offline".to_string() };
+        
+        assert_eq!(Presence::from_value("Presence", &presence.to_value()[..]).unwrap(), vec![presence]);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_status() {
+        Presence::from_value("Presence", "<presence><contactID>bob</contactID></presence>").unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use std::error::{Error};
+
+use hyper::{Client};
+use hyper::status::{StatusCode};
+
+use {GENAResult, SOAPError, SOAPResult};
+use forum::service::{optional, GenericService};
+use forum::service::print::{JobAttributes, JobTemplate, PrintJob, PrinterAttributes, PrinterState};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+const CREATE_JOB_ACTION:             &'static str = "CreateJob";
+const GET_JOB_ATTRIBUTES_ACTION:     &'static str = "GetJobAttributes";
+const CANCEL_JOB_ACTION:             &'static str = "CancelJob";
+const GET_PRINTER_ATTRIBUTES_ACTION: &'static str = "GetPrinterAttributes";
+
+const JOB_ID_ARG:                &'static str = "JobId";
+const DATA_SINK_ARG:             &'static str = "DataSink";
+const JOB_NAME_ARG:              &'static str = "JobName";
+const JOB_ORIGINATING_USER_ARG:  &'static str = "JobOriginatingUserName";
+const DOCUMENT_FORMAT_ARG:       &'static str = "DocumentFormat";
+const COPIES_ARG:                &'static str = "Copies";
+const SIDES_ARG:                 &'static str = "Sides";
+const NUMBER_UP_ARG:             &'static str = "NumberUp";
+const ORIENTATION_REQUESTED_ARG: &'static str = "OrientationRequested";
+const MEDIA_SIZE_ARG:            &'static str = "MediaSize";
+const MEDIA_TYPE_ARG:            &'static str = "MediaType";
+const PRINT_QUALITY_ARG:         &'static str = "PrintQuality";
+const JOB_MEDIA_SHEETS_ARG:      &'static str = "JobMediaSheetsCompleted";
+const PRINTER_STATE_ARG:         &'static str = "PrinterState";
+const PRINTER_STATE_REASONS_ARG: &'static str = "PrinterStateReasons";
+const JOB_ID_LIST_ARG:           &'static str = "JobIdList";
+
+const PRINTER_STATE_VARIABLE:         &'static str = "PrinterState";
+const PRINTER_STATE_REASONS_VARIABLE: &'static str = "PrinterStateReasons";
+const JOB_ID_LIST_VARIABLE:           &'static str = "JobIdList";
+const JOB_END_STATE_VARIABLE:         &'static str = "JobEndState";
+const JOB_MEDIA_SHEETS_VARIABLE:      &'static str = "JobMediaSheetsCompleted";
+const JOB_ABORT_STATE_VARIABLE:       &'static str = "JobAbortState";
+const CONTENT_COMPLETE_LIST_VARIABLE: &'static str = "ContentCompleteList";
+
+/// Evented state variables of a PrintBasic or PrintEnhanced service.
+///
+/// Job related variables are comma separated records whose layout depends
+/// on the version of the service, so they are passed through unparsed.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PrintEvent {
+    PrinterState(PrinterState),
+    PrinterStateReasons(Vec<String>),
+    JobIdList(Vec<u32>),
+    JobEndState(String),
+    JobMediaSheetsCompleted(String),
+    JobAbortState(String),
+    ContentCompleteList(String)
+}
+
+/// Client for a PrintBasic or PrintEnhanced service.
+///
+/// Only actions common to both services are exposed.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PrintClient {
+    service: GenericService
+}
+
+impl PrintClient {
+    pub fn new(service: GenericService) -> PrintClient {
+        PrintClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Create a new job on the printer.
+    ///
+    /// The document for the job should then be sent to the data sink of the
+    /// returned job, see send_document.
+    pub fn create_job(&self, template: &JobTemplate) -> SOAPResult<PrintJob> {
+        let copies = template.copies.map(|n| n.to_string());
+        
+        let response = try!(self.service.send_action(CREATE_JOB_ACTION, &[
+            (JOB_NAME_ARG,              &template.job_name[..]),
+            (JOB_ORIGINATING_USER_ARG,  &template.originating_user[..]),
+            (DOCUMENT_FORMAT_ARG,       optional(&template.document_format)),
+            (COPIES_ARG,                optional(&copies)),
+            (SIDES_ARG,                 optional(&template.sides)),
+            (NUMBER_UP_ARG,             optional(&template.number_up)),
+            (ORIENTATION_REQUESTED_ARG, optional(&template.orientation_requested)),
+            (MEDIA_SIZE_ARG,            optional(&template.media_size)),
+            (MEDIA_TYPE_ARG,            optional(&template.media_type)),
+            (PRINT_QUALITY_ARG,         optional(&template.print_quality))
+        ]));
+        
+        let job_id = try!(value::to_number(JOB_ID_ARG, &try!(response.argument(JOB_ID_ARG))[..]));
+        let data_sink = try!(value::to_url(DATA_SINK_ARG, &try!(response.argument(DATA_SINK_ARG))[..]));
+        
+        Ok(PrintJob{ job_id: job_id, data_sink: data_sink })
+    }
+    
+    /// Send the document for a job to the data sink of that job.
+    ///
+    /// This is a blocking operation.
+    pub fn send_document(&self, job: &PrintJob, document: &[u8]) -> SOAPResult<()> {
+        let mut client = Client::new();
+        let response = try!(client.post(job.data_sink.clone()).body(document).send().map_err(|e|
+            SOAPError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        match response.status {
+            StatusCode::Ok => Ok(()),
+            n => Err(SOAPError::ResponseCode(n.to_u16()))
+        }
+    }
+    
+    /// Returns the attributes of the given job.
+    pub fn job_attributes(&self, job_id: u32) -> SOAPResult<JobAttributes> {
+        let response = try!(self.service.send_action(GET_JOB_ATTRIBUTES_ACTION,
+            &[(JOB_ID_ARG, &job_id.to_string()[..])]));
+        
+        let sheets = try!(value::to_number(JOB_MEDIA_SHEETS_ARG, &try!(response.argument(JOB_MEDIA_SHEETS_ARG))[..]));
+        
+        Ok(JobAttributes{
+            job_name:               try!(response.argument(JOB_NAME_ARG)),
+            originating_user:       try!(response.argument(JOB_ORIGINATING_USER_ARG)),
+            media_sheets_completed: sheets
+        })
+    }
+    
+    /// Cancel the given job.
+    pub fn cancel_job(&self, job_id: u32) -> SOAPResult<()> {
+        try!(self.service.send_action(CANCEL_JOB_ACTION, &[(JOB_ID_ARG, &job_id.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the attributes of the printer.
+    pub fn printer_attributes(&self) -> SOAPResult<PrinterAttributes> {
+        let response = try!(self.service.send_action(GET_PRINTER_ATTRIBUTES_ACTION, &[]));
+        
+        let state = PrinterState::from_value(&try!(response.argument(PRINTER_STATE_ARG))[..]);
+        let reasons = to_reasons(&try!(response.argument(PRINTER_STATE_REASONS_ARG))[..]);
+        let job_ids = try!(to_job_ids(&try!(response.argument(JOB_ID_LIST_ARG))[..]));
+        let job_id = try!(value::to_number(JOB_ID_ARG, &try!(response.argument(JOB_ID_ARG))[..]));
+        
+        Ok(PrinterAttributes{ state: state, state_reasons: reasons, job_ids: job_ids, job_id: job_id })
+    }
+    
+    /// Subscribe to changes in the state of the printer and its jobs.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(PrintEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+fn to_reasons(value: &str) -> Vec<String> {
+    value::to_list(value).into_iter().map(|n| n.to_owned()).collect()
+}
+
+fn to_job_ids(value: &str) -> SOAPResult<Vec<u32>> {
+    value::to_list(value).into_iter().map(|n| value::to_number(JOB_ID_LIST_ARG, n)).collect()
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<PrintEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            PRINTER_STATE_VARIABLE         => Some(PrintEvent::PrinterState(PrinterState::from_value(&value[..]))),
+            PRINTER_STATE_REASONS_VARIABLE => Some(PrintEvent::PrinterStateReasons(to_reasons(&value[..]))),
+            JOB_ID_LIST_VARIABLE           => to_job_ids(&value[..]).ok().map(|n| PrintEvent::JobIdList(n)),
+            JOB_END_STATE_VARIABLE         => Some(PrintEvent::JobEndState(value.clone())),
+            JOB_MEDIA_SHEETS_VARIABLE      => Some(PrintEvent::JobMediaSheetsCompleted(value.clone())),
+            JOB_ABORT_STATE_VARIABLE       => Some(PrintEvent::JobAbortState(value.clone())),
+            CONTENT_COMPLETE_LIST_VARIABLE => Some(PrintEvent::ContentCompleteList(value.clone())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use forum::service::print::{PrinterState};
+    use super::{PrintEvent};
+    
+    #[test]
+    fn positive_printer_events() {
+        let body = "<e:propertyset><e:property><PrinterState>stopped</PrinterState></e:property>\
+                    <e:property><PrinterStateReasons>media-empty, door-open</PrinterStateReasons></e:property>\
+                    <e:property><JobIdList>3,4</JobIdList></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![
+            PrintEvent::PrinterState(PrinterState::Stopped),
+            PrintEvent::PrinterStateReasons(vec!["media-empty".to_string(), "door-open".to_string()]),
+            PrintEvent::JobIdList(vec![3, 4])
+        ]);
+    }
+    
+    #[test]
+    fn positive_empty_job_list() {
+        assert!(super::to_job_ids("").unwrap().is_empty());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_invalid_job_list() {
+        super::to_job_ids("1,two").unwrap();
+    }
+}
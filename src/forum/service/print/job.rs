@@ -0,0 +1,92 @@
+use std::borrow::{ToOwned};
+
+use url::{Url};
+
+const IDLE_VALUE:       &'static str = "idle";
+const PROCESSING_VALUE: &'static str = "processing";
+const STOPPED_VALUE:    &'static str = "stopped";
+
+/// Overall state of a printer.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PrinterState {
+    Idle,
+    Processing,
+    Stopped,
+    /// Vendor defined printer state.
+    Other(String)
+}
+
+impl PrinterState {
+    /// Convert the text value of an argument or state variable to a PrinterState.
+    pub fn from_value(value: &str) -> PrinterState {
+        match value.trim() {
+            IDLE_VALUE       => PrinterState::Idle,
+            PROCESSING_VALUE => PrinterState::Processing,
+            STOPPED_VALUE    => PrinterState::Stopped,
+            n => PrinterState::Other(n.to_owned())
+        }
+    }
+}
+
+/// Parameters of a job to be created on a printer.
+///
+/// Values left as None are sent empty, which leaves the choice of setting
+/// up to the printer.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct JobTemplate {
+    pub job_name:              String,
+    pub originating_user:      String,
+    /// MIME type of the document, such as application/pdf.
+    pub document_format:       Option<String>,
+    pub copies:                Option<u16>,
+    /// Sides of the media to print on, such as one-sided.
+    pub sides:                 Option<String>,
+    pub number_up:             Option<String>,
+    pub orientation_requested: Option<String>,
+    pub media_size:            Option<String>,
+    pub media_type:            Option<String>,
+    pub print_quality:         Option<String>
+}
+
+/// Job that was created on a printer.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PrintJob {
+    pub job_id:    u32,
+    /// Location that the document for the job should be posted to.
+    pub data_sink: Url
+}
+
+/// Attributes of a job on a printer.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct JobAttributes {
+    pub job_name:               String,
+    pub originating_user:       String,
+    pub media_sheets_completed: u32
+}
+
+/// Attributes of a printer.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PrinterAttributes {
+    pub state:         PrinterState,
+    /// Reasons for the current state, such as media-empty.
+    pub state_reasons: Vec<String>,
+    /// Jobs that are currently queued on the printer.
+    pub job_ids:       Vec<u32>,
+    /// Most recently created job.
+    pub job_id:        u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrinterState};
+    
+    #[test]
+    fn positive_printer_state() {
+        assert_eq!(PrinterState::from_value(" processing "), PrinterState::Processing);
+    }
+    
+    #[test]
+    fn positive_vendor_printer_state() {
+        assert_eq!(PrinterState::from_value("warming-up"), PrinterState::Other("warming-up".to_string()));
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod job;
+
+pub use self::client::{PrintClient, PrintEvent};
+pub use self::job::{JobAttributes, JobTemplate, PrintJob, PrinterAttributes, PrinterState};
@@ -0,0 +1,258 @@
+use std::borrow::{ToOwned};
+use std::error::{Error};
+use std::io::{Read};
+
+use hyper::{Client};
+use hyper::status::{StatusCode};
+use url::{Url};
+
+use {GENAResult, SOAPError, SOAPResult};
+use forum::service::{optional, GenericService};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+const CREATE_JOB_ACTION:             &'static str = "CreateJob";
+const GET_JOB_ATTRIBUTES_ACTION:     &'static str = "GetJobAttributes";
+const CANCEL_JOB_ACTION:             &'static str = "CancelJob";
+const GET_SCANNER_ATTRIBUTES_ACTION: &'static str = "GetScannerAttributes";
+
+const JOB_ID_ARG:               &'static str = "JobId";
+const DATA_SOURCE_ARG:          &'static str = "DataSource";
+const JOB_NAME_ARG:             &'static str = "JobName";
+const JOB_ORIGINATING_USER_ARG: &'static str = "JobOriginatingUserName";
+const DOCUMENT_FORMAT_ARG:      &'static str = "DocumentFormat";
+const RESOLUTION_ARG:           &'static str = "Resolution";
+const COLOR_MODE_ARG:           &'static str = "ColorMode";
+const JOB_STATE_ARG:            &'static str = "JobState";
+const JOB_PAGES_COMPLETED_ARG:  &'static str = "JobPagesCompleted";
+const SCANNER_STATE_ARG:        &'static str = "ScannerState";
+
+const SCANNER_STATE_VARIABLE: &'static str = "ScannerState";
+const JOB_STATE_VARIABLE:     &'static str = "JobState";
+
+const IDLE_VALUE:       &'static str = "idle";
+const PROCESSING_VALUE: &'static str = "processing";
+const STOPPED_VALUE:    &'static str = "stopped";
+
+const PENDING_VALUE:   &'static str = "pending";
+const COMPLETED_VALUE: &'static str = "completed";
+const CANCELED_VALUE:  &'static str = "canceled";
+const ABORTED_VALUE:   &'static str = "aborted";
+
+/// Overall state of a scanner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ScannerState {
+    Idle,
+    Processing,
+    Stopped,
+    /// Vendor defined scanner state.
+    Other(String)
+}
+
+impl ScannerState {
+    /// Convert the text value of an argument or state variable to a ScannerState.
+    pub fn from_value(value: &str) -> ScannerState {
+        match value.trim() {
+            IDLE_VALUE       => ScannerState::Idle,
+            PROCESSING_VALUE => ScannerState::Processing,
+            STOPPED_VALUE    => ScannerState::Stopped,
+            n => ScannerState::Other(n.to_owned())
+        }
+    }
+}
+
+/// State of a job on a scanner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum JobState {
+    Pending,
+    Processing,
+    Completed,
+    Canceled,
+    Aborted,
+    /// Vendor defined job state.
+    Other(String)
+}
+
+impl JobState {
+    /// Convert the text value of an argument or state variable to a JobState.
+    pub fn from_value(value: &str) -> JobState {
+        match value.trim() {
+            PENDING_VALUE    => JobState::Pending,
+            PROCESSING_VALUE => JobState::Processing,
+            COMPLETED_VALUE  => JobState::Completed,
+            CANCELED_VALUE   => JobState::Canceled,
+            ABORTED_VALUE    => JobState::Aborted,
+            n => JobState::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns true if the job will not make any further progress.
+    pub fn is_finished(&self) -> bool {
+        match *self {
+            JobState::Completed | JobState::Canceled | JobState::Aborted => true,
+            _ => false
+        }
+    }
+}
+
+/// Parameters of a job to be created on a scanner.
+///
+/// Values left as None are sent empty, which leaves the choice of setting
+/// up to the scanner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ScanTemplate {
+    pub job_name:         String,
+    pub originating_user: String,
+    /// MIME type of the scanned document, such as image/jpeg.
+    pub document_format:  Option<String>,
+    /// Resolution in dots per inch.
+    pub resolution:       Option<u16>,
+    pub color_mode:       Option<String>
+}
+
+/// Job that was created on a scanner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ScanJob {
+    pub job_id:      u32,
+    /// Location that the scanned document can be retrieved from.
+    pub data_source: Url
+}
+
+/// Progress of a job on a scanner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct JobStatus {
+    pub state:           JobState,
+    pub pages_completed: u32
+}
+
+/// Evented state variables of a ScanService.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ScanEvent {
+    ScannerState(ScannerState),
+    JobState(JobState)
+}
+
+/// Client for the ScanService of a Scanner device.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ScanClient {
+    service: GenericService
+}
+
+impl ScanClient {
+    pub fn new(service: GenericService) -> ScanClient {
+        ScanClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Create a new job on the scanner.
+    pub fn create_job(&self, template: &ScanTemplate) -> SOAPResult<ScanJob> {
+        let resolution = template.resolution.map(|n| n.to_string());
+        
+        let response = try!(self.service.send_action(CREATE_JOB_ACTION, &[
+            (JOB_NAME_ARG,             &template.job_name[..]),
+            (JOB_ORIGINATING_USER_ARG, &template.originating_user[..]),
+            (DOCUMENT_FORMAT_ARG,      optional(&template.document_format)),
+            (RESOLUTION_ARG,           optional(&resolution)),
+            (COLOR_MODE_ARG,           optional(&template.color_mode))
+        ]));
+        
+        let job_id = try!(value::to_number(JOB_ID_ARG, &try!(response.argument(JOB_ID_ARG))[..]));
+        let data_source = try!(value::to_url(DATA_SOURCE_ARG, &try!(response.argument(DATA_SOURCE_ARG))[..]));
+        
+        Ok(ScanJob{ job_id: job_id, data_source: data_source })
+    }
+    
+    /// Returns the progress of the given job.
+    pub fn job_status(&self, job_id: u32) -> SOAPResult<JobStatus> {
+        let response = try!(self.service.send_action(GET_JOB_ATTRIBUTES_ACTION,
+            &[(JOB_ID_ARG, &job_id.to_string()[..])]));
+        
+        let state = JobState::from_value(&try!(response.argument(JOB_STATE_ARG))[..]);
+        let pages = try!(value::to_number(JOB_PAGES_COMPLETED_ARG,
+            &try!(response.argument(JOB_PAGES_COMPLETED_ARG))[..]));
+        
+        Ok(JobStatus{ state: state, pages_completed: pages })
+    }
+    
+    /// Cancel the given job.
+    pub fn cancel_job(&self, job_id: u32) -> SOAPResult<()> {
+        try!(self.service.send_action(CANCEL_JOB_ACTION, &[(JOB_ID_ARG, &job_id.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the state of the scanner.
+    pub fn scanner_state(&self) -> SOAPResult<ScannerState> {
+        let response = try!(self.service.send_action(GET_SCANNER_ATTRIBUTES_ACTION, &[]));
+        
+        Ok(ScannerState::from_value(&try!(response.argument(SCANNER_STATE_ARG))[..]))
+    }
+    
+    /// Retrieve the scanned document from the data source of a job.
+    ///
+    /// This is a blocking operation.
+    pub fn fetch_document(&self, job: &ScanJob) -> SOAPResult<Vec<u8>> {
+        let mut client = Client::new();
+        let mut response = try!(client.get(job.data_source.clone()).send().map_err(|e|
+            SOAPError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        if response.status != StatusCode::Ok {
+            return Err(SOAPError::ResponseCode(response.status.to_u16()))
+        }
+        
+        let mut document = Vec::new();
+        try!(response.read_to_end(&mut document).map_err(|e|
+            SOAPError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        Ok(document)
+    }
+    
+    /// Subscribe to changes in the state of the scanner and its jobs.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(ScanEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<ScanEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            SCANNER_STATE_VARIABLE => Some(ScanEvent::ScannerState(ScannerState::from_value(&value[..]))),
+            JOB_STATE_VARIABLE     => Some(ScanEvent::JobState(JobState::from_value(&value[..]))),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{JobState, ScanEvent, ScannerState};
+    
+    #[test]
+    fn positive_scan_events() {
+        let body = "<e:propertyset><e:property><ScannerState>processing</ScannerState></e:property>\
+                    <e:property><JobState>completed</JobState></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![ScanEvent::ScannerState(ScannerState::Processing), ScanEvent::JobState(JobState::Completed)]);
+    }
+    
+    #[test]
+    fn positive_finished_job() {
+        assert!(JobState::Aborted.is_finished());
+        assert!(!JobState::from_value("pending").is_finished());
+    }
+}
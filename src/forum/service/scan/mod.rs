@@ -0,0 +1,3 @@
+mod client;
+
+pub use self::client::{JobState, JobStatus, ScanClient, ScanEvent, ScanJob, ScanTemplate, ScannerState};
@@ -0,0 +1,97 @@
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::data_store::{self, DataRecord};
+use forum::service::sensor_transport::{SensorConnection};
+use soap::value;
+
+const CONNECT_SENSOR_ACTION:    &'static str = "ConnectSensor";
+const DISCONNECT_SENSOR_ACTION: &'static str = "DisconnectSensor";
+const READ_SENSOR_ACTION:       &'static str = "ReadSensor";
+const WRITE_SENSOR_ACTION:      &'static str = "WriteSensor";
+const GET_CONNECTIONS_ACTION:   &'static str = "GetSensorTransportConnections";
+
+const SENSOR_ID_ARG:             &'static str = "SensorID";
+const SENSOR_URN_ARG:            &'static str = "SensorURN";
+const SENSOR_CLIENT_ID_ARG:      &'static str = "SensorClientID";
+const SENSOR_DATA_TYPE_ARG:      &'static str = "SensorDataTypeEnable";
+const SENSOR_RECORD_COUNT_ARG:   &'static str = "SensorRecordCount";
+const TRANSPORT_CONNECTION_ARG:  &'static str = "TransportConnectionID";
+const TRANSPORT_CONNECTIONS_ARG: &'static str = "SensorTransportConnections";
+const DATA_RECORDS_ARG:          &'static str = "DataRecords";
+
+/// Client for a SensorTransportGeneric service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SensorTransportClient {
+    service: GenericService
+}
+
+impl SensorTransportClient {
+    pub fn new(service: GenericService) -> SensorTransportClient {
+        SensorTransportClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Connect to the data item of a sensor identified by the given urn.
+    ///
+    /// Returns the id of the transport connection.
+    pub fn connect_sensor(&self, sensor_id: &str, sensor_urn: &str, client_id: &str) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(CONNECT_SENSOR_ACTION, &[
+            (SENSOR_ID_ARG,        sensor_id),
+            (SENSOR_URN_ARG,       sensor_urn),
+            (SENSOR_CLIENT_ID_ARG, client_id)
+        ]));
+        
+        response.argument(TRANSPORT_CONNECTION_ARG)
+    }
+    
+    /// Disconnect a transport connection previously made to a sensor.
+    pub fn disconnect_sensor(&self, sensor_id: &str, connection_id: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(DISCONNECT_SENSOR_ACTION, &[
+            (SENSOR_ID_ARG,            sensor_id),
+            (TRANSPORT_CONNECTION_ARG, connection_id)
+        ]));
+        
+        Ok(())
+    }
+    
+    /// Read up to count records that the sensor has queued for the client.
+    pub fn read_sensor(&self, sensor_id: &str, sensor_urn: &str, client_id: &str, count: u32)
+        -> SOAPResult<Vec<DataRecord>> {
+        let response = try!(self.service.send_action(READ_SENSOR_ACTION, &[
+            (SENSOR_ID_ARG,           sensor_id),
+            (SENSOR_CLIENT_ID_ARG,    client_id),
+            (SENSOR_URN_ARG,          sensor_urn),
+            (SENSOR_DATA_TYPE_ARG,    value::from_bool(false)),
+            (SENSOR_RECORD_COUNT_ARG, &count.to_string()[..])
+        ]));
+        
+        data_store::parse_records(DATA_RECORDS_ARG, &try!(response.argument(DATA_RECORDS_ARG))[..])
+    }
+    
+    /// Write the given records to the sensor.
+    pub fn write_sensor(&self, sensor_id: &str, sensor_urn: &str, client_id: &str, records: &[DataRecord])
+        -> SOAPResult<()> {
+        let records = data_store::format_records(records);
+        
+        try!(self.service.send_action(WRITE_SENSOR_ACTION, &[
+            (SENSOR_ID_ARG,        sensor_id),
+            (SENSOR_CLIENT_ID_ARG, client_id),
+            (SENSOR_URN_ARG,       sensor_urn),
+            (DATA_RECORDS_ARG,     &records[..])
+        ]));
+        
+        Ok(())
+    }
+    
+    /// Returns the transport connections currently held to the sensor.
+    pub fn connections(&self, sensor_id: &str) -> SOAPResult<Vec<SensorConnection>> {
+        let response = try!(self.service.send_action(GET_CONNECTIONS_ACTION, &[(SENSOR_ID_ARG, sensor_id)]));
+        
+        SensorConnection::from_value(TRANSPORT_CONNECTIONS_ARG,
+                                     &try!(response.argument(TRANSPORT_CONNECTIONS_ARG))[..])
+    }
+}
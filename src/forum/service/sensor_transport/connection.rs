@@ -0,0 +1,73 @@
+use {SOAPError, SOAPResult};
+use xml;
+
+const TRANSPORT_CONNECTIONS_ELEMENT: &'static str = "SensorTransportConnections";
+const TRANSPORT_CONNECTION_ELEMENT:  &'static str = "sensortransport";
+const CLIENT_ID_ELEMENT:             &'static str = "SensorClientID";
+const SENSOR_URN_ELEMENT:            &'static str = "SensorURN";
+const CONNECTION_ID_ELEMENT:         &'static str = "TransportConnectionID";
+
+/// Connection that a client holds to a sensor.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SensorConnection {
+    pub client_id:     String,
+    /// Urn identifying the data item of the sensor being transported.
+    pub sensor_urn:    String,
+    pub connection_id: String
+}
+
+impl SensorConnection {
+    /// Parse the connections out of a SensorTransportConnections document.
+    pub fn from_value(name: &'static str, doc: &str) -> SOAPResult<Vec<SensorConnection>> {
+        if doc.trim().is_empty() {
+            return Ok(Vec::new())
+        }
+        
+        let connections = match xml::element(doc, TRANSPORT_CONNECTIONS_ELEMENT) {
+            Some(n) => n,
+            None    => return Err(SOAPError::InvalidArgument(name, "Value Is Not A SensorTransportConnections Document"))
+        };
+        
+        xml::Elements::new(connections, TRANSPORT_CONNECTION_ELEMENT).map(|connection| {
+            let field = |element: &str| match xml::element(connection, element) {
+                Some(n) => Ok(xml::unescape(n.trim())),
+                None    => Err(SOAPError::InvalidArgument(name, "Sensor Connection Is Missing A Field"))
+            };
+            
+            Ok(SensorConnection{
+                client_id:     try!(field(CLIENT_ID_ELEMENT)),
+                sensor_urn:    try!(field(SENSOR_URN_ELEMENT)),
+                connection_id: try!(field(CONNECTION_ID_ELEMENT))
+            })
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::{ToOwned};
+    
+    use super::{SensorConnection};
+    
+    #[test]
+    fn positive_sensor_connections() {
+        let doc = "<SensorTransportConnections><sensortransport><SensorClientID>gateway</SensorClientID>\
+                   <SensorURN>urn:upnp-org:smgt-surn:temp</SensorURN>\
+                   <TransportConnectionID>7</TransportConnectionID></sensortransport>\
+                   </SensorTransportConnections>";
+        let connections = SensorConnection::from_value("Connections", doc).unwrap();
+        
+        assert_eq!(connections, vec![SensorConnection{ client_id: "gateway".to_owned(),
+            sensor_urn: "urn:upnp-org:smgt-surn:temp".to_owned(), connection_id: "7".to_owned() }]);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_connection_id() {
+        let doc = "<SensorTransportConnections><sensortransport><SensorClientID>gateway</SensorClientID>\
+                   <SensorURN>urn:upnp-org:smgt-surn:temp</SensorURN></sensortransport>\
+                   </SensorTransportConnections>";
+        
+        SensorConnection::from_value("Connections", doc).unwrap();
+    }
+}
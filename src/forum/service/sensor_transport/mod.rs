@@ -0,0 +1,5 @@
+mod client;
+mod connection;
+
+pub use self::client::{SensorTransportClient};
+pub use self::connection::{SensorConnection};
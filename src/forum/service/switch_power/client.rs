@@ -0,0 +1,106 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+const SET_TARGET_ACTION: &'static str = "SetTarget";
+const GET_TARGET_ACTION: &'static str = "GetTarget";
+const GET_STATUS_ACTION: &'static str = "GetStatus";
+
+const NEW_TARGET_VALUE_ARG: &'static str = "newTargetValue";
+const RET_TARGET_VALUE_ARG: &'static str = "RetTargetValue";
+const RESULT_STATUS_ARG:    &'static str = "ResultStatus";
+
+const STATUS_VARIABLE: &'static str = "Status";
+
+/// Evented state variables of a SwitchPower service.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SwitchPowerEvent {
+    /// Current power state of the device.
+    Status(bool)
+}
+
+/// Client for a SwitchPower service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SwitchPowerClient {
+    service: GenericService
+}
+
+impl SwitchPowerClient {
+    pub fn new(service: GenericService) -> SwitchPowerClient {
+        SwitchPowerClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Turn the device on or off.
+    pub fn set_target(&self, on: bool) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_TARGET_ACTION,
+                                      &[(NEW_TARGET_VALUE_ARG, value::from_bool(on))]));
+        
+        Ok(())
+    }
+    
+    /// Returns the power state that the device was last asked to be in.
+    pub fn target(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_TARGET_ACTION, &[]));
+        
+        value::to_bool(RET_TARGET_VALUE_ARG, &try!(response.argument(RET_TARGET_VALUE_ARG))[..])
+    }
+    
+    /// Returns the current power state of the device.
+    pub fn status(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_STATUS_ACTION, &[]));
+        
+        value::to_bool(RESULT_STATUS_ARG, &try!(response.argument(RESULT_STATUS_ARG))[..])
+    }
+    
+    /// Subscribe to changes in the power state of the device.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(SwitchPowerEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<SwitchPowerEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            STATUS_VARIABLE => value::to_bool(STATUS_VARIABLE, &value[..]).ok()
+                .map(|n| SwitchPowerEvent::Status(n)),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{SwitchPowerEvent};
+    
+    #[test]
+    fn positive_status_event() {
+        let body = "<e:propertyset><e:property><Status>1</Status></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set), vec![SwitchPowerEvent::Status(true)]);
+    }
+    
+    #[test]
+    fn positive_ignore_unknown_variable() {
+        let body = "<e:propertyset><e:property><Vendor>1</Vendor></e:property>\
+                    <e:property><Status>bogus</Status></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert!(super::to_events(&property_set).is_empty());
+    }
+}
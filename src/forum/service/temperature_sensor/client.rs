@@ -0,0 +1,125 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::temperature_sensor::{Temperature};
+use gena::{PropertySet, Subscription};
+
+const GET_CURRENT_TEMPERATURE_ACTION: &'static str = "GetCurrentTemperature";
+const GET_APPLICATION_ACTION:         &'static str = "GetApplication";
+const SET_APPLICATION_ACTION:         &'static str = "SetApplication";
+const GET_NAME_ACTION:                &'static str = "GetName";
+const SET_NAME_ACTION:                &'static str = "SetName";
+
+const CURRENT_TEMP_ARG:    &'static str = "CurrentTemp";
+const CURRENT_APP_ARG:     &'static str = "CurrentApp";
+const NEW_APPLICATION_ARG: &'static str = "NewApplication";
+const CURRENT_NAME_ARG:    &'static str = "CurrentName";
+const NEW_NAME_ARG:        &'static str = "NewName";
+
+const CURRENT_TEMPERATURE_VARIABLE: &'static str = "CurrentTemperature";
+const APPLICATION_VARIABLE:         &'static str = "Application";
+const NAME_VARIABLE:                &'static str = "Name";
+
+/// Evented state variables of a TemperatureSensor service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TemperatureSensorEvent {
+    /// Current temperature reported by the sensor.
+    CurrentTemperature(Temperature),
+    /// What the sensor is measuring (Room, Outdoor, etc.).
+    Application(String),
+    /// User assigned name of the sensor.
+    Name(String)
+}
+
+/// Client for a TemperatureSensor service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TemperatureSensorClient {
+    service: GenericService
+}
+
+impl TemperatureSensorClient {
+    pub fn new(service: GenericService) -> TemperatureSensorClient {
+        TemperatureSensorClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the current temperature reported by the sensor.
+    pub fn current_temperature(&self) -> SOAPResult<Temperature> {
+        let response = try!(self.service.send_action(GET_CURRENT_TEMPERATURE_ACTION, &[]));
+        
+        Temperature::from_value(CURRENT_TEMP_ARG, &try!(response.argument(CURRENT_TEMP_ARG))[..])
+    }
+    
+    /// Returns what the sensor is measuring (Room, Outdoor, etc.).
+    pub fn application(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_APPLICATION_ACTION, &[]));
+        
+        response.argument(CURRENT_APP_ARG)
+    }
+    
+    /// Set what the sensor is measuring.
+    pub fn set_application(&self, application: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_APPLICATION_ACTION, &[(NEW_APPLICATION_ARG, application)]));
+        
+        Ok(())
+    }
+    
+    /// Returns the user assigned name of the sensor.
+    pub fn name(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_NAME_ACTION, &[]));
+        
+        response.argument(CURRENT_NAME_ARG)
+    }
+    
+    /// Set the user assigned name of the sensor.
+    pub fn set_name(&self, name: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_NAME_ACTION, &[(NEW_NAME_ARG, name)]));
+        
+        Ok(())
+    }
+    
+    /// Subscribe to changes in the temperature reported by the sensor.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(TemperatureSensorEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<TemperatureSensorEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            CURRENT_TEMPERATURE_VARIABLE => Temperature::from_value(CURRENT_TEMPERATURE_VARIABLE,
+                &value[..]).ok().map(|n| TemperatureSensorEvent::CurrentTemperature(n)),
+            APPLICATION_VARIABLE => Some(TemperatureSensorEvent::Application(value.clone())),
+            NAME_VARIABLE        => Some(TemperatureSensorEvent::Name(value.clone())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use forum::service::temperature_sensor::{Temperature};
+    use gena::{PropertySet};
+    use super::{TemperatureSensorEvent};
+    
+    #[test]
+    fn positive_temperature_event() {
+        let body = "<e:propertyset><e:property><CurrentTemperature>2150</CurrentTemperature>\
+                    </e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![TemperatureSensorEvent::CurrentTemperature(Temperature(2150))]);
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod temperature;
+
+pub use self::client::{TemperatureSensorClient, TemperatureSensorEvent};
+pub use self::temperature::{Temperature, TEMPERATURE_MIN};
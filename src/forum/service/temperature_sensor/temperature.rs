@@ -0,0 +1,111 @@
+use {SOAPResult};
+use soap::value;
+
+/// Lowest temperature that can be represented (absolute zero).
+pub const TEMPERATURE_MIN: i32 = -27315;
+
+/// Temperature as transferred by HVAC services, in hundredths of a degree
+/// Celsius.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Temperature(pub i32);
+
+impl Temperature {
+    /// Create a Temperature from the given degrees Celsius.
+    ///
+    /// Values are rounded to the nearest hundredth of a degree. Returns None if
+    /// the value is not finite, is below absolute zero or is too large to be
+    /// represented.
+    pub fn from_celsius(degrees: f64) -> Option<Temperature> {
+        let hundredths = (degrees * 100.0).round();
+        
+        let in_range = hundredths >= TEMPERATURE_MIN as f64 && hundredths <= ::std::i32::MAX as f64;
+        
+        if !hundredths.is_finite() || !in_range {
+            None
+        } else {
+            Some(Temperature(hundredths as i32))
+        }
+    }
+    
+    /// Create a Temperature from the given degrees Fahrenheit.
+    ///
+    /// Values are rounded to the nearest hundredth of a degree Celsius. Returns
+    /// None under the same conditions as from_celsius.
+    pub fn from_fahrenheit(degrees: f64) -> Option<Temperature> {
+        Temperature::from_celsius((degrees - 32.0) * 5.0 / 9.0)
+    }
+    
+    /// Create a Temperature from the text value of an argument or state variable.
+    pub fn from_value(name: &'static str, value: &str) -> SOAPResult<Temperature> {
+        value::to_ranged(name, value, TEMPERATURE_MIN, ::std::i32::MAX).map(|n| Temperature(n))
+    }
+    
+    /// Returns the temperature in degrees Celsius.
+    pub fn celsius(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+    
+    /// Returns the temperature in degrees Fahrenheit.
+    pub fn fahrenheit(&self) -> f64 {
+        self.celsius() * 9.0 / 5.0 + 32.0
+    }
+    
+    /// Returns the text value used when sending the temperature to a service.
+    pub fn to_value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Temperature};
+    
+    #[test]
+    fn positive_celsius_round_trip() {
+        let temperature = Temperature::from_celsius(21.5).unwrap();
+        
+        assert_eq!(temperature, Temperature(2150));
+        assert_eq!(temperature.celsius(), 21.5);
+    }
+    
+    #[test]
+    fn positive_fahrenheit() {
+        assert_eq!(Temperature::from_fahrenheit(212.0), Some(Temperature(10000)));
+        assert_eq!(Temperature(0).fahrenheit(), 32.0);
+    }
+    
+    #[test]
+    fn positive_negative_value() {
+        assert_eq!(Temperature::from_value("CurrentTemp", "-550").unwrap(), Temperature(-550));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_celsius_below_absolute_zero() {
+        Temperature::from_celsius(-273.16).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_celsius_not_finite() {
+        Temperature::from_celsius(::std::f64::NAN).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_celsius_too_large() {
+        Temperature::from_celsius(1.0e12).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_fahrenheit_not_finite() {
+        Temperature::from_fahrenheit(::std::f64::INFINITY).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_below_absolute_zero() {
+        Temperature::from_value("CurrentTemp", "-27316").unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::temperature_sensor::{Temperature};
+use gena::{PropertySet, Subscription};
+use soap::value;
+
+const SET_CURRENT_SETPOINT_ACTION:  &'static str = "SetCurrentSetpoint";
+const GET_CURRENT_SETPOINT_ACTION:  &'static str = "GetCurrentSetpoint";
+const GET_SETPOINT_ACHIEVED_ACTION: &'static str = "GetSetpointAchieved";
+const GET_APPLICATION_ACTION:       &'static str = "GetApplication";
+const GET_NAME_ACTION:              &'static str = "GetName";
+const SET_NAME_ACTION:              &'static str = "SetName";
+
+const NEW_CURRENT_SETPOINT_ARG:      &'static str = "NewCurrentSetpoint";
+const CURRENT_SETPOINT_ARG:          &'static str = "CurrentSetpoint";
+const CURRENT_SETPOINT_ACHIEVED_ARG: &'static str = "CurrentSetpointAchieved";
+const CURRENT_APP_ARG:               &'static str = "CurrentApp";
+const CURRENT_NAME_ARG:              &'static str = "CurrentName";
+const NEW_NAME_ARG:                  &'static str = "NewName";
+
+const CURRENT_SETPOINT_VARIABLE:  &'static str = "CurrentSetpoint";
+const SETPOINT_ACHIEVED_VARIABLE: &'static str = "SetpointAchieved";
+const NAME_VARIABLE:              &'static str = "Name";
+
+/// Evented state variables of a TemperatureSetpoint service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TemperatureSetpointEvent {
+    /// Temperature that the system is trying to reach.
+    CurrentSetpoint(Temperature),
+    /// Whether or not the setpoint has been reached.
+    SetpointAchieved(bool),
+    /// User assigned name of the setpoint.
+    Name(String)
+}
+
+/// Client for a TemperatureSetpoint service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TemperatureSetpointClient {
+    service: GenericService
+}
+
+impl TemperatureSetpointClient {
+    pub fn new(service: GenericService) -> TemperatureSetpointClient {
+        TemperatureSetpointClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Set the temperature that the system should try to reach.
+    pub fn set_current_setpoint(&self, setpoint: Temperature) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_CURRENT_SETPOINT_ACTION,
+                                      &[(NEW_CURRENT_SETPOINT_ARG, &setpoint.to_value()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the temperature that the system is trying to reach.
+    pub fn current_setpoint(&self) -> SOAPResult<Temperature> {
+        let response = try!(self.service.send_action(GET_CURRENT_SETPOINT_ACTION, &[]));
+        
+        Temperature::from_value(CURRENT_SETPOINT_ARG, &try!(response.argument(CURRENT_SETPOINT_ARG))[..])
+    }
+    
+    /// Returns whether or not the setpoint has been reached.
+    pub fn setpoint_achieved(&self) -> SOAPResult<bool> {
+        let response = try!(self.service.send_action(GET_SETPOINT_ACHIEVED_ACTION, &[]));
+        
+        value::to_bool(CURRENT_SETPOINT_ACHIEVED_ARG,
+                       &try!(response.argument(CURRENT_SETPOINT_ACHIEVED_ARG))[..])
+    }
+    
+    /// Returns what the setpoint applies to (Heating, Cooling, etc.).
+    pub fn application(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_APPLICATION_ACTION, &[]));
+        
+        response.argument(CURRENT_APP_ARG)
+    }
+    
+    /// Returns the user assigned name of the setpoint.
+    pub fn name(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_NAME_ACTION, &[]));
+        
+        response.argument(CURRENT_NAME_ARG)
+    }
+    
+    /// Set the user assigned name of the setpoint.
+    pub fn set_name(&self, name: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_NAME_ACTION, &[(NEW_NAME_ARG, name)]));
+        
+        Ok(())
+    }
+    
+    /// Subscribe to changes in the setpoint.
+    ///
+    /// Values that could not be converted are not forwarded to the handler.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(TemperatureSetpointEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<TemperatureSetpointEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            CURRENT_SETPOINT_VARIABLE => Temperature::from_value(CURRENT_SETPOINT_VARIABLE,
+                &value[..]).ok().map(|n| TemperatureSetpointEvent::CurrentSetpoint(n)),
+            SETPOINT_ACHIEVED_VARIABLE => value::to_bool(SETPOINT_ACHIEVED_VARIABLE, &value[..])
+                .ok().map(|n| TemperatureSetpointEvent::SetpointAchieved(n)),
+            NAME_VARIABLE => Some(TemperatureSetpointEvent::Name(value.clone())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use forum::service::temperature_sensor::{Temperature};
+    use gena::{PropertySet};
+    use super::{TemperatureSetpointEvent};
+    
+    #[test]
+    fn positive_setpoint_events() {
+        let body = "<e:propertyset><e:property><CurrentSetpoint>-150</CurrentSetpoint></e:property>\
+                    <e:property><SetpointAchieved>0</SetpointAchieved></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![TemperatureSetpointEvent::CurrentSetpoint(Temperature(-150)),
+                        TemperatureSetpointEvent::SetpointAchieved(false)]);
+    }
+}
@@ -0,0 +1,267 @@
+use std::borrow::{ToOwned};
+
+use forum;
+use version::{Version};
+
+const SWITCH_POWER_NAME:             &'static str = "SwitchPower";
+const DIMMING_NAME:                  &'static str = "Dimming";
+const USER_OPERATING_MODE_NAME:      &'static str = "HVAC_UserOperatingMode";
+const TEMPERATURE_SENSOR_NAME:       &'static str = "TemperatureSensor";
+const TEMPERATURE_SETPOINT_NAME:     &'static str = "TemperatureSetpoint";
+const FAN_OPERATING_MODE_NAME:       &'static str = "HVAC_FanOperatingMode";
+const FAN_SPEED_NAME:                &'static str = "FanSpeed";
+const WLAN_CONFIGURATION_NAME:       &'static str = "WLANConfiguration";
+const PRINT_BASIC_NAME:              &'static str = "PrintBasic";
+const PRINT_ENHANCED_NAME:           &'static str = "PrintEnhanced";
+const SCAN_NAME:                     &'static str = "Scan";
+const CAMERA_SETTINGS_NAME:          &'static str = "DigitalSecurityCameraSettings";
+const MOTION_IMAGE_NAME:             &'static str = "DigitalSecurityCameraMotionImage";
+const STILL_IMAGE_NAME:              &'static str = "DigitalSecurityCameraStillImage";
+const SENSOR_TRANSPORT_NAME:         &'static str = "SensorTransportGeneric";
+const DATA_STORE_NAME:               &'static str = "DataStore";
+const CONFIGURATION_MANAGEMENT_NAME: &'static str = "ConfigurationManagement";
+const CALL_MANAGEMENT_NAME:          &'static str = "CallManagement";
+const MESSAGING_NAME:                &'static str = "Messaging";
+const PRESENCE_NAME:                 &'static str = "PresenceService";
+const ADDRESS_BOOK_NAME:             &'static str = "AddressBook";
+const BASIC_MANAGEMENT_NAME:         &'static str = "BasicManagement";
+const DEVICE_PROTECTION_NAME:        &'static str = "DeviceProtection";
+const WAN_IP_CONNECTION_NAME:        &'static str = "WANIPConnection";
+
+/// Service types included in the UPnP Forum layer of the UPnP architecture.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ServiceType {
+    /// Service that provides an interface for toggling power.
+    SwitchPower(Version),
+    /// Service that provides an interface for dimming a light.
+    Dimming(Version),
+    /// Service that provides an interface for setting the operating mode of an HVAC system.
+    UserOperatingMode(Version),
+    /// Service that provides an interface for reading a temperature.
+    TemperatureSensor(Version),
+    /// Service that provides an interface for adjusting a temperature setpoint.
+    TemperatureSetpoint(Version),
+    /// Service that provides an interface for setting the operating mode of a fan.
+    FanOperatingMode(Version),
+    /// Service that provides an interface for adjusting the speed of a fan.
+    FanSpeed(Version),
+    /// Service that provides an interface for configuring a wireless access point.
+    WLANConfiguration(Version),
+    /// Service that provides an interface for basic printing.
+    PrintBasic(Version),
+    /// Service that provides an interface for printing with extended job control.
+    PrintEnhanced(Version),
+    /// Service that provides an interface for scanning documents.
+    Scan(Version),
+    /// Service that provides an interface for adjusting the picture of a security camera.
+    CameraSettings(Version),
+    /// Service that provides an interface for retrieving video from a security camera.
+    MotionImage(Version),
+    /// Service that provides an interface for retrieving snapshots from a security camera.
+    StillImage(Version),
+    /// Service that provides an interface for reading and writing sensor data.
+    SensorTransportGeneric(Version),
+    /// Service that provides an interface for storing sensor data in tables.
+    DataStore(Version),
+    /// Service that provides an interface for reading and changing the data model of a device.
+    ConfigurationManagement(Version),
+    /// Service that provides an interface for placing and answering calls.
+    CallManagement(Version),
+    /// Service that provides an interface for sending and reading messages.
+    Messaging(Version),
+    /// Service that provides an interface for sharing the availability of contacts.
+    Presence(Version),
+    /// Service that provides an interface for managing contacts.
+    AddressBook(Version),
+    /// Service that provides an interface for diagnosing and rebooting a device.
+    BasicManagement(Version),
+    /// Service that provides an interface for authenticating control points.
+    DeviceProtection(Version),
+    /// Service that provides an interface for mapping ports on an internet gateway.
+    WANIPConnection(Version),
+    /// Service that has not been implemented.
+    Unimplemented(String, Version),
+    /// Service not included in the UPnP Forum layer, along with its schema.
+    Vendor(String, String, Version)
+}
+
+impl ServiceType {
+    /// Create a new ServiceType from the given values.
+    pub fn new(schema: &str, srv_type: &str, version: Version) -> ServiceType {
+        match schema {
+            forum::UPNP_SCHEMA_VALUE => match_service_name(srv_type, version),
+            _ => ServiceType::Vendor(schema.to_owned(), srv_type.to_owned(), version)
+        }
+    }
+    
+    pub fn version(&self) -> Version {
+        match *self {
+            ServiceType::SwitchPower(n)             => n,
+            ServiceType::Dimming(n)                 => n,
+            ServiceType::UserOperatingMode(n)       => n,
+            ServiceType::TemperatureSensor(n)       => n,
+            ServiceType::TemperatureSetpoint(n)     => n,
+            ServiceType::FanOperatingMode(n)        => n,
+            ServiceType::FanSpeed(n)                => n,
+            ServiceType::WLANConfiguration(n)       => n,
+            ServiceType::PrintBasic(n)              => n,
+            ServiceType::PrintEnhanced(n)           => n,
+            ServiceType::Scan(n)                    => n,
+            ServiceType::CameraSettings(n)          => n,
+            ServiceType::MotionImage(n)             => n,
+            ServiceType::StillImage(n)              => n,
+            ServiceType::SensorTransportGeneric(n)  => n,
+            ServiceType::DataStore(n)               => n,
+            ServiceType::ConfigurationManagement(n) => n,
+            ServiceType::CallManagement(n)          => n,
+            ServiceType::Messaging(n)               => n,
+            ServiceType::Presence(n)                => n,
+            ServiceType::AddressBook(n)             => n,
+            ServiceType::BasicManagement(n)         => n,
+            ServiceType::DeviceProtection(n)        => n,
+            ServiceType::WANIPConnection(n)         => n,
+            ServiceType::Unimplemented(_, n)        => n,
+            ServiceType::Vendor(_, _, n)            => n
+        }
+    }
+    
+    /// Returns the service type name as it appears in a urn.
+    pub fn name(&self) -> &str {
+        match *self {
+            ServiceType::SwitchPower(_)             => SWITCH_POWER_NAME,
+            ServiceType::Dimming(_)                 => DIMMING_NAME,
+            ServiceType::UserOperatingMode(_)       => USER_OPERATING_MODE_NAME,
+            ServiceType::TemperatureSensor(_)       => TEMPERATURE_SENSOR_NAME,
+            ServiceType::TemperatureSetpoint(_)     => TEMPERATURE_SETPOINT_NAME,
+            ServiceType::FanOperatingMode(_)        => FAN_OPERATING_MODE_NAME,
+            ServiceType::FanSpeed(_)                => FAN_SPEED_NAME,
+            ServiceType::WLANConfiguration(_)       => WLAN_CONFIGURATION_NAME,
+            ServiceType::PrintBasic(_)              => PRINT_BASIC_NAME,
+            ServiceType::PrintEnhanced(_)           => PRINT_ENHANCED_NAME,
+            ServiceType::Scan(_)                    => SCAN_NAME,
+            ServiceType::CameraSettings(_)          => CAMERA_SETTINGS_NAME,
+            ServiceType::MotionImage(_)             => MOTION_IMAGE_NAME,
+            ServiceType::StillImage(_)              => STILL_IMAGE_NAME,
+            ServiceType::SensorTransportGeneric(_)  => SENSOR_TRANSPORT_NAME,
+            ServiceType::DataStore(_)               => DATA_STORE_NAME,
+            ServiceType::ConfigurationManagement(_) => CONFIGURATION_MANAGEMENT_NAME,
+            ServiceType::CallManagement(_)          => CALL_MANAGEMENT_NAME,
+            ServiceType::Messaging(_)               => MESSAGING_NAME,
+            ServiceType::Presence(_)                => PRESENCE_NAME,
+            ServiceType::AddressBook(_)             => ADDRESS_BOOK_NAME,
+            ServiceType::BasicManagement(_)         => BASIC_MANAGEMENT_NAME,
+            ServiceType::DeviceProtection(_)        => DEVICE_PROTECTION_NAME,
+            ServiceType::WANIPConnection(_)         => WAN_IP_CONNECTION_NAME,
+            ServiceType::Unimplemented(ref n, _)    => &n[..],
+            ServiceType::Vendor(_, ref n, _)        => &n[..]
+        }
+    }
+    
+    /// Returns the schema that the service type is defined under.
+    pub fn schema(&self) -> &str {
+        match *self {
+            ServiceType::Vendor(ref n, _, _) => &n[..],
+            _ => forum::UPNP_SCHEMA_VALUE
+        }
+    }
+    
+    /// Returns the full urn identifying this service type.
+    pub fn urn(&self) -> String {
+        format!("urn:{}:{}:{}:{}", self.schema(), forum::URN_SERVICE_VALUE,
+                self.name(), self.version().to_u8())
+    }
+    
+    /// Returns true if a service advertised as the given type can be used in
+    /// place of this service type.
+    ///
+    /// Services are backwards compatible, so any later version of the same
+    /// service type is acceptable.
+    pub fn is_compatible(&self, advertised: &ServiceType) -> bool {
+        self.schema() == advertised.schema() && self.name() == advertised.name() &&
+            self.version() <= advertised.version()
+    }
+}
+
+/// Match the type to a service name.
+///
+/// Returns the appropriate ServiceType.
+fn match_service_name(srv_type: &str, version: Version) -> ServiceType {
+    match srv_type {
+        SWITCH_POWER_NAME             => ServiceType::SwitchPower(version),
+        DIMMING_NAME                  => ServiceType::Dimming(version),
+        USER_OPERATING_MODE_NAME      => ServiceType::UserOperatingMode(version),
+        TEMPERATURE_SENSOR_NAME       => ServiceType::TemperatureSensor(version),
+        TEMPERATURE_SETPOINT_NAME     => ServiceType::TemperatureSetpoint(version),
+        FAN_OPERATING_MODE_NAME       => ServiceType::FanOperatingMode(version),
+        FAN_SPEED_NAME                => ServiceType::FanSpeed(version),
+        WLAN_CONFIGURATION_NAME       => ServiceType::WLANConfiguration(version),
+        PRINT_BASIC_NAME              => ServiceType::PrintBasic(version),
+        PRINT_ENHANCED_NAME           => ServiceType::PrintEnhanced(version),
+        SCAN_NAME                     => ServiceType::Scan(version),
+        CAMERA_SETTINGS_NAME          => ServiceType::CameraSettings(version),
+        MOTION_IMAGE_NAME             => ServiceType::MotionImage(version),
+        STILL_IMAGE_NAME              => ServiceType::StillImage(version),
+        SENSOR_TRANSPORT_NAME         => ServiceType::SensorTransportGeneric(version),
+        DATA_STORE_NAME               => ServiceType::DataStore(version),
+        CONFIGURATION_MANAGEMENT_NAME => ServiceType::ConfigurationManagement(version),
+        CALL_MANAGEMENT_NAME          => ServiceType::CallManagement(version),
+        MESSAGING_NAME                => ServiceType::Messaging(version),
+        PRESENCE_NAME                 => ServiceType::Presence(version),
+        ADDRESS_BOOK_NAME             => ServiceType::AddressBook(version),
+        BASIC_MANAGEMENT_NAME         => ServiceType::BasicManagement(version),
+        DEVICE_PROTECTION_NAME        => ServiceType::DeviceProtection(version),
+        WAN_IP_CONNECTION_NAME        => ServiceType::WANIPConnection(version),
+        _ => ServiceType::Unimplemented(srv_type.to_owned(), version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceType};
+    use version::{Version};
+
+    #[test]
+    fn positive_upnp_schema() {
+        let service_type = ServiceType::new("schemas-upnp-org", "SwitchPower", Version::V1);
+        
+        assert_eq!(service_type, ServiceType::SwitchPower(Version::V1));
+    }
+    
+    #[test]
+    fn positive_unimplemented_service() {
+        let service_type = ServiceType::new("schemas-upnp-org", "Blargenfargen", Version::V1);
+        
+        assert_eq!(service_type, ServiceType::Unimplemented("Blargenfargen".to_string(), Version::V1));
+    }
+    
+    #[test]
+    fn positive_vendor_service() {
+        let service_type = ServiceType::new("schemas-example-com", "Blargenfargen", Version::V1);
+        
+        assert_eq!(service_type, ServiceType::Vendor("schemas-example-com".to_string(),
+            "Blargenfargen".to_string(), Version::V1));
+        assert_eq!(service_type.urn(), "urn:schemas-example-com:service:Blargenfargen:1");
+    }
+    
+    #[test]
+    fn positive_urn() {
+        let service_type = ServiceType::Dimming(Version::V1);
+        
+        assert_eq!(service_type.urn(), "urn:schemas-upnp-org:service:Dimming:1");
+    }
+    
+    #[test]
+    fn positive_compatible_later_version() {
+        let wanted = ServiceType::SwitchPower(Version::V1);
+        
+        assert!(wanted.is_compatible(&ServiceType::SwitchPower(Version::V2)));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_compatible_earlier_version() {
+        let wanted = ServiceType::SwitchPower(Version::V2);
+        
+        assert!(wanted.is_compatible(&ServiceType::SwitchPower(Version::V1)));
+    }
+}
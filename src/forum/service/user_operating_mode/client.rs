@@ -0,0 +1,199 @@
+use std::borrow::{ToOwned};
+
+use {GENAResult, SOAPResult};
+use forum::service::{GenericService};
+use gena::{PropertySet, Subscription};
+
+const SET_MODE_TARGET_ACTION: &'static str = "SetModeTarget";
+const GET_MODE_TARGET_ACTION: &'static str = "GetModeTarget";
+const GET_MODE_STATUS_ACTION: &'static str = "GetModeStatus";
+const GET_NAME_ACTION:        &'static str = "GetName";
+const SET_NAME_ACTION:        &'static str = "SetName";
+
+const NEW_MODE_TARGET_ARG:     &'static str = "NewModeTarget";
+const CURRENT_MODE_TARGET_ARG: &'static str = "CurrentModeTarget";
+const CURRENT_MODE_STATUS_ARG: &'static str = "CurrentModeStatus";
+const CURRENT_NAME_ARG:        &'static str = "CurrentName";
+const NEW_NAME_ARG:            &'static str = "NewName";
+
+const MODE_STATUS_VARIABLE: &'static str = "ModeStatus";
+const NAME_VARIABLE:        &'static str = "Name";
+
+const OFF_VALUE:                 &'static str = "Off";
+const HEAT_ON_VALUE:             &'static str = "HeatOn";
+const COOL_ON_VALUE:             &'static str = "CoolOn";
+const AUTO_CHANGE_OVER_VALUE:    &'static str = "AutoChangeOver";
+const AUX_HEAT_ON_VALUE:         &'static str = "AuxHeatOn";
+const ECONOMY_HEAT_ON_VALUE:     &'static str = "EconomyHeatOn";
+const EMERGENCY_HEAT_ON_VALUE:   &'static str = "EmergencyHeatOn";
+const AUX_COOL_ON_VALUE:         &'static str = "AuxCoolOn";
+const ECONOMY_COOL_ON_VALUE:     &'static str = "EconomyCoolOn";
+const BUILDING_PROTECTION_VALUE: &'static str = "BuildingProtection";
+const ENERGY_SAVINGS_MODE_VALUE: &'static str = "EnergySavingsMode";
+
+/// Operating modes of an HVAC system.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OperatingMode {
+    Off,
+    HeatOn,
+    CoolOn,
+    AutoChangeOver,
+    AuxHeatOn,
+    EconomyHeatOn,
+    EmergencyHeatOn,
+    AuxCoolOn,
+    EconomyCoolOn,
+    BuildingProtection,
+    EnergySavingsMode,
+    /// Vendor defined operating mode.
+    Other(String)
+}
+
+impl OperatingMode {
+    /// Convert the text value of an argument or state variable to an OperatingMode.
+    pub fn from_value(value: &str) -> OperatingMode {
+        match value.trim() {
+            OFF_VALUE                 => OperatingMode::Off,
+            HEAT_ON_VALUE             => OperatingMode::HeatOn,
+            COOL_ON_VALUE             => OperatingMode::CoolOn,
+            AUTO_CHANGE_OVER_VALUE    => OperatingMode::AutoChangeOver,
+            AUX_HEAT_ON_VALUE         => OperatingMode::AuxHeatOn,
+            ECONOMY_HEAT_ON_VALUE     => OperatingMode::EconomyHeatOn,
+            EMERGENCY_HEAT_ON_VALUE   => OperatingMode::EmergencyHeatOn,
+            AUX_COOL_ON_VALUE         => OperatingMode::AuxCoolOn,
+            ECONOMY_COOL_ON_VALUE     => OperatingMode::EconomyCoolOn,
+            BUILDING_PROTECTION_VALUE => OperatingMode::BuildingProtection,
+            ENERGY_SAVINGS_MODE_VALUE => OperatingMode::EnergySavingsMode,
+            n => OperatingMode::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns the text value used when sending the mode to a service.
+    pub fn as_value(&self) -> &str {
+        match *self {
+            OperatingMode::Off                => OFF_VALUE,
+            OperatingMode::HeatOn             => HEAT_ON_VALUE,
+            OperatingMode::CoolOn             => COOL_ON_VALUE,
+            OperatingMode::AutoChangeOver     => AUTO_CHANGE_OVER_VALUE,
+            OperatingMode::AuxHeatOn          => AUX_HEAT_ON_VALUE,
+            OperatingMode::EconomyHeatOn      => ECONOMY_HEAT_ON_VALUE,
+            OperatingMode::EmergencyHeatOn    => EMERGENCY_HEAT_ON_VALUE,
+            OperatingMode::AuxCoolOn          => AUX_COOL_ON_VALUE,
+            OperatingMode::EconomyCoolOn      => ECONOMY_COOL_ON_VALUE,
+            OperatingMode::BuildingProtection => BUILDING_PROTECTION_VALUE,
+            OperatingMode::EnergySavingsMode  => ENERGY_SAVINGS_MODE_VALUE,
+            OperatingMode::Other(ref n)       => &n[..]
+        }
+    }
+}
+
+/// Evented state variables of an HVAC_UserOperatingMode service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum UserOperatingModeEvent {
+    /// Operating mode that the system is currently in.
+    ModeStatus(OperatingMode),
+    /// User assigned name of the service.
+    Name(String)
+}
+
+/// Client for an HVAC_UserOperatingMode service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct UserOperatingModeClient {
+    service: GenericService
+}
+
+impl UserOperatingModeClient {
+    pub fn new(service: GenericService) -> UserOperatingModeClient {
+        UserOperatingModeClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Set the operating mode that the system should be in.
+    pub fn set_mode_target(&self, mode: &OperatingMode) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_MODE_TARGET_ACTION, &[(NEW_MODE_TARGET_ARG, mode.as_value())]));
+        
+        Ok(())
+    }
+    
+    /// Returns the operating mode that the system was last asked to be in.
+    pub fn mode_target(&self) -> SOAPResult<OperatingMode> {
+        let response = try!(self.service.send_action(GET_MODE_TARGET_ACTION, &[]));
+        
+        Ok(OperatingMode::from_value(&try!(response.argument(CURRENT_MODE_TARGET_ARG))[..]))
+    }
+    
+    /// Returns the operating mode that the system is currently in.
+    pub fn mode_status(&self) -> SOAPResult<OperatingMode> {
+        let response = try!(self.service.send_action(GET_MODE_STATUS_ACTION, &[]));
+        
+        Ok(OperatingMode::from_value(&try!(response.argument(CURRENT_MODE_STATUS_ARG))[..]))
+    }
+    
+    /// Returns the user assigned name of the service.
+    pub fn name(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_NAME_ACTION, &[]));
+        
+        response.argument(CURRENT_NAME_ARG)
+    }
+    
+    /// Set the user assigned name of the service.
+    pub fn set_name(&self, name: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_NAME_ACTION, &[(NEW_NAME_ARG, name)]));
+        
+        Ok(())
+    }
+    
+    /// Subscribe to changes in the operating mode of the system.
+    pub fn subscribe<F>(&self, mut handler: F) -> GENAResult<Subscription>
+        where F: FnMut(UserOperatingModeEvent) + Send + 'static {
+        self.service.subscribe(move |property_set: PropertySet| {
+            for event in to_events(&property_set) {
+                handler(event);
+            }
+        })
+    }
+}
+
+/// Convert the property set of an event message into typed events.
+fn to_events(property_set: &PropertySet) -> Vec<UserOperatingModeEvent> {
+    property_set.iter().filter_map(|&(ref name, ref value)| {
+        match &name[..] {
+            MODE_STATUS_VARIABLE => Some(UserOperatingModeEvent::ModeStatus(OperatingMode::from_value(&value[..]))),
+            NAME_VARIABLE        => Some(UserOperatingModeEvent::Name(value.clone())),
+            _ => None
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gena::{PropertySet};
+    use super::{OperatingMode, UserOperatingModeEvent};
+    
+    #[test]
+    fn positive_standard_mode() {
+        assert_eq!(OperatingMode::from_value("AutoChangeOver"), OperatingMode::AutoChangeOver);
+        assert_eq!(OperatingMode::AutoChangeOver.as_value(), "AutoChangeOver");
+    }
+    
+    #[test]
+    fn positive_vendor_mode() {
+        let mode = OperatingMode::from_value("X_DehumidifyOnly");
+        
+        assert_eq!(mode, OperatingMode::Other("X_DehumidifyOnly".to_string()));
+        assert_eq!(mode.as_value(), "X_DehumidifyOnly");
+    }
+    
+    #[test]
+    fn positive_mode_status_event() {
+        let body = "<e:propertyset><e:property><ModeStatus>HeatOn</ModeStatus></e:property></e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(super::to_events(&property_set),
+                   vec![UserOperatingModeEvent::ModeStatus(OperatingMode::HeatOn)]);
+    }
+}
@@ -0,0 +1,3 @@
+mod client;
+
+pub use self::client::{OperatingMode, UserOperatingModeClient, UserOperatingModeEvent};
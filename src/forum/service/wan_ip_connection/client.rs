@@ -0,0 +1,181 @@
+use std::net::{Ipv4Addr};
+
+use {SOAPError, SOAPResult};
+use forum::service::{GenericService};
+use forum::service::wan_ip_connection::{PortMapping, Protocol};
+use soap::{value, ActionResponse};
+
+/// Fault sent back when no port mapping exists at the given index.
+pub const ARRAY_INDEX_INVALID_CODE:       u16 = 713;
+/// Fault sent back when the requested port mapping does not exist.
+pub const NO_SUCH_ENTRY_CODE:             u16 = 714;
+/// Fault sent back when the port mapping conflicts with one that is assigned
+/// to another client.
+pub const CONFLICT_IN_MAPPING_ENTRY_CODE: u16 = 718;
+
+const ADD_PORT_MAPPING_ACTION:        &'static str = "AddPortMapping";
+const ADD_ANY_PORT_MAPPING_ACTION:    &'static str = "AddAnyPortMapping";
+const DELETE_PORT_MAPPING_ACTION:     &'static str = "DeletePortMapping";
+const GET_SPECIFIC_ENTRY_ACTION:      &'static str = "GetSpecificPortMappingEntry";
+const GET_GENERIC_ENTRY_ACTION:       &'static str = "GetGenericPortMappingEntry";
+const GET_EXTERNAL_IP_ADDRESS_ACTION: &'static str = "GetExternalIPAddress";
+
+const REMOTE_HOST_ARG:         &'static str = "NewRemoteHost";
+const EXTERNAL_PORT_ARG:       &'static str = "NewExternalPort";
+const PROTOCOL_ARG:            &'static str = "NewProtocol";
+const INTERNAL_PORT_ARG:       &'static str = "NewInternalPort";
+const INTERNAL_CLIENT_ARG:     &'static str = "NewInternalClient";
+const ENABLED_ARG:             &'static str = "NewEnabled";
+const DESCRIPTION_ARG:         &'static str = "NewPortMappingDescription";
+const LEASE_DURATION_ARG:      &'static str = "NewLeaseDuration";
+const RESERVED_PORT_ARG:       &'static str = "NewReservedPort";
+const PORT_MAPPING_INDEX_ARG:  &'static str = "NewPortMappingIndex";
+const EXTERNAL_IP_ADDRESS_ARG: &'static str = "NewExternalIPAddress";
+
+/// Client for a WANIPConnection service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WANIPConnectionClient {
+    service: GenericService
+}
+
+impl WANIPConnectionClient {
+    pub fn new(service: GenericService) -> WANIPConnectionClient {
+        WANIPConnectionClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Add the port mapping, or update it if it already exists for this client.
+    ///
+    /// Fails with a CONFLICT_IN_MAPPING_ENTRY_CODE fault if the external port
+    /// is already mapped to another client.
+    pub fn add_port_mapping(&self, mapping: &PortMapping) -> SOAPResult<()> {
+        try!(self.send_mapping(ADD_PORT_MAPPING_ACTION, mapping));
+        
+        Ok(())
+    }
+    
+    /// Add the port mapping, letting the gateway pick another external port if
+    /// the requested one is taken.
+    ///
+    /// Returns the external port that was reserved. Only supported by version
+    /// 2 of the service.
+    pub fn add_any_port_mapping(&self, mapping: &PortMapping) -> SOAPResult<u16> {
+        let response = try!(self.send_mapping(ADD_ANY_PORT_MAPPING_ACTION, mapping));
+        
+        value::to_number(RESERVED_PORT_ARG, &try!(response.argument(RESERVED_PORT_ARG))[..])
+    }
+    
+    /// Delete the port mapping for the given remote host, external port and protocol.
+    pub fn delete_port_mapping(&self, remote_host: &str, external_port: u16, protocol: Protocol)
+        -> SOAPResult<()> {
+        let external_port = external_port.to_string();
+        
+        try!(self.service.send_action(DELETE_PORT_MAPPING_ACTION, &[(REMOTE_HOST_ARG, remote_host),
+            (EXTERNAL_PORT_ARG, &external_port[..]), (PROTOCOL_ARG, protocol.value())]));
+        
+        Ok(())
+    }
+    
+    /// Returns the port mapping for the given remote host, external port and protocol.
+    pub fn port_mapping(&self, remote_host: &str, external_port: u16, protocol: Protocol)
+        -> SOAPResult<PortMapping> {
+        let port_str = external_port.to_string();
+        let response = try!(self.service.send_action(GET_SPECIFIC_ENTRY_ACTION,
+            &[(REMOTE_HOST_ARG, remote_host), (EXTERNAL_PORT_ARG, &port_str[..]),
+              (PROTOCOL_ARG, protocol.value())]));
+        
+        to_mapping(&response, remote_host, external_port, protocol)
+    }
+    
+    /// Returns the port mapping at the given index.
+    ///
+    /// Fails with an ARRAY_INDEX_INVALID_CODE fault once the index runs past
+    /// the last port mapping, which can be used to list every port mapping.
+    pub fn port_mapping_at(&self, index: u16) -> SOAPResult<PortMapping> {
+        let index = index.to_string();
+        let response = try!(self.service.send_action(GET_GENERIC_ENTRY_ACTION,
+            &[(PORT_MAPPING_INDEX_ARG, &index[..])]));
+        
+        let remote_host = try!(response.argument(REMOTE_HOST_ARG));
+        let external_port = try!(value::to_number(EXTERNAL_PORT_ARG,
+            &try!(response.argument(EXTERNAL_PORT_ARG))[..]));
+        let protocol = try!(Protocol::new(&try!(response.argument(PROTOCOL_ARG))[..]).ok_or(
+            SOAPError::InvalidArgument(PROTOCOL_ARG, "Value Is Not A Valid Protocol")
+        ));
+        
+        to_mapping(&response, &remote_host[..], external_port, protocol)
+    }
+    
+    /// Returns the external address of the gateway.
+    pub fn external_ip_address(&self) -> SOAPResult<Ipv4Addr> {
+        let response = try!(self.service.send_action(GET_EXTERNAL_IP_ADDRESS_ACTION, &[]));
+        
+        value::to_number(EXTERNAL_IP_ADDRESS_ARG, &try!(response.argument(EXTERNAL_IP_ADDRESS_ARG))[..])
+    }
+    
+    /// Send an action that takes every field of a port mapping as arguments.
+    fn send_mapping(&self, action: &str, mapping: &PortMapping) -> SOAPResult<ActionResponse> {
+        let external_port = mapping.external_port().to_string();
+        let internal_port = mapping.internal_port().to_string();
+        let lease_duration = mapping.lease_duration().to_string();
+        
+        self.service.send_action(action, &[(REMOTE_HOST_ARG, mapping.remote_host()),
+            (EXTERNAL_PORT_ARG, &external_port[..]), (PROTOCOL_ARG, mapping.protocol().value()),
+            (INTERNAL_PORT_ARG, &internal_port[..]), (INTERNAL_CLIENT_ARG, mapping.internal_client()),
+            (ENABLED_ARG, value::from_bool(mapping.enabled())), (DESCRIPTION_ARG, mapping.description()),
+            (LEASE_DURATION_ARG, &lease_duration[..])])
+    }
+}
+
+/// Build a port mapping from the out arguments of a port mapping entry.
+fn to_mapping(response: &ActionResponse, remote_host: &str, external_port: u16, protocol: Protocol)
+    -> SOAPResult<PortMapping> {
+    let internal_port = try!(value::to_number(INTERNAL_PORT_ARG,
+        &try!(response.argument(INTERNAL_PORT_ARG))[..]));
+    let enabled = try!(value::to_bool(ENABLED_ARG, &try!(response.argument(ENABLED_ARG))[..]));
+    let lease_duration = try!(value::to_number(LEASE_DURATION_ARG,
+        &try!(response.argument(LEASE_DURATION_ARG))[..]));
+    
+    Ok(PortMapping::new(protocol, external_port, &try!(response.argument(INTERNAL_CLIENT_ARG))[..],
+        internal_port).with_remote_host(remote_host).with_enabled(enabled)
+        .with_description(&try!(response.argument(DESCRIPTION_ARG))[..]).with_lease_duration(lease_duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use forum::service::wan_ip_connection::{Protocol};
+    use soap::{ActionResponse};
+    
+    #[test]
+    fn positive_mapping_entry() {
+        let payload = "<u:GetSpecificPortMappingEntryResponse>\
+                       <NewInternalPort>8080</NewInternalPort>\
+                       <NewInternalClient>192.168.1.20</NewInternalClient>\
+                       <NewEnabled>1</NewEnabled><NewPortMappingDescription>web</NewPortMappingDescription>\
+                       <NewLeaseDuration>3600</NewLeaseDuration></u:GetSpecificPortMappingEntryResponse>";
+        let response = ActionResponse::new("GetSpecificPortMappingEntry", payload).unwrap();
+        let mapping = super::to_mapping(&response, "", 80, Protocol::TCP).unwrap();
+        
+        assert_eq!(mapping.internal_client(), "192.168.1.20");
+        assert_eq!(mapping.internal_port(), 8080);
+        assert_eq!(mapping.lease_duration(), 3600);
+        assert!(mapping.enabled());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_invalid_internal_port() {
+        let payload = "<u:GetSpecificPortMappingEntryResponse>\
+                       <NewInternalPort>99999</NewInternalPort>\
+                       <NewInternalClient>192.168.1.20</NewInternalClient>\
+                       <NewEnabled>1</NewEnabled><NewPortMappingDescription></NewPortMappingDescription>\
+                       <NewLeaseDuration>0</NewLeaseDuration></u:GetSpecificPortMappingEntryResponse>";
+        let response = ActionResponse::new("GetSpecificPortMappingEntry", payload).unwrap();
+        
+        super::to_mapping(&response, "", 80, Protocol::TCP).unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+use std::ascii::{AsciiExt};
+
+const TCP_VALUE: &'static str = "TCP";
+const UDP_VALUE: &'static str = "UDP";
+
+/// Transport protocol of a port mapping.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Protocol {
+    TCP,
+    UDP
+}
+
+impl Protocol {
+    /// Create a new Protocol from its UPnP value.
+    pub fn new(value: &str) -> Option<Protocol> {
+        let value = value.trim();
+        
+        if value.eq_ignore_ascii_case(TCP_VALUE) {
+            Some(Protocol::TCP)
+        } else if value.eq_ignore_ascii_case(UDP_VALUE) {
+            Some(Protocol::UDP)
+        } else {
+            None
+        }
+    }
+    
+    /// Returns the UPnP value of the protocol.
+    pub fn value(&self) -> &'static str {
+        match *self {
+            Protocol::TCP => TCP_VALUE,
+            Protocol::UDP => UDP_VALUE
+        }
+    }
+}
+
+/// Port mapping from an external port on the gateway to a port on some
+/// client inside the network.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PortMapping {
+    remote_host:     String,
+    external_port:   u16,
+    protocol:        Protocol,
+    internal_port:   u16,
+    internal_client: String,
+    enabled:         bool,
+    description:     String,
+    lease_duration:  u32
+}
+
+impl PortMapping {
+    /// Create a new PortMapping that is enabled, accepts traffic from any
+    /// remote host and never expires.
+    pub fn new(protocol: Protocol, external_port: u16, internal_client: &str, internal_port: u16)
+        -> PortMapping {
+        PortMapping{ remote_host: String::new(), external_port: external_port, protocol: protocol,
+            internal_port: internal_port, internal_client: internal_client.to_string(), enabled: true,
+            description: String::new(), lease_duration: 0 }
+    }
+    
+    /// Only accept traffic from the given remote host.
+    pub fn with_remote_host(mut self, remote_host: &str) -> PortMapping {
+        self.remote_host = remote_host.to_string();
+        
+        self
+    }
+    
+    /// Set the human readable description of the port mapping.
+    pub fn with_description(mut self, description: &str) -> PortMapping {
+        self.description = description.to_string();
+        
+        self
+    }
+    
+    /// Have the port mapping expire after the given number of seconds.
+    ///
+    /// A lease duration of 0 denotes a permanent port mapping.
+    pub fn with_lease_duration(mut self, seconds: u32) -> PortMapping {
+        self.lease_duration = seconds;
+        
+        self
+    }
+    
+    /// Set whether the port mapping is enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> PortMapping {
+        self.enabled = enabled;
+        
+        self
+    }
+    
+    /// Returns the remote host traffic is accepted from, or an empty string if
+    /// traffic from any remote host is accepted.
+    pub fn remote_host(&self) -> &str {
+        &self.remote_host[..]
+    }
+    
+    pub fn external_port(&self) -> u16 {
+        self.external_port
+    }
+    
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+    
+    pub fn internal_port(&self) -> u16 {
+        self.internal_port
+    }
+    
+    pub fn internal_client(&self) -> &str {
+        &self.internal_client[..]
+    }
+    
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    pub fn description(&self) -> &str {
+        &self.description[..]
+    }
+    
+    /// Returns the number of seconds the port mapping is leased for, or 0 for
+    /// a permanent port mapping.
+    pub fn lease_duration(&self) -> u32 {
+        self.lease_duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Protocol};
+    
+    #[test]
+    fn positive_lowercase_protocol() {
+        assert_eq!(Protocol::new("udp"), Some(Protocol::UDP));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_unknown_protocol() {
+        Protocol::new("SCTP").unwrap();
+    }
+}
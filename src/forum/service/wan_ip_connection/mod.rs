@@ -0,0 +1,6 @@
+mod client;
+mod mapping;
+
+pub use self::client::{WANIPConnectionClient, ARRAY_INDEX_INVALID_CODE, CONFLICT_IN_MAPPING_ENTRY_CODE,
+                       NO_SUCH_ENTRY_CODE};
+pub use self::mapping::{PortMapping, Protocol};
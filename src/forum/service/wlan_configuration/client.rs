@@ -0,0 +1,358 @@
+use {SOAPResult};
+use forum::service::{GenericService};
+use forum::service::wlan_configuration::{AuthenticationMode, BeaconType, EncryptionModes,
+    SecurityKeys, SecurityProperties};
+use soap::{ActionResponse};
+use soap::value;
+
+/// Largest index that a WEP key may be stored at.
+const WEP_KEY_INDEX_MAX: u8 = 3;
+
+const CHANNEL_LIST_SEP:  char = ',';
+const CHANNEL_RANGE_SEP: char = '-';
+
+const GET_SSID_ACTION:                      &'static str = "GetSSID";
+const SET_SSID_ACTION:                      &'static str = "SetSSID";
+const GET_BSSID_ACTION:                     &'static str = "GetBSSID";
+const GET_CHANNEL_INFO_ACTION:              &'static str = "GetChannelInfo";
+const SET_CHANNEL_ACTION:                   &'static str = "SetChannel";
+const GET_BEACON_TYPE_ACTION:               &'static str = "GetBeaconType";
+const SET_BEACON_TYPE_ACTION:               &'static str = "SetBeaconType";
+const GET_SECURITY_KEYS_ACTION:             &'static str = "GetSecurityKeys";
+const SET_SECURITY_KEYS_ACTION:             &'static str = "SetSecurityKeys";
+const GET_DEFAULT_WEP_KEY_INDEX_ACTION:     &'static str = "GetDefaultWEPKeyIndex";
+const SET_DEFAULT_WEP_KEY_INDEX_ACTION:     &'static str = "SetDefaultWEPKeyIndex";
+const GET_BASIC_SECURITY_ACTION:            &'static str = "GetBasBeaconSecurityProperties";
+const SET_BASIC_SECURITY_ACTION:            &'static str = "SetBasBeaconSecurityProperties";
+const GET_WPA_SECURITY_ACTION:              &'static str = "GetWPABeaconSecurityProperties";
+const SET_WPA_SECURITY_ACTION:              &'static str = "SetWPABeaconSecurityProperties";
+const GET_11I_SECURITY_ACTION:              &'static str = "Get11iBeaconSecurityProperties";
+const SET_11I_SECURITY_ACTION:              &'static str = "Set11iBeaconSecurityProperties";
+const GET_TOTAL_ASSOCIATIONS_ACTION:        &'static str = "GetTotalAssociations";
+const GET_GENERIC_ASSOCIATED_DEVICE_ACTION: &'static str = "GetGenericAssociatedDeviceInfo";
+const GET_STATISTICS_ACTION:                &'static str = "GetStatistics";
+
+const SSID_ARG:                    &'static str = "NewSSID";
+const BSSID_ARG:                   &'static str = "NewBSSID";
+const CHANNEL_ARG:                 &'static str = "NewChannel";
+const POSSIBLE_CHANNELS_ARG:       &'static str = "NewPossibleChannels";
+const BEACON_TYPE_ARG:             &'static str = "NewBeaconType";
+const WEP_KEY_ARGS:                [&'static str; 4] = ["NewWEPKey0", "NewWEPKey1", "NewWEPKey2", "NewWEPKey3"];
+const PRE_SHARED_KEY_ARG:          &'static str = "NewPreSharedKey";
+const KEY_PASSPHRASE_ARG:          &'static str = "NewKeyPassphrase";
+const DEFAULT_WEP_KEY_INDEX_ARG:   &'static str = "NewDefaultWEPKeyIndex";
+const BASIC_ENCRYPTION_ARG:        &'static str = "NewBasicEncryptionModes";
+const BASIC_AUTHENTICATION_ARG:    &'static str = "NewBasicAuthenticationMode";
+const WPA_ENCRYPTION_ARG:          &'static str = "NewWPAEncryptionModes";
+const WPA_AUTHENTICATION_ARG:      &'static str = "NewWPAAuthenticationMode";
+const IEEE_11I_ENCRYPTION_ARG:     &'static str = "NewIEEE11iEncryptionModes";
+const IEEE_11I_AUTHENTICATION_ARG: &'static str = "NewIEEE11iAuthenticationMode";
+const TOTAL_ASSOCIATIONS_ARG:      &'static str = "NewTotalAssociations";
+const ASSOCIATED_DEVICE_INDEX_ARG: &'static str = "NewAssociatedDeviceIndex";
+const ASSOCIATED_DEVICE_MAC_ARG:   &'static str = "NewAssociatedDeviceMACAddress";
+const ASSOCIATED_DEVICE_IP_ARG:    &'static str = "NewAssociatedDeviceIPAddress";
+const ASSOCIATED_DEVICE_AUTH_ARG:  &'static str = "NewAssociatedDeviceAuthState";
+const TOTAL_BYTES_SENT_ARG:        &'static str = "NewTotalBytesSent";
+const TOTAL_BYTES_RECEIVED_ARG:    &'static str = "NewTotalBytesReceived";
+const TOTAL_PACKETS_SENT_ARG:      &'static str = "NewTotalPacketsSent";
+const TOTAL_PACKETS_RECEIVED_ARG:  &'static str = "NewTotalPacketsReceived";
+
+/// Channel that an access point is operating on.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ChannelInfo {
+    pub channel:           u8,
+    /// Channels that the access point is able to operate on.
+    pub possible_channels: Vec<u8>
+}
+
+/// Device currently associated with an access point.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AssociatedDevice {
+    pub mac_address:   String,
+    /// May be empty if the access point does not track addresses.
+    pub ip_address:    String,
+    pub authenticated: bool
+}
+
+/// Traffic counters of an access point.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WLANStatistics {
+    pub bytes_sent:       u32,
+    pub bytes_received:   u32,
+    pub packets_sent:     u32,
+    pub packets_received: u32
+}
+
+/// Client for a WLANConfiguration service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WLANConfigurationClient {
+    service: GenericService
+}
+
+impl WLANConfigurationClient {
+    pub fn new(service: GenericService) -> WLANConfigurationClient {
+        WLANConfigurationClient{ service: service }
+    }
+    
+    /// Returns the underlying service that this client is controlling.
+    pub fn service(&self) -> &GenericService {
+        &self.service
+    }
+    
+    /// Returns the name of the wireless network.
+    pub fn ssid(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_SSID_ACTION, &[]));
+        
+        response.argument(SSID_ARG)
+    }
+    
+    /// Set the name of the wireless network.
+    pub fn set_ssid(&self, ssid: &str) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_SSID_ACTION, &[(SSID_ARG, ssid)]));
+        
+        Ok(())
+    }
+    
+    /// Returns the MAC address of the access point.
+    pub fn bssid(&self) -> SOAPResult<String> {
+        let response = try!(self.service.send_action(GET_BSSID_ACTION, &[]));
+        
+        response.argument(BSSID_ARG)
+    }
+    
+    /// Returns the current and possible channels of the access point.
+    pub fn channel_info(&self) -> SOAPResult<ChannelInfo> {
+        let response = try!(self.service.send_action(GET_CHANNEL_INFO_ACTION, &[]));
+        
+        let channel = try!(value::to_number(CHANNEL_ARG, &try!(response.argument(CHANNEL_ARG))[..]));
+        let possible = try!(parse_channels(&try!(response.argument(POSSIBLE_CHANNELS_ARG))[..]));
+        
+        Ok(ChannelInfo{ channel: channel, possible_channels: possible })
+    }
+    
+    /// Set the channel that the access point operates on.
+    pub fn set_channel(&self, channel: u8) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_CHANNEL_ACTION, &[(CHANNEL_ARG, &channel.to_string()[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the beacons that the access point advertises.
+    pub fn beacon_type(&self) -> SOAPResult<BeaconType> {
+        let response = try!(self.service.send_action(GET_BEACON_TYPE_ACTION, &[]));
+        
+        Ok(BeaconType::from_value(&try!(response.argument(BEACON_TYPE_ARG))[..]))
+    }
+    
+    /// Set the beacons that the access point advertises.
+    pub fn set_beacon_type(&self, beacon: &BeaconType) -> SOAPResult<()> {
+        try!(self.service.send_action(SET_BEACON_TYPE_ACTION, &[(BEACON_TYPE_ARG, beacon.as_value())]));
+        
+        Ok(())
+    }
+    
+    /// Returns the WEP keys, pre-shared key and passphrase of the access point.
+    pub fn security_keys(&self) -> SOAPResult<SecurityKeys> {
+        let response = try!(self.service.send_action(GET_SECURITY_KEYS_ACTION, &[]));
+        
+        let mut keys = SecurityKeys::default();
+        for (key, &arg) in keys.wep_keys.iter_mut().zip(WEP_KEY_ARGS.iter()) {
+            *key = try!(response.argument(arg));
+        }
+        keys.pre_shared_key = try!(response.argument(PRE_SHARED_KEY_ARG));
+        keys.key_passphrase = try!(response.argument(KEY_PASSPHRASE_ARG));
+        
+        Ok(keys)
+    }
+    
+    /// Set the WEP keys, pre-shared key and passphrase of the access point.
+    pub fn set_security_keys(&self, keys: &SecurityKeys) -> SOAPResult<()> {
+        let mut args = WEP_KEY_ARGS.iter().zip(keys.wep_keys.iter()).map(|(&arg, key)| {
+            (arg, &key[..])
+        }).collect::<Vec<(&str, &str)>>();
+        args.push((PRE_SHARED_KEY_ARG, &keys.pre_shared_key[..]));
+        args.push((KEY_PASSPHRASE_ARG, &keys.key_passphrase[..]));
+        
+        try!(self.service.send_action(SET_SECURITY_KEYS_ACTION, &args[..]));
+        
+        Ok(())
+    }
+    
+    /// Returns the index of the WEP key used to encrypt traffic.
+    pub fn default_wep_key_index(&self) -> SOAPResult<u8> {
+        let response = try!(self.service.send_action(GET_DEFAULT_WEP_KEY_INDEX_ACTION, &[]));
+        
+        value::to_ranged(DEFAULT_WEP_KEY_INDEX_ARG, &try!(response.argument(DEFAULT_WEP_KEY_INDEX_ARG))[..],
+                         0, WEP_KEY_INDEX_MAX)
+    }
+    
+    /// Set the index of the WEP key used to encrypt traffic.
+    pub fn set_default_wep_key_index(&self, index: u8) -> SOAPResult<()> {
+        let index_value = index.to_string();
+        try!(value::to_ranged(DEFAULT_WEP_KEY_INDEX_ARG, &index_value[..], 0, WEP_KEY_INDEX_MAX));
+        
+        try!(self.service.send_action(SET_DEFAULT_WEP_KEY_INDEX_ACTION,
+                                      &[(DEFAULT_WEP_KEY_INDEX_ARG, &index_value[..])]));
+        
+        Ok(())
+    }
+    
+    /// Returns the security properties used with the Basic beacon.
+    pub fn basic_security(&self) -> SOAPResult<SecurityProperties> {
+        self.get_security(GET_BASIC_SECURITY_ACTION, BASIC_ENCRYPTION_ARG, BASIC_AUTHENTICATION_ARG)
+    }
+    
+    /// Set the security properties used with the Basic beacon.
+    pub fn set_basic_security(&self, properties: &SecurityProperties) -> SOAPResult<()> {
+        self.set_security(SET_BASIC_SECURITY_ACTION, BASIC_ENCRYPTION_ARG, BASIC_AUTHENTICATION_ARG, properties)
+    }
+    
+    /// Returns the security properties used with the WPA beacon.
+    pub fn wpa_security(&self) -> SOAPResult<SecurityProperties> {
+        self.get_security(GET_WPA_SECURITY_ACTION, WPA_ENCRYPTION_ARG, WPA_AUTHENTICATION_ARG)
+    }
+    
+    /// Set the security properties used with the WPA beacon.
+    pub fn set_wpa_security(&self, properties: &SecurityProperties) -> SOAPResult<()> {
+        self.set_security(SET_WPA_SECURITY_ACTION, WPA_ENCRYPTION_ARG, WPA_AUTHENTICATION_ARG, properties)
+    }
+    
+    /// Returns the security properties used with the 802.11i beacon.
+    pub fn ieee11i_security(&self) -> SOAPResult<SecurityProperties> {
+        self.get_security(GET_11I_SECURITY_ACTION, IEEE_11I_ENCRYPTION_ARG, IEEE_11I_AUTHENTICATION_ARG)
+    }
+    
+    /// Set the security properties used with the 802.11i beacon.
+    pub fn set_ieee11i_security(&self, properties: &SecurityProperties) -> SOAPResult<()> {
+        self.set_security(SET_11I_SECURITY_ACTION, IEEE_11I_ENCRYPTION_ARG, IEEE_11I_AUTHENTICATION_ARG, properties)
+    }
+    
+    /// Returns the number of devices currently associated with the access point.
+    pub fn total_associations(&self) -> SOAPResult<u16> {
+        let response = try!(self.service.send_action(GET_TOTAL_ASSOCIATIONS_ACTION, &[]));
+        
+        value::to_number(TOTAL_ASSOCIATIONS_ARG, &try!(response.argument(TOTAL_ASSOCIATIONS_ARG))[..])
+    }
+    
+    /// Returns the associated device at the given index.
+    ///
+    /// Indices range from 0 up to, but not including, the total number of
+    /// associations.
+    pub fn associated_device(&self, index: u16) -> SOAPResult<AssociatedDevice> {
+        let response = try!(self.service.send_action(GET_GENERIC_ASSOCIATED_DEVICE_ACTION,
+            &[(ASSOCIATED_DEVICE_INDEX_ARG, &index.to_string()[..])]));
+        
+        to_associated_device(&response)
+    }
+    
+    /// Returns all devices currently associated with the access point.
+    ///
+    /// This issues one action per associated device, so the list may be out of
+    /// date if devices associate or disassociate while it is being built.
+    pub fn associated_devices(&self) -> SOAPResult<Vec<AssociatedDevice>> {
+        let total = try!(self.total_associations());
+        
+        (0..total).map(|index| self.associated_device(index)).collect()
+    }
+    
+    /// Returns the traffic counters of the access point.
+    pub fn statistics(&self) -> SOAPResult<WLANStatistics> {
+        let response = try!(self.service.send_action(GET_STATISTICS_ACTION, &[]));
+        
+        Ok(WLANStatistics{
+            bytes_sent:       try!(to_counter(&response, TOTAL_BYTES_SENT_ARG)),
+            bytes_received:   try!(to_counter(&response, TOTAL_BYTES_RECEIVED_ARG)),
+            packets_sent:     try!(to_counter(&response, TOTAL_PACKETS_SENT_ARG)),
+            packets_received: try!(to_counter(&response, TOTAL_PACKETS_RECEIVED_ARG))
+        })
+    }
+    
+    fn get_security(&self, action: &str, encryption_arg: &'static str, authentication_arg: &'static str)
+        -> SOAPResult<SecurityProperties> {
+        let response = try!(self.service.send_action(action, &[]));
+        
+        let encryption = try!(EncryptionModes::from_value(encryption_arg,
+            &try!(response.argument(encryption_arg))[..]));
+        let authentication = AuthenticationMode::from_value(&try!(response.argument(authentication_arg))[..]);
+        
+        Ok(SecurityProperties{ encryption: encryption, authentication: authentication })
+    }
+    
+    fn set_security(&self, action: &str, encryption_arg: &str, authentication_arg: &str,
+        properties: &SecurityProperties) -> SOAPResult<()> {
+        let encryption = properties.encryption.to_value();
+        
+        try!(self.service.send_action(action, &[(encryption_arg, &encryption[..]),
+            (authentication_arg, properties.authentication.as_value())]));
+        
+        Ok(())
+    }
+}
+
+fn to_associated_device(response: &ActionResponse) -> SOAPResult<AssociatedDevice> {
+    let authenticated = try!(value::to_bool(ASSOCIATED_DEVICE_AUTH_ARG,
+        &try!(response.argument(ASSOCIATED_DEVICE_AUTH_ARG))[..]));
+    
+    Ok(AssociatedDevice{
+        mac_address:   try!(response.argument(ASSOCIATED_DEVICE_MAC_ARG)),
+        ip_address:    try!(response.argument(ASSOCIATED_DEVICE_IP_ARG)),
+        authenticated: authenticated
+    })
+}
+
+fn to_counter(response: &ActionResponse, name: &'static str) -> SOAPResult<u32> {
+    value::to_number(name, &try!(response.argument(name))[..])
+}
+
+/// Parse a list of possible channels.
+///
+/// Access points report these either as a comma separated list or as a range
+/// such as 1-11, and some mix the two.
+fn parse_channels(value: &str) -> SOAPResult<Vec<u8>> {
+    let mut channels = Vec::new();
+    
+    for entry in value.split(CHANNEL_LIST_SEP).map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        let mut bounds = entry.splitn(2, CHANNEL_RANGE_SEP);
+        let start = try!(value::to_number::<u8>(POSSIBLE_CHANNELS_ARG, bounds.next().unwrap()));
+        
+        match bounds.next() {
+            Some(end) => {
+                let end = try!(value::to_ranged(POSSIBLE_CHANNELS_ARG, end, start, ::std::u8::MAX));
+                // Widen The Bounds So An End Of 255 Does Not Overflow
+                channels.extend((start as u16..end as u16 + 1).map(|n| n as u8));
+            },
+            None => channels.push(start)
+        }
+    }
+    
+    Ok(channels)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn positive_channel_range() {
+        assert_eq!(super::parse_channels("1-11").unwrap(), (1..12).collect::<Vec<u8>>());
+    }
+    
+    #[test]
+    fn positive_channel_list() {
+        assert_eq!(super::parse_channels("1, 6,11").unwrap(), vec![1, 6, 11]);
+    }
+    
+    #[test]
+    fn positive_channel_mixed() {
+        assert_eq!(super::parse_channels("1-3,36").unwrap(), vec![1, 2, 3, 36]);
+    }
+    
+    #[test]
+    fn positive_channel_range_max() {
+        assert_eq!(super::parse_channels("250-255").unwrap(), vec![250, 251, 252, 253, 254, 255]);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_channel_reversed_range() {
+        super::parse_channels("11-1").unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod security;
+
+pub use self::client::{AssociatedDevice, ChannelInfo, WLANConfigurationClient, WLANStatistics};
+pub use self::security::{AuthenticationMode, BeaconType, EncryptionModes, SecurityKeys, SecurityProperties};
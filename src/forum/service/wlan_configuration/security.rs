@@ -0,0 +1,211 @@
+use std::borrow::{ToOwned};
+
+use {SOAPError, SOAPResult};
+
+const NONE_VALUE:                  &'static str = "None";
+const BASIC_VALUE:                 &'static str = "Basic";
+const WPA_VALUE:                   &'static str = "WPA";
+const IEEE_11I_VALUE:              &'static str = "11i";
+const BASIC_AND_WPA_VALUE:         &'static str = "BasicandWPA";
+const BASIC_AND_11I_VALUE:         &'static str = "Basicand11i";
+const WPA_AND_11I_VALUE:           &'static str = "WPAand11i";
+const BASIC_AND_WPA_AND_11I_VALUE: &'static str = "BasicandWPAand11i";
+
+const PSK_AUTHENTICATION_VALUE:         &'static str = "PSKAuthentication";
+const EAP_AUTHENTICATION_VALUE:         &'static str = "EAPAuthentication";
+const EAP_AND_PSK_AUTHENTICATION_VALUE: &'static str = "EAPandPSKAuthentication";
+
+const ENCRYPTION_SUFFIX:    &'static str = "Encryption";
+const ENCRYPTION_SEP:       &'static str = "and";
+const WEP_ENCRYPTION_NAME:  &'static str = "WEP";
+const TKIP_ENCRYPTION_NAME: &'static str = "TKIP";
+const AES_ENCRYPTION_NAME:  &'static str = "AES";
+
+/// Beacons that an access point advertises, which determines the security
+/// properties that clients may associate with.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BeaconType {
+    None,
+    Basic,
+    WPA,
+    IEEE11i,
+    BasicAndWPA,
+    BasicAnd11i,
+    WPAAnd11i,
+    BasicAndWPAAnd11i,
+    /// Vendor defined beacon type.
+    Other(String)
+}
+
+impl BeaconType {
+    /// Convert the text value of an argument to a BeaconType.
+    pub fn from_value(value: &str) -> BeaconType {
+        match value.trim() {
+            NONE_VALUE                  => BeaconType::None,
+            BASIC_VALUE                 => BeaconType::Basic,
+            WPA_VALUE                   => BeaconType::WPA,
+            IEEE_11I_VALUE              => BeaconType::IEEE11i,
+            BASIC_AND_WPA_VALUE         => BeaconType::BasicAndWPA,
+            BASIC_AND_11I_VALUE         => BeaconType::BasicAnd11i,
+            WPA_AND_11I_VALUE           => BeaconType::WPAAnd11i,
+            BASIC_AND_WPA_AND_11I_VALUE => BeaconType::BasicAndWPAAnd11i,
+            n => BeaconType::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns the text value used when sending the beacon type to a service.
+    pub fn as_value(&self) -> &str {
+        match *self {
+            BeaconType::None              => NONE_VALUE,
+            BeaconType::Basic             => BASIC_VALUE,
+            BeaconType::WPA               => WPA_VALUE,
+            BeaconType::IEEE11i           => IEEE_11I_VALUE,
+            BeaconType::BasicAndWPA       => BASIC_AND_WPA_VALUE,
+            BeaconType::BasicAnd11i       => BASIC_AND_11I_VALUE,
+            BeaconType::WPAAnd11i         => WPA_AND_11I_VALUE,
+            BeaconType::BasicAndWPAAnd11i => BASIC_AND_WPA_AND_11I_VALUE,
+            BeaconType::Other(ref n)      => &n[..]
+        }
+    }
+}
+
+/// Methods used to authenticate clients associating with an access point.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum AuthenticationMode {
+    None,
+    /// Pre-shared key authentication.
+    PSK,
+    /// Extensible authentication protocol (802.1X).
+    EAP,
+    EAPAndPSK,
+    /// Vendor defined authentication mode.
+    Other(String)
+}
+
+impl AuthenticationMode {
+    /// Convert the text value of an argument to an AuthenticationMode.
+    pub fn from_value(value: &str) -> AuthenticationMode {
+        match value.trim() {
+            NONE_VALUE                       => AuthenticationMode::None,
+            PSK_AUTHENTICATION_VALUE         => AuthenticationMode::PSK,
+            EAP_AUTHENTICATION_VALUE         => AuthenticationMode::EAP,
+            EAP_AND_PSK_AUTHENTICATION_VALUE => AuthenticationMode::EAPAndPSK,
+            n => AuthenticationMode::Other(n.to_owned())
+        }
+    }
+    
+    /// Returns the text value used when sending the mode to a service.
+    pub fn as_value(&self) -> &str {
+        match *self {
+            AuthenticationMode::None         => NONE_VALUE,
+            AuthenticationMode::PSK          => PSK_AUTHENTICATION_VALUE,
+            AuthenticationMode::EAP          => EAP_AUTHENTICATION_VALUE,
+            AuthenticationMode::EAPAndPSK    => EAP_AND_PSK_AUTHENTICATION_VALUE,
+            AuthenticationMode::Other(ref n) => &n[..]
+        }
+    }
+}
+
+/// Set of encryption methods that an access point accepts.
+///
+/// A set with no methods enabled corresponds to no encryption.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct EncryptionModes {
+    pub wep:  bool,
+    pub tkip: bool,
+    pub aes:  bool
+}
+
+impl EncryptionModes {
+    /// Convert the text value of an argument, such as WEPandTKIPEncryption, to
+    /// an EncryptionModes.
+    pub fn from_value(name: &'static str, value: &str) -> SOAPResult<EncryptionModes> {
+        let value = value.trim();
+        let mut modes = EncryptionModes::default();
+        
+        if value == NONE_VALUE {
+            return Ok(modes)
+        }
+        if !value.ends_with(ENCRYPTION_SUFFIX) {
+            return Err(SOAPError::InvalidArgument(name, "Value Is Not A Valid Encryption Mode"))
+        }
+        
+        let methods = &value[..value.len() - ENCRYPTION_SUFFIX.len()];
+        for method in methods.split(ENCRYPTION_SEP) {
+            match method {
+                WEP_ENCRYPTION_NAME  => modes.wep = true,
+                TKIP_ENCRYPTION_NAME => modes.tkip = true,
+                AES_ENCRYPTION_NAME  => modes.aes = true,
+                _ => return Err(SOAPError::InvalidArgument(name, "Value Is Not A Valid Encryption Mode"))
+            }
+        }
+        
+        Ok(modes)
+    }
+    
+    /// Returns the text value used when sending the modes to a service.
+    pub fn to_value(&self) -> String {
+        let methods = [(self.wep, WEP_ENCRYPTION_NAME), (self.tkip, TKIP_ENCRYPTION_NAME),
+            (self.aes, AES_ENCRYPTION_NAME)];
+        let enabled = methods.iter().filter(|&&(on, _)| on).map(|&(_, n)| n).collect::<Vec<&str>>();
+        
+        if enabled.is_empty() {
+            NONE_VALUE.to_owned()
+        } else {
+            enabled.connect(ENCRYPTION_SEP) + ENCRYPTION_SUFFIX
+        }
+    }
+}
+
+/// Encryption and authentication settings for a single beacon type.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SecurityProperties {
+    pub encryption:     EncryptionModes,
+    pub authentication: AuthenticationMode
+}
+
+/// Keys used to secure a wireless network.
+///
+/// Access points will commonly report keys as empty or masked for security
+/// reasons even though they are in use.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct SecurityKeys {
+    pub wep_keys:       [String; 4],
+    pub pre_shared_key: String,
+    pub key_passphrase: String
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BeaconType, EncryptionModes};
+    
+    #[test]
+    fn positive_beacon_round_trip() {
+        let beacon = BeaconType::from_value("BasicandWPAand11i");
+        
+        assert_eq!(beacon, BeaconType::BasicAndWPAAnd11i);
+        assert_eq!(beacon.as_value(), "BasicandWPAand11i");
+    }
+    
+    #[test]
+    fn positive_combined_encryption() {
+        let modes = EncryptionModes::from_value("Modes", "WEPandAESEncryption").unwrap();
+        
+        assert_eq!(modes, EncryptionModes{ wep: true, tkip: false, aes: true });
+        assert_eq!(modes.to_value(), "WEPandAESEncryption");
+    }
+    
+    #[test]
+    fn positive_no_encryption() {
+        let modes = EncryptionModes::from_value("Modes", "None").unwrap();
+        
+        assert_eq!(modes, EncryptionModes::default());
+        assert_eq!(modes.to_value(), "None");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_unknown_encryption() {
+        EncryptionModes::from_value("Modes", "ROT13Encryption").unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+//! Trust decisions for retrieving device descriptions over https.
+//!
+//! UPnP 2.0 devices may advertise a secure location alongside their regular
+//! location. Devices commonly present self-signed certificates, so a trust
+//! policy allows the certificate of individual devices to be pinned by udn.
+
+use std::borrow::{ToOwned};
+use std::collections::{BTreeMap};
+
+use openssl::crypto::hash::{self, Type};
+
+/// How the connection a description was retrieved over was verified.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Verification {
+    /// Retrieved over plain http.
+    Unverified,
+    /// Retrieved over https from a device whose certificate the system trusts.
+    System,
+    /// Retrieved over https from a device presenting the pinned certificate
+    /// with the given fingerprint.
+    Pinned(Vec<u8>)
+}
+
+/// Policy deciding which certificates are trusted when fetching descriptions
+/// over https, and whether plain http may be used instead.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TrustPolicy {
+    pinned:   BTreeMap<String, Vec<u8>>,
+    fallback: bool
+}
+
+impl TrustPolicy {
+    /// Create a new TrustPolicy with no pinned certificates that falls back to
+    /// the regular location when no secure location is usable.
+    pub fn new() -> TrustPolicy {
+        TrustPolicy{ pinned: BTreeMap::new(), fallback: true }
+    }
+    
+    /// Create a new TrustPolicy with no pinned certificates that never falls
+    /// back to the regular location.
+    pub fn strict() -> TrustPolicy {
+        TrustPolicy{ pinned: BTreeMap::new(), fallback: false }
+    }
+    
+    /// Pin the DER encoded certificate that the device with the given udn must
+    /// present.
+    pub fn pin_certificate(&mut self, udn: &str, certificate: &[u8]) {
+        self.pin_fingerprint(udn, hash::hash(Type::SHA256, certificate));
+    }
+    
+    /// Pin the SHA-256 fingerprint of the certificate that the device with the
+    /// given udn must present.
+    pub fn pin_fingerprint(&mut self, udn: &str, fingerprint: Vec<u8>) {
+        self.pinned.insert(udn.to_owned(), fingerprint);
+    }
+    
+    /// Remove any certificate pinned for the device with the given udn.
+    pub fn unpin(&mut self, udn: &str) {
+        self.pinned.remove(udn);
+    }
+    
+    /// Returns the fingerprint pinned for the device with the given udn.
+    pub fn pinned(&self, udn: &str) -> Option<&[u8]> {
+        self.pinned.get(udn).map(|n| &n[..])
+    }
+    
+    /// Set whether or not the regular location may be used when the secure
+    /// location is missing or could not be verified.
+    pub fn set_fallback(&mut self, fallback: bool) {
+        self.fallback = fallback;
+    }
+    
+    /// Returns true if the description of the device with the given udn may be
+    /// fetched from the regular location.
+    ///
+    /// Devices with a pinned certificate are never fetched over plain http.
+    pub fn allows_fallback(&self, udn: &str) -> bool {
+        self.fallback && !self.pinned.contains_key(udn)
+    }
+    
+    /// Returns the verifications that the policy accepts for the description
+    /// of the device with the given udn, strongest first.
+    pub fn accepts(&self, udn: &str) -> Vec<Verification> {
+        match self.pinned(udn) {
+            Some(n) => vec![Verification::Pinned(n.to_vec())],
+            None if self.fallback => vec![Verification::System, Verification::Unverified],
+            None => vec![Verification::System]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TrustPolicy, Verification};
+    
+    #[test]
+    fn positive_default_fallback() {
+        assert!(TrustPolicy::new().allows_fallback("uuid:device"));
+        assert!(!TrustPolicy::strict().allows_fallback("uuid:device"));
+    }
+    
+    #[test]
+    fn positive_pinned_no_fallback() {
+        let mut policy = TrustPolicy::new();
+        policy.pin_fingerprint("uuid:device", vec![1, 2, 3]);
+        
+        assert!(!policy.allows_fallback("uuid:device"));
+        assert!(policy.allows_fallback("uuid:other"));
+        assert_eq!(policy.pinned("uuid:device").unwrap(), &[1, 2, 3][..]);
+    }
+    
+    #[test]
+    fn positive_accepts() {
+        let mut policy = TrustPolicy::strict();
+        policy.pin_fingerprint("uuid:device", vec![1, 2, 3]);
+        
+        assert_eq!(policy.accepts("uuid:device"), vec![Verification::Pinned(vec![1, 2, 3])]);
+        assert_eq!(policy.accepts("uuid:other"), vec![Verification::System]);
+        assert_eq!(TrustPolicy::new().accepts("uuid:other"),
+            vec![Verification::System, Verification::Unverified]);
+    }
+    
+    #[test]
+    fn positive_unpin() {
+        let mut policy = TrustPolicy::new();
+        policy.pin_certificate("uuid:device", b"certificate");
+        policy.unpin("uuid:device");
+        
+        assert!(policy.pinned("uuid:device").is_none());
+    }
+}
@@ -0,0 +1,80 @@
+use std::slice::{Iter};
+
+use {GENAError, GENAResult};
+use xml;
+
+const PROPERTY_SET_ELEMENT: &'static str = "propertyset";
+const PROPERTY_ELEMENT:     &'static str = "property";
+
+/// Set of evented state variables sent by a publisher in an event message.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PropertySet {
+    seq:        u32,
+    properties: Vec<(String, String)>
+}
+
+impl PropertySet {
+    /// Create a new PropertySet from the event key and body of an event message.
+    pub fn new(seq: u32, body: &str) -> GENAResult<PropertySet> {
+        let property_set = try!(xml::element(body, PROPERTY_SET_ELEMENT).ok_or(
+            GENAError::InvalidBody("Event Body Is Missing A propertyset")
+        ));
+        
+        let properties = xml::Elements::new(property_set, PROPERTY_ELEMENT).filter_map(|n|
+            xml::first_child(n).map(|(name, value)| (name.to_owned(), xml::unescape(value)))
+        ).collect();
+        
+        Ok(PropertySet{ seq: seq, properties: properties })
+    }
+    
+    /// Returns the event key of the message that carried this property set.
+    ///
+    /// An event key of 0 denotes the initial event message for a subscription.
+    pub fn seq(&self) -> u32 {
+        self.seq
+    }
+    
+    /// Returns the value of the given state variable if it was included.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties.iter().find(|&&(ref n, _)| &n[..] == name).map(|&(_, ref v)| &v[..])
+    }
+    
+    /// Returns an iterator over all (name, value) pairs in the property set.
+    pub fn iter(&self) -> Iter<(String, String)> {
+        self.properties.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PropertySet};
+    
+    #[test]
+    fn positive_property_set() {
+        let body = "<?xml version=\"1.0\"?>\
+                    <e:propertyset xmlns:e=\"urn:schemas-upnp-org:event-1-0\">\
+                    <e:property><Status>1</Status></e:property>\
+                    <e:property><Target>0</Target></e:property>\
+                    </e:propertyset>";
+        let property_set = PropertySet::new(0, body).unwrap();
+        
+        assert_eq!(property_set.get("Status").unwrap(), "1");
+        assert_eq!(property_set.get("Target").unwrap(), "0");
+        assert_eq!(property_set.iter().count(), 2);
+    }
+    
+    #[test]
+    fn positive_escaped_value() {
+        let body = "<e:propertyset><e:property><A>&lt;b&gt;</A></e:property></e:propertyset>";
+        let property_set = PropertySet::new(3, body).unwrap();
+        
+        assert_eq!(property_set.get("A").unwrap(), "<b>");
+        assert_eq!(property_set.seq(), 3);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_property_set() {
+        PropertySet::new(0, "<e:property><A>1</A></e:property>").unwrap();
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::{Formatter, Result};
+use std::str;
+
+use hyper::header::{HeaderFormat, Header};
+use url::{Url};
+
+const CALLBACK_HEADER_NAME: &'static str = "CALLBACK";
+
+/// Represents a CALLBACK header which specifies one or more urls, in order of
+/// preference, that event messages should be delivered to.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Callback(pub Vec<Url>);
+
+unsafe impl Sync for Callback { }
+
+unsafe impl Send for Callback { }
+
+impl Header for Callback {
+    fn header_name() -> &'static str {
+        CALLBACK_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        let value = match str::from_utf8(&raw[0][..]) {
+            Ok(n)  => n.trim(),
+            Err(_) => return None
+        };
+        
+        // Each Url Is Enclosed In Angle Brackets
+        let mut urls = Vec::new();
+        for part in value.split('<').skip(1) {
+            let url_str = match part.find('>') {
+                Some(n) => &part[..n],
+                None    => return None
+            };
+            
+            match Url::parse(url_str) {
+                Ok(n)  => urls.push(n),
+                Err(_) => return None
+            }
+        }
+        
+        if urls.is_empty() || !value.starts_with("<") {
+            None
+        } else {
+            Some(Callback(urls))
+        }
+    }
+}
+
+impl HeaderFormat for Callback {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        for url in self.0.iter() {
+            try!(fmt.write_fmt(format_args!("<{}>", url)));
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use super::{Callback};
+    
+    #[test]
+    fn positive_single_url() {
+        let callback_header_value = &[b"<http://192.168.1.2:5000/event>"[..].to_vec()];
+        let Callback(urls) = Callback::parse_header(callback_header_value).unwrap();
+        
+        assert_eq!(urls.len(), 1);
+    }
+    
+    #[test]
+    fn positive_multiple_urls() {
+        let callback_header_value = &[b"<http://192.168.1.2/a><http://10.0.0.2/b>"[..].to_vec()];
+        let Callback(urls) = Callback::parse_header(callback_header_value).unwrap();
+        
+        assert_eq!(urls.len(), 2);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_brackets() {
+        let callback_header_value = &[b"http://192.168.1.2/a"[..].to_vec()];
+        
+        Callback::parse_header(callback_header_value).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_unclosed_bracket() {
+        let callback_header_value = &[b"<http://192.168.1.2/a"[..].to_vec()];
+        
+        Callback::parse_header(callback_header_value).unwrap();
+    }
+}
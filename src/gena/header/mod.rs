@@ -0,0 +1,11 @@
+//! Headers for parsing and writing headers within GENA requests.
+
+mod callback;
+mod seq;
+mod sid;
+mod timeout;
+
+pub use self::callback::Callback;
+pub use self::seq::Seq;
+pub use self::sid::SID;
+pub use self::timeout::Timeout;
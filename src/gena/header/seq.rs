@@ -0,0 +1,80 @@
+use std::fmt::{Formatter, Result};
+
+use hyper::header::{HeaderFormat, Header};
+
+const SEQ_HEADER_NAME: &'static str = "SEQ";
+
+/// Represents a SEQ header which specifies the event key of an event message.
+///
+/// The initial event message of a subscription has a key of 0 and each
+/// following message increments the key by one, wrapping back to 1.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Seq(pub u32);
+
+impl Seq {
+    /// Returns the event key that follows the current one.
+    pub fn next(&self) -> Seq {
+        match self.0.checked_add(1) {
+            Some(n) => Seq(n),
+            None    => Seq(1)
+        }
+    }
+}
+
+unsafe impl Sync for Seq { }
+
+unsafe impl Send for Seq { }
+
+impl Header for Seq {
+    fn header_name() -> &'static str {
+        SEQ_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        let cow_str = String::from_utf8_lossy(&raw[0][..]);
+        
+        match u32::from_str_radix(&*cow_str, 10) {
+            Ok(n)  => Some(Seq(n)),
+            Err(_) => None
+        }
+    }
+}
+
+impl HeaderFormat for Seq {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        try!(fmt.write_fmt(format_args!("{}", self.0)));
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use super::{Seq};
+    
+    #[test]
+    fn positive_seq() {
+        let seq_header_value = &[b"0"[..].to_vec()];
+        
+        assert_eq!(Seq::parse_header(seq_header_value).unwrap(), Seq(0));
+    }
+    
+    #[test]
+    fn positive_next_wraps() {
+        assert_eq!(Seq(::std::u32::MAX).next(), Seq(1));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_negative() {
+        let seq_header_value = &[b"-1"[..].to_vec()];
+        
+        Seq::parse_header(seq_header_value).unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt::{Formatter, Result};
+
+use hyper::header::{HeaderFormat, Header};
+
+const SID_HEADER_NAME: &'static str = "SID";
+
+/// Prefix that all subscription identifiers start with.
+const SID_PREFIX: &'static str = "uuid:";
+
+/// Represents a SID header which specifies the subscription identifier that
+/// a publisher assigned to a subscription.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SID(pub String);
+
+unsafe impl Sync for SID { }
+
+unsafe impl Send for SID { }
+
+impl Header for SID {
+    fn header_name() -> &'static str {
+        SID_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        if raw.len() != 1 || !raw[0].starts_with(SID_PREFIX.as_bytes())
+            || raw[0].len() == SID_PREFIX.len() {
+            return None
+        }
+        
+        match String::from_utf8(raw[0].clone()) {
+            Ok(n)  => Some(SID(n)),
+            Err(_) => None
+        }
+    }
+}
+
+impl HeaderFormat for SID {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        try!(fmt.write_str(&self.0));
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use super::{SID};
+    
+    #[test]
+    fn positive_sid() {
+        let sid_header_value = &[b"uuid:a984bc8c-aaf0-5dff-b980-00d098bda247"[..].to_vec()];
+        
+        SID::parse_header(sid_header_value).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_prefix() {
+        let sid_header_value = &[b"a984bc8c-aaf0-5dff-b980-00d098bda247"[..].to_vec()];
+        
+        SID::parse_header(sid_header_value).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_empty_uuid() {
+        let sid_header_value = &[b"uuid:"[..].to_vec()];
+        
+        SID::parse_header(sid_header_value).unwrap();
+    }
+}
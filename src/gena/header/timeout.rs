@@ -0,0 +1,108 @@
+use std::ascii::{AsciiExt};
+use std::fmt::{Formatter, Result};
+
+use hyper::header::{HeaderFormat, Header};
+
+const TIMEOUT_HEADER_NAME: &'static str = "TIMEOUT";
+
+const TIMEOUT_PREFIX:         &'static str = "Second-";
+const TIMEOUT_INFINITE_VALUE: &'static str = "infinite";
+
+/// Represents a TIMEOUT header which specifies the requested or actual duration
+/// of a subscription.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Timeout {
+    /// Subscription lasts for the given number of seconds.
+    Seconds(u32),
+    /// Subscription does not expire (deprecated as of UPnP 1.1).
+    Infinite
+}
+
+unsafe impl Sync for Timeout { }
+
+unsafe impl Send for Timeout { }
+
+impl Header for Timeout {
+    fn header_name() -> &'static str {
+        TIMEOUT_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        let cow_str = String::from_utf8_lossy(&raw[0][..]);
+        let value = cow_str.trim();
+        
+        // Prefix Is Case Insensitive In Practice
+        if value.len() <= TIMEOUT_PREFIX.len() ||
+            !value[..TIMEOUT_PREFIX.len()].eq_ignore_ascii_case(TIMEOUT_PREFIX) {
+            return None
+        }
+        
+        let seconds = &value[TIMEOUT_PREFIX.len()..];
+        if seconds.eq_ignore_ascii_case(TIMEOUT_INFINITE_VALUE) {
+            Some(Timeout::Infinite)
+        } else {
+            u32::from_str_radix(seconds, 10).ok().map(|n| Timeout::Seconds(n))
+        }
+    }
+}
+
+impl HeaderFormat for Timeout {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        try!(fmt.write_str(TIMEOUT_PREFIX));
+        
+        match *self {
+            Timeout::Seconds(n) => try!(fmt.write_fmt(format_args!("{}", n))),
+            Timeout::Infinite   => try!(fmt.write_str(TIMEOUT_INFINITE_VALUE))
+        };
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use super::{Timeout};
+    
+    #[test]
+    fn positive_seconds() {
+        let timeout_header_value = &[b"Second-1800"[..].to_vec()];
+        
+        assert_eq!(Timeout::parse_header(timeout_header_value).unwrap(), Timeout::Seconds(1800));
+    }
+    
+    #[test]
+    fn positive_infinite() {
+        let timeout_header_value = &[b"Second-infinite"[..].to_vec()];
+        
+        assert_eq!(Timeout::parse_header(timeout_header_value).unwrap(), Timeout::Infinite);
+    }
+    
+    #[test]
+    fn positive_lowercase_prefix() {
+        let timeout_header_value = &[b"second-300"[..].to_vec()];
+        
+        assert_eq!(Timeout::parse_header(timeout_header_value).unwrap(), Timeout::Seconds(300));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_prefix() {
+        let timeout_header_value = &[b"1800"[..].to_vec()];
+        
+        Timeout::parse_header(timeout_header_value).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_value() {
+        let timeout_header_value = &[b"Second-"[..].to_vec()];
+        
+        Timeout::parse_header(timeout_header_value).unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+//! Implements the GENA (eventing) layer of the UPnP standard.
+//!
+//! This module deals with subscribing to the evented state variables of a
+//! service and receiving the event messages published for that subscription,
+//! as well as publishing events for services that we are hosting ourselves.
+
+pub mod header;
+
+mod event;
+mod publisher;
+mod subscriber;
+
+pub use gena::event::{PropertySet};
+pub use gena::publisher::{Moderation, Publisher};
+pub use gena::subscriber::{Subscription};
+
+/// Default duration requested for a subscription, in seconds.
+pub const DEFAULT_TIMEOUT_SECONDS: u32 = 1800;
+
+const SUBSCRIBE_METHOD:   &'static str = "SUBSCRIBE";
+const UNSUBSCRIBE_METHOD: &'static str = "UNSUBSCRIBE";
+const NOTIFY_METHOD:      &'static str = "NOTIFY";
+
+const NTS_HEADER_NAME: &'static str = "NTS";
+const EVENT_NT_VALUE:  &'static [u8] = b"event";
+const EVENT_NTS_VALUE: &'static [u8] = b"upnp:propchange";
@@ -0,0 +1,705 @@
+use std::borrow::{ToOwned};
+use std::cmp;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration as StdDuration};
+
+use hyper::{Client};
+use hyper::header::{Header, Headers};
+use hyper::method::{Method};
+use hyper::status::{StatusCode};
+use time::{Duration, SteadyTime};
+use url::{Url};
+
+use gena::{self, EVENT_NT_VALUE, EVENT_NTS_VALUE, NOTIFY_METHOD, NTS_HEADER_NAME, SUBSCRIBE_METHOD,
+           UNSUBSCRIBE_METHOD};
+use gena::header::{Callback, Seq, SID, Timeout};
+use ssdp::{FieldPair};
+use ssdp::header::{NT};
+use util;
+use xml;
+
+const CONTENT_TYPE_HEADER: &'static str = "CONTENT-TYPE";
+const CONTENT_TYPE_VALUE:  &'static str = "text/xml; charset=\"utf-8\"";
+
+const XML_DECLARATION: &'static str = "<?xml version=\"1.0\"?>";
+const EVENT_NAMESPACE: &'static str = "urn:schemas-upnp-org:event-1-0";
+
+/// Time to sleep between checks for moderated variables and expired subscriptions.
+const POLL_INTERVAL_MS: u32 = 50;
+/// Time allowed for a subscriber to accept an event message before that message
+/// is given up on.
+const DELIVERY_TIMEOUT_MS: u64 = 5000;
+/// Longest subscription granted, in seconds, whatever was requested.
+const MAX_TIMEOUT_SECONDS: u32 = 1800;
+
+/// Rule limiting how often changes to an evented state variable are sent out.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Moderation {
+    /// Every change is sent out right away.
+    Unmoderated,
+    /// Changes are sent out at most once per the given number of milliseconds.
+    ///
+    /// Changes made in between are held back and the latest value is sent out
+    /// once the period has passed.
+    MaximumRate(u32),
+    /// Changes are only sent out once the numeric value has moved by at least
+    /// the given amount since the last value that was sent out.
+    MinimumDelta(u32)
+}
+
+/// Publishes changes to the evented state variables of a service we are hosting.
+///
+/// Event messages are delivered by a thread per subscription, so that a slow or
+/// unreachable subscriber does not hold up the others. Delivery stops once
+/// shutdown is called or the Publisher and all of its clones have been dropped.
+#[derive(Clone)]
+pub struct Publisher {
+    shared: Arc<Shared>
+}
+
+impl Publisher {
+    /// Create a new Publisher with no evented state variables.
+    pub fn new() -> Publisher {
+        let state = Arc::new(Mutex::new(PublisherState::new()));
+        let kill_flag = Arc::new(AtomicBool::new(false));
+        
+        let (state_clone, kill_clone) = (state.clone(), kill_flag.clone());
+        let worker = thread::spawn(move || {
+            let mut queues: Vec<DeliveryQueue> = Vec::new();
+            
+            while !kill_clone.load(Ordering::SeqCst) {
+                let (outbox, active) = {
+                    let mut state = state_clone.lock().unwrap();
+                    state.expire(SteadyTime::now());
+                    state.flush_moderated(SteadyTime::now());
+                    
+                    let active = state.subscribers.iter().map(|n| n.sid.clone()).collect::<Vec<_>>();
+                    (mem::replace(&mut state.outbox, Vec::new()), active)
+                };
+                
+                // Stop Delivering To Subscriptions That Have Gone Away
+                for queue in queues.iter().filter(|n| !active.contains(&n.sid)) {
+                    queue.cancel();
+                }
+                queues.retain(|n| active.contains(&n.sid));
+                
+                for notification in outbox.into_iter().filter(|n| active.contains(&n.sid)) {
+                    match queues.iter().position(|n| n.sid == notification.sid) {
+                        Some(index) => queues[index].push(notification),
+                        None => {
+                            let queue = DeliveryQueue::spawn(notification.sid.clone());
+                            queue.push(notification);
+                            
+                            queues.push(queue);
+                        }
+                    }
+                }
+                thread::sleep_ms(POLL_INTERVAL_MS);
+            }
+            
+            for queue in queues {
+                queue.finish();
+            }
+        });
+        
+        Publisher{ shared: Arc::new(Shared{ state: state, kill_flag: kill_flag,
+            worker: Mutex::new(Some(worker)) }) }
+    }
+    
+    /// Stop delivering event messages and wait for the delivery threads to
+    /// finish whatever they are sending out.
+    ///
+    /// Changes made afterwards are still recorded but never sent out.
+    pub fn shutdown(&self) {
+        self.shared.kill_flag.store(true, Ordering::SeqCst);
+        
+        if let Some(worker) = self.shared.worker.lock().unwrap().take() {
+            let _ = worker.join();
+        }
+    }
+    
+    /// Add an evented state variable with the given initial value.
+    pub fn add_variable(&self, name: &str, value: &str, moderation: Moderation) {
+        self.shared.state.lock().unwrap().add_variable(name, value, moderation);
+    }
+    
+    /// Change the value of an evented state variable, sending the change out
+    /// to subscribers as its moderation allows.
+    ///
+    /// Returns false if the state variable is not evented.
+    pub fn set_variable(&self, name: &str, value: &str) -> bool {
+        self.shared.state.lock().unwrap().set_variable(name, value, SteadyTime::now())
+    }
+    
+    /// Returns the current value of an evented state variable.
+    pub fn variable(&self, name: &str) -> Option<String> {
+        self.shared.state.lock().unwrap().variable(name).map(|n| n.to_owned())
+    }
+    
+    /// Returns the number of active subscriptions.
+    pub fn subscriptions(&self) -> usize {
+        self.shared.state.lock().unwrap().subscribers.len()
+    }
+    
+    /// Handle a SUBSCRIBE or UNSUBSCRIBE request sent to the event url.
+    ///
+    /// Returns the headers for the response along with the SID of a new
+    /// subscription, which should be passed to send_initial_event after the
+    /// response has been sent. Returns the status code to send back if the
+    /// request was not accepted.
+    pub fn accept(&self, method: &Method, headers: &Headers) -> Result<(Headers, Option<SID>), StatusCode> {
+        self.shared.state.lock().unwrap().accept(method, headers, SteadyTime::now())
+    }
+    
+    /// Send the initial event message, which carries every evented state
+    /// variable, to a new subscription.
+    ///
+    /// No other event messages are sent to the subscription before this one.
+    pub fn send_initial_event(&self, sid: &SID) {
+        self.shared.state.lock().unwrap().queue_initial_event(sid);
+    }
+}
+
+/// State shared between the clones of a Publisher.
+struct Shared {
+    state:     Arc<Mutex<PublisherState>>,
+    kill_flag: Arc<AtomicBool>,
+    worker:    Mutex<Option<JoinHandle<()>>>
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        self.kill_flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Evented state variable along with the bookkeeping needed for moderation.
+struct EventedVariable {
+    name:          String,
+    value:         String,
+    moderation:    Moderation,
+    evented_value: String,
+    evented_at:    Option<SteadyTime>,
+    pending:       bool
+}
+
+/// Subscription that a control point made with us.
+struct Subscriber {
+    sid:          SID,
+    callbacks:    Vec<Url>,
+    expires:      SteadyTime,
+    seq:          Seq,
+    initial_sent: bool
+}
+
+/// Event message waiting to be delivered to a subscriber.
+struct Notification {
+    callbacks: Vec<Url>,
+    sid:       SID,
+    seq:       Seq,
+    body:      String
+}
+
+impl Notification {
+    /// Send the event message to the first callback url that accepts it.
+    fn deliver(&self) {
+        let timeout = StdDuration::from_millis(DELIVERY_TIMEOUT_MS);
+        
+        for url in self.callbacks.iter() {
+            let mut headers = Headers::new();
+            headers.set_raw(CONTENT_TYPE_HEADER, vec![CONTENT_TYPE_VALUE.as_bytes().to_vec()]);
+            headers.set(NT(FieldPair::UPnP(EVENT_NT_VALUE.to_vec())));
+            headers.set_raw(NTS_HEADER_NAME, vec![EVENT_NTS_VALUE.to_vec()]);
+            headers.set(self.sid.clone());
+            headers.set(self.seq);
+            
+            let mut client = Client::new();
+            client.set_read_timeout(Some(timeout));
+            client.set_write_timeout(Some(timeout));
+            
+            let result = client.request(Method::Extension(NOTIFY_METHOD.to_owned()), url.clone())
+                .headers(headers).body(&self.body[..]).send();
+            
+            if result.is_ok() {
+                return
+            }
+        }
+    }
+}
+
+/// Subscriptions and evented state variables of a Publisher.
+struct PublisherState {
+    variables:   Vec<EventedVariable>,
+    subscribers: Vec<Subscriber>,
+    outbox:      Vec<Notification>
+}
+
+impl PublisherState {
+    fn new() -> PublisherState {
+        PublisherState{ variables: Vec::new(), subscribers: Vec::new(), outbox: Vec::new() }
+    }
+    
+    fn add_variable(&mut self, name: &str, value: &str, moderation: Moderation) {
+        self.variables.retain(|n| n.name != name);
+        self.variables.push(EventedVariable{ name: name.to_owned(), value: value.to_owned(),
+            moderation: moderation, evented_value: value.to_owned(), evented_at: None, pending: false });
+    }
+    
+    fn variable(&self, name: &str) -> Option<&str> {
+        self.variables.iter().find(|n| n.name == name).map(|n| &n.value[..])
+    }
+    
+    fn set_variable(&mut self, name: &str, value: &str, now: SteadyTime) -> bool {
+        let send_now = match self.variables.iter_mut().find(|n| n.name == name) {
+            Some(variable) => {
+                variable.value = value.to_owned();
+                
+                let send_now = match variable.moderation {
+                    Moderation::Unmoderated     => true,
+                    Moderation::MaximumRate(ms) => variable.evented_at.map_or(true, |n|
+                        now - n >= Duration::milliseconds(ms as i64)
+                    ),
+                    Moderation::MinimumDelta(delta) => {
+                        exceeds_delta(&variable.evented_value[..], value, delta)
+                    }
+                };
+                
+                if send_now {
+                    variable.evented_value = value.to_owned();
+                    variable.evented_at = Some(now);
+                    variable.pending = false;
+                } else if let Moderation::MaximumRate(_) = variable.moderation {
+                    variable.pending = true;
+                }
+                
+                send_now
+            },
+            None => return false
+        };
+        
+        if send_now {
+            self.queue_event(&[(name.to_owned(), value.to_owned())]);
+        }
+        
+        true
+    }
+    
+    /// Send out any held back changes whose maximum rate period has passed.
+    fn flush_moderated(&mut self, now: SteadyTime) {
+        let mut properties = Vec::new();
+        
+        for variable in self.variables.iter_mut().filter(|n| n.pending) {
+            let due = match (variable.moderation, variable.evented_at) {
+                (Moderation::MaximumRate(ms), Some(n)) => now - n >= Duration::milliseconds(ms as i64),
+                _ => true
+            };
+            
+            if due {
+                variable.evented_value = variable.value.clone();
+                variable.evented_at = Some(now);
+                variable.pending = false;
+                
+                properties.push((variable.name.clone(), variable.value.clone()));
+            }
+        }
+        
+        if !properties.is_empty() {
+            self.queue_event(&properties[..]);
+        }
+    }
+    
+    /// Drop every subscription that was not renewed in time.
+    fn expire(&mut self, now: SteadyTime) {
+        self.subscribers.retain(|n| n.expires > now);
+    }
+    
+    fn accept(&mut self, method: &Method, headers: &Headers, now: SteadyTime)
+        -> Result<(Headers, Option<SID>), StatusCode> {
+        let method = match *method {
+            Method::Extension(ref n) => &n[..],
+            _ => return Err(StatusCode::MethodNotAllowed)
+        };
+        
+        let has_sid = headers.get_raw(SID::header_name()).is_some();
+        let has_callback = headers.get_raw(Callback::header_name()).is_some();
+        let has_nt = headers.get_raw(NT::header_name()).is_some();
+        
+        // SID Can Not Be Combined With CALLBACK Or NT
+        if has_sid && (has_callback || has_nt) {
+            return Err(StatusCode::BadRequest)
+        }
+        
+        let mut response = Headers::new();
+        match method {
+            SUBSCRIBE_METHOD if has_sid => {
+                let sid = try!(headers.get::<SID>().ok_or(StatusCode::PreconditionFailed));
+                let timeout = try!(self.renew(sid, headers.get::<Timeout>().map(|&n| n), now)
+                    .ok_or(StatusCode::PreconditionFailed));
+                
+                response.set(sid.clone());
+                response.set(timeout);
+                
+                Ok((response, None))
+            },
+            SUBSCRIBE_METHOD => {
+                let nt_valid = match headers.get::<NT>() {
+                    Some(&NT(FieldPair::UPnP(ref n))) => &n[..] == EVENT_NT_VALUE,
+                    _ => false
+                };
+                let callbacks = match headers.get::<Callback>() {
+                    Some(&Callback(ref n)) if nt_valid => n.clone(),
+                    _ => return Err(StatusCode::PreconditionFailed)
+                };
+                
+                let (sid, timeout) = self.subscribe(callbacks, headers.get::<Timeout>().map(|&n| n), now);
+                response.set(sid.clone());
+                response.set(timeout);
+                
+                Ok((response, Some(sid)))
+            },
+            UNSUBSCRIBE_METHOD => {
+                let sid = try!(headers.get::<SID>().ok_or(StatusCode::PreconditionFailed));
+                
+                if self.unsubscribe(sid) {
+                    Ok((response, None))
+                } else {
+                    Err(StatusCode::PreconditionFailed)
+                }
+            },
+            _ => Err(StatusCode::MethodNotAllowed)
+        }
+    }
+    
+    fn subscribe(&mut self, callbacks: Vec<Url>, timeout: Option<Timeout>, now: SteadyTime)
+        -> (SID, Timeout) {
+        let sid = SID(format!("uuid:{}", util::random_uuid()));
+        let seconds = accepted_seconds(timeout);
+        
+        self.subscribers.push(Subscriber{ sid: sid.clone(), callbacks: callbacks,
+            expires: now + Duration::seconds(seconds as i64), seq: Seq(0), initial_sent: false });
+        
+        (sid, Timeout::Seconds(seconds))
+    }
+    
+    fn renew(&mut self, sid: &SID, timeout: Option<Timeout>, now: SteadyTime) -> Option<Timeout> {
+        let seconds = accepted_seconds(timeout);
+        
+        self.subscribers.iter_mut().find(|n| n.sid == *sid).map(|subscriber| {
+            subscriber.expires = now + Duration::seconds(seconds as i64);
+            
+            Timeout::Seconds(seconds)
+        })
+    }
+    
+    fn unsubscribe(&mut self, sid: &SID) -> bool {
+        let count = self.subscribers.len();
+        self.subscribers.retain(|n| n.sid != *sid);
+        
+        self.subscribers.len() != count
+    }
+    
+    fn queue_initial_event(&mut self, sid: &SID) {
+        let properties = self.variables.iter().map(|n| (n.name.clone(), n.value.clone())).collect::<Vec<_>>();
+        let body = property_set_body(&properties[..]);
+        
+        if let Some(subscriber) = self.subscribers.iter_mut().find(|n| n.sid == *sid && !n.initial_sent) {
+            subscriber.initial_sent = true;
+            
+            self.outbox.push(subscriber.notification(body));
+        }
+    }
+    
+    /// Queue an event message carrying the given properties for every
+    /// subscription that has already received its initial event message.
+    fn queue_event(&mut self, properties: &[(String, String)]) {
+        let body = property_set_body(properties);
+        
+        for subscriber in self.subscribers.iter_mut().filter(|n| n.initial_sent) {
+            self.outbox.push(subscriber.notification(body.clone()));
+        }
+    }
+}
+
+impl Subscriber {
+    /// Build the next event message for the subscription.
+    fn notification(&mut self, body: String) -> Notification {
+        let seq = self.seq;
+        self.seq = seq.next();
+        
+        Notification{ callbacks: self.callbacks.clone(), sid: self.sid.clone(), seq: seq, body: body }
+    }
+}
+
+/// Event messages waiting to be delivered to a single subscription.
+///
+/// Messages are sent in order from a thread of the queue's own. A message that
+/// could not be delivered is given up on, but the subscription is kept until
+/// it expires or is cancelled.
+struct DeliveryQueue {
+    sid:       SID,
+    sender:    Sender<Notification>,
+    cancelled: Arc<AtomicBool>,
+    worker:    JoinHandle<()>
+}
+
+impl DeliveryQueue {
+    fn spawn(sid: SID) -> DeliveryQueue {
+        let (sender, receiver) = mpsc::channel::<Notification>();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        
+        let cancel_clone = cancelled.clone();
+        let worker = thread::spawn(move || {
+            for notification in receiver.iter() {
+                if cancel_clone.load(Ordering::SeqCst) {
+                    break;
+                }
+                
+                notification.deliver();
+            }
+        });
+        
+        DeliveryQueue{ sid: sid, sender: sender, cancelled: cancelled, worker: worker }
+    }
+    
+    fn push(&self, notification: Notification) {
+        let _ = self.sender.send(notification);
+    }
+    
+    /// Drop any messages that have not been sent out yet.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+    
+    /// Wait for every queued message to be sent out.
+    fn finish(self) {
+        let DeliveryQueue{ sender, worker, .. } = self;
+        mem::drop(sender);
+        
+        let _ = worker.join();
+    }
+}
+
+/// Returns the number of seconds a subscription is granted for the requested
+/// timeout, capped at the maximum and falling back to the default if none (or
+/// infinite) was requested.
+fn accepted_seconds(timeout: Option<Timeout>) -> u32 {
+    match timeout {
+        Some(Timeout::Seconds(n)) if n > 0 => cmp::min(n, MAX_TIMEOUT_SECONDS),
+        _ => gena::DEFAULT_TIMEOUT_SECONDS
+    }
+}
+
+/// Returns true if the numeric values differ by at least the given delta.
+///
+/// Values that are not numeric always count as a change.
+fn exceeds_delta(old: &str, new: &str, delta: u32) -> bool {
+    match (old.trim().parse::<f64>(), new.trim().parse::<f64>()) {
+        (Ok(old), Ok(new)) => (new - old).abs() >= delta as f64,
+        _ => true
+    }
+}
+
+/// Build the body of an event message carrying the given properties.
+fn property_set_body(properties: &[(String, String)]) -> String {
+    let mut body = format!("{}<e:propertyset xmlns:e=\"{}\">", XML_DECLARATION, EVENT_NAMESPACE);
+    
+    for &(ref name, ref value) in properties.iter() {
+        body.push_str(&format!("<e:property><{0}>{1}</{0}></e:property>", name, xml::escape(&value[..]))[..]);
+    }
+    body.push_str("</e:propertyset>");
+    
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener};
+    use std::sync::{Arc};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::{self};
+    
+    use hyper::header::{Headers};
+    use hyper::method::{Method};
+    use hyper::status::{StatusCode};
+    use time::{Duration, SteadyTime};
+    
+    use gena::event::{PropertySet};
+    use gena::header::{SID, Seq, Timeout};
+    use super::{Moderation, Publisher, PublisherState};
+    
+    fn subscribe_headers() -> Headers {
+        let mut headers = Headers::new();
+        headers.set_raw("CALLBACK", vec![b"<http://192.168.1.5:49200/event>".to_vec()]);
+        headers.set_raw("NT", vec![b"upnp:event".to_vec()]);
+        headers.set_raw("TIMEOUT", vec![b"Second-300".to_vec()]);
+        
+        headers
+    }
+    
+    fn subscribe(publisher: &Publisher, addr: &str) -> SID {
+        let mut headers = subscribe_headers();
+        headers.set_raw("CALLBACK", vec![format!("<http://{}/event>", addr).into_bytes()]);
+        
+        let (_, sid) = publisher.accept(&Method::Extension("SUBSCRIBE".to_string()), &headers).unwrap();
+        sid.unwrap()
+    }
+    
+    fn subscribed_state() -> (PublisherState, SID) {
+        let mut state = PublisherState::new();
+        state.add_variable("Status", "0", Moderation::Unmoderated);
+        state.add_variable("LoadLevel", "10", Moderation::MinimumDelta(5));
+        state.add_variable("Power", "100", Moderation::MaximumRate(1000));
+        
+        let method = Method::Extension("SUBSCRIBE".to_string());
+        let (_, sid) = state.accept(&method, &subscribe_headers(), SteadyTime::now()).unwrap();
+        let sid = sid.unwrap();
+        state.queue_initial_event(&sid);
+        
+        (state, sid)
+    }
+    
+    #[test]
+    fn positive_initial_event() {
+        let (state, _) = subscribed_state();
+        let property_set = PropertySet::new(state.outbox[0].seq.0, &state.outbox[0].body[..]).unwrap();
+        
+        assert_eq!(state.outbox.len(), 1);
+        assert_eq!(property_set.seq(), 0);
+        assert_eq!(property_set.iter().count(), 3);
+        assert_eq!(property_set.get("LoadLevel").unwrap(), "10");
+    }
+    
+    #[test]
+    fn positive_increasing_seq() {
+        let (mut state, _) = subscribed_state();
+        state.set_variable("Status", "1", SteadyTime::now());
+        state.set_variable("Status", "0", SteadyTime::now());
+        
+        assert_eq!(state.outbox.iter().map(|n| n.seq).collect::<Vec<_>>(), vec![Seq(0), Seq(1), Seq(2)]);
+    }
+    
+    #[test]
+    fn positive_minimum_delta() {
+        let (mut state, _) = subscribed_state();
+        state.set_variable("LoadLevel", "12", SteadyTime::now());
+        assert_eq!(state.outbox.len(), 1);
+        
+        state.set_variable("LoadLevel", "15", SteadyTime::now());
+        assert_eq!(state.outbox.len(), 2);
+    }
+    
+    #[test]
+    fn positive_maximum_rate() {
+        let (mut state, _) = subscribed_state();
+        let start = SteadyTime::now();
+        
+        state.set_variable("Power", "90", start);
+        state.set_variable("Power", "80", start + Duration::milliseconds(100));
+        state.set_variable("Power", "70", start + Duration::milliseconds(200));
+        state.flush_moderated(start + Duration::milliseconds(500));
+        assert_eq!(state.outbox.len(), 2);
+        
+        state.flush_moderated(start + Duration::milliseconds(1000));
+        let property_set = PropertySet::new(state.outbox[2].seq.0, &state.outbox[2].body[..]).unwrap();
+        assert_eq!(property_set.get("Power").unwrap(), "70");
+    }
+    
+    #[test]
+    fn positive_renew_and_unsubscribe() {
+        let (mut state, sid) = subscribed_state();
+        let mut headers = Headers::new();
+        headers.set(sid);
+        
+        let subscribe = Method::Extension("SUBSCRIBE".to_string());
+        let unsubscribe = Method::Extension("UNSUBSCRIBE".to_string());
+        state.accept(&subscribe, &headers, SteadyTime::now()).unwrap();
+        state.accept(&unsubscribe, &headers, SteadyTime::now()).unwrap();
+        
+        assert!(state.subscribers.is_empty());
+        assert_eq!(state.accept(&unsubscribe, &headers, SteadyTime::now()).err(),
+            Some(StatusCode::PreconditionFailed));
+    }
+    
+    #[test]
+    fn positive_expired_subscription() {
+        let (mut state, _) = subscribed_state();
+        state.expire(SteadyTime::now() + Duration::seconds(301));
+        
+        assert!(state.subscribers.is_empty());
+    }
+    
+    #[test]
+    fn positive_capped_timeout() {
+        let mut state = PublisherState::new();
+        let (_, requested) = state.subscribe(Vec::new(), Some(Timeout::Seconds(86400)), SteadyTime::now());
+        let (_, infinite) = state.subscribe(Vec::new(), Some(Timeout::Infinite), SteadyTime::now());
+        
+        assert_eq!(requested, Timeout::Seconds(1800));
+        assert_eq!(infinite, Timeout::Seconds(1800));
+    }
+    
+    #[test]
+    fn positive_keep_unreachable() {
+        let publisher = Publisher::new();
+        publisher.add_variable("Status", "0", Moderation::Unmoderated);
+        
+        let sid = subscribe(&publisher, "127.0.0.1:1");
+        publisher.send_initial_event(&sid);
+        thread::sleep_ms(500);
+        
+        assert_eq!(publisher.subscriptions(), 1);
+    }
+    
+    #[test]
+    fn positive_slow_subscriber() {
+        let slow = TcpListener::bind("127.0.0.1:0").unwrap();
+        let fast = TcpListener::bind("127.0.0.1:0").unwrap();
+        let publisher = Publisher::new();
+        publisher.add_variable("Status", "0", Moderation::Unmoderated);
+        
+        // Slow Subscriber Never Answers, Fast Subscriber Answers Right Away
+        let delivered = Arc::new(AtomicUsize::new(0));
+        let (fast_addr, delivered_clone) = (fast.local_addr().unwrap(), delivered.clone());
+        thread::spawn(move || {
+            for stream in fast.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                
+                while !String::from_utf8_lossy(&request[..]).contains("</e:propertyset>") {
+                    let size = stream.read(&mut buf).unwrap();
+                    request.extend(buf[..size].iter().cloned());
+                }
+                stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+                
+                delivered_clone.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        
+        let slow_sid = subscribe(&publisher, &slow.local_addr().unwrap().to_string()[..]);
+        let fast_sid = subscribe(&publisher, &fast_addr.to_string()[..]);
+        publisher.send_initial_event(&slow_sid);
+        publisher.send_initial_event(&fast_sid);
+        thread::sleep_ms(500);
+        
+        publisher.set_variable("Status", "1");
+        thread::sleep_ms(1000);
+        
+        assert_eq!(delivered.load(Ordering::SeqCst), 2);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_sid_with_callback() {
+        let (mut state, sid) = subscribed_state();
+        let mut headers = subscribe_headers();
+        headers.set(sid);
+        
+        state.accept(&Method::Extension("SUBSCRIBE".to_string()), &headers, SteadyTime::now()).unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+use std::borrow::{ToOwned};
+use std::cmp;
+use std::error::{Error};
+use std::io::{Read};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self};
+
+use hyper::{Client, Server};
+use hyper::header::{Header, Headers};
+use hyper::method::{Method};
+use hyper::net::{Fresh};
+use hyper::server::{Handler, Listening, Request, Response};
+use hyper::status::{StatusCode};
+use time::{Duration, PreciseTime};
+use url::{Url};
+
+use {GENAError, GENAResult};
+use gena::{EVENT_NT_VALUE, EVENT_NTS_VALUE, NOTIFY_METHOD, NTS_HEADER_NAME, SUBSCRIBE_METHOD,
+           UNSUBSCRIBE_METHOD};
+use gena::event::{PropertySet};
+use gena::header::{Callback, Seq, SID, Timeout};
+use ssdp::{FieldPair};
+use ssdp::header::{NT};
+use util;
+
+/// Shortest time to wait between renewals of a subscription.
+const MIN_RENEW_SECONDS: i64 = 15;
+
+/// Time to sleep between checks for a cancelled subscription.
+const POLL_INTERVAL_MS: u32 = 100;
+
+/// Subscription to the evented state variables of a service.
+///
+/// Event messages are received on a local HTTP listener and forwarded to the
+/// handler that the subscription was created with. The subscription is renewed
+/// in the background once half of its timeout has passed, and is cancelled with
+/// the publisher when it is dropped.
+pub struct Subscription {
+    event_url: Url,
+    sid:       SID,
+    timeout:   Arc<Mutex<Timeout>>,
+    kill_flag: Arc<AtomicBool>,
+    listening: Option<Listening>
+}
+
+impl Subscription {
+    /// Subscribe to the publisher at the given event url.
+    ///
+    /// The handler will be called from a separate thread for every event
+    /// message received, starting with the initial event message. This is a
+    /// blocking operation.
+    pub fn new<F>(event_url: &Url, timeout: Timeout, handler: F) -> GENAResult<Subscription>
+        where F: FnMut(PropertySet) + Send + 'static {
+        let host = try!(event_url.serialize_host().ok_or(
+            GENAError::InvalidHeader(Callback::header_name(), "Event Url Has No Host")
+        ));
+        let port = event_url.port_or_default().unwrap_or(80);
+        let local_ip = try!(util::local_ipv4_for((&host[..], port)).map_err(|e|
+            GENAError::Other(Box::new(e) as Box<Error>)
+        ));
+        
+        let expected_sid = Arc::new(Mutex::new(None));
+        let kill_flag = Arc::new(AtomicBool::new(false));
+        let notify_handler = NotifyHandler::new(handler, expected_sid.clone(), kill_flag.clone());
+        
+        let listening = try!(Server::http(notify_handler).listen((local_ip, 0))
+            .map_err(|e| GENAError::Other(Box::new(e) as Box<Error>)));
+        let callback_url = try!(Url::parse(&format!("http://{}/", listening.socket)[..]).map_err(|_|
+            GENAError::InvalidHeader(Callback::header_name(), "Could Not Create Callback Url")
+        ));
+        
+        let mut headers = Headers::new();
+        headers.set(Callback(vec![callback_url]));
+        headers.set(NT(FieldPair::UPnP(EVENT_NT_VALUE.to_vec())));
+        headers.set(timeout);
+        
+        let response_headers = match send_request(SUBSCRIBE_METHOD, event_url, headers) {
+            Ok(n)  => n,
+            Err(e) => {
+                close_in_background(listening);
+                return Err(e)
+            }
+        };
+        let sid = match response_headers.get::<SID>() {
+            Some(n) => n.clone(),
+            None    => {
+                close_in_background(listening);
+                return Err(GENAError::MissingHeader(SID::header_name()))
+            }
+        };
+        *expected_sid.lock().unwrap() = Some(sid.clone());
+        
+        let timeout = Arc::new(Mutex::new(response_headers.get::<Timeout>().map(|&n| n).unwrap_or(timeout)));
+        spawn_renewal(event_url.clone(), sid.clone(), timeout.clone(), kill_flag.clone());
+        
+        Ok(Subscription{ event_url: event_url.clone(), sid: sid, timeout: timeout, kill_flag: kill_flag,
+            listening: Some(listening) })
+    }
+    
+    /// Returns the subscription identifier assigned by the publisher.
+    pub fn sid(&self) -> &str {
+        &self.sid.0[..]
+    }
+    
+    /// Returns the duration of the subscription that the publisher accepted.
+    pub fn timeout(&self) -> Timeout {
+        *self.timeout.lock().unwrap()
+    }
+    
+    /// Renew the subscription right away, without waiting for the background
+    /// renewal.
+    ///
+    /// This is a blocking operation.
+    pub fn renew(&mut self) -> GENAResult<()> {
+        renew(&self.event_url, &self.sid, &self.timeout)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // Stop Renewing And Turn Away Any Further Event Messages
+        self.kill_flag.store(true, Ordering::SeqCst);
+        
+        let mut headers = Headers::new();
+        headers.set(self.sid.clone());
+        
+        // Don't Care About Return Values, If They Fail Nothing We Can Do...
+        let _ = send_request(UNSUBSCRIBE_METHOD, &self.event_url, headers);
+        if let Some(listening) = self.listening.take() {
+            close_in_background(listening);
+        }
+    }
+}
+
+/// Close the listener without blocking the current thread, since closing waits
+/// on the listening thread to finish up.
+fn close_in_background(mut listening: Listening) {
+    thread::spawn(move || {
+        let _ = listening.close();
+    });
+}
+
+/// Spawn a thread that renews the subscription once half of its timeout has
+/// passed, until the kill flag is set.
+///
+/// Subscriptions with an infinite timeout are never renewed.
+fn spawn_renewal(event_url: Url, sid: SID, timeout: Arc<Mutex<Timeout>>, kill_flag: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        loop {
+            let delay = match *timeout.lock().unwrap() {
+                Timeout::Seconds(n) => Duration::seconds(cmp::max(n as i64 / 2, MIN_RENEW_SECONDS)),
+                Timeout::Infinite   => break
+            };
+            
+            let start = PreciseTime::now();
+            while start.to(PreciseTime::now()) < delay && !kill_flag.load(Ordering::SeqCst) {
+                thread::sleep_ms(POLL_INTERVAL_MS);
+            }
+            if kill_flag.load(Ordering::SeqCst) {
+                break;
+            }
+            
+            // Half Of The Timeout Is Left To Try Again If This Fails
+            let _ = renew(&event_url, &sid, &timeout);
+        }
+    });
+}
+
+/// Renew the subscription, storing the timeout that the publisher accepted.
+fn renew(event_url: &Url, sid: &SID, timeout: &Mutex<Timeout>) -> GENAResult<()> {
+    let mut headers = Headers::new();
+    headers.set(sid.clone());
+    headers.set(*timeout.lock().unwrap());
+    
+    let response_headers = try!(send_request(SUBSCRIBE_METHOD, event_url, headers));
+    if let Some(&n) = response_headers.get::<Timeout>() {
+        *timeout.lock().unwrap() = n;
+    }
+    
+    Ok(())
+}
+
+/// Send a GENA request with the given method and headers to the event url.
+///
+/// Returns the headers of the response.
+fn send_request(method: &str, event_url: &Url, headers: Headers) -> GENAResult<Headers> {
+    let mut client = Client::new();
+    
+    let response = try!(client.request(Method::Extension(method.to_owned()), event_url.clone())
+        .headers(headers).send().map_err(|e| GENAError::Other(Box::new(e) as Box<Error>)));
+    
+    match response.status {
+        StatusCode::Ok => Ok(response.headers.clone()),
+        n => Err(GENAError::ResponseCode(n.to_u16()))
+    }
+}
+
+/// Handles event messages sent to the callback of a subscription.
+///
+/// Event messages for some other SID, or that arrive after the subscription
+/// was cancelled, are turned away so that the publisher drops them.
+struct NotifyHandler<F> {
+    handler:   Mutex<F>,
+    sid:       Arc<Mutex<Option<SID>>>,
+    kill_flag: Arc<AtomicBool>
+}
+
+impl<F> NotifyHandler<F> where F: FnMut(PropertySet) + Send {
+    fn new(handler: F, sid: Arc<Mutex<Option<SID>>>, kill_flag: Arc<AtomicBool>) -> NotifyHandler<F> {
+        NotifyHandler{ handler: Mutex::new(handler), sid: sid, kill_flag: kill_flag }
+    }
+}
+
+impl<F> Handler for NotifyHandler<F> where F: FnMut(PropertySet) + Send {
+    fn handle(&self, mut req: Request, mut res: Response<Fresh>) {
+        let expected_sid = self.sid.lock().unwrap().clone();
+        
+        let status = match accept_notify(&mut req, expected_sid.as_ref()) {
+            Ok(_) if self.kill_flag.load(Ordering::SeqCst) => StatusCode::PreconditionFailed,
+            Ok(property_set) => {
+                // Let Thread Panic If Handler Panicked Previously
+                let mut handler = self.handler.lock().unwrap();
+                (&mut *handler)(property_set);
+                
+                StatusCode::Ok
+            },
+            Err(n) => n
+        };
+        
+        *res.status_mut() = status;
+        let _ = res.start().and_then(|res| res.end());
+    }
+}
+
+/// Validate an incoming event message and parse its property set.
+///
+/// The SID is only checked once it is known, since the initial event message
+/// may arrive before the response to the subscription request. Returns the
+/// status code that should be sent back on failure.
+fn accept_notify(req: &mut Request, expected_sid: Option<&SID>) -> Result<PropertySet, StatusCode> {
+    match req.method {
+        Method::Extension(ref n) if &n[..] == NOTIFY_METHOD => (),
+        _ => return Err(StatusCode::MethodNotAllowed)
+    };
+    
+    let nt_valid = match req.headers.get::<NT>() {
+        Some(&NT(FieldPair::UPnP(ref n))) => &n[..] == EVENT_NT_VALUE,
+        _ => false
+    };
+    let nts_valid = match req.headers.get_raw(NTS_HEADER_NAME) {
+        Some(n) => n.len() == 1 && &n[0][..] == EVENT_NTS_VALUE,
+        None    => false
+    };
+    let sid_valid = match (req.headers.get::<SID>(), expected_sid) {
+        (Some(n), Some(expected)) => n == expected,
+        (Some(_), None)           => true,
+        (None, _)                 => false
+    };
+    if !nt_valid || !nts_valid || !sid_valid {
+        return Err(StatusCode::PreconditionFailed)
+    }
+    
+    let seq = match req.headers.get::<Seq>() {
+        Some(&Seq(n)) => n,
+        None          => return Err(StatusCode::PreconditionFailed)
+    };
+    
+    let mut body = String::new();
+    if req.read_to_string(&mut body).is_err() {
+        return Err(StatusCode::BadRequest)
+    }
+    
+    PropertySet::new(seq, &body[..]).map_err(|_| StatusCode::BadRequest)
+}
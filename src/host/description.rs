@@ -0,0 +1,182 @@
+//! Generates the device and service descriptions for devices we are hosting.
+
+use host::model::{Direction, HostedDevice, HostedService, StateVariable};
+use xml;
+
+/// Path that the description of the root device is served at.
+pub const DESCRIPTION_PATH: &'static str = "/description.xml";
+
+const SCPD_PATH_SUFFIX:    &'static str = "scpd.xml";
+const CONTROL_PATH_SUFFIX: &'static str = "control";
+const EVENT_PATH_SUFFIX:   &'static str = "event";
+
+const SERVICE_ID_SEPARATOR: char = ':';
+
+const XML_DECLARATION: &'static str = "<?xml version=\"1.0\"?>";
+const SPEC_VERSION:    &'static str = "<specVersion><major>1</major><minor>1</minor></specVersion>";
+
+/// Returns the path that the description of the service is served at.
+pub fn scpd_path(device: &HostedDevice, service: &HostedService) -> String {
+    service_path(device, service, SCPD_PATH_SUFFIX)
+}
+
+/// Returns the path that actions on the service are invoked at.
+pub fn control_path(device: &HostedDevice, service: &HostedService) -> String {
+    service_path(device, service, CONTROL_PATH_SUFFIX)
+}
+
+/// Returns the path that subscriptions to the service are made at.
+pub fn event_path(device: &HostedDevice, service: &HostedService) -> String {
+    service_path(device, service, EVENT_PATH_SUFFIX)
+}
+
+/// Build the description document of the root device.
+pub fn device_description(root: &HostedDevice, config_id: u32) -> String {
+    let mut desc = format!("{}<root xmlns=\"urn:schemas-upnp-org:device-1-0\" configId=\"{}\">{}",
+        XML_DECLARATION, config_id, SPEC_VERSION);
+    
+    push_device(&mut desc, root);
+    desc.push_str("</root>");
+    
+    desc
+}
+
+/// Build the description document of the service.
+pub fn service_description(service: &HostedService, config_id: u32) -> String {
+    let mut desc = format!("{}<scpd xmlns=\"urn:schemas-upnp-org:service-1-0\" configId=\"{}\">{}",
+        XML_DECLARATION, config_id, SPEC_VERSION);
+    
+    desc.push_str("<actionList>");
+    for action in service.actions().iter() {
+        desc.push_str(&format!("<action><name>{}</name><argumentList>", xml::escape(action.name()))[..]);
+        
+        for arg in action.arguments().iter() {
+            let direction = match arg.direction() {
+                Direction::In  => "in",
+                Direction::Out => "out"
+            };
+            
+            desc.push_str(&format!("<argument><name>{}</name><direction>{}</direction>\
+                <relatedStateVariable>{}</relatedStateVariable></argument>", xml::escape(arg.name()),
+                direction, xml::escape(arg.related_state_variable()))[..]);
+        }
+        desc.push_str("</argumentList></action>");
+    }
+    desc.push_str("</actionList><serviceStateTable>");
+    
+    for variable in service.state_variables().iter() {
+        push_state_variable(&mut desc, variable);
+    }
+    desc.push_str("</serviceStateTable></scpd>");
+    
+    desc
+}
+
+/// Append the device element for the device and its embedded devices.
+fn push_device(desc: &mut String, device: &HostedDevice) {
+    desc.push_str(&format!("<device><deviceType>{}</deviceType><friendlyName>{}</friendlyName>\
+        <manufacturer>{}</manufacturer><modelName>{}</modelName><UDN>uuid:{}</UDN>",
+        xml::escape(&device.device_type().urn()[..]), xml::escape(device.friendly_name()),
+        xml::escape(device.manufacturer()), xml::escape(device.model_name()),
+        xml::escape(device.uuid()))[..]);
+    
+    if !device.services().is_empty() {
+        desc.push_str("<serviceList>");
+        for service in device.services().iter() {
+            desc.push_str(&format!("<service><serviceType>{}</serviceType><serviceId>{}</serviceId>\
+                <SCPDURL>{}</SCPDURL><controlURL>{}</controlURL><eventSubURL>{}</eventSubURL></service>",
+                xml::escape(&service.service_type().urn()[..]), xml::escape(service.service_id()),
+                xml::escape(&scpd_path(device, service)[..]), xml::escape(&control_path(device, service)[..]),
+                xml::escape(&event_path(device, service)[..]))[..]);
+        }
+        desc.push_str("</serviceList>");
+    }
+    
+    if !device.devices().is_empty() {
+        desc.push_str("<deviceList>");
+        for embedded in device.devices().iter() {
+            push_device(desc, embedded);
+        }
+        desc.push_str("</deviceList>");
+    }
+    desc.push_str("</device>");
+}
+
+/// Append the stateVariable element for the state variable.
+fn push_state_variable(desc: &mut String, variable: &StateVariable) {
+    let send_events = if variable.sends_events() { "yes" } else { "no" };
+    
+    desc.push_str(&format!("<stateVariable sendEvents=\"{}\"><name>{}</name><dataType>{}</dataType>",
+        send_events, xml::escape(variable.name()), xml::escape(variable.data_type()))[..]);
+    
+    if let Some(n) = variable.default_value() {
+        desc.push_str(&format!("<defaultValue>{}</defaultValue>", xml::escape(n))[..]);
+    }
+    if !variable.allowed_values().is_empty() {
+        desc.push_str("<allowedValueList>");
+        for value in variable.allowed_values().iter() {
+            desc.push_str(&format!("<allowedValue>{}</allowedValue>", xml::escape(&value[..]))[..]);
+        }
+        desc.push_str("</allowedValueList>");
+    }
+    if let Some((min, max)) = variable.range() {
+        desc.push_str(&format!("<allowedValueRange><minimum>{}</minimum><maximum>{}</maximum>\
+            </allowedValueRange>", xml::escape(min), xml::escape(max))[..]);
+    }
+    desc.push_str("</stateVariable>");
+}
+
+/// Returns the path for some resource of the service.
+///
+/// Only the last portion of the service id is used, since it already has to be
+/// unique within the device.
+fn service_path(device: &HostedDevice, service: &HostedService, suffix: &str) -> String {
+    let service_id = service.service_id();
+    let short_id = match service_id.rfind(SERVICE_ID_SEPARATOR) {
+        Some(n) => &service_id[n + 1..],
+        None    => service_id
+    };
+    
+    format!("/{}/{}/{}", device.uuid(), short_id, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use forum::device::{DeviceType};
+    use forum::service::{ServiceType};
+    use host::model::{Action, HostedDevice, HostedService, StateVariable};
+    use version::{Version};
+    use xml;
+    
+    fn light() -> HostedDevice {
+        let service_type = ServiceType::SwitchPower(Version::V1);
+        let service = HostedService::new(service_type, "urn:upnp-org:serviceId:SwitchPower1")
+            .with_state_variable(StateVariable::new("Status", "boolean").with_default("0").evented())
+            .with_action(Action::new("GetStatus").with_output("ResultStatus", "Status"), |_| {
+                Ok(vec![("ResultStatus", "0".to_string())])
+            });
+        
+        HostedDevice::new(DeviceType::BinaryLight(Version::V1), "ae239f00-ae2b-bbad-ddf1-88ddcc00a234",
+            "Porch Light", "omni", "Light").with_service(service)
+    }
+    
+    #[test]
+    fn positive_device_description() {
+        let desc = super::device_description(&light(), 3);
+        
+        assert!(desc.contains("configId=\"3\""));
+        assert_eq!(xml::element(&desc[..], "deviceType").unwrap(),
+            "urn:schemas-upnp-org:device:BinaryLight:1");
+        assert_eq!(xml::element(&desc[..], "controlURL").unwrap(),
+            "/ae239f00-ae2b-bbad-ddf1-88ddcc00a234/SwitchPower1/control");
+    }
+    
+    #[test]
+    fn positive_service_description() {
+        let device = light();
+        let desc = super::service_description(&device.services()[0], 3);
+        
+        assert_eq!(xml::element(&desc[..], "relatedStateVariable").unwrap(), "Status");
+        assert!(desc.contains("<stateVariable sendEvents=\"yes\"><name>Status</name>"));
+    }
+}
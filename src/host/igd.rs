@@ -0,0 +1,632 @@
+//! In-process InternetGatewayDevice for testing port mapping clients.
+//!
+//! The emulator is put together from the same host, eventing and SSDP pieces
+//! that are available for hosting any other device. It keeps port mappings in
+//! memory and lets tests inject faults, expire leases and simulate a reboot of
+//! the gateway, none of which can be done reliably against a real router.
+
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+
+use time::{Duration, SteadyTime};
+use url::{Url};
+
+use {SSDPError, SSDPResult, SimpleError};
+use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use forum::service::wan_ip_connection::{PortMapping, Protocol, ARRAY_INDEX_INVALID_CODE,
+                                        CONFLICT_IN_MAPPING_ENTRY_CODE, NO_SUCH_ENTRY_CODE};
+use gena::{Publisher};
+use host::model::{Action, ActionError, ActionResult, Arguments, HostedDevice, HostedService, StateVariable};
+use host::server::{DeviceHost};
+use soap::{value};
+use ssdp::{Policy};
+use ssdp::advertiser::{Advertiser};
+use ssdp::responder::{Responder};
+use util;
+use version::{Version};
+
+const SERVER_VALUE: &'static str = "Linux/3.0 UPnP/1.1 omni-igd/1.0";
+const MANUFACTURER: &'static str = "omni";
+const MODEL_NAME:   &'static str = "IGDEmulator";
+const CONFIG_ID:    u32 = 1;
+
+/// Longest lease granted by version 2 of the service, which has no permanent leases.
+const MAX_LEASE_DURATION: u32 = 604800;
+/// First external port handed out by AddAnyPortMapping when the requested one is taken.
+const MIN_DYNAMIC_PORT:   u16 = 1024;
+
+const WILDCARD_NOT_PERMITTED_CODE: u16 = 716;
+
+const ARRAY_INDEX_INVALID_DESC:       &'static str = "SpecifiedArrayIndexInvalid";
+const NO_SUCH_ENTRY_DESC:             &'static str = "NoSuchEntryInArray";
+const WILDCARD_NOT_PERMITTED_DESC:    &'static str = "WildCardNotPermittedInExtPort";
+const CONFLICT_IN_MAPPING_ENTRY_DESC: &'static str = "ConflictInMappingEntry";
+const INJECTED_FAULT_DESC:            &'static str = "Injected Fault";
+
+const ADD_PORT_MAPPING_ACTION:        &'static str = "AddPortMapping";
+const ADD_ANY_PORT_MAPPING_ACTION:    &'static str = "AddAnyPortMapping";
+const DELETE_PORT_MAPPING_ACTION:     &'static str = "DeletePortMapping";
+const GET_SPECIFIC_ENTRY_ACTION:      &'static str = "GetSpecificPortMappingEntry";
+const GET_GENERIC_ENTRY_ACTION:       &'static str = "GetGenericPortMappingEntry";
+const GET_EXTERNAL_IP_ADDRESS_ACTION: &'static str = "GetExternalIPAddress";
+
+const REMOTE_HOST_ARG:         &'static str = "NewRemoteHost";
+const EXTERNAL_PORT_ARG:       &'static str = "NewExternalPort";
+const PROTOCOL_ARG:            &'static str = "NewProtocol";
+const INTERNAL_PORT_ARG:       &'static str = "NewInternalPort";
+const INTERNAL_CLIENT_ARG:     &'static str = "NewInternalClient";
+const ENABLED_ARG:             &'static str = "NewEnabled";
+const DESCRIPTION_ARG:         &'static str = "NewPortMappingDescription";
+const LEASE_DURATION_ARG:      &'static str = "NewLeaseDuration";
+const RESERVED_PORT_ARG:       &'static str = "NewReservedPort";
+const PORT_MAPPING_INDEX_ARG:  &'static str = "NewPortMappingIndex";
+const EXTERNAL_IP_ADDRESS_ARG: &'static str = "NewExternalIPAddress";
+
+const EXTERNAL_IP_ADDRESS_VAR: &'static str = "ExternalIPAddress";
+const NUMBER_OF_ENTRIES_VAR:   &'static str = "PortMappingNumberOfEntries";
+const REMOTE_HOST_VAR:         &'static str = "RemoteHost";
+const EXTERNAL_PORT_VAR:       &'static str = "ExternalPort";
+const INTERNAL_PORT_VAR:       &'static str = "InternalPort";
+const PROTOCOL_VAR:            &'static str = "PortMappingProtocol";
+const INTERNAL_CLIENT_VAR:     &'static str = "InternalClient";
+const ENABLED_VAR:             &'static str = "PortMappingEnabled";
+const DESCRIPTION_VAR:         &'static str = "PortMappingDescription";
+const LEASE_DURATION_VAR:      &'static str = "PortMappingLeaseDuration";
+
+const WAN_DEVICE_NAME:            &'static str = "WANDevice";
+const WAN_CONNECTION_DEVICE_NAME: &'static str = "WANConnectionDevice";
+const WAN_IP_CONNECTION_ID:       &'static str = "urn:upnp-org:serviceId:WANIPConn1";
+
+/// InternetGatewayDevice advertised over SSDP and served over HTTP from
+/// within the current process.
+///
+/// Nothing is sent out on the network, SSDP traffic stays on the loopback
+/// interface. The gateway says byebye and stops serving when it is dropped.
+pub struct IGDEmulator {
+    gateway:     Arc<Mutex<Gateway>>,
+    publisher:   Publisher,
+    advertiser:  Advertiser,
+    _responder:  Responder,
+    search_addr: SocketAddr,
+    host:        Option<DeviceHost>,
+    uuid:        String,
+    boot_id:     u32
+}
+
+impl IGDEmulator {
+    /// Start an emulated gateway implementing the given version of the
+    /// InternetGatewayDevice, served on the loopback interface.
+    ///
+    /// Notify messages are sent to the given address rather than the multicast
+    /// group, and search requests are answered on search_addr.
+    ///
+    /// Version 2 of the gateway adds AddAnyPortMapping and only hands out
+    /// leases of up to a week.
+    pub fn start(version: Version, notify_addr: SocketAddr) -> SSDPResult<IGDEmulator> {
+        let uuid = util::random_uuid();
+        let gateway = Arc::new(Mutex::new(Gateway::new(version)));
+        
+        let service = wan_ip_connection(version, &gateway);
+        let publisher = service.publisher().clone();
+        
+        // WANIPConnection Lives On A WANConnectionDevice Inside A WANDevice
+        let connection_type = DeviceType::Unimplemented(WAN_CONNECTION_DEVICE_NAME.to_string(), version);
+        let connection_device = HostedDevice::new(connection_type, &util::random_uuid()[..],
+            "WAN Connection Device", MANUFACTURER, MODEL_NAME).with_service(service);
+        let wan_device = HostedDevice::new(DeviceType::Unimplemented(WAN_DEVICE_NAME.to_string(), version),
+            &util::random_uuid()[..], "WAN Device", MANUFACTURER, MODEL_NAME).with_device(connection_device);
+        let root = HostedDevice::new(DeviceType::InternetGateway(version), &uuid[..], "Emulated Gateway",
+            MANUFACTURER, MODEL_NAME).with_device(wan_device);
+        
+        let host = try!(DeviceHost::start(&root, Ipv4Addr::new(127, 0, 0, 1), 0, CONFIG_ID)
+            .map_err(|_| SSDPError::Other(Box::new(SimpleError::new("Could Not Start The Device Host")))));
+        let advert = Arc::new(Mutex::new(root.advertisement(host.location().clone(), SERVER_VALUE, 1,
+            CONFIG_ID)));
+        
+        // Keep SSDP Traffic Off Of The Network
+        let (search_udp, search_addr) = try!(bind_loopback());
+        let (notify_udp, _) = try!(bind_loopback());
+        
+        let responder = try!(Responder::start_on(advert.clone(), search_udp, Policy::default()));
+        let advertiser = try!(Advertiser::start_on(advert, notify_udp, notify_addr));
+        
+        Ok(IGDEmulator{ gateway: gateway, publisher: publisher, advertiser: advertiser, _responder: responder,
+            search_addr: search_addr, host: Some(host), uuid: uuid, boot_id: 1 })
+    }
+    
+    /// Returns the address that search requests for the gateway should be sent to.
+    pub fn search_addr(&self) -> SocketAddr {
+        self.search_addr
+    }
+    
+    /// Returns the url that the description of the gateway is served at.
+    pub fn location(&self) -> &Url {
+        self.host.as_ref().unwrap().location()
+    }
+    
+    /// Returns the uuid of the root device, without the "uuid:" prefix.
+    pub fn uuid(&self) -> &str {
+        &self.uuid[..]
+    }
+    
+    /// Returns the current BOOTID.UPNP.ORG of the gateway.
+    pub fn boot_id(&self) -> u32 {
+        self.boot_id
+    }
+    
+    /// Returns the port mappings that are currently in place.
+    pub fn mappings(&self) -> Vec<PortMapping> {
+        let mut gateway = self.gateway.lock().unwrap();
+        gateway.expire(SteadyTime::now());
+        
+        gateway.leases.iter().map(|n| n.mapping.clone()).collect()
+    }
+    
+    /// Change the external address reported by the gateway.
+    pub fn set_external_ip(&self, ip: Ipv4Addr) {
+        self.gateway.lock().unwrap().external_ip = ip;
+        
+        self.publisher.set_variable(EXTERNAL_IP_ADDRESS_VAR, &ip.to_string()[..]);
+    }
+    
+    /// Have the next invocation of the given action fail with the given fault
+    /// code, such as CONFLICT_IN_MAPPING_ENTRY_CODE.
+    ///
+    /// Faults injected for the same action are used up in order.
+    pub fn inject_fault(&self, action: &str, code: u16) {
+        self.gateway.lock().unwrap().faults.push((action.to_string(), code));
+    }
+    
+    /// Expire every port mapping that has a lease right away, as if their
+    /// lease durations had passed.
+    pub fn expire_leases(&self) {
+        let mut gateway = self.gateway.lock().unwrap();
+        gateway.leases.retain(|n| n.expires.is_none());
+        
+        self.publisher.set_variable(NUMBER_OF_ENTRIES_VAR, &gateway.leases.len().to_string()[..]);
+    }
+    
+    /// Simulate a reboot of the gateway.
+    ///
+    /// Every port mapping is lost and the gateway re-announces itself with
+    /// the next BOOTID.UPNP.ORG, which control points use to notice that their
+    /// port mappings need to be put back in place.
+    pub fn reboot(&mut self) -> SSDPResult<()> {
+        {
+            let mut gateway = self.gateway.lock().unwrap();
+            gateway.leases.clear();
+            gateway.faults.clear();
+        }
+        self.publisher.set_variable(NUMBER_OF_ENTRIES_VAR, "0");
+        
+        self.boot_id += 1;
+        self.advertiser.set_boot_id(self.boot_id)
+    }
+}
+
+impl Drop for IGDEmulator {
+    fn drop(&mut self) {
+        if let Some(n) = self.host.take() {
+            let _ = n.close();
+        }
+    }
+}
+
+/// Port mapping along with the time its lease runs out, if it has one.
+struct Lease {
+    mapping: PortMapping,
+    expires: Option<SteadyTime>
+}
+
+/// Port mapping state of the emulated gateway.
+struct Gateway {
+    version:     Version,
+    external_ip: Ipv4Addr,
+    leases:      Vec<Lease>,
+    faults:      Vec<(String, u16)>
+}
+
+impl Gateway {
+    fn new(version: Version) -> Gateway {
+        Gateway{ version: version, external_ip: default_external_ip(), leases: Vec::new(),
+            faults: Vec::new() }
+    }
+    
+    /// Fail with the next fault injected for the given action, if any.
+    fn take_fault(&mut self, action: &str) -> Result<(), ActionError> {
+        match self.faults.iter().position(|&(ref n, _)| n == action) {
+            Some(index) => {
+                let (_, code) = self.faults.remove(index);
+                
+                Err(ActionError::new(code, fault_description(code)))
+            },
+            None => Ok(())
+        }
+    }
+    
+    /// Drop every port mapping whose lease has run out.
+    fn expire(&mut self, now: SteadyTime) {
+        self.leases.retain(|n| n.expires.map_or(true, |expires| expires > now));
+    }
+    
+    fn position(&self, remote_host: &str, external_port: u16, protocol: Protocol) -> Option<usize> {
+        self.leases.iter().position(|n| {
+            n.mapping.remote_host() == remote_host && n.mapping.external_port() == external_port &&
+                n.mapping.protocol() == protocol
+        })
+    }
+    
+    /// Returns true if the external port of the mapping is free for its internal client.
+    fn is_available(&self, mapping: &PortMapping, external_port: u16) -> bool {
+        match self.position(mapping.remote_host(), external_port, mapping.protocol()) {
+            Some(n) => self.leases[n].mapping.internal_client() == mapping.internal_client(),
+            None    => true
+        }
+    }
+    
+    /// Returns the lease duration granted for the requested one.
+    fn granted_lease(&self, requested: u32) -> u32 {
+        if self.version >= Version::V2 && (requested == 0 || requested > MAX_LEASE_DURATION) {
+            MAX_LEASE_DURATION
+        } else {
+            requested
+        }
+    }
+    
+    fn add(&mut self, mapping: PortMapping, now: SteadyTime) -> Result<(), ActionError> {
+        if mapping.external_port() == 0 {
+            return Err(ActionError::new(WILDCARD_NOT_PERMITTED_CODE, WILDCARD_NOT_PERMITTED_DESC))
+        }
+        if !self.is_available(&mapping, mapping.external_port()) {
+            return Err(ActionError::new(CONFLICT_IN_MAPPING_ENTRY_CODE, CONFLICT_IN_MAPPING_ENTRY_DESC))
+        }
+        
+        // Re-Adding A Mapping For The Same Client Updates It
+        if let Some(n) = self.position(mapping.remote_host(), mapping.external_port(), mapping.protocol()) {
+            self.leases.remove(n);
+        }
+        
+        let lease_duration = self.granted_lease(mapping.lease_duration());
+        let expires = if lease_duration == 0 {
+            None
+        } else {
+            Some(now + Duration::seconds(lease_duration as i64))
+        };
+        
+        self.leases.push(Lease{ mapping: mapping.with_lease_duration(lease_duration), expires: expires });
+        
+        Ok(())
+    }
+    
+    fn add_any(&mut self, mapping: PortMapping, now: SteadyTime) -> Result<u16, ActionError> {
+        let requested = mapping.external_port();
+        
+        let port = if requested != 0 && self.is_available(&mapping, requested) {
+            requested
+        } else {
+            try!((MIN_DYNAMIC_PORT..65535).chain(Some(65535).into_iter()).find(|&n|
+                self.position(mapping.remote_host(), n, mapping.protocol()).is_none()
+            ).ok_or(ActionError::new(CONFLICT_IN_MAPPING_ENTRY_CODE, CONFLICT_IN_MAPPING_ENTRY_DESC)))
+        };
+        
+        let mapping = PortMapping::new(mapping.protocol(), port, mapping.internal_client(),
+            mapping.internal_port()).with_remote_host(mapping.remote_host()).with_enabled(mapping.enabled())
+            .with_description(mapping.description()).with_lease_duration(mapping.lease_duration());
+        try!(self.add(mapping, now));
+        
+        Ok(port)
+    }
+    
+    fn delete(&mut self, remote_host: &str, external_port: u16, protocol: Protocol)
+        -> Result<(), ActionError> {
+        match self.position(remote_host, external_port, protocol) {
+            Some(n) => { self.leases.remove(n); Ok(()) },
+            None    => Err(ActionError::new(NO_SUCH_ENTRY_CODE, NO_SUCH_ENTRY_DESC))
+        }
+    }
+}
+
+/// Build the WANIPConnection service of the gateway.
+fn wan_ip_connection(version: Version, gateway: &Arc<Mutex<Gateway>>) -> HostedService {
+    let lease_range = if version >= Version::V2 { MAX_LEASE_DURATION } else { u32::max_value() };
+    let mapping_inputs = |action: Action| {
+        action.with_input(REMOTE_HOST_ARG, REMOTE_HOST_VAR).with_input(EXTERNAL_PORT_ARG, EXTERNAL_PORT_VAR)
+            .with_input(PROTOCOL_ARG, PROTOCOL_VAR).with_input(INTERNAL_PORT_ARG, INTERNAL_PORT_VAR)
+            .with_input(INTERNAL_CLIENT_ARG, INTERNAL_CLIENT_VAR).with_input(ENABLED_ARG, ENABLED_VAR)
+            .with_input(DESCRIPTION_ARG, DESCRIPTION_VAR).with_input(LEASE_DURATION_ARG, LEASE_DURATION_VAR)
+    };
+    let entry_outputs = |action: Action| {
+        action.with_output(INTERNAL_PORT_ARG, INTERNAL_PORT_VAR)
+            .with_output(INTERNAL_CLIENT_ARG, INTERNAL_CLIENT_VAR).with_output(ENABLED_ARG, ENABLED_VAR)
+            .with_output(DESCRIPTION_ARG, DESCRIPTION_VAR).with_output(LEASE_DURATION_ARG, LEASE_DURATION_VAR)
+    };
+    
+    let service = HostedService::new(ServiceType::WANIPConnection(version), WAN_IP_CONNECTION_ID)
+        .with_state_variable(StateVariable::new(EXTERNAL_IP_ADDRESS_VAR, "string")
+            .with_default(&default_external_ip().to_string()[..]).evented())
+        .with_state_variable(StateVariable::new(NUMBER_OF_ENTRIES_VAR, "ui2").with_default("0").evented())
+        .with_state_variable(StateVariable::new(REMOTE_HOST_VAR, "string"))
+        .with_state_variable(StateVariable::new(EXTERNAL_PORT_VAR, "ui2"))
+        .with_state_variable(StateVariable::new(INTERNAL_PORT_VAR, "ui2").with_range("1", "65535"))
+        .with_state_variable(StateVariable::new(PROTOCOL_VAR, "string")
+            .with_allowed_values(&[Protocol::TCP.value(), Protocol::UDP.value()]))
+        .with_state_variable(StateVariable::new(INTERNAL_CLIENT_VAR, "string"))
+        .with_state_variable(StateVariable::new(ENABLED_VAR, "boolean"))
+        .with_state_variable(StateVariable::new(DESCRIPTION_VAR, "string"))
+        .with_state_variable(StateVariable::new(LEASE_DURATION_VAR, "ui4")
+            .with_range("0", &lease_range.to_string()[..]));
+    let publisher = service.publisher().clone();
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    let service = service.with_action(mapping_inputs(Action::new(ADD_PORT_MAPPING_ACTION)), move |args| {
+        let mapping = try!(mapping_arg(args));
+        
+        invoke(&g, &p, ADD_PORT_MAPPING_ACTION, |gateway, now| {
+            try!(gateway.add(mapping, now));
+            
+            Ok(vec![])
+        })
+    });
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    let service = service.with_action(Action::new(DELETE_PORT_MAPPING_ACTION)
+        .with_input(REMOTE_HOST_ARG, REMOTE_HOST_VAR).with_input(EXTERNAL_PORT_ARG, EXTERNAL_PORT_VAR)
+        .with_input(PROTOCOL_ARG, PROTOCOL_VAR), move |args| {
+        let (remote_host, external_port, protocol) = try!(key_args(args));
+        
+        invoke(&g, &p, DELETE_PORT_MAPPING_ACTION, |gateway, _| {
+            try!(gateway.delete(&remote_host[..], external_port, protocol));
+            
+            Ok(vec![])
+        })
+    });
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    let service = service.with_action(entry_outputs(Action::new(GET_SPECIFIC_ENTRY_ACTION)
+        .with_input(REMOTE_HOST_ARG, REMOTE_HOST_VAR).with_input(EXTERNAL_PORT_ARG, EXTERNAL_PORT_VAR)
+        .with_input(PROTOCOL_ARG, PROTOCOL_VAR)), move |args| {
+        let (remote_host, external_port, protocol) = try!(key_args(args));
+        
+        invoke(&g, &p, GET_SPECIFIC_ENTRY_ACTION, |gateway, now| {
+            let index = try!(gateway.position(&remote_host[..], external_port, protocol).ok_or(
+                ActionError::new(NO_SUCH_ENTRY_CODE, NO_SUCH_ENTRY_DESC)
+            ));
+            
+            Ok(entry_args(&gateway.leases[index], now))
+        })
+    });
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    let service = service.with_action(entry_outputs(Action::new(GET_GENERIC_ENTRY_ACTION)
+        .with_input(PORT_MAPPING_INDEX_ARG, NUMBER_OF_ENTRIES_VAR)
+        .with_output(REMOTE_HOST_ARG, REMOTE_HOST_VAR).with_output(EXTERNAL_PORT_ARG, EXTERNAL_PORT_VAR)
+        .with_output(PROTOCOL_ARG, PROTOCOL_VAR)), move |args| {
+        let index = try!(args.integer(PORT_MAPPING_INDEX_ARG)) as usize;
+        
+        invoke(&g, &p, GET_GENERIC_ENTRY_ACTION, |gateway, now| {
+            let lease = try!(gateway.leases.get(index).ok_or(
+                ActionError::new(ARRAY_INDEX_INVALID_CODE, ARRAY_INDEX_INVALID_DESC)
+            ));
+            
+            let mut args = vec![(REMOTE_HOST_ARG, lease.mapping.remote_host().to_string()),
+                                (EXTERNAL_PORT_ARG, lease.mapping.external_port().to_string()),
+                                (PROTOCOL_ARG, lease.mapping.protocol().value().to_string())];
+            args.extend(entry_args(lease, now).into_iter());
+            
+            Ok(args)
+        })
+    });
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    let service = service.with_action(Action::new(GET_EXTERNAL_IP_ADDRESS_ACTION)
+        .with_output(EXTERNAL_IP_ADDRESS_ARG, EXTERNAL_IP_ADDRESS_VAR), move |_| {
+        invoke(&g, &p, GET_EXTERNAL_IP_ADDRESS_ACTION, |gateway, _| {
+            Ok(vec![(EXTERNAL_IP_ADDRESS_ARG, gateway.external_ip.to_string())])
+        })
+    });
+    
+    if version < Version::V2 {
+        return service
+    }
+    
+    let (g, p) = (gateway.clone(), publisher.clone());
+    service.with_action(mapping_inputs(Action::new(ADD_ANY_PORT_MAPPING_ACTION))
+        .with_output(RESERVED_PORT_ARG, EXTERNAL_PORT_VAR), move |args| {
+        let mapping = try!(mapping_arg(args));
+        
+        invoke(&g, &p, ADD_ANY_PORT_MAPPING_ACTION, |gateway, now| {
+            let port = try!(gateway.add_any(mapping, now));
+            
+            Ok(vec![(RESERVED_PORT_ARG, port.to_string())])
+        })
+    })
+}
+
+/// Run an action against the gateway, failing with an injected fault first if
+/// there is one and publishing any change in the number of port mappings.
+fn invoke<F>(gateway: &Mutex<Gateway>, publisher: &Publisher, action: &str, f: F) -> ActionResult
+    where F: FnOnce(&mut Gateway, SteadyTime) -> ActionResult {
+    let mut gateway = gateway.lock().unwrap();
+    let now = SteadyTime::now();
+    let count = gateway.leases.len();
+    
+    try!(gateway.take_fault(action));
+    gateway.expire(now);
+    let result = f(&mut *gateway, now);
+    
+    if gateway.leases.len() != count {
+        publisher.set_variable(NUMBER_OF_ENTRIES_VAR, &gateway.leases.len().to_string()[..]);
+    }
+    
+    result
+}
+
+/// Pull out the arguments identifying a port mapping.
+///
+/// Ranges and allowed values were already checked against the state variables.
+fn key_args(args: &Arguments) -> Result<(String, u16, Protocol), ActionError> {
+    let remote_host = try!(args.string(REMOTE_HOST_ARG)).to_string();
+    let external_port = try!(args.integer(EXTERNAL_PORT_ARG)) as u16;
+    let protocol = try!(Protocol::new(try!(args.string(PROTOCOL_ARG))).ok_or(ActionError::invalid_args()));
+    
+    Ok((remote_host, external_port, protocol))
+}
+
+/// Pull out every argument of a port mapping.
+fn mapping_arg(args: &Arguments) -> Result<PortMapping, ActionError> {
+    let (remote_host, external_port, protocol) = try!(key_args(args));
+    let internal_port = try!(args.integer(INTERNAL_PORT_ARG)) as u16;
+    let lease_duration = try!(args.integer(LEASE_DURATION_ARG)) as u32;
+    
+    Ok(PortMapping::new(protocol, external_port, try!(args.string(INTERNAL_CLIENT_ARG)), internal_port)
+        .with_remote_host(&remote_host[..]).with_enabled(try!(args.boolean(ENABLED_ARG)))
+        .with_lease_duration(lease_duration).with_description(try!(args.string(DESCRIPTION_ARG))))
+}
+
+/// Returns the out arguments describing the lease, with the lease duration
+/// counting down to its expiry.
+fn entry_args(lease: &Lease, now: SteadyTime) -> Vec<(&'static str, String)> {
+    let mapping = &lease.mapping;
+    let remaining = lease.expires.map_or(0, |n| (n - now).num_seconds() as u32);
+    
+    vec![(INTERNAL_PORT_ARG, mapping.internal_port().to_string()),
+         (INTERNAL_CLIENT_ARG, mapping.internal_client().to_string()),
+         (ENABLED_ARG, value::from_bool(mapping.enabled()).to_string()),
+         (DESCRIPTION_ARG, mapping.description().to_string()),
+         (LEASE_DURATION_ARG, remaining.to_string())]
+}
+
+/// Bind a socket to an available port on the loopback interface.
+fn bind_loopback() -> SSDPResult<(UdpSocket, SocketAddr)> {
+    let udp = try!(UdpSocket::bind(("127.0.0.1", 0)).map_err(|e| SSDPError::Other(Box::new(e))));
+    let addr = try!(udp.local_addr().map_err(|e| SSDPError::Other(Box::new(e))));
+    
+    Ok((udp, addr))
+}
+
+/// Returns the external address reported by the emulator until told otherwise.
+fn default_external_ip() -> Ipv4Addr {
+    Ipv4Addr::new(203, 0, 113, 1)
+}
+
+/// Returns the description sent along with an injected fault.
+fn fault_description(code: u16) -> &'static str {
+    match code {
+        ARRAY_INDEX_INVALID_CODE       => ARRAY_INDEX_INVALID_DESC,
+        NO_SUCH_ENTRY_CODE             => NO_SUCH_ENTRY_DESC,
+        WILDCARD_NOT_PERMITTED_CODE    => WILDCARD_NOT_PERMITTED_DESC,
+        CONFLICT_IN_MAPPING_ENTRY_CODE => CONFLICT_IN_MAPPING_ENTRY_DESC,
+        _                              => INJECTED_FAULT_DESC
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+    
+    use time::{Duration, SteadyTime};
+    
+    use forum::{GenericQuery};
+    use forum::device::{DeviceType};
+    use forum::device::internet_gateway::{InternetGatewayQuery};
+    use forum::service::wan_ip_connection::{PortMapping, Protocol, CONFLICT_IN_MAPPING_ENTRY_CODE};
+    use net::receiver::{PacketListener};
+    use ssdp::{FieldPair, Policy};
+    use ssdp::header::{NTS, ST};
+    use ssdp::packet::{Packet};
+    use ssdp::search::{self};
+    use version::{Version};
+    use super::{Gateway, IGDEmulator, ADD_PORT_MAPPING_ACTION, MAX_LEASE_DURATION};
+    
+    const GATEWAY_TYPE: &'static [u8] = b"schemas-upnp-org:device:InternetGatewayDevice:1";
+    const WAIT_MS:      i64 = 500;
+    
+    fn mapping(client: &str) -> PortMapping {
+        PortMapping::new(Protocol::TCP, 8080, client, 80)
+    }
+    
+    #[test]
+    fn positive_conflicting_client() {
+        let mut gateway = Gateway::new(Version::V1);
+        let now = SteadyTime::now();
+        gateway.add(mapping("192.168.1.20"), now).unwrap();
+        
+        let code = gateway.add(mapping("192.168.1.21"), now).unwrap_err().code();
+        
+        assert_eq!(code, CONFLICT_IN_MAPPING_ENTRY_CODE);
+    }
+    
+    #[test]
+    fn positive_lease_expiry() {
+        let mut gateway = Gateway::new(Version::V1);
+        let now = SteadyTime::now();
+        gateway.add(mapping("192.168.1.20").with_lease_duration(60), now).unwrap();
+        
+        gateway.expire(now + Duration::seconds(59));
+        assert_eq!(gateway.leases.len(), 1);
+        
+        gateway.expire(now + Duration::seconds(60));
+        assert_eq!(gateway.leases.len(), 0);
+    }
+    
+    #[test]
+    fn positive_v2_lease_clamp() {
+        let mut gateway = Gateway::new(Version::V2);
+        gateway.add(mapping("192.168.1.20"), SteadyTime::now()).unwrap();
+        
+        assert_eq!(gateway.leases[0].mapping.lease_duration(), MAX_LEASE_DURATION);
+    }
+    
+    #[test]
+    fn positive_add_any_next_port() {
+        let mut gateway = Gateway::new(Version::V2);
+        let now = SteadyTime::now();
+        gateway.add(mapping("192.168.1.20"), now).unwrap();
+        
+        let port = gateway.add_any(mapping("192.168.1.21"), now).unwrap();
+        
+        assert!(port != 8080);
+    }
+    
+    #[test]
+    fn positive_end_to_end_mapping() {
+        let notify_udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let notify_listener = PacketListener::new(&notify_udp).unwrap();
+        let emulator = IGDEmulator::start(Version::V1, notify_udp.local_addr().unwrap()).unwrap();
+        
+        let (pckt, _) = notify_listener.recv_timeout(Duration::milliseconds(WAIT_MS)).unwrap();
+        let alive = Packet::parse(&pckt[..]).unwrap();
+        assert_eq!(alive.headers().get::<NTS>(), Some(&NTS::Alive));
+        
+        // Discover The Gateway Through Its Search Address
+        let search_port = match emulator.search_addr() {
+            SocketAddr::V4(n) => n.port(),
+            SocketAddr::V6(n) => n.port()
+        };
+        let target = ST::Target(FieldPair::URN(GATEWAY_TYPE.to_vec()));
+        let responses = search::search_device(Ipv4Addr::new(127, 0, 0, 1), Some(search_port), target,
+            Duration::milliseconds(WAIT_MS), Policy::default()).unwrap();
+        assert_eq!(responses.len(), 1);
+        
+        let udn = format!("uuid:{}", emulator.uuid());
+        let query = GenericQuery::new(responses[0].location(), udn.as_bytes());
+        let gateway = InternetGatewayQuery::new(query, DeviceType::InternetGateway(Version::V1));
+        let client = gateway.wan_ip_connection().unwrap();
+        
+        client.add_port_mapping(&mapping("127.0.0.1")).unwrap();
+        let mapped = client.port_mapping("", 8080, Protocol::TCP).unwrap();
+        
+        assert_eq!(mapped.internal_client(), "127.0.0.1");
+        assert_eq!(emulator.mappings().len(), 1);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_injected_fault() {
+        let mut gateway = Gateway::new(Version::V1);
+        gateway.faults.push((ADD_PORT_MAPPING_ACTION.to_string(), 501));
+        
+        gateway.take_fault(ADD_PORT_MAPPING_ACTION).unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+//! Implements the device side of the description and control layers.
+//!
+//! This module deals with hosting our own devices: serving the device and
+//! service descriptions to control points, dispatching the actions they
+//! invoke to handlers written in Rust, and sending them events when evented
+//! state variables change.
+//!
+//! An emulated InternetGatewayDevice built from these pieces is included for
+//! testing port mapping clients without a router on the network.
+
+mod description;
+mod igd;
+mod model;
+mod server;
+
+pub use host::description::{DESCRIPTION_PATH};
+pub use host::igd::{IGDEmulator};
+pub use host::model::{Action, ActionError, ActionResult, Argument, Arguments, ArgumentValue, Direction,
+                      HostedDevice, HostedService, StateVariable, INVALID_ACTION_CODE, INVALID_ARGS_CODE,
+                      ACTION_FAILED_CODE, ARGUMENT_INVALID_CODE, OUT_OF_RANGE_CODE};
+pub use host::server::{DeviceHost};
@@ -0,0 +1,748 @@
+use std::convert::{From};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc};
+
+use url::{Url};
+
+use {SOAPError};
+use forum::device::{DeviceType};
+use forum::service::{ServiceType};
+use gena::{Moderation, Publisher};
+use soap::{ActionRequest};
+use soap::value::{self};
+use ssdp::advertiser::{AdvertisedDevice, Advertisement};
+
+/// Standard error codes that can be sent back in a UPnPError fault.
+pub const INVALID_ACTION_CODE:   u16 = 401;
+pub const INVALID_ARGS_CODE:     u16 = 402;
+pub const ACTION_FAILED_CODE:    u16 = 501;
+pub const ARGUMENT_INVALID_CODE: u16 = 600;
+pub const OUT_OF_RANGE_CODE:     u16 = 601;
+
+const INVALID_ACTION_DESC:   &'static str = "Invalid Action";
+const INVALID_ARGS_DESC:     &'static str = "Invalid Args";
+const ACTION_FAILED_DESC:    &'static str = "Action Failed";
+const ARGUMENT_INVALID_DESC: &'static str = "Argument Value Invalid";
+const OUT_OF_RANGE_DESC:     &'static str = "Argument Value Out of Range";
+
+const OUT_OF_RANGE_MESSAGE: &'static str = "Value Is Out Of Range";
+const URN_PREFIX:           &'static str = "urn:";
+
+const BOOLEAN_TYPE: &'static str = "boolean";
+
+/// Integer data types along with the inclusive range of values they hold.
+const INTEGER_TYPES: [(&'static str, i64, i64); 7] = [("ui1", 0, 255), ("ui2", 0, 65535),
+    ("ui4", 0, 4294967295), ("i1", -128, 127), ("i2", -32768, 32767), ("i4", -2147483648, 2147483647),
+    ("int", -2147483648, 2147483647)];
+/// Floating point data types.
+const FLOAT_TYPES: [&'static str; 5] = ["r4", "r8", "number", "fixed.14.4", "float"];
+
+/// Out arguments of an action as (name, value) pairs, or the fault to send back.
+pub type ActionResult = Result<Vec<(&'static str, String)>, ActionError>;
+
+/// Error raised by an action handler, which is sent back as a UPnPError fault.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionError {
+    code: u16,
+    desc: String
+}
+
+impl ActionError {
+    /// Create a new ActionError with the given error code and description.
+    pub fn new(code: u16, desc: &str) -> ActionError {
+        ActionError{ code: code, desc: desc.to_string() }
+    }
+    
+    /// Error for an action that the service does not offer.
+    pub fn invalid_action() -> ActionError {
+        ActionError::new(INVALID_ACTION_CODE, INVALID_ACTION_DESC)
+    }
+    
+    /// Error for an action invoked with missing or unexpected arguments.
+    pub fn invalid_args() -> ActionError {
+        ActionError::new(INVALID_ARGS_CODE, INVALID_ARGS_DESC)
+    }
+    
+    /// Error for an action that could not be carried out.
+    pub fn action_failed() -> ActionError {
+        ActionError::new(ACTION_FAILED_CODE, ACTION_FAILED_DESC)
+    }
+    
+    /// Error for an argument value that does not fit its state variable.
+    pub fn argument_invalid() -> ActionError {
+        ActionError::new(ARGUMENT_INVALID_CODE, ARGUMENT_INVALID_DESC)
+    }
+    
+    /// Error for a numeric argument value outside the allowed range.
+    pub fn out_of_range() -> ActionError {
+        ActionError::new(OUT_OF_RANGE_CODE, OUT_OF_RANGE_DESC)
+    }
+    
+    /// Returns the error code.
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+    
+    /// Returns the error description.
+    pub fn description(&self) -> &str {
+        &self.desc[..]
+    }
+}
+
+impl From<SOAPError> for ActionError {
+    fn from(err: SOAPError) -> ActionError {
+        match err {
+            SOAPError::Fault(code, desc) => ActionError{ code: code, desc: desc },
+            SOAPError::MissingArgument(_) => ActionError::invalid_args(),
+            SOAPError::InvalidArgument(_, OUT_OF_RANGE_MESSAGE) => ActionError::out_of_range(),
+            SOAPError::InvalidArgument(..) => ActionError::argument_invalid(),
+            _ => ActionError::action_failed()
+        }
+    }
+}
+
+/// State variable of a service we are hosting.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct StateVariable {
+    name:        String,
+    data_type:   String,
+    default:     Option<String>,
+    allowed:     Vec<String>,
+    range:       Option<(String, String)>,
+    send_events: bool,
+    moderation:  Moderation
+}
+
+impl StateVariable {
+    /// Create a new StateVariable with the given name and UPnP data type (ui2,
+    /// string, boolean, etc.).
+    pub fn new(name: &str, data_type: &str) -> StateVariable {
+        StateVariable{ name: name.to_string(), data_type: data_type.to_string(), default: None,
+            allowed: Vec::new(), range: None, send_events: false, moderation: Moderation::Unmoderated }
+    }
+    
+    /// Set the default value of the state variable.
+    pub fn with_default(mut self, value: &str) -> StateVariable {
+        self.default = Some(value.to_string());
+        
+        self
+    }
+    
+    /// Restrict the state variable to the given list of values.
+    pub fn with_allowed_values(mut self, values: &[&str]) -> StateVariable {
+        self.allowed = values.iter().map(|n| n.to_string()).collect();
+        
+        self
+    }
+    
+    /// Restrict the state variable to the inclusive range [min,max].
+    pub fn with_range(mut self, min: &str, max: &str) -> StateVariable {
+        self.range = Some((min.to_string(), max.to_string()));
+        
+        self
+    }
+    
+    /// Have changes to the state variable sent out to subscribers.
+    pub fn evented(mut self) -> StateVariable {
+        self.send_events = true;
+        
+        self
+    }
+    
+    /// Send changes to the evented state variable out at most once per the
+    /// given number of milliseconds.
+    pub fn with_maximum_rate(mut self, ms: u32) -> StateVariable {
+        self.moderation = Moderation::MaximumRate(ms);
+        
+        self
+    }
+    
+    /// Only send changes to the evented state variable out once its numeric
+    /// value has moved by at least the given amount.
+    pub fn with_minimum_delta(mut self, delta: u32) -> StateVariable {
+        self.moderation = Moderation::MinimumDelta(delta);
+        
+        self
+    }
+    
+    /// Returns the name of the state variable.
+    pub fn name(&self) -> &str {
+        &self.name[..]
+    }
+    
+    /// Returns the UPnP data type of the state variable.
+    pub fn data_type(&self) -> &str {
+        &self.data_type[..]
+    }
+    
+    /// Returns the default value of the state variable.
+    pub fn default_value(&self) -> Option<&str> {
+        self.default.as_ref().map(|n| &n[..])
+    }
+    
+    /// Returns the values that the state variable is restricted to, if any.
+    pub fn allowed_values(&self) -> &[String] {
+        &self.allowed[..]
+    }
+    
+    /// Returns the range that the state variable is restricted to, if any.
+    pub fn range(&self) -> Option<(&str, &str)> {
+        self.range.as_ref().map(|&(ref min, ref max)| (&min[..], &max[..]))
+    }
+    
+    /// Returns true if changes to the state variable are sent to subscribers.
+    pub fn sends_events(&self) -> bool {
+        self.send_events
+    }
+    
+    /// Returns the rule limiting how often changes are sent to subscribers.
+    pub fn moderation(&self) -> Moderation {
+        self.moderation
+    }
+    
+    /// Check that the value fits the data type, allowed values and range of
+    /// the state variable, converting it to a value of the data type.
+    ///
+    /// Values of data types other than booleans and numbers are kept as strings.
+    fn convert(&self, value: &str) -> Result<ArgumentValue, ActionError> {
+        let trimmed = value.trim();
+        
+        let converted = if &self.data_type[..] == BOOLEAN_TYPE {
+            let boolean = try!(value::to_bool("", value).map_err(|_| ActionError::argument_invalid()));
+            
+            ArgumentValue::Boolean(boolean)
+        } else if let Some(&(_, min, max)) = INTEGER_TYPES.iter().find(|n| n.0 == &self.data_type[..]) {
+            let number = try!(trimmed.parse::<i64>().map_err(|_| ActionError::argument_invalid()));
+            
+            if number < min || number > max {
+                return Err(ActionError::argument_invalid())
+            }
+            ArgumentValue::Integer(number)
+        } else if FLOAT_TYPES.contains(&&self.data_type[..]) {
+            ArgumentValue::Float(try!(trimmed.parse::<f64>().map_err(|_| ActionError::argument_invalid())))
+        } else {
+            ArgumentValue::String(value.to_string())
+        };
+        
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|n| n == value) {
+            return Err(ActionError::argument_invalid())
+        }
+        
+        if let Some((ref min, ref max)) = self.range {
+            let bounds = (min.trim().parse::<f64>(), max.trim().parse::<f64>(), trimmed.parse::<f64>());
+            
+            match bounds {
+                (Ok(min), Ok(max), Ok(n)) if n < min || n > max => return Err(ActionError::out_of_range()),
+                (Ok(_), Ok(_), Err(_)) => return Err(ActionError::argument_invalid()),
+                _ => ()
+            }
+        }
+        
+        Ok(converted)
+    }
+}
+
+/// Direction of an argument.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    In,
+    Out
+}
+
+/// Argument of an action, which takes on the type of its related state variable.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Argument {
+    name:      String,
+    direction: Direction,
+    related:   String
+}
+
+impl Argument {
+    /// Returns the name of the argument.
+    pub fn name(&self) -> &str {
+        &self.name[..]
+    }
+    
+    /// Returns the direction of the argument.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+    
+    /// Returns the name of the related state variable.
+    pub fn related_state_variable(&self) -> &str {
+        &self.related[..]
+    }
+}
+
+/// Value of an in argument, converted according to the data type of its related
+/// state variable.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ArgumentValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String)
+}
+
+/// In arguments of an action request, keyed by name, that have been checked
+/// against their related state variables.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Arguments {
+    values: Vec<(String, ArgumentValue)>
+}
+
+impl Arguments {
+    /// Returns the value of the in argument with the given name.
+    pub fn get(&self, name: &str) -> Option<&ArgumentValue> {
+        self.values.iter().find(|n| n.0 == name).map(|n| &n.1)
+    }
+    
+    /// Returns the value of a boolean in argument.
+    ///
+    /// Fails with Action Failed if the action has no boolean in argument with
+    /// the given name.
+    pub fn boolean(&self, name: &str) -> Result<bool, ActionError> {
+        match self.get(name) {
+            Some(&ArgumentValue::Boolean(n)) => Ok(n),
+            _ => Err(ActionError::action_failed())
+        }
+    }
+    
+    /// Returns the value of an integer in argument.
+    ///
+    /// Fails with Action Failed if the action has no integer in argument with
+    /// the given name.
+    pub fn integer(&self, name: &str) -> Result<i64, ActionError> {
+        match self.get(name) {
+            Some(&ArgumentValue::Integer(n)) => Ok(n),
+            _ => Err(ActionError::action_failed())
+        }
+    }
+    
+    /// Returns the value of a numeric in argument.
+    ///
+    /// Fails with Action Failed if the action has no numeric in argument with
+    /// the given name.
+    pub fn float(&self, name: &str) -> Result<f64, ActionError> {
+        match self.get(name) {
+            Some(&ArgumentValue::Float(n))   => Ok(n),
+            Some(&ArgumentValue::Integer(n)) => Ok(n as f64),
+            _ => Err(ActionError::action_failed())
+        }
+    }
+    
+    /// Returns the value of an in argument whose data type is not converted,
+    /// such as a string.
+    ///
+    /// Fails with Action Failed if the action has no such in argument with the
+    /// given name.
+    pub fn string(&self, name: &str) -> Result<&str, ActionError> {
+        match self.get(name) {
+            Some(&ArgumentValue::String(ref n)) => Ok(&n[..]),
+            _ => Err(ActionError::action_failed())
+        }
+    }
+}
+
+/// Action offered by a service we are hosting.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Action {
+    name:      String,
+    arguments: Vec<Argument>
+}
+
+impl Action {
+    /// Create a new Action with the given name and no arguments.
+    pub fn new(name: &str) -> Action {
+        Action{ name: name.to_string(), arguments: Vec::new() }
+    }
+    
+    /// Add an in argument tied to the given state variable.
+    pub fn with_input(self, name: &str, related: &str) -> Action {
+        self.with_argument(name, Direction::In, related)
+    }
+    
+    /// Add an out argument tied to the given state variable.
+    pub fn with_output(self, name: &str, related: &str) -> Action {
+        self.with_argument(name, Direction::Out, related)
+    }
+    
+    fn with_argument(mut self, name: &str, direction: Direction, related: &str) -> Action {
+        let argument = Argument{ name: name.to_string(), direction: direction, related: related.to_string() };
+        self.arguments.push(argument);
+        
+        self
+    }
+    
+    /// Returns the name of the action.
+    pub fn name(&self) -> &str {
+        &self.name[..]
+    }
+    
+    /// Returns the arguments of the action, in order.
+    pub fn arguments(&self) -> &[Argument] {
+        &self.arguments[..]
+    }
+}
+
+/// Closure invoked when a control point invokes an action.
+type ActionHandler = Box<Fn(&Arguments) -> ActionResult + Send + Sync>;
+
+/// Service that we are hosting, with a handler for each of its actions.
+pub struct HostedService {
+    service_type:    ServiceType,
+    service_id:      String,
+    state_variables: Vec<StateVariable>,
+    actions:         Vec<(Action, ActionHandler)>,
+    publisher:       Publisher
+}
+
+impl HostedService {
+    /// Create a new HostedService of the given type.
+    ///
+    /// The service id looks something like "urn:upnp-org:serviceId:SwitchPower1"
+    /// and has to be unique within the device.
+    pub fn new(service_type: ServiceType, service_id: &str) -> HostedService {
+        HostedService{ service_type: service_type, service_id: service_id.to_string(),
+            state_variables: Vec::new(), actions: Vec::new(), publisher: Publisher::new() }
+    }
+    
+    /// Add a state variable to the service.
+    ///
+    /// Evented state variables start out with their default value, or an
+    /// empty string if they have none.
+    pub fn with_state_variable(mut self, variable: StateVariable) -> HostedService {
+        if variable.sends_events() {
+            self.publisher.add_variable(variable.name(), variable.default_value().unwrap_or(""),
+                variable.moderation());
+        }
+        self.state_variables.push(variable);
+        
+        self
+    }
+    
+    /// Add an action to the service, which is carried out by the given handler.
+    ///
+    /// The handler is given the in arguments converted according to their
+    /// related state variables, and has to return the out arguments in the
+    /// order they were declared. Errors converted from a SOAPError are sent
+    /// back as the matching UPnPError, so handlers can use try! on them.
+    pub fn with_action<F>(mut self, action: Action, handler: F) -> HostedService
+        where F: Fn(&Arguments) -> ActionResult + Send + Sync + 'static {
+        self.actions.push((action, Box::new(handler)));
+        
+        self
+    }
+    
+    /// Returns the type of the service.
+    pub fn service_type(&self) -> &ServiceType {
+        &self.service_type
+    }
+    
+    /// Returns the id of the service.
+    pub fn service_id(&self) -> &str {
+        &self.service_id[..]
+    }
+    
+    /// Returns the state variables of the service.
+    pub fn state_variables(&self) -> &[StateVariable] {
+        &self.state_variables[..]
+    }
+    
+    /// Returns the publisher that changes to evented state variables are sent
+    /// out through.
+    ///
+    /// Handlers can hold on to a clone of the publisher to change the value of
+    /// a state variable when an action is invoked.
+    pub fn publisher(&self) -> &Publisher {
+        &self.publisher
+    }
+    
+    /// Stop sending out event messages for the service and wait for its
+    /// delivery thread to finish.
+    pub fn shutdown(&self) {
+        self.publisher.shutdown();
+    }
+    
+    /// Returns the actions offered by the service.
+    pub fn actions(&self) -> Vec<&Action> {
+        self.actions.iter().map(|&(ref n, _)| n).collect()
+    }
+    
+    /// Invoke the handler for the requested action.
+    ///
+    /// Fails with Invalid Action if the service does not offer the action, with
+    /// Invalid Args if any in argument is missing, and with Argument Value
+    /// Invalid or Out of Range if an in argument does not fit its related state
+    /// variable. Handlers are only invoked once every in argument checks out.
+    ///
+    /// Fails with Action Failed if the handler does not return exactly the
+    /// declared out arguments, in order.
+    pub fn invoke(&self, request: &ActionRequest) -> ActionResult {
+        let &(ref action, ref handler) = try!(self.actions.iter().find(|&&(ref n, _)|
+            n.name() == request.action()
+        ).ok_or(ActionError::invalid_action()));
+        
+        let mut values = Vec::new();
+        for arg in action.arguments().iter().filter(|n| n.direction() == Direction::In) {
+            let value = try!(request.value(arg.name()).ok_or(ActionError::invalid_args()));
+            
+            let related = self.state_variables.iter().find(|n| n.name() == arg.related_state_variable());
+            let converted = match related {
+                Some(variable) => try!(variable.convert(&value[..])),
+                None           => ArgumentValue::String(value)
+            };
+            values.push((arg.name().to_string(), converted));
+        }
+        
+        let outs = try!(handler(&Arguments{ values: values }));
+        
+        // Out Arguments Have To Go Back Exactly As The Action Declared Them
+        let declared = action.arguments().iter().filter(|n| n.direction() == Direction::Out)
+            .map(|n| n.name()).collect::<Vec<&str>>();
+        if declared != outs.iter().map(|&(name, _)| name).collect::<Vec<&str>>() {
+            return Err(ActionError::action_failed())
+        }
+        
+        Ok(outs)
+    }
+}
+
+impl Debug for HostedService {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        try!(f.write_str("HostedService { service_type: "));
+        try!(Debug::fmt(&self.service_type, f));
+        try!(f.write_str(", service_id: "));
+        try!(Debug::fmt(&self.service_id, f));
+        try!(f.write_str(", actions: "));
+        try!(Debug::fmt(&self.actions(), f));
+        
+        f.write_str(" }")
+    }
+}
+
+/// Device that we are hosting, along with its services and embedded devices.
+#[derive(Debug)]
+pub struct HostedDevice {
+    device_type:   DeviceType,
+    uuid:          String,
+    friendly_name: String,
+    manufacturer:  String,
+    model_name:    String,
+    services:      Vec<Arc<HostedService>>,
+    devices:       Vec<HostedDevice>
+}
+
+impl HostedDevice {
+    /// Create a new HostedDevice of the given type.
+    ///
+    /// The uuid should not include the "uuid:" prefix and has to stay the same
+    /// across restarts of the device.
+    pub fn new(device_type: DeviceType, uuid: &str, friendly_name: &str, manufacturer: &str,
+        model_name: &str) -> HostedDevice {
+        HostedDevice{ device_type: device_type, uuid: uuid.to_string(),
+            friendly_name: friendly_name.to_string(), manufacturer: manufacturer.to_string(),
+            model_name: model_name.to_string(), services: Vec::new(), devices: Vec::new() }
+    }
+    
+    /// Add a service to the device.
+    pub fn with_service(mut self, service: HostedService) -> HostedDevice {
+        self.services.push(Arc::new(service));
+        
+        self
+    }
+    
+    /// Add an embedded device to the device.
+    pub fn with_device(mut self, device: HostedDevice) -> HostedDevice {
+        self.devices.push(device);
+        
+        self
+    }
+    
+    /// Returns the type of the device.
+    pub fn device_type(&self) -> &DeviceType {
+        &self.device_type
+    }
+    
+    /// Returns the uuid of the device.
+    pub fn uuid(&self) -> &str {
+        &self.uuid[..]
+    }
+    
+    /// Returns the short description of the device shown to end users.
+    pub fn friendly_name(&self) -> &str {
+        &self.friendly_name[..]
+    }
+    
+    /// Returns the manufacturer of the device.
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer[..]
+    }
+    
+    /// Returns the model name of the device.
+    pub fn model_name(&self) -> &str {
+        &self.model_name[..]
+    }
+    
+    /// Returns the services of the device.
+    pub fn services(&self) -> &[Arc<HostedService>] {
+        &self.services[..]
+    }
+    
+    /// Returns the embedded devices of the device.
+    pub fn devices(&self) -> &[HostedDevice] {
+        &self.devices[..]
+    }
+    
+    /// Build the SSDP advertisement for this device as the root device, with
+    /// every embedded device at any depth included.
+    pub fn advertisement(&self, location: Url, server: &str, boot_id: u32, config_id: u32) -> Advertisement {
+        let mut advert = Advertisement::new(self.advertised(), location, server, boot_id, config_id);
+        
+        let mut pending = self.devices.iter().collect::<Vec<&HostedDevice>>();
+        while let Some(device) = pending.pop() {
+            advert = advert.with_embedded(device.advertised());
+            pending.extend(device.devices.iter());
+        }
+        
+        advert
+    }
+    
+    /// Returns the AdvertisedDevice for this device alone.
+    fn advertised(&self) -> AdvertisedDevice {
+        let device_type = self.device_type.urn();
+        let mut advertised = AdvertisedDevice::new(&self.uuid[..], &device_type[URN_PREFIX.len()..]);
+        
+        for service in self.services.iter() {
+            let service_type = service.service_type().urn();
+            
+            advertised = advertised.with_service(&service_type[URN_PREFIX.len()..]);
+        }
+        
+        advertised
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soap::{ActionRequest};
+    use forum::service::{ServiceType};
+    use version::{Version};
+    use super::{Action, ActionError, ArgumentValue, HostedService, StateVariable};
+    
+    const SET_TARGET: &'static str = "<s:Envelope><s:Body><u:SetTarget xmlns:u=\"urn\">\
+                                      <newTargetValue>1</newTargetValue></u:SetTarget></s:Body></s:Envelope>";
+    
+    fn switch_power() -> HostedService {
+        let action = Action::new("SetTarget").with_input("newTargetValue", "Target");
+        
+        HostedService::new(ServiceType::SwitchPower(Version::V1), "urn:upnp-org:serviceId:SwitchPower1")
+            .with_action(action, |args| {
+                let value = try!(args.string("newTargetValue"));
+                
+                if value == "1" { Ok(vec![]) } else { Err(ActionError::action_failed()) }
+            })
+    }
+    
+    fn dimming(outs: Vec<(&'static str, String)>) -> HostedService {
+        let action = Action::new("SetLoadLevelTarget").with_input("newLoadlevelTarget", "LoadLevelTarget")
+            .with_output("OldLoadLevel", "LoadLevelStatus").with_output("NewLoadLevel", "LoadLevelStatus");
+        
+        HostedService::new(ServiceType::Dimming(Version::V1), "urn:upnp-org:serviceId:Dimming1")
+            .with_state_variable(StateVariable::new("LoadLevelTarget", "ui1").with_range("0", "100"))
+            .with_state_variable(StateVariable::new("LoadLevelStatus", "ui1"))
+            .with_action(action, move |args| {
+                assert_eq!(args.get("newLoadlevelTarget"), Some(&ArgumentValue::Integer(42)));
+                assert!(args.string("newLoadlevelTarget").is_err());
+                
+                Ok(outs.clone())
+            })
+    }
+    
+    fn set_load_level() -> ActionRequest {
+        ActionRequest::new("<s:Envelope><s:Body><u:SetLoadLevelTarget xmlns:u=\"urn\">\
+            <newLoadlevelTarget>42</newLoadlevelTarget>\
+            </u:SetLoadLevelTarget></s:Body></s:Envelope>").unwrap()
+    }
+    
+    #[test]
+    fn positive_invoke() {
+        let request = ActionRequest::new(SET_TARGET).unwrap();
+        
+        assert_eq!(switch_power().invoke(&request), Ok(vec![]));
+    }
+    
+    #[test]
+    fn positive_invalid_action() {
+        let request = ActionRequest::new(&SET_TARGET.replace("SetTarget", "GetTarget")[..]).unwrap();
+        
+        assert_eq!(switch_power().invoke(&request), Err(ActionError::invalid_action()));
+    }
+    
+    #[test]
+    fn positive_missing_argument() {
+        let payload = SET_TARGET.replace("newTargetValue", "oldTargetValue");
+        let request = ActionRequest::new(&payload[..]).unwrap();
+        
+        assert_eq!(switch_power().invoke(&request).unwrap_err().code(), super::INVALID_ARGS_CODE);
+    }
+    
+    #[test]
+    fn positive_invalid_argument_value() {
+        let service = switch_power().with_state_variable(StateVariable::new("Target", "boolean"));
+        let request = ActionRequest::new(&SET_TARGET.replace(">1<", ">maybe<")[..]).unwrap();
+        
+        assert_eq!(service.invoke(&request).unwrap_err().code(), super::ARGUMENT_INVALID_CODE);
+    }
+    
+    #[test]
+    fn positive_argument_out_of_range() {
+        let service = switch_power()
+            .with_state_variable(StateVariable::new("Target", "ui1").with_range("0", "1"));
+        let request = ActionRequest::new(&SET_TARGET.replace(">1<", ">2<")[..]).unwrap();
+        
+        assert_eq!(service.invoke(&request).unwrap_err().code(), super::OUT_OF_RANGE_CODE);
+    }
+    
+    #[test]
+    fn positive_argument_not_allowed() {
+        let service = switch_power()
+            .with_state_variable(StateVariable::new("Target", "string").with_allowed_values(&["0", "1"]));
+        let request = ActionRequest::new(&SET_TARGET.replace(">1<", ">On<")[..]).unwrap();
+        
+        assert_eq!(service.invoke(&request).unwrap_err().code(), super::ARGUMENT_INVALID_CODE);
+    }
+    
+    #[test]
+    fn positive_typed_arguments() {
+        let outs = vec![("OldLoadLevel", "0".to_string()), ("NewLoadLevel", "42".to_string())];
+        
+        assert_eq!(dimming(outs.clone()).invoke(&set_load_level()), Ok(outs));
+    }
+    
+    #[test]
+    fn positive_outputs_out_of_order() {
+        let outs = vec![("NewLoadLevel", "42".to_string()), ("OldLoadLevel", "0".to_string())];
+        
+        assert_eq!(dimming(outs).invoke(&set_load_level()), Err(ActionError::action_failed()));
+    }
+    
+    #[test]
+    fn positive_undeclared_output() {
+        let outs = vec![("OldLoadLevel", "0".to_string()), ("NewLoadLevel", "42".to_string()),
+                        ("Extra", "1".to_string())];
+        
+        assert_eq!(dimming(outs).invoke(&set_load_level()), Err(ActionError::action_failed()));
+    }
+    
+    #[test]
+    fn positive_evented_variable() {
+        let service = switch_power()
+            .with_state_variable(StateVariable::new("Status", "boolean").with_default("0").evented())
+            .with_state_variable(StateVariable::new("Target", "boolean"));
+        
+        assert_eq!(service.publisher().variable("Status").unwrap(), "0");
+        assert!(service.publisher().set_variable("Status", "1"));
+        assert!(!service.publisher().set_variable("Target", "1"));
+    }
+}
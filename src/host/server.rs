@@ -0,0 +1,287 @@
+//! Serves descriptions, dispatches action invocations and accepts event
+//! subscriptions for a hosted device.
+
+use std::io::{Read};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc};
+
+use hyper::method::{Method};
+use hyper::header::{Headers};
+use hyper::net::{Fresh};
+use hyper::server::{Handler, Listening, Request, Response, Server};
+use hyper::status::{StatusCode};
+use hyper::uri::{RequestUri};
+use url::{Url};
+
+use {SOAPError, SOAPResult};
+use gena::{Publisher};
+use host::description::{self, DESCRIPTION_PATH};
+use host::model::{ActionError, HostedDevice, HostedService};
+use soap::{self, ActionRequest, CONTENT_TYPE_HEADER, CONTENT_TYPE_VALUE, SOAP_ACTION_HEADER};
+
+const EXT_HEADER_NAME: &'static str = "EXT";
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+/// Serves a root device, along with its embedded devices, over HTTP.
+///
+/// The server is shut down when the DeviceHost is stopped, closed or dropped.
+pub struct DeviceHost {
+    listening: Option<Listening>,
+    location:  Url,
+    services:  Vec<Arc<HostedService>>
+}
+
+impl DeviceHost {
+    /// Start serving the root device on the given address.
+    ///
+    /// A port of 0 will have the operating system pick an available port.
+    pub fn start(root: &HostedDevice, ip: Ipv4Addr, port: u16, config_id: u32) -> SOAPResult<DeviceHost> {
+        let router = Router::new(root, config_id);
+        let services = router.services.iter().map(|n| n.service.clone()).collect();
+        
+        let server = Server::http(router);
+        let listening = try!(server.listen((ip, port)).map_err(|e| SOAPError::Other(Box::new(e))));
+        
+        let actual_port = match listening.socket {
+            SocketAddr::V4(n) => n.port(),
+            SocketAddr::V6(n) => n.port()
+        };
+        let location = try!(Url::parse(&format!("http://{}:{}{}", ip, actual_port, DESCRIPTION_PATH)[..])
+            .map_err(|e| SOAPError::Other(Box::new(e))));
+        
+        Ok(DeviceHost{ listening: Some(listening), location: location, services: services })
+    }
+    
+    /// Returns the url that the description of the root device is served at.
+    pub fn location(&self) -> &Url {
+        &self.location
+    }
+    
+    /// Stop serving the root device and sending out event messages for its
+    /// services.
+    ///
+    /// Does nothing if the DeviceHost was already stopped.
+    pub fn stop(&mut self) -> SOAPResult<()> {
+        for service in self.services.iter() {
+            service.shutdown();
+        }
+        
+        match self.listening.take() {
+            Some(mut n) => n.close().map_err(|e| SOAPError::Other(Box::new(e))),
+            None        => Ok(())
+        }
+    }
+    
+    /// Stop serving the root device.
+    pub fn close(mut self) -> SOAPResult<()> {
+        self.stop()
+    }
+}
+
+impl Drop for DeviceHost {
+    fn drop(&mut self) {
+        // Nothing We Can Do If The Server Fails To Close...
+        let _ = self.stop();
+    }
+}
+
+/// Resources served for a single service.
+struct ServiceRoute {
+    scpd_path:    String,
+    control_path: String,
+    event_path:   String,
+    scpd:         String,
+    service:      Arc<HostedService>
+}
+
+/// Maps request paths to the resources of the hosted devices.
+struct Router {
+    description: String,
+    services:    Vec<ServiceRoute>
+}
+
+impl Router {
+    fn new(root: &HostedDevice, config_id: u32) -> Router {
+        let mut services = Vec::new();
+        add_routes(&mut services, root, config_id);
+        
+        Router{ description: description::device_description(root, config_id), services: services }
+    }
+    
+    /// Returns the status code and body for the given request.
+    fn route(&self, method: &Method, path: &str, soap_action: Option<&str>, body: &str)
+        -> (StatusCode, String) {
+        match *method {
+            Method::Get if path == DESCRIPTION_PATH => {
+                return (StatusCode::Ok, self.description.clone())
+            },
+            Method::Get => {
+                if let Some(n) = self.services.iter().find(|n| n.scpd_path == path) {
+                    return (StatusCode::Ok, n.scpd.clone())
+                }
+            },
+            Method::Post => {
+                if let Some(n) = self.services.iter().find(|n| n.control_path == path) {
+                    return control(&n.service, soap_action, body)
+                }
+            },
+            _ => ()
+        }
+        
+        (StatusCode::NotFound, String::new())
+    }
+}
+
+impl Handler for Router {
+    fn handle<'a, 'k>(&'a self, mut req: Request<'a, 'k>, mut res: Response<'a, Fresh>) {
+        let path = match req.uri {
+            RequestUri::AbsolutePath(ref n) => n.clone(),
+            _ => String::new()
+        };
+        
+        if let Some(n) = self.services.iter().find(|n| n.event_path == path) {
+            return event(n.service.publisher(), &req.method, &req.headers, res)
+        }
+        
+        let soap_action = req.headers.get_raw(SOAP_ACTION_HEADER)
+            .and_then(|n| n.first())
+            .map(|n| String::from_utf8_lossy(n).into_owned());
+        
+        // Read One Byte Past The Limit So That Exceeding It Can Be Detected
+        let mut body = String::new();
+        if (&mut req).take(MAX_BODY_SIZE + 1).read_to_string(&mut body).is_err() {
+            *res.status_mut() = StatusCode::BadRequest;
+            return
+        } else if body.len() as u64 > MAX_BODY_SIZE {
+            *res.status_mut() = StatusCode::PayloadTooLarge;
+            return
+        }
+        
+        let (status, payload) = self.route(&req.method, &path[..], soap_action.as_ref().map(|n| &n[..]),
+            &body[..]);
+        
+        *res.status_mut() = status;
+        res.headers_mut().set_raw(CONTENT_TYPE_HEADER, vec![CONTENT_TYPE_VALUE.as_bytes().to_vec()]);
+        res.headers_mut().set_raw(EXT_HEADER_NAME, vec![Vec::new()]);
+        
+        // Nothing We Can Do If The Control Point Went Away...
+        let _ = res.send(payload.as_bytes());
+    }
+}
+
+/// Add routes for the services of the device and its embedded devices.
+fn add_routes(routes: &mut Vec<ServiceRoute>, device: &HostedDevice, config_id: u32) {
+    for service in device.services().iter() {
+        routes.push(ServiceRoute{
+            scpd_path:    description::scpd_path(device, service),
+            control_path: description::control_path(device, service),
+            event_path:   description::event_path(device, service),
+            scpd:         description::service_description(service, config_id),
+            service:      service.clone()
+        });
+    }
+    
+    for embedded in device.devices().iter() {
+        add_routes(routes, embedded, config_id);
+    }
+}
+
+/// Hand a subscription request over to the publisher of the service, sending
+/// the initial event message once the response is out.
+fn event(publisher: &Publisher, method: &Method, headers: &Headers, mut res: Response<Fresh>) {
+    match publisher.accept(method, headers) {
+        Ok((headers, sid)) => {
+            *res.headers_mut() = headers;
+            
+            if res.send(b"").is_ok() {
+                if let Some(n) = sid {
+                    publisher.send_initial_event(&n);
+                }
+            }
+        },
+        Err(n) => {
+            *res.status_mut() = n;
+            let _ = res.send(b"");
+        }
+    }
+}
+
+/// Invoke the requested action on the service, returning the status code and
+/// the response or fault envelope.
+fn control(service: &HostedService, soap_action: Option<&str>, body: &str) -> (StatusCode, String) {
+    let result = ActionRequest::new(body).map_err(ActionError::from).and_then(|request| {
+        // Header Has To Agree With The Body On Which Action Is Being Invoked
+        match soap_action.and_then(soap::header_action) {
+            Some(n) if n == request.action() => (),
+            _ => return Err(ActionError::invalid_action())
+        };
+        
+        service.invoke(&request).map(|outs| (request.action().to_owned(), outs))
+    });
+    
+    match result {
+        Ok((action, outs)) => {
+            let args = outs.iter().map(|&(name, ref value)| (name, &value[..])).collect::<Vec<_>>();
+            let service_type = service.service_type().urn();
+            
+            (StatusCode::Ok, soap::response_envelope(&service_type[..], &action[..], &args[..]))
+        },
+        Err(e) => (StatusCode::InternalServerError, soap::fault_envelope(e.code(), e.description()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::method::{Method};
+    use hyper::status::{StatusCode};
+    
+    use forum::device::{DeviceType};
+    use forum::service::{ServiceType};
+    use host::model::{Action, HostedDevice, HostedService};
+    use version::{Version};
+    use super::{Router};
+    
+    const CONTROL_PATH: &'static str = "/ae239f00-ae2b-bbad-ddf1-88ddcc00a234/SwitchPower1/control";
+    const SOAP_ACTION: &'static str = "\"urn:schemas-upnp-org:service:SwitchPower:1#GetStatus\"";
+    const GET_STATUS: &'static str = "<s:Envelope><s:Body>\
+        <u:GetStatus xmlns:u=\"urn:schemas-upnp-org:service:SwitchPower:1\"></u:GetStatus>\
+        </s:Body></s:Envelope>";
+    
+    fn router() -> Router {
+        let service_type = ServiceType::SwitchPower(Version::V1);
+        let service = HostedService::new(service_type, "urn:upnp-org:serviceId:SwitchPower1")
+            .with_action(Action::new("GetStatus").with_output("ResultStatus", "Status"), |_| {
+                Ok(vec![("ResultStatus", "1".to_string())])
+            });
+        let device = HostedDevice::new(DeviceType::BinaryLight(Version::V1),
+            "ae239f00-ae2b-bbad-ddf1-88ddcc00a234", "Porch Light", "omni", "Light").with_service(service);
+        
+        Router::new(&device, 1)
+    }
+    
+    #[test]
+    fn positive_control() {
+        let (status, body) = router().route(&Method::Post, CONTROL_PATH, Some(SOAP_ACTION), GET_STATUS);
+        
+        assert_eq!(status, StatusCode::Ok);
+        assert!(body.contains("<ResultStatus>1</ResultStatus>"));
+    }
+    
+    #[test]
+    fn positive_mismatched_soap_action() {
+        let soap_action = SOAP_ACTION.replace("GetStatus", "SetTarget");
+        let (status, body) = router().route(&Method::Post, CONTROL_PATH, Some(&soap_action[..]), GET_STATUS);
+        
+        assert_eq!(status, StatusCode::InternalServerError);
+        assert!(body.contains("<errorCode>401</errorCode>"));
+    }
+    
+    #[test]
+    fn positive_unknown_path() {
+        let (status, _) = router().route(&Method::Get, "/unknown.xml", None, "");
+        
+        assert_eq!(status, StatusCode::NotFound);
+    }
+}
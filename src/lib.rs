@@ -1,15 +1,24 @@
-#![feature(lookup_host, udp, libc, collections)]
+#![feature(lookup_host, udp, libc, collections, ip_addr)]
 
 use std::error::{Error};
 use std::fmt::{self, Display, Formatter};
 
+extern crate flate2;
 extern crate hyper;
 extern crate libc;
+extern crate openssl;
+extern crate rand;
+extern crate rustc_serialize;
 extern crate time;
 extern crate url;
 
+mod xml;
+
 pub mod forum;
+pub mod gena;
+pub mod host;
 pub mod net;
+pub mod soap;
 pub mod ssdp;
 pub mod util;
 pub mod version;
@@ -18,6 +27,7 @@ pub mod vendor;
 pub type SSDPResult<T> = Result<T, SSDPError>;
 pub type SimpleResult<T> = Result<T, SimpleError>;
 pub type SOAPResult<T> = Result<T, SOAPError>;
+pub type GENAResult<T> = Result<T, GENAError>;
 
 /// Enumerates all errors that can occur when dealing with an SSDP message.
 #[derive(Debug)]
@@ -104,9 +114,113 @@ impl Error for SimpleError {
     }
 }
 
-/// Enumerates all errors that can occure when dealing with a SOAP message.
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+/// Enumerates all errors that can occur when dealing with a SOAP message.
+#[derive(Debug)]
 pub enum SOAPError {
-    None
+    /// Service responded with a UPnPError fault.
+    ///
+    /// Error code and error description are supplied.
+    Fault(u16, String),
+    /// Response consists of an error code that is not 200 and carries no fault.
+    ///
+    /// Error code is supplied.
+    ResponseCode(u16),
+    /// Response is not a valid SOAP envelope for the action that was sent.
+    ///
+    /// Error message is supplied.
+    InvalidResponse(&'static str),
+    /// Request is not a valid SOAP envelope for invoking an action.
+    ///
+    /// Error message is supplied.
+    InvalidRequest(&'static str),
+    /// Out argument is missing from the response.
+    ///
+    /// Expected argument is supplied.
+    MissingArgument(&'static str),
+    /// Argument has an invalid value.
+    ///
+    /// Argument name with error message are supplied.
+    InvalidArgument(&'static str, &'static str),
+    /// Service could not be found in the description of the device.
+    ///
+    /// Service type searched for is supplied.
+    MissingService(String),
+    /// Some other error occurred.
+    Other(Box<Error>)
+}
+
+impl Display for SOAPError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            SOAPError::Fault(code, ref desc) => {
+                f.write_fmt(format_args!("Fault: {}: {}", code, desc))
+            },
+            SOAPError::ResponseCode(n) => {
+                f.write_fmt(format_args!("Response Code: {}", n))
+            },
+            SOAPError::InvalidResponse(n) => {
+                f.write_fmt(format_args!("Invalid Response: {}", n))
+            },
+            SOAPError::InvalidRequest(n) => {
+                f.write_fmt(format_args!("Invalid Request: {}", n))
+            },
+            SOAPError::MissingArgument(n) => {
+                f.write_fmt(format_args!("Missing Argument: {}", n))
+            },
+            SOAPError::InvalidArgument(name, value) => {
+                f.write_fmt(format_args!("Invalid Argument: {}: {}", name, value))
+            },
+            SOAPError::MissingService(ref n) => {
+                f.write_fmt(format_args!("Missing Service: {}", n))
+            },
+            SOAPError::Other(ref n) => {
+                f.write_fmt(format_args!("Other: {}", n.description()))
+            }
+        }
+    }
+}
+
+/// Enumerates all errors that can occur when dealing with a GENA message.
+#[derive(Debug)]
+pub enum GENAError {
+    /// Message consists of an error code that is not 200.
+    ///
+    /// Error code is supplied.
+    ResponseCode(u16),
+    /// Header is missing from the message.
+    ///
+    /// Expected header is supplied.
+    MissingHeader(&'static str),
+    /// Header has an invalid value.
+    ///
+    /// Header name with error message are supplied.
+    InvalidHeader(&'static str, &'static str),
+    /// Message body is invalid.
+    ///
+    /// Error message is supplied.
+    InvalidBody(&'static str),
+    /// Some other error occurred.
+    Other(Box<Error>)
 }
 
+impl Display for GENAError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            GENAError::ResponseCode(n) => {
+                f.write_fmt(format_args!("Response Code: {}", n))
+            },
+            GENAError::MissingHeader(n) => {
+                f.write_fmt(format_args!("Missing Header: {}", n))
+            },
+            GENAError::InvalidHeader(name, value) => {
+                f.write_fmt(format_args!("Invalid Header: {}: {}", name, value))
+            },
+            GENAError::InvalidBody(n) => {
+                f.write_fmt(format_args!("Invalid Body: {}", n))
+            },
+            GENAError::Other(ref n) => {
+                f.write_fmt(format_args!("Other: {}", n.description()))
+            }
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::io::{Error, ErrorKind, Result};
-use std::net::{UdpSocket, ToSocketAddrs};
+use std::error::{Error as StdError};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration};
 
-use hyper::net::{NetworkConnector};
+use hyper::net::{ContextVerifier, NetworkConnector, NetworkStream};
+use openssl::ssl::{SslContext, SslMethod, SslStream};
 
 use net::receiver::{PacketReceiver};
 use net::sender::{UdpSender};
@@ -45,4 +48,120 @@ impl NetworkConnector for UdpConnector {
                                          "Couldn't Convert host:port To SocketAddr"))
         }
     }
-}
\ No newline at end of file
+}
+
+/// A TimeoutConnector allows Hyper to obtain NetworkStream objects over TCP,
+/// secured with TLS for https, while bounding the time it takes to establish
+/// each connection instead of leaving that up to the operating system.
+pub struct TimeoutConnector {
+    timeout:  Duration,
+    verifier: Option<ContextVerifier>
+}
+
+impl TimeoutConnector {
+    /// Create a new TimeoutConnector that gives up on connecting after the
+    /// given amount of time.
+    pub fn new(timeout: Duration) -> TimeoutConnector {
+        TimeoutConnector{ timeout: timeout, verifier: None }
+    }
+}
+
+impl NetworkConnector for TimeoutConnector {
+    type Stream = TcpSender;
+    
+    fn connect(&mut self, host: &str, port: u16, scheme: &str) -> Result<<Self as NetworkConnector>::Stream> {
+        let tcp = try!(connect_timeout(host, port, self.timeout));
+        
+        match scheme {
+            "http"  => Ok(TcpSender::Http(tcp)),
+            "https" => {
+                let mut context = try!(SslContext::new(SslMethod::Sslv23).map_err(to_io_error));
+                if let Some(ref mut verifier) = self.verifier {
+                    verifier(&mut context);
+                }
+                
+                SslStream::new(&context, tcp).map(TcpSender::Https).map_err(to_io_error)
+            },
+            _ => Err(Error::new(ErrorKind::InvalidInput, "Invalid Scheme For TimeoutConnector"))
+        }
+    }
+    
+    fn set_ssl_verifier(&mut self, verifier: ContextVerifier) {
+        self.verifier = Some(verifier);
+    }
+}
+
+/// A NetworkStream over TCP, which may be secured with TLS.
+pub enum TcpSender {
+    Http(TcpStream),
+    Https(SslStream<TcpStream>)
+}
+
+impl TcpSender {
+    /// Creates a new handle to the same connection, sharing any TLS session.
+    pub fn try_clone(&self) -> Result<TcpSender> {
+        match *self {
+            TcpSender::Http(ref n)  => n.try_clone().map(TcpSender::Http),
+            TcpSender::Https(ref n) => n.try_clone().map(TcpSender::Https)
+        }
+    }
+}
+
+impl NetworkStream for TcpSender {
+    fn peer_addr(&mut self) -> Result<SocketAddr> {
+        match *self {
+            TcpSender::Http(ref n)  => n.peer_addr(),
+            TcpSender::Https(ref n) => n.get_ref().peer_addr()
+        }
+    }
+}
+
+impl Read for TcpSender {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match *self {
+            TcpSender::Http(ref mut n)  => n.read(buf),
+            TcpSender::Https(ref mut n) => n.read(buf)
+        }
+    }
+}
+
+impl Write for TcpSender {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match *self {
+            TcpSender::Http(ref mut n)  => n.write(buf),
+            TcpSender::Https(ref mut n) => n.write(buf)
+        }
+    }
+    
+    fn flush(&mut self) -> Result<()> {
+        match *self {
+            TcpSender::Http(ref mut n)  => n.flush(),
+            TcpSender::Https(ref mut n) => n.flush()
+        }
+    }
+}
+
+impl Clone for TcpSender {
+    fn clone(&self) -> TcpSender {
+        self.try_clone().unwrap()
+    }
+}
+
+/// Connect to the first address of the host that accepts the connection
+/// within the given amount of time.
+fn connect_timeout(host: &str, port: u16, timeout: Duration) -> Result<TcpStream> {
+    let mut last_error = Error::new(ErrorKind::InvalidInput, "Couldn't Convert host:port To SocketAddr");
+    
+    for addr in try!((host, port).to_socket_addrs()) {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(n)  => return Ok(n),
+            Err(e) => last_error = e
+        }
+    }
+    
+    Err(last_error)
+}
+
+fn to_io_error<E>(error: E) -> Error where E: StdError + Send + Sync + 'static {
+    Error::new(ErrorKind::Other, error)
+}
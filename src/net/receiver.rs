@@ -1,12 +1,17 @@
 use std::io::{Error, ErrorKind, Result};
 use std::net::{UdpSocket, SocketAddr};
+use std::time::{Duration as StdDuration};
+
+use time::{Duration};
 
 /// Maximum length for packets received on a PacketReceiver.
-pub const MAX_PCKT_LEN: usize = 600;
+///
+/// This is the largest payload that a UDP datagram can carry over IPv4.
+pub const MAX_PCKT_LEN: usize = 65507;
 
 /// A PacketReceiver that abstract over a network socket and reads full packets
-/// from the connection. Packets received from this connection are assumed to
-/// be no larger than what the typical MTU would be on a standard router.
+/// from the connection. Packets of any size that fit within a UDP datagram can
+/// be received, even if they were fragmented along the way.
 ///
 /// See net::packet::MAX_PCKT_LEN.
 pub struct PacketReceiver(UdpSocket);
@@ -28,9 +33,48 @@ impl PacketReceiver {
             Err(Error::new(ErrorKind::Other, 
                            "UdpSocket Reported Receive Length Greater Than Buffer"))
         } else {
-            unsafe{ pckt_buf.set_len(size) };
+            pckt_buf.truncate(size);
+            
+            // Most Packets Are Small, Dont Hold On To The Whole Buffer
+            pckt_buf.shrink_to_fit();
             
             Ok((pckt_buf, addr))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Receives packets from a UdpSocket so that they can be waited on with a
+/// timeout, or picked up only when they have already arrived.
+pub struct PacketListener(PacketReceiver);
+
+impl PacketListener {
+    /// Create a new PacketListener that receives packets from the given UdpSocket.
+    pub fn new(udp: &UdpSocket) -> Result<PacketListener> {
+        Ok(PacketListener(PacketReceiver::new(try!(udp.try_clone()))))
+    }
+    
+    /// Returns a packet that was already received, if any.
+    pub fn try_recv(&self) -> Option<(Vec<u8>, SocketAddr)> {
+        if (self.0).0.set_nonblocking(true).is_err() {
+            return None
+        }
+        
+        self.0.recv_pckt().ok()
+    }
+    
+    /// Wait up to the given amount of time for a packet to be received.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<(Vec<u8>, SocketAddr)> {
+        let millis = timeout.num_milliseconds();
+        if millis <= 0 {
+            return self.try_recv()
+        }
+        
+        let udp = &(self.0).0;
+        if udp.set_nonblocking(false).is_err() ||
+           udp.set_read_timeout(Some(StdDuration::from_millis(millis as u64))).is_err() {
+            return None
+        }
+        
+        self.0.recv_pckt().ok()
+    }
+}
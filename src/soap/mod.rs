@@ -0,0 +1,323 @@
+//! Implements the SOAP control layer of the UPnP standard.
+//!
+//! This module deals with building action requests for services and pulling
+//! out arguments or faults from the responses sent back by those services, as
+//! well as the reverse for services that we are hosting ourselves.
+
+use std::ascii::{AsciiExt};
+use std::error::{Error};
+use std::io::{Read};
+
+use hyper::{Client};
+use hyper::header::{Headers};
+use hyper::status::{StatusCode};
+use url::{Url};
+
+use {SOAPError, SOAPResult};
+use xml;
+
+pub mod session;
+pub mod value;
+
+/// Header naming the action being invoked.
+pub const SOAP_ACTION_HEADER:  &'static str = "SOAPACTION";
+/// Header and value describing the content of a SOAP payload.
+pub const CONTENT_TYPE_HEADER: &'static str = "CONTENT-TYPE";
+pub const CONTENT_TYPE_VALUE:  &'static str = "text/xml; charset=\"utf-8\"";
+
+const BODY_ELEMENT:            &'static str = "Body";
+const UPNP_ERROR_ELEMENT:      &'static str = "UPnPError";
+const ERROR_CODE_ELEMENT:      &'static str = "errorCode";
+const ERROR_DESC_ELEMENT:      &'static str = "errorDescription";
+const RESPONSE_ELEMENT_SUFFIX: &'static str = "Response";
+
+const CONNECTION_HEADER: &'static str = "CONNECTION";
+const CLOSE_VALUE:       &'static str = "close";
+
+const ACTION_SEPARATOR: char = '#';
+
+/// Response to an action that was successfully invoked on a service.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionResponse {
+    body: String
+}
+
+impl ActionResponse {
+    /// Create a new ActionResponse from the payload returned for the action.
+    pub fn new(action: &str, payload: &str) -> SOAPResult<ActionResponse> {
+        let response_name = format!("{}{}", action, RESPONSE_ELEMENT_SUFFIX);
+        
+        match xml::element(payload, &response_name[..]) {
+            Some(n) => Ok(ActionResponse{ body: n.to_owned() }),
+            None    => Err(SOAPError::InvalidResponse("Action Response Element Not Found"))
+        }
+    }
+    
+    /// Returns the unescaped value of the given out argument.
+    pub fn argument(&self, name: &'static str) -> SOAPResult<String> {
+        match xml::element(&self.body[..], name) {
+            Some(n) => Ok(xml::unescape(n)),
+            None    => Err(SOAPError::MissingArgument(name))
+        }
+    }
+}
+
+/// Action invocation received from a control point for a service we are hosting.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionRequest {
+    action: String,
+    body:   String
+}
+
+impl ActionRequest {
+    /// Create a new ActionRequest from the payload of an incoming request.
+    pub fn new(payload: &str) -> SOAPResult<ActionRequest> {
+        let body = try!(xml::element(payload, BODY_ELEMENT).ok_or(
+            SOAPError::InvalidRequest("Body Element Not Found")
+        ));
+        
+        match xml::first_child(body) {
+            Some((action, args)) => Ok(ActionRequest{ action: action.to_owned(), body: args.to_owned() }),
+            None => Err(SOAPError::InvalidRequest("Action Element Not Found"))
+        }
+    }
+    
+    /// Returns the name of the action being invoked.
+    pub fn action(&self) -> &str {
+        &self.action[..]
+    }
+    
+    /// Returns the unescaped value of the given in argument.
+    pub fn argument(&self, name: &'static str) -> SOAPResult<String> {
+        match xml::element(&self.body[..], name) {
+            Some(n) => Ok(xml::unescape(n)),
+            None    => Err(SOAPError::MissingArgument(name))
+        }
+    }
+    
+    /// Returns the unescaped value of the given in argument, if present.
+    ///
+    /// Unlike argument, this works with names only known at runtime.
+    pub fn value(&self, name: &str) -> Option<String> {
+        xml::element(&self.body[..], name).map(xml::unescape)
+    }
+    
+    /// Returns true if the request holds the given in argument, even if empty.
+    pub fn has_argument(&self, name: &str) -> bool {
+        xml::element(&self.body[..], name).is_some()
+    }
+}
+
+/// Returns the action named in the value of a SOAPACTION header.
+pub fn header_action(value: &str) -> Option<&str> {
+    let value = value.trim().trim_matches('"');
+    
+    value.find(ACTION_SEPARATOR).map(|n| &value[n + 1..])
+}
+
+/// Builds the SOAP envelope for the response to the given action.
+pub fn response_envelope(service_type: &str, action: &str, args: &[(&str, &str)]) -> String {
+    let response_name = format!("{}{}", action, RESPONSE_ELEMENT_SUFFIX);
+    
+    envelope(service_type, &response_name[..], args)
+}
+
+/// Builds the SOAP envelope for a UPnPError fault.
+pub fn fault_envelope(code: u16, desc: &str) -> String {
+    format!("<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring>\
+             <detail><UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\
+             <errorCode>{}</errorCode><errorDescription>{}</errorDescription>\
+             </UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+            code, xml::escape(desc))
+}
+
+/// Invoke an action on the service of the given type located at the control url.
+///
+/// Arguments are supplied as (name, value) pairs and will be escaped before
+/// being sent. This is a blocking operation.
+pub fn send_action(control: &Url, service_type: &str, action: &str, args: &[(&str, &str)])
+    -> SOAPResult<ActionResponse> {
+    let (status, body, _) = try!(post_action(&mut Client::new(), control, service_type, action, args));
+    
+    action_response(action, status, &body[..])
+}
+
+/// Post the action to the control url using the given client.
+///
+/// Returns the status code and body of the response, along with whether the
+/// device is keeping the connection open for further requests.
+fn post_action(client: &mut Client, control: &Url, service_type: &str, action: &str, args: &[(&str, &str)])
+    -> SOAPResult<(StatusCode, String, bool)> {
+    let payload = action_envelope(service_type, action, args);
+    
+    let mut headers = Headers::new();
+    headers.set_raw(SOAP_ACTION_HEADER, vec![action_header(service_type, action).into_bytes()]);
+    headers.set_raw(CONTENT_TYPE_HEADER, vec![CONTENT_TYPE_VALUE.as_bytes().to_vec()]);
+    
+    let mut response = try!(client.post(control.clone()).headers(headers)
+        .body(&payload[..]).send().map_err(|e| SOAPError::Other(Box::new(e) as Box<Error>)));
+    
+    let mut body = String::new();
+    try!(response.read_to_string(&mut body).map_err(|e|
+        SOAPError::Other(Box::new(e) as Box<Error>)
+    ));
+    
+    let keep_alive = !response.headers.get_raw(CONNECTION_HEADER).map_or(false, |n| n.iter().any(|value|
+        String::from_utf8_lossy(value).split(',').any(|n| n.trim().eq_ignore_ascii_case(CLOSE_VALUE))
+    ));
+    
+    Ok((response.status, body, keep_alive))
+}
+
+/// Pull the action response, or the fault, out of the body of a response.
+fn action_response(action: &str, status: StatusCode, body: &str) -> SOAPResult<ActionResponse> {
+    match status {
+        StatusCode::Ok => ActionResponse::new(action, body),
+        n => Err(parse_fault(body).unwrap_or(SOAPError::ResponseCode(n.to_u16())))
+    }
+}
+
+/// Returns the value of the SOAPACTION header for the given action.
+fn action_header(service_type: &str, action: &str) -> String {
+    format!("\"{}#{}\"", service_type, action)
+}
+
+/// Builds the SOAP envelope for invoking the given action.
+fn action_envelope(service_type: &str, action: &str, args: &[(&str, &str)]) -> String {
+    envelope(service_type, action, args)
+}
+
+/// Builds a SOAP envelope whose body holds the given element and arguments.
+fn envelope(service_type: &str, element: &str, args: &[(&str, &str)]) -> String {
+    let mut arguments = String::new();
+    
+    for &(name, value) in args.iter() {
+        arguments.push_str(&format!("<{0}>{1}</{0}>", name, xml::escape(value))[..]);
+    }
+    
+    format!("<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{0} xmlns:u=\"{1}\">{2}</u:{0}></s:Body></s:Envelope>",
+            element, service_type, arguments)
+}
+
+/// Parses a UPnPError out of a SOAP fault payload.
+fn parse_fault(payload: &str) -> Option<SOAPError> {
+    let upnp_error = match xml::element(payload, UPNP_ERROR_ELEMENT) {
+        Some(n) => n,
+        None    => return None
+    };
+    
+    let code = match xml::element(upnp_error, ERROR_CODE_ELEMENT) {
+        Some(n) => match u16::from_str_radix(n.trim(), 10) {
+            Ok(n)  => n,
+            Err(_) => return None
+        },
+        None => return None
+    };
+    let desc = xml::element(upnp_error, ERROR_DESC_ELEMENT).map(|n| xml::unescape(n));
+    
+    Some(SOAPError::Fault(code, desc.unwrap_or(String::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {SOAPError};
+    use super::{ActionRequest, ActionResponse};
+    
+    const SWITCH_POWER_URN: &'static str = "urn:schemas-upnp-org:service:SwitchPower:1";
+    
+    #[test]
+    fn positive_action_envelope() {
+        let envelope = super::action_envelope(SWITCH_POWER_URN, "SetTarget", &[("newTargetValue", "1")]);
+        
+        assert!(envelope.contains("<u:SetTarget xmlns:u=\"urn:schemas-upnp-org:service:SwitchPower:1\">\
+                                   <newTargetValue>1</newTargetValue></u:SetTarget>"));
+    }
+    
+    #[test]
+    fn positive_escaped_argument() {
+        let envelope = super::action_envelope(SWITCH_POWER_URN, "Action", &[("Arg", "<&>")]);
+        
+        assert!(envelope.contains("<Arg>&lt;&amp;&gt;</Arg>"));
+    }
+    
+    #[test]
+    fn positive_action_header() {
+        assert_eq!(super::action_header(SWITCH_POWER_URN, "GetStatus"),
+                   "\"urn:schemas-upnp-org:service:SwitchPower:1#GetStatus\"");
+    }
+    
+    #[test]
+    fn positive_response_argument() {
+        let payload = "<s:Envelope><s:Body><u:GetStatusResponse xmlns:u=\"urn\">\
+                       <ResultStatus>1</ResultStatus></u:GetStatusResponse></s:Body></s:Envelope>";
+        let response = ActionResponse::new("GetStatus", payload).unwrap();
+        
+        assert_eq!(response.argument("ResultStatus").unwrap(), "1");
+    }
+    
+    #[test]
+    fn positive_fault() {
+        let payload = "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode>\
+                       <detail><UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\
+                       <errorCode>718</errorCode><errorDescription>ConflictInMappingEntry\
+                       </errorDescription></UPnPError></detail></s:Fault></s:Body></s:Envelope>";
+        
+        match super::parse_fault(payload) {
+            Some(SOAPError::Fault(718, ref n)) if n == "ConflictInMappingEntry" => (),
+            _ => panic!("Failed To Parse UPnPError Fault")
+        }
+    }
+    
+    #[test]
+    fn positive_action_request() {
+        let payload = super::action_envelope(SWITCH_POWER_URN, "SetTarget", &[("newTargetValue", "<1>")]);
+        let request = ActionRequest::new(&payload[..]).unwrap();
+        
+        assert_eq!(request.action(), "SetTarget");
+        assert_eq!(request.argument("newTargetValue").unwrap(), "<1>");
+    }
+    
+    #[test]
+    fn positive_header_action() {
+        let header = super::action_header(SWITCH_POWER_URN, "GetStatus");
+        
+        assert_eq!(super::header_action(&header[..]), Some("GetStatus"));
+    }
+    
+    #[test]
+    fn positive_fault_round_trip() {
+        match super::parse_fault(&super::fault_envelope(402, "Invalid Args")[..]) {
+            Some(SOAPError::Fault(402, ref n)) if n == "Invalid Args" => (),
+            _ => panic!("Failed To Parse UPnPError Fault")
+        }
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_request_without_body() {
+        ActionRequest::new("<u:SetTarget xmlns:u=\"urn\"></u:SetTarget>").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_argument() {
+        let payload = "<u:GetStatusResponse xmlns:u=\"urn\"></u:GetStatusResponse>";
+        let response = ActionResponse::new("GetStatus", payload).unwrap();
+        
+        response.argument("ResultStatus").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_wrong_response() {
+        let payload = "<u:GetTargetResponse xmlns:u=\"urn\"></u:GetTargetResponse>";
+        
+        ActionResponse::new("GetStatus", payload).unwrap();
+    }
+}
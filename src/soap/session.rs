@@ -0,0 +1,306 @@
+//! Secure sessions that actions can be sent over.
+//!
+//! DeviceProtection ties introductions and logins to the TLS session they were
+//! made over, so a control point has to keep that session open and send every
+//! protected action after logging in over it as well.
+
+use std::error::{Error};
+use std::fmt::{self, Debug, Formatter};
+use std::fs::{File};
+use std::hash::{Hash, Hasher};
+use std::io::{self};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration};
+
+use hyper::{Client};
+use hyper::net::{NetworkConnector};
+use openssl::crypto::pkey::{PKey};
+use openssl::ssl::{SslContext};
+use openssl::x509::{X509, X509FileType};
+use url::{Url};
+
+use {SimpleError, SOAPError, SOAPResult};
+use net::connector::{TcpSender, TimeoutConnector};
+use soap::{self, ActionResponse};
+
+/// Time allowed for establishing the connection to a device.
+pub const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 5000;
+
+const HTTPS_SCHEME: &'static str = "https";
+
+/// Certificate, along with its private key, that a control point identifies
+/// itself to a device with.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ClientCertificate {
+    certificate: PathBuf,
+    private_key: PathBuf
+}
+
+impl ClientCertificate {
+    /// Create a new ClientCertificate from a PEM encoded certificate and
+    /// private key, both of which are checked to be readable up front.
+    pub fn new<C, K>(certificate: C, private_key: K) -> SOAPResult<ClientCertificate>
+        where C: AsRef<Path>, K: AsRef<Path> {
+        let mut file = try!(File::open(certificate.as_ref()).map_err(to_soap_error));
+        try!(X509::from_pem(&mut file).map_err(to_soap_error));
+        
+        let mut file = try!(File::open(private_key.as_ref()).map_err(to_soap_error));
+        try!(PKey::private_key_from_pem(&mut file).map_err(to_soap_error));
+        
+        Ok(ClientCertificate{ certificate: certificate.as_ref().to_path_buf(),
+            private_key: private_key.as_ref().to_path_buf() })
+    }
+    
+    /// Returns the path of the certificate.
+    pub fn certificate(&self) -> &Path {
+        &self.certificate
+    }
+    
+    /// Returns the path of the private key.
+    pub fn private_key(&self) -> &Path {
+        &self.private_key
+    }
+}
+
+/// TLS session with a device that is kept open across actions.
+///
+/// The connection is opened by the first action and reused by every action
+/// after it, as long as they are sent to the same host and port. Clones share
+/// the session and are compared by identity.
+#[derive(Clone)]
+pub struct SecureSession {
+    shared: Arc<Shared>
+}
+
+impl SecureSession {
+    /// Create a new SecureSession that does not present a client certificate.
+    pub fn new() -> SecureSession {
+        SecureSession::with_state(None)
+    }
+    
+    /// Create a new SecureSession that presents the given client certificate.
+    pub fn with_certificate(certificate: ClientCertificate) -> SecureSession {
+        SecureSession::with_state(Some(certificate))
+    }
+    
+    fn with_state(certificate: Option<ClientCertificate>) -> SecureSession {
+        let state = SessionState{ certificate: certificate, stream: None, connections: 0 };
+        let shared = Shared{ state: Arc::new(Mutex::new(state)), in_flight: Mutex::new(()) };
+        
+        SecureSession{ shared: Arc::new(shared) }
+    }
+    
+    /// Returns the client certificate presented to devices, if any.
+    pub fn certificate(&self) -> Option<ClientCertificate> {
+        self.shared.state.lock().unwrap().certificate.clone()
+    }
+    
+    /// Returns the number of connections that have been opened so far.
+    pub fn connections(&self) -> usize {
+        self.shared.state.lock().unwrap().connections
+    }
+    
+    /// Close the connection, if open, so that the next action opens a new one.
+    ///
+    /// Devices forget any login that was made over the old connection.
+    pub fn close(&self) {
+        self.shared.state.lock().unwrap().stream = None;
+    }
+    
+    /// Invoke an action on the service of the given type located at the secure
+    /// control url.
+    ///
+    /// Actions are sent one at a time. If the request fails or the device does
+    /// not keep the connection open, the connection is closed and the next
+    /// action opens a new one. This is a blocking operation.
+    pub fn send_action(&self, control: &Url, service_type: &str, action: &str, args: &[(&str, &str)])
+        -> SOAPResult<ActionResponse> {
+        if control.scheme != HTTPS_SCHEME {
+            return Err(SOAPError::Other(Box::new(SimpleError::new("Control URL Does Not Use HTTPS"))
+                as Box<Error>))
+        }
+        
+        self.send(control, service_type, action, args)
+    }
+    
+    /// Invoke an action over the session, whatever the scheme of the control url.
+    fn send(&self, control: &Url, service_type: &str, action: &str, args: &[(&str, &str)])
+        -> SOAPResult<ActionResponse> {
+        let _in_flight = self.shared.in_flight.lock().unwrap();
+        
+        let mut client = Client::with_connector(SessionConnector{ state: self.shared.state.clone() });
+        match soap::post_action(&mut client, control, service_type, action, args) {
+            Ok((status, body, keep_alive)) => {
+                if !keep_alive {
+                    self.close();
+                }
+                
+                soap::action_response(action, status, &body[..])
+            },
+            Err(e) => {
+                self.close();
+                
+                Err(e)
+            }
+        }
+    }
+}
+
+impl PartialEq for SecureSession {
+    fn eq(&self, other: &SecureSession) -> bool {
+        &*self.shared as *const Shared == &*other.shared as *const Shared
+    }
+}
+
+impl Eq for SecureSession {}
+
+impl Hash for SecureSession {
+    fn hash<H>(&self, state: &mut H) where H: Hasher {
+        (&*self.shared as *const Shared as usize).hash(state);
+    }
+}
+
+impl Debug for SecureSession {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("SecureSession {{ certificate: {:?}, connections: {} }}",
+            self.certificate(), self.connections()))
+    }
+}
+
+/// State shared between the clones of a SecureSession.
+struct Shared {
+    state:     Arc<Mutex<SessionState>>,
+    in_flight: Mutex<()>
+}
+
+/// Connection held open by a SecureSession.
+struct SessionState {
+    certificate: Option<ClientCertificate>,
+    stream:      Option<(String, u16, TcpSender)>,
+    connections: usize
+}
+
+/// Hands Hyper the open connection of a session, opening one if the session
+/// has no connection to the requested host and port.
+struct SessionConnector {
+    state: Arc<Mutex<SessionState>>
+}
+
+impl NetworkConnector for SessionConnector {
+    type Stream = TcpSender;
+    
+    fn connect(&mut self, host: &str, port: u16, scheme: &str)
+        -> io::Result<<Self as NetworkConnector>::Stream> {
+        let mut state = self.state.lock().unwrap();
+        
+        if let Some((ref open_host, open_port, ref stream)) = state.stream {
+            if &open_host[..] == host && open_port == port {
+                return stream.try_clone()
+            }
+        }
+        
+        let mut connector = TimeoutConnector::new(Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS));
+        if let Some(certificate) = state.certificate.clone() {
+            connector.set_ssl_verifier(Box::new(move |ctx: &mut SslContext| {
+                // Both Files Were Checked When The Certificate Was Created
+                let _ = ctx.set_certificate_file(certificate.certificate(), X509FileType::PEM);
+                let _ = ctx.set_private_key_file(certificate.private_key(), X509FileType::PEM);
+            }));
+        }
+        
+        let stream = try!(connector.connect(host, port, scheme));
+        state.stream = Some((host.to_string(), port, try!(stream.try_clone())));
+        state.connections += 1;
+        
+        Ok(stream)
+    }
+}
+
+fn to_soap_error<E>(error: E) -> SOAPError where E: Error + 'static {
+    SOAPError::Other(Box::new(error) as Box<Error>)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ascii::{AsciiExt};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::{self};
+    
+    use url::{Url};
+    
+    use super::{SecureSession};
+    
+    const RESPONSE_BODY: &'static str = "<s:Envelope><s:Body><u:GetStatusResponse xmlns:u=\"urn\">\
+                                         <ResultStatus>1</ResultStatus>\
+                                         </u:GetStatusResponse></s:Body></s:Envelope>";
+    
+    /// Read a single request off of the stream, returning false once the
+    /// connection has been closed.
+    fn read_request(stream: &mut TcpStream) -> bool {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        
+        loop {
+            let text = String::from_utf8_lossy(&request[..]).into_owned();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end].lines().filter_map(|n| {
+                    let mut parts = n.splitn(2, ':');
+                    match (parts.next(), parts.next()) {
+                        (Some(name), Some(value)) if name.trim().eq_ignore_ascii_case("content-length") =>
+                            value.trim().parse::<usize>().ok(),
+                        _ => None
+                    }
+                }).next().unwrap_or(0);
+                
+                if request.len() >= end + 4 + length {
+                    return true
+                }
+            }
+            
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => return false,
+                Ok(n) => request.extend(buf[..n].iter().cloned())
+            }
+        }
+    }
+    
+    #[test]
+    fn positive_reuse_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let control = Url::parse(&format!("http://{}/control", listener.local_addr().unwrap())[..]).unwrap();
+        
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let accepted_clone = accepted.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                accepted_clone.fetch_add(1, Ordering::SeqCst);
+                
+                while read_request(&mut stream) {
+                    let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        RESPONSE_BODY.len(), RESPONSE_BODY);
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            }
+        });
+        
+        let session = SecureSession::new();
+        session.send(&control, "urn", "GetStatus", &[]).unwrap();
+        session.send(&control, "urn", "GetStatus", &[]).unwrap();
+        
+        assert_eq!(session.connections(), 1);
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_plain_control_url() {
+        let control = Url::parse("http://192.168.1.5:49152/control").unwrap();
+        
+        SecureSession::new().send_action(&control, "urn", "GetStatus", &[]).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+//! Conversions between UPnP data types and Rust types.
+//!
+//! Argument and state variable values are always transferred as text, so
+//! typed service clients use these to move between the two representations.
+
+use std::ascii::{AsciiExt};
+use std::str::{FromStr};
+
+use url::{Url};
+
+use {SOAPError, SOAPResult};
+
+const TRUE_VALUES:  [&'static str; 3] = ["1", "true", "yes"];
+const FALSE_VALUES: [&'static str; 3] = ["0", "false", "no"];
+
+const LIST_SEP: char = ',';
+
+/// Convert a UPnP boolean value to a bool.
+///
+/// Accepts any of the representations allowed by the UPnP standard.
+pub fn to_bool(name: &'static str, value: &str) -> SOAPResult<bool> {
+    let value = value.trim();
+    
+    if TRUE_VALUES.iter().any(|n| n.eq_ignore_ascii_case(value)) {
+        Ok(true)
+    } else if FALSE_VALUES.iter().any(|n| n.eq_ignore_ascii_case(value)) {
+        Ok(false)
+    } else {
+        Err(SOAPError::InvalidArgument(name, "Value Is Not A Valid Boolean"))
+    }
+}
+
+/// Convert a bool to the canonical UPnP boolean value.
+pub fn from_bool(value: bool) -> &'static str {
+    if value { TRUE_VALUES[0] } else { FALSE_VALUES[0] }
+}
+
+/// Convert a UPnP numeric value (ui1, i4, etc.) to the given numeric type.
+pub fn to_number<T>(name: &'static str, value: &str) -> SOAPResult<T> where T: FromStr {
+    value.trim().parse::<T>().map_err(|_|
+        SOAPError::InvalidArgument(name, "Value Is Not A Valid Number")
+    )
+}
+
+/// Convert a numeric value within the inclusive range [min,max] to the given
+/// numeric type.
+pub fn to_ranged<T>(name: &'static str, value: &str, min: T, max: T) -> SOAPResult<T>
+    where T: FromStr + PartialOrd {
+    let number = try!(to_number::<T>(name, value));
+    
+    if number < min || number > max {
+        Err(SOAPError::InvalidArgument(name, "Value Is Out Of Range"))
+    } else {
+        Ok(number)
+    }
+}
+
+/// Convert a UPnP uri value to a Url.
+pub fn to_url(name: &'static str, value: &str) -> SOAPResult<Url> {
+    Url::parse(value.trim()).map_err(|_|
+        SOAPError::InvalidArgument(name, "Value Is Not A Valid URL")
+    )
+}
+
+/// Split a comma separated list value into its (trimmed) entries.
+///
+/// Empty entries are skipped, so an empty value yields an empty list.
+pub fn to_list(value: &str) -> Vec<&str> {
+    value.split(LIST_SEP).map(|n| n.trim()).filter(|n| !n.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn positive_bool_values() {
+        assert_eq!(super::to_bool("Arg", "1").unwrap(), true);
+        assert_eq!(super::to_bool("Arg", "TRUE").unwrap(), true);
+        assert_eq!(super::to_bool("Arg", " yes ").unwrap(), true);
+        assert_eq!(super::to_bool("Arg", "0").unwrap(), false);
+        assert_eq!(super::to_bool("Arg", "False").unwrap(), false);
+        assert_eq!(super::to_bool("Arg", "no").unwrap(), false);
+    }
+    
+    #[test]
+    fn positive_number() {
+        assert_eq!(super::to_number::<i32>("Arg", " -2150 ").unwrap(), -2150);
+    }
+    
+    #[test]
+    fn positive_ranged_bound() {
+        assert_eq!(super::to_ranged::<u8>("Arg", "100", 0, 100).unwrap(), 100);
+    }
+    
+    #[test]
+    fn positive_list() {
+        assert_eq!(super::to_list(" a, b,,c "), vec!["a", "b", "c"]);
+        assert!(super::to_list("").is_empty());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_bool_value() {
+        super::to_bool("Arg", "on").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_ranged_overflow() {
+        super::to_ranged::<u8>("Arg", "101", 0, 100).unwrap();
+    }
+}
@@ -0,0 +1,594 @@
+//! Advertises a root device that we are hosting to the network.
+//!
+//! A full set of alive messages is sent when advertising starts, which is
+//! three messages for the root device, two for every embedded device, and one
+//! for every distinct service type within each device. The set is re-sent at
+//! a random interval under half of the max-age, and a matching set of byebye
+//! messages is sent when advertising stops.
+
+use std::cmp;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::str;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self};
+
+use hyper::header::{CacheControl, CacheDirective, Host, Location, Server};
+use rand::{self, Rng};
+use time::{Duration, PreciseTime};
+use url::{Url};
+
+use {SSDPError, SSDPResult, SimpleError};
+use ssdp::{self, FieldPair};
+use ssdp::header::{BootID, ConfigID, NextBootID, NT, NTS, SearchPort, SecureLocation, ST, USN};
+use ssdp::message::{self};
+use ssdp::message::search::{DEFAULT_SEARCH_PORT};
+use ssdp::packet::{Packet};
+
+/// Default number of seconds that an advertisement stays valid for.
+pub const DEFAULT_MAX_AGE: u32 = 1800;
+
+/// Default number of hops that multicast messages live for, as recommended by
+/// the UPnP 1.1 standard.
+pub const DEFAULT_MULTICAST_TTL: u32 = 2;
+
+/// Time to sleep between checks for a shutdown of the advertiser.
+const POLL_INTERVAL_MS: u32 = 100;
+
+const ROOT_DEVICE_VALUE: &'static [u8] = b"rootdevice";
+
+const SEARCH_RESPONSE_CODE:   u16 = 200;
+const SEARCH_RESPONSE_REASON: &'static str = "OK";
+const EXT_HEADER_NAME:        &'static str = "EXT";
+
+/// Device whose presence is being advertised.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AdvertisedDevice {
+    uuid:          String,
+    device_type:   String,
+    service_types: Vec<String>
+}
+
+impl AdvertisedDevice {
+    /// Create a new AdvertisedDevice from its uuid and device type.
+    ///
+    /// Neither value should include the "uuid:" or "urn:" prefix, so the device
+    /// type would look something like "schemas-upnp-org:device:Basic:1".
+    pub fn new(uuid: &str, device_type: &str) -> AdvertisedDevice {
+        AdvertisedDevice{ uuid: uuid.to_string(), device_type: device_type.to_string(),
+            service_types: Vec::new() }
+    }
+    
+    /// Add a service type that the device offers, without the "urn:" prefix.
+    ///
+    /// Service types that were already added are ignored.
+    pub fn with_service(mut self, service_type: &str) -> AdvertisedDevice {
+        if !self.service_types.iter().any(|n| n == service_type) {
+            self.service_types.push(service_type.to_string());
+        }
+        
+        self
+    }
+    
+    /// Returns the uuid of the device.
+    pub fn uuid(&self) -> &str {
+        &self.uuid[..]
+    }
+    
+    /// Returns the device type of the device.
+    pub fn device_type(&self) -> &str {
+        &self.device_type[..]
+    }
+    
+    /// Returns the distinct service types offered by the device.
+    pub fn service_types(&self) -> &[String] {
+        &self.service_types[..]
+    }
+}
+
+/// Everything that gets announced about a root device and its embedded devices.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Advertisement {
+    root:            AdvertisedDevice,
+    embedded:        Vec<AdvertisedDevice>,
+    location:        Url,
+    server:          String,
+    max_age:         u32,
+    boot_id:         u32,
+    config_id:       u32,
+    search_port:     Option<u16>,
+    secure_location: Option<Url>
+}
+
+impl Advertisement {
+    /// Create a new Advertisement for the root device whose description can be
+    /// found at the given location.
+    ///
+    /// The server value should follow the "OS/version UPnP/1.1 product/version" form.
+    pub fn new(root: AdvertisedDevice, location: Url, server: &str, boot_id: u32, config_id: u32)
+        -> Advertisement {
+        Advertisement{ root: root, embedded: Vec::new(), location: location, server: server.to_string(),
+            max_age: DEFAULT_MAX_AGE, boot_id: boot_id, config_id: config_id, search_port: None,
+            secure_location: None }
+    }
+    
+    /// Add an embedded device to the advertisement.
+    pub fn with_embedded(mut self, device: AdvertisedDevice) -> Advertisement {
+        self.embedded.push(device);
+        
+        self
+    }
+    
+    /// Set the number of seconds that the advertisement stays valid for.
+    pub fn with_max_age(mut self, max_age: u32) -> Advertisement {
+        self.max_age = max_age;
+        
+        self
+    }
+    
+    /// Set the port that the device answers unicast search requests on, if it
+    /// is not the default search port.
+    pub fn with_search_port(mut self, search_port: u16) -> Advertisement {
+        self.search_port = Some(search_port);
+        
+        self
+    }
+    
+    /// Set the secure location of the description, for UPnP 2.0 devices.
+    pub fn with_secure_location(mut self, secure_location: Url) -> Advertisement {
+        self.secure_location = Some(secure_location);
+        
+        self
+    }
+    
+    /// Returns the root device being advertised.
+    pub fn root(&self) -> &AdvertisedDevice {
+        &self.root
+    }
+    
+    /// Returns the embedded devices being advertised.
+    pub fn embedded(&self) -> &[AdvertisedDevice] {
+        &self.embedded[..]
+    }
+    
+    /// Returns the location of the root device description.
+    pub fn location(&self) -> &Url {
+        &self.location
+    }
+    
+    /// Returns the number of seconds that the advertisement stays valid for.
+    pub fn max_age(&self) -> u32 {
+        self.max_age
+    }
+    
+    /// Returns the current boot instance of the root device.
+    pub fn boot_id(&self) -> u32 {
+        self.boot_id
+    }
+    
+    /// Returns the configuration number of the root device.
+    pub fn config_id(&self) -> u32 {
+        self.config_id
+    }
+    
+    /// Returns the port that the device answers unicast search requests on.
+    pub fn search_port(&self) -> u16 {
+        self.search_port.unwrap_or(DEFAULT_SEARCH_PORT)
+    }
+    
+    /// Change the port that the device answers unicast search requests on.
+    pub fn set_search_port(&mut self, search_port: Option<u16>) {
+        self.search_port = search_port;
+    }
+    
+    /// Returns every notification type along with its unique service name.
+    ///
+    /// These are the 3+2d+k targets that are announced, where d is the number
+    /// of embedded devices and k is the number of distinct service types.
+    pub fn targets(&self) -> Vec<(NT, USN)> {
+        let root_uuid = FieldPair::UUID(self.root.uuid.as_bytes().to_vec());
+        let root_pair = FieldPair::UPnP(ROOT_DEVICE_VALUE.to_vec());
+        
+        let mut targets = vec![(NT(root_pair.clone()), USN(root_uuid, Some(root_pair)))];
+        for device in Some(&self.root).into_iter().chain(self.embedded.iter()) {
+            let uuid = FieldPair::UUID(device.uuid.as_bytes().to_vec());
+            let device_type = FieldPair::URN(device.device_type.as_bytes().to_vec());
+            
+            targets.push((NT(uuid.clone()), USN(uuid.clone(), None)));
+            targets.push((NT(device_type.clone()), USN(uuid.clone(), Some(device_type))));
+            
+            for service_type in device.service_types.iter() {
+                let service_type = FieldPair::URN(service_type.as_bytes().to_vec());
+                
+                targets.push((NT(service_type.clone()), USN(uuid.clone(), Some(service_type))));
+            }
+        }
+        
+        targets
+    }
+    
+    /// Returns the search target to answer with along with the unique service
+    /// name for every target that matches the given search target.
+    ///
+    /// Device and service types match if they are the same or a later version
+    /// of the type searched for, in which case the response carries the version
+    /// that was searched for.
+    pub fn matching_targets(&self, target: &ST) -> Vec<(ST, USN)> {
+        let field = match *target {
+            ST::All => return self.targets().into_iter().map(|(nt, usn)| (ST::Target(nt.0), usn)).collect(),
+            ST::Target(ref n) => n
+        };
+        
+        self.targets().into_iter().filter(|&(ref nt, _)| {
+            match (&nt.0, field) {
+                (&FieldPair::URN(ref ours), &FieldPair::URN(ref theirs)) => urn_satisfies(ours, theirs),
+                (ours, theirs) => ours == theirs
+            }
+        }).map(|(_, usn)| (target.clone(), usn)).collect()
+    }
+    
+    /// Build the search responses for every target that matches the given
+    /// search target.
+    pub fn search_responses(&self, target: &ST) -> Vec<Packet> {
+        self.matching_targets(target).into_iter().map(|(st, usn)| {
+            let mut packet = Packet::response(SEARCH_RESPONSE_CODE, SEARCH_RESPONSE_REASON);
+            
+            packet.set(CacheControl(vec![CacheDirective::MaxAge(self.max_age)]));
+            packet.set_raw(EXT_HEADER_NAME, "");
+            packet.set(Location(self.location.serialize()));
+            packet.set(Server(self.server.clone()));
+            packet.set(st);
+            packet.set(usn);
+            self.set_identifiers(&mut packet);
+            
+            packet
+        }).collect()
+    }
+    
+    /// Build the ssdp:alive messages for every target.
+    pub fn alive_packets(&self) -> Vec<Packet> {
+        self.targets().into_iter().map(|(nt, usn)| {
+            let mut packet = notify_packet(nt, NTS::Alive, usn);
+            
+            packet.set(CacheControl(vec![CacheDirective::MaxAge(self.max_age)]));
+            packet.set(Location(self.location.serialize()));
+            packet.set(Server(self.server.clone()));
+            self.set_identifiers(&mut packet);
+            
+            packet
+        }).collect()
+    }
+    
+    /// Build the ssdp:byebye messages for every target.
+    pub fn byebye_packets(&self) -> Vec<Packet> {
+        self.targets().into_iter().map(|(nt, usn)| {
+            let mut packet = notify_packet(nt, NTS::ByeBye, usn);
+            
+            packet.set(BootID(self.boot_id));
+            packet.set(ConfigID(self.config_id));
+            
+            packet
+        }).collect()
+    }
+    
+    /// Build the ssdp:update messages for every target, announcing that the
+    /// given boot instance will be used from now on.
+    pub fn update_packets(&self, next_boot_id: u32) -> Vec<Packet> {
+        self.targets().into_iter().map(|(nt, usn)| {
+            let mut packet = notify_packet(nt, NTS::Update, usn);
+            
+            packet.set(Location(self.location.serialize()));
+            self.set_identifiers(&mut packet);
+            packet.set(NextBootID(next_boot_id));
+            
+            packet
+        }).collect()
+    }
+    
+    /// Set the headers that identify the boot instance and configuration.
+    fn set_identifiers(&self, packet: &mut Packet) {
+        packet.set(BootID(self.boot_id));
+        packet.set(ConfigID(self.config_id));
+        if let Some(port) = self.search_port {
+            packet.set(SearchPort(port));
+        }
+        if let Some(ref location) = self.secure_location {
+            packet.set(SecureLocation(location.serialize()));
+        }
+    }
+}
+
+/// Sends out an Advertisement for as long as it is alive.
+///
+/// Byebye messages are sent out when the Advertiser is shut down or dropped.
+pub struct Advertiser {
+    udp:       UdpSocket,
+    dest:      SocketAddr,
+    advert:    Arc<Mutex<Advertisement>>,
+    kill_flag: Arc<AtomicBool>,
+    stopped:   bool
+}
+
+impl Advertiser {
+    /// Send out the initial set of alive messages and spawn a thread that will
+    /// keep re-announcing the advertisement.
+    pub fn start(advert: Advertisement) -> SSDPResult<Advertiser> {
+        Advertiser::start_shared(Arc::new(Mutex::new(advert)))
+    }
+    
+    /// Start advertising an advertisement that is shared with a Responder, so
+    /// that changes made to it are picked up by both.
+    pub fn start_shared(advert: Arc<Mutex<Advertisement>>) -> SSDPResult<Advertiser> {
+        Advertiser::start_shared_with_ttl(advert, DEFAULT_MULTICAST_TTL)
+    }
+    
+    /// Start advertising a shared advertisement with multicast messages that
+    /// live for the given number of hops.
+    pub fn start_shared_with_ttl(advert: Arc<Mutex<Advertisement>>, ttl: u32) -> SSDPResult<Advertiser> {
+        let udp = try!(UdpSocket::bind(("0.0.0.0", 0)).map_err(ssdp::to_ssdp_error));
+        try!(udp.set_multicast_ttl_v4(ttl).map_err(ssdp::to_ssdp_error));
+        let dest = try!(multicast_addr());
+        
+        Advertiser::start_on(advert, udp, dest)
+    }
+    
+    /// Start advertising over the given socket to the given address instead of
+    /// the multicast group, such as a socket bound to the loopback interface.
+    pub fn start_on(advert: Arc<Mutex<Advertisement>>, udp: UdpSocket, dest: SocketAddr)
+        -> SSDPResult<Advertiser> {
+        try!(send_packets(&udp, dest, &advert.lock().unwrap().alive_packets()[..]));
+        
+        let kill_flag = Arc::new(AtomicBool::new(false));
+        
+        let udp_clone = try!(udp.try_clone().map_err(ssdp::to_ssdp_error));
+        let (advert_clone, kill_clone) = (advert.clone(), kill_flag.clone());
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            
+            loop {
+                // Re-Announce At A Random Interval Under Half Of The Max-Age
+                let half_age = cmp::max(advert_clone.lock().unwrap().max_age() as i64 * 500, 2);
+                let delay = Duration::milliseconds(rng.gen_range(half_age / 2, half_age));
+                
+                if !sleep_unless_killed(&kill_clone, delay) {
+                    break;
+                }
+                
+                let packets = advert_clone.lock().unwrap().alive_packets();
+                let _ = send_packets(&udp_clone, dest, &packets[..]);
+            }
+        });
+        
+        Ok(Advertiser{ udp: udp, dest: dest, advert: advert, kill_flag: kill_flag, stopped: false })
+    }
+    
+    /// Send out the set of alive messages right away.
+    pub fn announce(&self) -> SSDPResult<()> {
+        let packets = self.advert.lock().unwrap().alive_packets();
+        
+        send_packets(&self.udp, self.dest, &packets[..])
+    }
+    
+    /// Change the boot instance of the root device.
+    ///
+    /// A set of update messages carrying the new boot instance is sent out,
+    /// followed by a set of alive messages using the new boot instance.
+    pub fn set_boot_id(&self, boot_id: u32) -> SSDPResult<()> {
+        let mut advert = self.advert.lock().unwrap();
+        if advert.boot_id == boot_id {
+            return Ok(())
+        }
+        
+        try!(send_packets(&self.udp, self.dest, &advert.update_packets(boot_id)[..]));
+        advert.boot_id = boot_id;
+        
+        send_packets(&self.udp, self.dest, &advert.alive_packets()[..])
+    }
+    
+    /// Returns a copy of the advertisement currently being sent out.
+    pub fn advertisement(&self) -> Advertisement {
+        self.advert.lock().unwrap().clone()
+    }
+    
+    /// Stop advertising, sending out the set of byebye messages.
+    pub fn shutdown(mut self) -> SSDPResult<()> {
+        self.stop()
+    }
+    
+    fn stop(&mut self) -> SSDPResult<()> {
+        self.stopped = true;
+        self.kill_flag.store(true, Ordering::SeqCst);
+        
+        let packets = self.advert.lock().unwrap().byebye_packets();
+        send_packets(&self.udp, self.dest, &packets[..])
+    }
+}
+
+impl Drop for Advertiser {
+    fn drop(&mut self) {
+        // Nothing We Can Do If This Fails...
+        if !self.stopped {
+            let _ = self.stop();
+        }
+    }
+}
+
+/// Create a notify packet with the headers common to all notify messages.
+fn notify_packet(nt: NT, nts: NTS, usn: USN) -> Packet {
+    let mut packet = Packet::request(message::NOTIFY_HEADER);
+    let hostname = message::MESSAGE_MULTICAST_HOST.to_string();
+    
+    packet.set(Host{ hostname: hostname, port: Some(DEFAULT_SEARCH_PORT) });
+    packet.set(nt);
+    packet.set(nts);
+    packet.set(usn);
+    
+    packet
+}
+
+/// Returns true if our device or service type urn satisfies the one searched for.
+///
+/// Urns look like "schemas-upnp-org:device:Basic:1", where everything but the
+/// version has to be the same and our version has to be at least as high.
+fn urn_satisfies(ours: &[u8], theirs: &[u8]) -> bool {
+    match (split_urn_version(ours), split_urn_version(theirs)) {
+        (Some((our_type, our_version)), Some((their_type, their_version))) => {
+            our_type == their_type && our_version >= their_version
+        },
+        _ => ours == theirs
+    }
+}
+
+/// Splits the version off of the end of a urn.
+fn split_urn_version(urn: &[u8]) -> Option<(&[u8], u32)> {
+    let index = match urn.iter().rposition(|&n| n == b':') {
+        Some(n) => n,
+        None    => return None
+    };
+    
+    str::from_utf8(&urn[index + 1..]).ok().and_then(|n| n.parse().ok()).map(|n| (&urn[..index], n))
+}
+
+/// Returns the address of the SSDP multicast group.
+fn multicast_addr() -> SSDPResult<SocketAddr> {
+    let mut addrs = try!((message::MESSAGE_MULTICAST_HOST, DEFAULT_SEARCH_PORT).to_socket_addrs()
+        .map_err(ssdp::to_ssdp_error));
+    
+    addrs.next().ok_or(SSDPError::Other(Box::new(SimpleError::new("Invalid Multicast Address"))))
+}
+
+/// Send each of the packets to the given address.
+fn send_packets(udp: &UdpSocket, dest: SocketAddr, packets: &[Packet]) -> SSDPResult<()> {
+    for packet in packets.iter() {
+        try!(udp.send_to(&packet.to_bytes()[..], dest).map_err(ssdp::to_ssdp_error));
+    }
+    
+    Ok(())
+}
+
+/// Sleep for the given duration, returning false if the kill flag was set.
+fn sleep_unless_killed(kill_flag: &AtomicBool, duration: Duration) -> bool {
+    let start = PreciseTime::now();
+    
+    while start.to(PreciseTime::now()) < duration {
+        if kill_flag.load(Ordering::SeqCst) {
+            return false
+        }
+        thread::sleep_ms(POLL_INTERVAL_MS);
+    }
+    
+    !kill_flag.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Location};
+    use url::{Url};
+    
+    use ssdp::{FieldPair};
+    use ssdp::header::{BootID, NextBootID, NTS, ST, USN};
+    use super::{AdvertisedDevice, Advertisement};
+    
+    fn advertisement() -> Advertisement {
+        let root = AdvertisedDevice::new("ae239f00-ae2b-bbad-ddf1-88ddcc00a234",
+            "schemas-upnp-org:device:InternetGatewayDevice:1")
+            .with_service("schemas-upnp-org:service:Layer3Forwarding:1");
+        let wan = AdvertisedDevice::new("ae239f00-ae2b-bbad-ddf1-88ddcc00a235",
+            "schemas-upnp-org:device:WANDevice:1")
+            .with_service("schemas-upnp-org:service:WANCommonInterfaceConfig:1")
+            .with_service("schemas-upnp-org:service:WANCommonInterfaceConfig:1");
+        let location = Url::parse("http://192.168.1.1:49152/desc.xml").unwrap();
+        
+        Advertisement::new(root, location, "Linux/3.2 UPnP/1.1 omni/0.0.1", 7, 1).with_embedded(wan)
+    }
+    
+    #[test]
+    fn positive_target_count() {
+        // 3 + 2 * 1 Embedded Device + 2 Distinct Service Types
+        assert_eq!(advertisement().targets().len(), 7);
+    }
+    
+    #[test]
+    fn positive_alive_packets() {
+        let packets = advertisement().alive_packets();
+        let text = String::from_utf8(packets[0].to_bytes()).unwrap();
+        
+        assert!(text.starts_with("NOTIFY * HTTP/1.1\r\n"));
+        assert!(text.contains("USN: uuid:ae239f00-ae2b-bbad-ddf1-88ddcc00a234::upnp:rootdevice\r\n"));
+        assert_eq!(packets[0].headers().get::<NTS>(), Some(&NTS::Alive));
+        assert_eq!(packets[0].headers().get::<BootID>(), Some(&BootID(7)));
+    }
+    
+    #[test]
+    fn positive_match_all() {
+        assert_eq!(advertisement().matching_targets(&ST::All).len(), 7);
+    }
+    
+    #[test]
+    fn positive_match_same_version() {
+        let device = ST::Target(FieldPair::URN(b"schemas-upnp-org:device:WANDevice:1".to_vec()));
+        let service = ST::Target(FieldPair::URN(b"schemas-upnp-org:service:Layer3Forwarding:1".to_vec()));
+        let advert = advertisement();
+        
+        assert_eq!(advert.matching_targets(&device).len(), 1);
+        assert_eq!(advert.matching_targets(&service).len(), 1);
+    }
+    
+    #[test]
+    fn positive_match_older_version() {
+        let root = AdvertisedDevice::new("ae239f00-ae2b-bbad-ddf1-88ddcc00a234",
+            "schemas-upnp-org:device:InternetGatewayDevice:2");
+        let location = Url::parse("http://192.168.1.1:49152/desc.xml").unwrap();
+        let advert = Advertisement::new(root, location, "Linux/3.2 UPnP/1.1 omni/0.0.1", 7, 1);
+        let older = ST::Target(FieldPair::URN(b"schemas-upnp-org:device:InternetGatewayDevice:1".to_vec()));
+        
+        let matches = advert.matching_targets(&older);
+        
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0, older);
+    }
+    
+    #[test]
+    fn positive_match_uuid() {
+        let uuid = FieldPair::UUID(b"ae239f00-ae2b-bbad-ddf1-88ddcc00a235".to_vec());
+        let matches = advertisement().matching_targets(&ST::Target(uuid.clone()));
+        
+        assert_eq!(matches, vec![(ST::Target(uuid.clone()), USN(uuid, None))]);
+    }
+    
+    #[test]
+    fn negative_match_newer_version() {
+        let target = ST::Target(FieldPair::URN(b"schemas-upnp-org:device:WANDevice:2".to_vec()));
+        
+        assert!(advertisement().matching_targets(&target).is_empty());
+    }
+    
+    #[test]
+    fn positive_search_responses() {
+        let target = ST::Target(FieldPair::UPnP(b"rootdevice".to_vec()));
+        let packets = advertisement().search_responses(&target);
+        let text = String::from_utf8(packets[0].to_bytes()).unwrap();
+        
+        assert_eq!(packets.len(), 1);
+        assert!(text.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert_eq!(packets[0].headers().get::<ST>(), Some(&target));
+    }
+    
+    #[test]
+    fn positive_byebye_packets() {
+        let packets = advertisement().byebye_packets();
+        
+        assert_eq!(packets.len(), 7);
+        assert_eq!(packets[0].headers().get::<NTS>(), Some(&NTS::ByeBye));
+        assert!(packets[0].headers().get::<Location>().is_none());
+    }
+    
+    #[test]
+    fn positive_update_packets() {
+        let packets = advertisement().update_packets(8);
+        
+        assert_eq!(packets[0].headers().get::<NTS>(), Some(&NTS::Update));
+        assert_eq!(packets[0].headers().get::<BootID>(), Some(&BootID(7)));
+        assert_eq!(packets[0].headers().get::<NextBootID>(), Some(&NextBootID(8)));
+    }
+}
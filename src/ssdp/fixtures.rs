@@ -0,0 +1,252 @@
+//! Synthetic SSDP packets, along with the result that parsing each of them
+//! should have.
+//!
+//! The packets are hand-written rather than captured off of a network, so each
+//! one is named after the quirk it exercises instead of a device or vendor.
+//!
+//! Packets that follow the specification have to be accepted in both modes
+//! without any warnings, whereas packets with a known quirk are only accepted
+//! when parsing leniently and have to carry a warning for it.
+//! New quirks should be added here before teaching the lenient parser about them.
+
+use std::net::{SocketAddr};
+
+use ssdp::{Policy, Strictness};
+use ssdp::message::notify::{AliveMessage};
+use ssdp::message::search::{SearchResponse};
+use ssdp::packet::{Packet};
+
+/// Expected outcome of parsing one of the fixtures.
+struct Entry {
+    name:     &'static str,
+    packet:   &'static [u8],
+    strict:   bool,
+    lenient:  bool,
+    max_age:  i64,
+    location: &'static str
+}
+
+const CONFORMANT_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age=120\r\n\
+    LOCATION: http://192.168.1.1:5000/rootDesc.xml\r\n\
+    SERVER: Linux/4.14 UPnP/1.1 example/2.1\r\n\
+    NT: upnp:rootdevice\r\n\
+    USN: uuid:5f2a1c3e-9b7d-4e61-a0c2-3d8e4f5a6b7c::upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    OPT: \"http://schemas.upnp.org/upnp/1/0/\"; ns=01\r\n\
+    01-NLS: 1\r\n\
+    BOOTID.UPNP.ORG: 1\r\n\
+    CONFIGID.UPNP.ORG: 1337\r\n\r\n";
+
+const MIXED_CASE_HEADERS_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    Host: 239.255.255.250:1900\r\n\
+    NT: uuid:8a2b5c4d-1e3f-4a6b-9c8d-7e6f5a4b3c2d\r\n\
+    NTS: ssdp:alive\r\n\
+    Location: http://192.168.1.20:2869/description?content=\
+    uuid:8a2b5c4d-1e3f-4a6b-9c8d-7e6f5a4b3c2d\r\n\
+    USN: uuid:8a2b5c4d-1e3f-4a6b-9c8d-7e6f5a4b3c2d\r\n\
+    Cache-Control: max-age=900\r\n\
+    Server: Example-OS/10.0 UPnP/1.0 example/1.0\r\n\
+    OPT: \"http://schemas.upnp.org/upnp/1/0/\"; ns=01\r\n\
+    01-NLS: 3c4f9e7a1b2d3c4e5f6a7b8c9d0e1f2a\r\n\r\n";
+
+const LOWERCASE_HEADERS_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    host: 239.255.255.250:1900\r\n\
+    cache-control: max-age=100\r\n\
+    location: http://192.168.1.40/description.xml\r\n\
+    server: Linux/3.14.0 UPnP/1.0 example/1.26.0\r\n\
+    nt: upnp:rootdevice\r\n\
+    nts: ssdp:alive\r\n\
+    usn: uuid:2f402f80-da50-11e1-9b23-001788255acc::upnp:rootdevice\r\n\
+    x-example-id: 001788FFFE255ACC\r\n\r\n";
+
+const SPACED_MAX_AGE_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age = 1800\r\n\
+    LOCATION: http://192.168.0.1:5431/dyndev/uuid:0013-a3f2-09c80000d0a8\r\n\
+    NT: upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    SERVER: LINUX/2.4 UPnP/1.0 example/1.0\r\n\
+    USN: uuid:0013-a3f2-09c80000d0a8::upnp:rootdevice\r\n\r\n";
+
+const SCHEMELESS_LOCATION_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age=1800\r\n\
+    LOCATION: 192.168.0.254:1780/device.xml\r\n\
+    NT: upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    SERVER: POSIX, UPnP/1.0, example/1.0.1347\r\n\
+    USN: uuid:a4b7e5f0-8c2d-11e3-baa8-0800200c9a66::upnp:rootdevice\r\n\r\n";
+
+const MISSING_SERVER_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age=86400\r\n\
+    LOCATION: http://192.168.1.77:49153/setup.xml\r\n\
+    NT: upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    USN: uuid:Example-1_0-221517K0101769::upnp:rootdevice\r\n\r\n";
+
+const SPACED_VERSION_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age=1800\r\n\
+    LOCATION: http://192.168.1.30:50001/desc/device.xml\r\n\
+    NT: upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    SERVER: Linux/2.6 UPnP 1.0 DLNADOC/1.50\r\n\
+    USN: uuid:4d696e69-444c-164e-9d41-001132a8f0c3::upnp:rootdevice\r\n\r\n";
+
+const WIDE_BOOT_ID_ALIVE: &'static [u8] = b"NOTIFY * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    CACHE-CONTROL: max-age=1800\r\n\
+    LOCATION: http://192.168.2.1:1990/device.xml\r\n\
+    NT: upnp:rootdevice\r\n\
+    NTS: ssdp:alive\r\n\
+    SERVER: Linux/4.1 UPnP/1.1 example/3.0\r\n\
+    USN: uuid:e1f2a3b4-c5d6-4e7f-8a9b-0c1d2e3f4a5b::upnp:rootdevice\r\n\
+    BOOTID.UPNP.ORG: 3000000000\r\n\
+    CONFIGID.UPNP.ORG: 1\r\n\r\n";
+
+const NON_URN_TARGET_RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+    Cache-Control: max-age=3600\r\n\
+    ST: example:ecp\r\n\
+    USN: uuid:example:ecp:P0A070000007\r\n\
+    Ext: \r\n\
+    Server: Example/9.4.0 UPnP/1.0 example/9.4.0\r\n\
+    LOCATION: http://192.168.1.134:8060/\r\n\
+    device-group.example.com: 46F5CCE2472F2B4F6A53\r\n\r\n";
+
+const SPACED_MAX_AGE_RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+    CACHE-CONTROL: max-age = 1800\r\n\
+    EXT:\r\n\
+    LOCATION: http://192.168.1.60:1400/device.xml\r\n\
+    SERVER: Linux UPnP/1.0 example/57.3-77280 (model)\r\n\
+    ST: urn:schemas-example-com:device:Player:1\r\n\
+    USN: uuid:EXAMPLE_000E58A0B1C201400::urn:schemas-example-com:device:Player:1\r\n\
+    X-EXAMPLE-HOUSEHOLD: household_asahHKgjgJGjgjGjggjJgjJG34\r\n\
+    X-EXAMPLE-BOOTSEQ: 42\r\n\r\n";
+
+const EXTENSION_HEADERS_RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+    CACHE-CONTROL: max-age=1800\r\n\
+    DATE: Thu, 01 Jan 1970 00:00:00 GMT\r\n\
+    EXT:\r\n\
+    LOCATION: http://192.168.1.90:8008/device.xml\r\n\
+    OPT: \"http://schemas.upnp.org/upnp/1/0/\"; ns=01\r\n\
+    01-NLS: 161d2e68-1dd2-11b2-9ef6-b3b9b1c4f9f2\r\n\
+    SERVER: Linux/3.8.13+, UPnP/1.0, example SDK/1.6.18\r\n\
+    X-User-Agent: example\r\n\
+    ST: urn:dial-multiscreen-org:service:dial:1\r\n\
+    USN: uuid:3e1cc7c8-f9ab-4a4f-b0c0-a3a1d8c7e4b2::urn:dial-multiscreen-org:service:dial:1\r\n\r\n";
+
+const MISSING_SERVER_RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+    cache-control: max-age=60\r\n\
+    ext:\r\n\
+    location: 192.168.1.108:49152/rootDesc.xml\r\n\
+    st: upnp:rootdevice\r\n\
+    usn: uuid:c2d4e6f8-0a1b-2c3d-4e5f-6a7b8c9d0e1f::upnp:rootdevice\r\n\r\n";
+
+const MISSING_USN_RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+    CACHE-CONTROL: max-age=1800\r\n\
+    EXT:\r\n\
+    LOCATION: http://192.168.1.50:80/DevDesc.xml\r\n\
+    SERVER: Example/1.0 UPnP/1.0 example/1.0\r\n\
+    ST: urn:schemas-upnp-org:device:Printer:1\r\n\r\n";
+
+const ALIVE_FIXTURES: &'static [Entry] = &[
+    Entry{ name: "conformant", packet: CONFORMANT_ALIVE, strict: true, lenient: true, max_age: 120,
+        location: "http://192.168.1.1:5000/rootDesc.xml" },
+    Entry{ name: "mixed case headers", packet: MIXED_CASE_HEADERS_ALIVE, strict: true, lenient: true,
+        max_age: 900, location: "http://192.168.1.20:2869/description?content=\
+                   uuid:8a2b5c4d-1e3f-4a6b-9c8d-7e6f5a4b3c2d" },
+    Entry{ name: "lowercase headers", packet: LOWERCASE_HEADERS_ALIVE, strict: true, lenient: true,
+        max_age: 100, location: "http://192.168.1.40/description.xml" },
+    Entry{ name: "spaced max-age", packet: SPACED_MAX_AGE_ALIVE, strict: false, lenient: true,
+        max_age: 1800, location: "http://192.168.0.1:5431/dyndev/uuid:0013-a3f2-09c80000d0a8" },
+    Entry{ name: "schemeless location", packet: SCHEMELESS_LOCATION_ALIVE, strict: false,
+        lenient: true, max_age: 1800, location: "http://192.168.0.254:1780/device.xml" },
+    Entry{ name: "missing server", packet: MISSING_SERVER_ALIVE, strict: false, lenient: true,
+        max_age: 86400, location: "http://192.168.1.77:49153/setup.xml" },
+    Entry{ name: "spaced version", packet: SPACED_VERSION_ALIVE, strict: false, lenient: true,
+        max_age: 1800, location: "http://192.168.1.30:50001/desc/device.xml" },
+    Entry{ name: "wide boot id", packet: WIDE_BOOT_ID_ALIVE, strict: false, lenient: true,
+        max_age: 1800, location: "http://192.168.2.1:1990/device.xml" }
+];
+
+const RESPONSE_FIXTURES: &'static [Entry] = &[
+    Entry{ name: "non urn target", packet: NON_URN_TARGET_RESPONSE, strict: true, lenient: true,
+        max_age: 3600, location: "http://192.168.1.134:8060/" },
+    Entry{ name: "spaced max-age", packet: SPACED_MAX_AGE_RESPONSE, strict: false, lenient: true,
+        max_age: 1800, location: "http://192.168.1.60:1400/device.xml" },
+    Entry{ name: "extension headers", packet: EXTENSION_HEADERS_RESPONSE, strict: true, lenient: true,
+        max_age: 1800, location: "http://192.168.1.90:8008/device.xml" },
+    Entry{ name: "missing server", packet: MISSING_SERVER_RESPONSE, strict: false,
+        lenient: true, max_age: 60, location: "http://192.168.1.108:49152/rootDesc.xml" },
+    Entry{ name: "missing usn", packet: MISSING_USN_RESPONSE, strict: false, lenient: false,
+        max_age: 0, location: "" }
+];
+
+fn remote() -> SocketAddr {
+    "192.168.1.2:1900".parse().unwrap()
+}
+
+/// Check that each alive message parses as expected under the given mode.
+fn check_alive(strictness: Strictness) {
+    for entry in ALIVE_FIXTURES.iter() {
+        let lenient = strictness == Strictness::Lenient;
+        let expected = if lenient { entry.lenient } else { entry.strict };
+        let result = Packet::parse(entry.packet).and_then(|n|
+            AliveMessage::parse(n, Policy::new(strictness))
+        );
+        
+        match result {
+            Ok(message) => {
+                assert!(expected, "{} Was Accepted In {:?} Mode", entry.name, strictness);
+                assert_eq!(message.max_age().num_seconds(), entry.max_age);
+                assert_eq!(message.location().serialize(), entry.location);
+                assert_eq!(message.warnings().is_empty(), entry.strict || !lenient);
+            },
+            Err(e) => assert!(!expected, "{} Was Rejected In {:?} Mode: {}", entry.name, strictness, e)
+        }
+    }
+}
+
+/// Check that each search response parses as expected under the given mode.
+fn check_responses(strictness: Strictness) {
+    for entry in RESPONSE_FIXTURES.iter() {
+        let lenient = strictness == Strictness::Lenient;
+        let expected = if lenient { entry.lenient } else { entry.strict };
+        let result = Packet::parse(entry.packet).and_then(|n|
+            SearchResponse::parse(n, remote(), Policy::new(strictness))
+        );
+        
+        match result {
+            Ok(response) => {
+                assert!(expected, "{} Was Accepted In {:?} Mode", entry.name, strictness);
+                assert_eq!(response.max_age().num_seconds(), entry.max_age);
+                assert_eq!(response.location().serialize(), entry.location);
+                assert_eq!(response.warnings().is_empty(), entry.strict || !lenient);
+            },
+            Err(e) => assert!(!expected, "{} Was Rejected In {:?} Mode: {}", entry.name, strictness, e)
+        }
+    }
+}
+
+#[test]
+fn positive_strict_alive_fixtures() {
+    check_alive(Strictness::Strict);
+}
+
+#[test]
+fn positive_lenient_alive_fixtures() {
+    check_alive(Strictness::Lenient);
+}
+
+#[test]
+fn positive_strict_response_fixtures() {
+    check_responses(Strictness::Strict);
+}
+
+#[test]
+fn positive_lenient_response_fixtures() {
+    check_responses(Strictness::Lenient);
+}
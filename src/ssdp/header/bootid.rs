@@ -13,6 +13,20 @@ unsafe impl Sync for BootID { }
 
 unsafe impl Send for BootID { }
 
+impl BootID {
+    /// Parse the header as any non-negative integer that fits in 32 bits, as
+    /// sent by devices that do not keep to the 31 bit limit.
+    pub fn parse_wide(raw: &[Vec<u8>]) -> Option<BootID> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        let cow_str = String::from_utf8_lossy(&raw[0][..]);
+        
+        u32::from_str_radix(&*cow_str, 10).ok().map(BootID)
+    }
+}
+
 impl Header for BootID {
     fn header_name() -> &'static str {
         BOOTID_HEADER_NAME
@@ -97,6 +111,21 @@ mod tests {
         BootID::parse_header(bootid_header_value).unwrap();
     }
     
+    #[test]
+    fn positive_wide_overflow() {
+        let bootid_header_value = &[b"2290649224"[..].to_vec()];
+        
+        assert_eq!(BootID::parse_wide(bootid_header_value), Some(BootID(2290649224)));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_wide_overflow() {
+        let bootid_header_value = &[b"4294967296"[..].to_vec()];
+        
+        BootID::parse_wide(bootid_header_value).unwrap();
+    }
+    
     #[test]
     #[should_panic]
     fn negative_negative_overflow() {
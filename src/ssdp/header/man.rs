@@ -1,3 +1,4 @@
+use std::ascii::{AsciiExt};
 use std::fmt::{Formatter, Result};
 
 use hyper::header::{HeaderFormat, Header};
@@ -14,6 +15,22 @@ unsafe impl Sync for Man { }
 
 unsafe impl Send for Man { }
 
+impl Man {
+    /// Parse the header without regard to the case of "ssdp:discover", which
+    /// some control points send as "SSDP:discover".
+    pub fn parse_ignore_case(raw: &[Vec<u8>]) -> Option<Man> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        if raw[0].eq_ignore_ascii_case(MAN_HEADER_VALUE.as_bytes()) {
+            Some(Man)
+        } else {
+            None
+        }
+    }
+}
+
 impl Header for Man {
     fn header_name() -> &'static str {
         MAN_HEADER_NAME
@@ -61,6 +78,21 @@ mod tests {
         Man::parse_header(wrong_case_man_header).unwrap();
     }
     
+    #[test]
+    fn positive_ignore_case() {
+        let wrong_case_man_header = &[b"\"SSDP:discover\""[..].to_vec()];
+        
+        Man::parse_ignore_case(wrong_case_man_header).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_ignore_case_missing_quotes() {
+        let missing_quotes_man_header = &[b"SSDP:discover"[..].to_vec()];
+        
+        Man::parse_ignore_case(missing_quotes_man_header).unwrap();
+    }
+    
     #[test]
     #[should_panic]
     fn negative_missing_quotes() {
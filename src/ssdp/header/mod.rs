@@ -12,8 +12,10 @@ mod bootid;
 mod configid;
 mod man;
 mod mx;
+mod nextbootid;
 mod nt;
 mod nts;
+mod product;
 mod searchport;
 mod securelocation;
 mod st;
@@ -23,9 +25,11 @@ pub use self::bootid::BootID;
 pub use self::configid::ConfigID;
 pub use self::man::Man;
 pub use self::mx::MX;
+pub use self::nextbootid::NextBootID;
 pub use self::nt::NT;
 pub use self::nts::NTS;
-pub use self::searchport::SearchPort;
+pub use self::product::{ProductTokens, ServerTokens, UserAgentTokens};
+pub use self::searchport::{SearchPort, SEARCHPORT_MIN_VALUE, SEARCHPORT_MAX_VALUE};
 pub use self::securelocation::SecureLocation;
 pub use self::st::ST;
 pub use self::usn::USN;
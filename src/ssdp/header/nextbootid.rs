@@ -0,0 +1,71 @@
+use std::fmt::{Formatter, Result};
+
+use hyper::header::{HeaderFormat, Header};
+
+const NEXTBOOTID_HEADER_NAME: &'static str = "NEXTBOOTID.UPNP.ORG";
+
+/// Represents a UPnP NextBootID header which is used by a root device to
+/// announce the boot instance that it will be using from now on.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NextBootID(pub u32);
+
+unsafe impl Sync for NextBootID { }
+
+unsafe impl Send for NextBootID { }
+
+impl Header for NextBootID {
+    fn header_name() -> &'static str {
+        NEXTBOOTID_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        if raw.len() != 1 {
+            return None
+        }
+        
+        let cow_str = String::from_utf8_lossy(&raw[0][..]);
+        
+        // Value needs to be a 31 bit non-negative integer, so convert to i32
+        let value = match i32::from_str_radix(&*cow_str, 10) {
+            Ok(n) => n,
+            Err(_) => return None
+        };
+        
+        // Check if value is negative, then convert to u32
+        if value.is_negative() {
+            None
+        } else {
+            Some(NextBootID(value as u32))
+        }
+    }
+}
+
+impl HeaderFormat for NextBootID {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        try!(fmt.write_fmt(format_args!("{}", self.0)));
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use super::{NextBootID};
+    
+    #[test]
+    fn positive_nextbootid() {
+        let nextbootid_header_value = &[b"1216907401"[..].to_vec()];
+        
+        NextBootID::parse_header(nextbootid_header_value).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_overflow() {
+        let nextbootid_header_value = &[b"2290649224"[..].to_vec()];
+        
+        NextBootID::parse_header(nextbootid_header_value).unwrap();
+    }
+}
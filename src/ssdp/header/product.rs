@@ -0,0 +1,341 @@
+use std::ascii::{AsciiExt};
+use std::fmt::{Formatter, Display, Result};
+
+use hyper::header::{HeaderFormat, Header};
+
+use ssdp::version::{UPnPVersion};
+
+const SERVER_HEADER_NAME:     &'static str = "SERVER";
+const USER_AGENT_HEADER_NAME: &'static str = "USER-AGENT";
+
+const UPNP_PRODUCT_NAME: &'static str = "UPnP";
+const VERSION_SEPARATOR: char = '/';
+const TOKEN_SEPARATOR:   &'static str = " ";
+
+/// Product tokens of the form "OS/version UPnP/major.minor product/version"
+/// which describe the environment that a device or control point runs in.
+///
+/// Tokens may also be separated by commas, as allowed by UPnP 1.0. Extra
+/// tokens, such as "DLNADOC/1.50", and comments in parentheses are skipped
+/// over when parsing.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ProductTokens {
+    os:              String,
+    os_version:      String,
+    upnp:            UPnPVersion,
+    product:         String,
+    product_version: String
+}
+
+impl ProductTokens {
+    /// Create a new ProductTokens object.
+    pub fn new(os: &str, os_version: &str, upnp: UPnPVersion, product: &str, product_version: &str)
+        -> ProductTokens {
+        ProductTokens{ os: os.to_string(), os_version: os_version.to_string(), upnp: upnp,
+            product: product.to_string(), product_version: product_version.to_string() }
+    }
+    
+    /// Parse the tokens out of a header value.
+    ///
+    /// The OS is taken from the first token before the UPnP token, and the
+    /// product from the last versioned token after it. Returns None if there
+    /// is no "UPnP/major.minor" token.
+    pub fn parse(value: &str) -> Option<ProductTokens> {
+        let tokens = split_tokens(value);
+        
+        let (index, upnp) = match tokens.iter().enumerate()
+            .filter_map(|(i, n)| upnp_version(n).map(|version| (i, version))).next() {
+            Some(n) => n,
+            None    => return None
+        };
+        
+        let (os, os_version) = tokens[..index].first().map_or((String::new(), String::new()),
+            |n| split_token(n));
+        let products = &tokens[index + 1..];
+        let product = products.iter().rev().find(|n| n.contains(VERSION_SEPARATOR)).or(products.last());
+        let (product, product_version) = product.map_or((String::new(), String::new()), |n| split_token(n));
+        
+        Some(ProductTokens{ os: os, os_version: os_version, upnp: upnp, product: product,
+            product_version: product_version })
+    }
+    
+    /// Returns the name of the operating system.
+    pub fn os(&self) -> &str {
+        &self.os[..]
+    }
+    
+    /// Returns the version of the operating system, which may be empty.
+    pub fn os_version(&self) -> &str {
+        &self.os_version[..]
+    }
+    
+    /// Returns the UPnP version.
+    pub fn upnp_version(&self) -> UPnPVersion {
+        self.upnp
+    }
+    
+    /// Returns the name of the product.
+    pub fn product(&self) -> &str {
+        &self.product[..]
+    }
+    
+    /// Returns the version of the product, which may be empty.
+    pub fn product_version(&self) -> &str {
+        &self.product_version[..]
+    }
+}
+
+impl Display for ProductTokens {
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        try!(fmt_token(&self.os[..], &self.os_version[..], fmt));
+        try!(fmt.write_fmt(format_args!(" {}{}{} ", UPNP_PRODUCT_NAME, VERSION_SEPARATOR, self.upnp)));
+        try!(fmt_token(&self.product[..], &self.product_version[..], fmt));
+        
+        Ok(())
+    }
+}
+
+/// Represents a SERVER header which describes the environment of a device.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ServerTokens(pub ProductTokens);
+
+unsafe impl Sync for ServerTokens { }
+
+unsafe impl Send for ServerTokens { }
+
+impl Header for ServerTokens {
+    fn header_name() -> &'static str {
+        SERVER_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        parse_raw(raw).map(ServerTokens)
+    }
+}
+
+impl HeaderFormat for ServerTokens {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        Display::fmt(&self.0, fmt)
+    }
+}
+
+/// Represents a UPnP 1.1 USER-AGENT header which describes the environment of
+/// a control point sending a search request.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct UserAgentTokens(pub ProductTokens);
+
+unsafe impl Sync for UserAgentTokens { }
+
+unsafe impl Send for UserAgentTokens { }
+
+impl Header for UserAgentTokens {
+    fn header_name() -> &'static str {
+        USER_AGENT_HEADER_NAME
+    }
+    
+    fn parse_header(raw: &[Vec<u8>]) -> Option<Self> {
+        parse_raw(raw).map(UserAgentTokens)
+    }
+}
+
+impl HeaderFormat for UserAgentTokens {
+    fn fmt_header(&self, fmt: &mut Formatter) -> Result {
+        Display::fmt(&self.0, fmt)
+    }
+}
+
+/// Parse the product tokens out of a single raw header field.
+fn parse_raw(raw: &[Vec<u8>]) -> Option<ProductTokens> {
+    if raw.len() != 1 {
+        return None
+    }
+    
+    ProductTokens::parse(&String::from_utf8_lossy(&raw[0][..]))
+}
+
+/// Split a header value into its "name/version" tokens.
+///
+/// Names may contain spaces, as in "Portable SDK for UPnP devices/1.6.18", so
+/// words are grouped up until one of them carries a version. A name without
+/// a version is only split off when it is followed by the UPnP token. Words
+/// within a "( ... )" comment are dropped.
+fn split_tokens(value: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+    let mut depth = 0usize;
+    
+    for word in value.split(|c: char| c.is_whitespace() || c == ',').filter(|n| !n.is_empty()) {
+        // Skip Over Comments, Which May Span Several Words
+        if depth > 0 || word.starts_with('(') {
+            depth = (depth + word.matches('(').count()).saturating_sub(word.matches(')').count());
+            
+            continue
+        }
+        
+        if upnp_version(word).is_some() {
+            if !pending.is_empty() {
+                tokens.push(pending.connect(TOKEN_SEPARATOR));
+                pending.clear();
+            }
+            tokens.push(word.to_string());
+            
+            continue
+        }
+        
+        pending.push(word);
+        if word.contains(VERSION_SEPARATOR) {
+            tokens.push(pending.connect(TOKEN_SEPARATOR));
+            pending.clear();
+        }
+    }
+    if !pending.is_empty() {
+        tokens.push(pending.connect(TOKEN_SEPARATOR));
+    }
+    
+    tokens
+}
+
+/// Returns the version if the word is a "UPnP/major.minor" token, the name of
+/// which is matched without regard to case.
+fn upnp_version(word: &str) -> Option<UPnPVersion> {
+    let (name, version) = split_token(word);
+    if !name.eq_ignore_ascii_case(UPNP_PRODUCT_NAME) {
+        return None
+    }
+    
+    UPnPVersion::parse(&version[..])
+}
+
+/// Split a token into its name and version, the latter of which may be empty.
+fn split_token(token: &str) -> (String, String) {
+    match token.find(VERSION_SEPARATOR) {
+        Some(n) => (token[..n].to_string(), token[n + 1..].to_string()),
+        None    => (token.to_string(), String::new())
+    }
+}
+
+/// Write a token, leaving off the separator if there is no version.
+fn fmt_token(name: &str, version: &str, fmt: &mut Formatter) -> Result {
+    if version.is_empty() {
+        fmt.write_str(name)
+    } else {
+        fmt.write_fmt(format_args!("{}{}{}", name, VERSION_SEPARATOR, version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{Header};
+    
+    use ssdp::version::{UPNP_11_VERSION, UPNP_20_VERSION};
+    use super::{ProductTokens, ServerTokens, UserAgentTokens};
+    
+    #[test]
+    fn positive_server() {
+        let server_header = &[b"OpenWRT/OpenWrt UPnP/1.1 MiniUPnPd/2.1"[..].to_vec()];
+        let tokens = ServerTokens::parse_header(server_header).unwrap().0;
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("OpenWRT", "OpenWrt"));
+        assert_eq!(tokens.upnp_version(), UPNP_11_VERSION);
+        assert_eq!((tokens.product(), tokens.product_version()), ("MiniUPnPd", "2.1"));
+    }
+    
+    #[test]
+    fn positive_user_agent() {
+        let user_agent_header = &[b"Windows/10.0 UPnP/2.0 omni/0.0.1"[..].to_vec()];
+        let tokens = UserAgentTokens::parse_header(user_agent_header).unwrap().0;
+        
+        assert_eq!(tokens.upnp_version(), UPNP_20_VERSION);
+        assert_eq!(tokens.product(), "omni");
+    }
+    
+    #[test]
+    fn positive_comma_separated() {
+        let server = "Linux/3.8.13+, UPnP/1.0, Portable SDK for UPnP devices/1.6.18";
+        let tokens = ProductTokens::parse(server).unwrap();
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("Linux", "3.8.13+"));
+        assert_eq!((tokens.product(), tokens.product_version()), ("Portable SDK for UPnP devices", "1.6.18"));
+    }
+    
+    #[test]
+    fn positive_missing_os_version() {
+        let tokens = ProductTokens::parse("POSIX UPnP/1.0 Intel MicroStack/1.0.1347").unwrap();
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("POSIX", ""));
+    }
+    
+    #[test]
+    fn positive_upnp_product_name() {
+        let tokens = ProductTokens::parse("Microsoft-Windows/10.0 UPnP/1.0 UPnP-Device-Host/1.0").unwrap();
+        
+        assert_eq!((tokens.product(), tokens.product_version()), ("UPnP-Device-Host", "1.0"));
+    }
+    
+    #[test]
+    fn positive_multiple_products() {
+        let tokens = ProductTokens::parse("Linux/3.4 DLNADOC/1.50 UPnP/1.0 Platinum/1.0.5").unwrap();
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("Linux", "3.4"));
+        assert_eq!((tokens.product(), tokens.product_version()), ("Platinum", "1.0.5"));
+    }
+    
+    #[test]
+    fn positive_leading_upnp() {
+        let tokens = ProductTokens::parse("UPnP/1.0 DLNADOC/1.50 Platinum/1.0.5.13").unwrap();
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("", ""));
+        assert_eq!((tokens.product(), tokens.product_version()), ("Platinum", "1.0.5.13"));
+    }
+    
+    #[test]
+    fn positive_upnp_case_insensitive() {
+        let tokens = ProductTokens::parse("Linux/2.6 upnp/1.1 MiniUPnPd/1.8").unwrap();
+        
+        assert_eq!(tokens.upnp_version(), UPNP_11_VERSION);
+        assert_eq!((tokens.os(), tokens.product()), ("Linux", "MiniUPnPd"));
+    }
+    
+    #[test]
+    fn positive_trailing_comment() {
+        let tokens = ProductTokens::parse("Linux UPnP/1.0 Sonos/57.3-77280 (ZPS1)").unwrap();
+        
+        assert_eq!((tokens.os(), tokens.os_version()), ("Linux", ""));
+        assert_eq!((tokens.product(), tokens.product_version()), ("Sonos", "57.3-77280"));
+    }
+    
+    #[test]
+    fn positive_multiple_word_comment() {
+        let tokens = ProductTokens::parse("Linux/4.9 UPnP/1.0 (build 12; release) player/2.0").unwrap();
+        
+        assert_eq!((tokens.product(), tokens.product_version()), ("player", "2.0"));
+    }
+    
+    #[test]
+    fn positive_round_trip() {
+        let tokens = ProductTokens::new("Linux", "3.2", UPNP_11_VERSION, "omni", "0.0.1");
+        
+        assert_eq!(tokens.to_string(), "Linux/3.2 UPnP/1.1 omni/0.0.1");
+        assert_eq!(ProductTokens::parse(&tokens.to_string()[..]), Some(tokens));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_spaced_upnp_version() {
+        ProductTokens::parse("Linux/2.6 UPnP 1.0 DLNADOC/1.50").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_minor_version() {
+        ProductTokens::parse("Linux/2.6 UPnP/1 DLNADOC/1.50").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_upnp() {
+        let server_header = &[b"Linux/2.6 DLNADOC/1.50"[..].to_vec()];
+        
+        ServerTokens::parse_header(server_header).unwrap();
+    }
+}
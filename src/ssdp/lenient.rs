@@ -0,0 +1,173 @@
+//! Puts up with quirks in packets received from devices that do not quite
+//! follow the specification, as far as the Policy allows.
+
+use std::ascii::{AsciiExt};
+
+use hyper::header::{CacheControl, Header, Location};
+
+use ssdp::{Policy, Strictness, Warning};
+use ssdp::header::{BootID, HeaderView, Man};
+use ssdp::packet::{Packet};
+use ssdp::version::{UPnPVersion};
+
+const DIRECTIVE_SEPARATOR: char = ',';
+const VALUE_SEPARATOR:     char = '=';
+const SCHEME_SEPARATOR:    &'static str = "://";
+const DEFAULT_SCHEME:      &'static str = "http://";
+const UPNP_TOKEN:          &'static str = "upnp";
+
+/// Rewrite the headers of the packet so that known quirks parse as if the
+/// device had followed the specification.
+pub fn repair(packet: &mut Packet, policy: &Policy, warnings: &mut Vec<Warning>) {
+    if policy.quirks == Strictness::Strict {
+        return
+    }
+    
+    let cache_control = first_raw(packet, CacheControl::header_name());
+    if let Some(n) = cache_control.and_then(|n| repair_cache_control(&n[..])) {
+        packet.set_raw(CacheControl::header_name(), &n[..]);
+        warnings.push(Warning::new(CacheControl::header_name(), "Whitespace Around Directive Value"));
+    }
+    
+    let location = first_raw(packet, Location::header_name());
+    if let Some(n) = location.and_then(|n| repair_location(&n[..])) {
+        packet.set_raw(Location::header_name(), &n[..]);
+        warnings.push(Warning::new(Location::header_name(), "Missing Scheme, Assuming http"));
+    }
+}
+
+/// Returns the MAN header, ignoring the case of its value if the policy allows.
+pub fn man<T>(headers: &T, policy: &Policy, warnings: &mut Vec<Warning>) -> Option<Man>
+    where T: HeaderView {
+    if let Some(n) = headers.view::<Man>() {
+        return Some(*n)
+    } else if policy.man_case == Strictness::Strict {
+        return None
+    }
+    
+    headers.view_raw(Man::header_name()).and_then(Man::parse_ignore_case).map(|n| {
+        warnings.push(Warning::new(Man::header_name(), "Value Is Not Lowercase"));
+        
+        n
+    })
+}
+
+/// Returns the BOOTID.UPNP.ORG header, allowing values that do not fit in 31
+/// bits if the policy allows.
+pub fn boot_id<T>(headers: &T, policy: &Policy, warnings: &mut Vec<Warning>) -> Option<BootID>
+    where T: HeaderView {
+    if let Some(n) = headers.view::<BootID>() {
+        return Some(*n)
+    } else if policy.boot_id_range == Strictness::Strict {
+        return None
+    }
+    
+    headers.view_raw(BootID::header_name()).and_then(BootID::parse_wide).map(|n| {
+        warnings.push(Warning::new(BootID::header_name(), "Value Does Not Fit In 31 Bits"));
+        
+        n
+    })
+}
+
+/// Returns the UPnP version found in a SERVER header that does not contain an
+/// exact version, as in "UPnP 1.0" or "upnp/1".
+pub fn upnp_version(server: &str) -> Option<UPnPVersion> {
+    server.to_ascii_lowercase().split(UPNP_TOKEN).skip(1).filter_map(version_after_token).next()
+}
+
+/// Returns the first value of the header as a string, if it is present.
+fn first_raw(packet: &Packet, name: &str) -> Option<String> {
+    packet.headers().get_raw(name).and_then(|n| n.first()).map(|n| String::from_utf8_lossy(n).into_owned())
+}
+
+/// Strip whitespace around the "=" of each directive, as in "max-age = 1800".
+///
+/// Returns None if no directive needed repairing.
+fn repair_cache_control(value: &str) -> Option<String> {
+    let mut repaired = false;
+    let directives = value.split(DIRECTIVE_SEPARATOR).map(|directive| {
+        let directive = directive.trim();
+        
+        match directive.find(VALUE_SEPARATOR) {
+            Some(n) => {
+                let (key, value) = (directive[..n].trim(), directive[n + 1..].trim());
+                repaired = repaired || key.len() + value.len() + 1 != directive.len();
+                
+                format!("{}{}{}", key, VALUE_SEPARATOR, value)
+            },
+            None => directive.to_string()
+        }
+    }).collect::<Vec<String>>();
+    
+    if repaired { Some(directives.connect(", ")) } else { None }
+}
+
+/// Prefix a location that is missing its scheme with http.
+///
+/// Returns None if the location already has a scheme.
+fn repair_location(value: &str) -> Option<String> {
+    let value = value.trim();
+    
+    if value.contains(SCHEME_SEPARATOR) {
+        None
+    } else {
+        Some(format!("{}{}", DEFAULT_SCHEME, value))
+    }
+}
+
+/// Parse the version following a "upnp" token, which has to be separated from
+/// the token by a slash or whitespace.
+fn version_after_token(rest: &str) -> Option<UPnPVersion> {
+    let version = rest.trim_left_matches(|c: char| c == '/' || c == ' ');
+    if version.len() == rest.len() {
+        return None
+    }
+    
+    let end = version.find(|c: char| !c.is_digit(10) && c != '.').unwrap_or(version.len());
+    let mut parts = version[..end].split('.');
+    
+    let major = match parts.next().and_then(|n| n.parse().ok()) {
+        Some(n) => n,
+        None    => return None
+    };
+    let minor = match parts.next() {
+        Some(n) => match n.parse().ok() { Some(n) => n, None => return None },
+        None    => 0
+    };
+    
+    Some(UPnPVersion::new(major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use ssdp::version::{UPnPVersion, UPNP_10_VERSION, UPNP_11_VERSION};
+    
+    #[test]
+    fn positive_spaced_max_age() {
+        assert_eq!(super::repair_cache_control("no-cache, max-age = 1800"),
+            Some("no-cache, max-age=1800".to_string()));
+    }
+    
+    #[test]
+    fn positive_unspaced_max_age() {
+        assert_eq!(super::repair_cache_control("no-cache,max-age=1800"), None);
+    }
+    
+    #[test]
+    fn positive_location_without_scheme() {
+        assert_eq!(super::repair_location(" 192.168.1.1:5431/dyndev/uuid "),
+            Some("http://192.168.1.1:5431/dyndev/uuid".to_string()));
+    }
+    
+    #[test]
+    fn positive_spaced_upnp_version() {
+        assert_eq!(super::upnp_version("Linux/2.6 UPnP 1.0 DLNADOC/1.50"), Some(UPNP_10_VERSION));
+        assert_eq!(super::upnp_version("POSIX UPNP/1.1 Stack/2.0"), Some(UPNP_11_VERSION));
+        assert_eq!(super::upnp_version("Linux upnp/2 Camera/1.0"), Some(UPnPVersion::new(2, 0)));
+    }
+    
+    #[test]
+    fn negative_upnp_version_without_separator() {
+        assert_eq!(super::upnp_version("Microsoft-Windows/10.0 UPnP-Device-Host/1.0"), None);
+    }
+}
@@ -0,0 +1,366 @@
+//! Loopback SSDP network for testing search and discovery end to end.
+//!
+//! Scripted devices sit behind a single UDP socket bound to the loopback
+//! interface. Search requests sent to that socket are answered by every device
+//! whose advertisement matches, while notify messages are only sent out when a
+//! test asks for them, to whatever address it is listening on. Each device can
+//! be given network conditions that delay, drop or mangle the packets it sends.
+
+use std::mem;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self};
+
+use rand::{self, Rng};
+use time::{Duration, PreciseTime};
+
+use {SSDPResult};
+use net::receiver::{PacketListener};
+use ssdp::{self};
+use ssdp::advertiser::{Advertisement};
+use ssdp::message::search::{SearchRequest};
+use ssdp::packet::{Packet};
+
+/// Time to sleep between checks for received requests.
+const POLL_INTERVAL_MS: u32 = 10;
+
+/// Header line without a separator, which no parser should accept.
+const MALFORMED_LINE: &'static str = "MALFORMED HEADER LINE\r\n\r\n";
+
+/// Network conditions that the packets sent by a device are subjected to.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Conditions {
+    min_delay_ms:      u32,
+    max_delay_ms:      u32,
+    loss_percent:      u8,
+    malformed_percent: u8
+}
+
+impl Conditions {
+    /// Create a new Conditions that delivers every packet intact right away.
+    pub fn new() -> Conditions {
+        Conditions{ min_delay_ms: 0, max_delay_ms: 0, loss_percent: 0, malformed_percent: 0 }
+    }
+    
+    /// Delay each packet by a random amount of time within the given bounds.
+    pub fn with_delay(mut self, min_ms: u32, max_ms: u32) -> Conditions {
+        self.min_delay_ms = min_ms;
+        self.max_delay_ms = if max_ms < min_ms { min_ms } else { max_ms };
+        
+        self
+    }
+    
+    /// Drop the given percentage of packets.
+    pub fn with_loss(mut self, percent: u8) -> Conditions {
+        self.loss_percent = percent;
+        
+        self
+    }
+    
+    /// Mangle the given percentage of packets so that they can not be parsed.
+    pub fn with_malformed(mut self, percent: u8) -> Conditions {
+        self.malformed_percent = percent;
+        
+        self
+    }
+}
+
+/// Device on the loopback network that sends out packets for its advertisement.
+#[derive(Clone, Debug)]
+pub struct ScriptedDevice {
+    advert:     Advertisement,
+    conditions: Conditions
+}
+
+impl ScriptedDevice {
+    /// Create a new ScriptedDevice that sends its packets out intact.
+    pub fn new(advert: Advertisement) -> ScriptedDevice {
+        ScriptedDevice{ advert: advert, conditions: Conditions::new() }
+    }
+    
+    /// Subject the packets sent by the device to the given conditions.
+    pub fn with_conditions(mut self, conditions: Conditions) -> ScriptedDevice {
+        self.conditions = conditions;
+        
+        self
+    }
+    
+    pub fn advertisement(&self) -> &Advertisement {
+        &self.advert
+    }
+}
+
+/// Packet waiting for its delay to pass before it is sent.
+struct Scheduled {
+    created: PreciseTime,
+    delay:   Duration,
+    dest:    SocketAddr,
+    payload: Vec<u8>
+}
+
+/// Group of scripted devices answering search requests on a loopback socket.
+///
+/// The devices stop answering once the LoopbackNetwork is dropped.
+pub struct LoopbackNetwork {
+    devices:   Arc<Mutex<Vec<ScriptedDevice>>>,
+    scheduled: Arc<Mutex<Vec<Scheduled>>>,
+    requests:  Arc<AtomicUsize>,
+    addr:      SocketAddr,
+    kill_flag: Arc<AtomicBool>
+}
+
+impl LoopbackNetwork {
+    /// Bind the loopback socket and start answering search requests sent to it.
+    pub fn start() -> SSDPResult<LoopbackNetwork> {
+        let udp = try!(UdpSocket::bind(("127.0.0.1", 0)).map_err(ssdp::to_ssdp_error));
+        let addr = try!(udp.local_addr().map_err(ssdp::to_ssdp_error));
+        let listener = try!(PacketListener::new(&udp).map_err(ssdp::to_ssdp_error));
+        
+        let devices = Arc::new(Mutex::new(Vec::new()));
+        let scheduled = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::new(AtomicUsize::new(0));
+        let kill_flag = Arc::new(AtomicBool::new(false));
+        
+        let (devices_clone, scheduled_clone) = (devices.clone(), scheduled.clone());
+        let (requests_clone, kill_clone) = (requests.clone(), kill_flag.clone());
+        thread::spawn(move || {
+            let mut rng = rand::thread_rng();
+            
+            while !kill_clone.load(Ordering::SeqCst) {
+                while let Some((pckt, src)) = listener.try_recv() {
+                    let request = match Packet::parse(&pckt[..]).and_then(|n| SearchRequest::new(&n)) {
+                        Ok(n)  => n,
+                        Err(_) => continue
+                    };
+                    requests_clone.fetch_add(1, Ordering::SeqCst);
+                    
+                    let mut scheduled = scheduled_clone.lock().unwrap();
+                    for device in devices_clone.lock().unwrap().iter() {
+                        let packets = device.advert.search_responses(request.target());
+                        
+                        schedule(&mut scheduled, &packets[..], device.conditions, src, &mut rng);
+                    }
+                }
+                
+                send_due(&udp, &mut scheduled_clone.lock().unwrap());
+                thread::sleep_ms(POLL_INTERVAL_MS);
+            }
+        });
+        
+        Ok(LoopbackNetwork{ devices: devices, scheduled: scheduled, requests: requests, addr: addr,
+            kill_flag: kill_flag })
+    }
+    
+    /// Add a device to the network, which will answer any search request
+    /// received from here on.
+    pub fn add_device(&self, device: ScriptedDevice) {
+        self.devices.lock().unwrap().push(device);
+    }
+    
+    /// Returns the address that search requests should be sent to.
+    pub fn search_addr(&self) -> SocketAddr {
+        self.addr
+    }
+    
+    /// Returns the port that search requests should be sent to on the loopback
+    /// interface.
+    pub fn search_port(&self) -> u16 {
+        match self.addr {
+            SocketAddr::V4(n) => n.port(),
+            SocketAddr::V6(n) => n.port()
+        }
+    }
+    
+    /// Returns the number of valid search requests received so far.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+    
+    /// Have every device send out its ssdp:alive messages to the given address.
+    pub fn send_alive(&self, dest: SocketAddr) {
+        self.send_notify(dest, |n| n.alive_packets());
+    }
+    
+    /// Have every device send out its ssdp:byebye messages to the given address.
+    pub fn send_byebye(&self, dest: SocketAddr) {
+        self.send_notify(dest, |n| n.byebye_packets());
+    }
+    
+    /// Have every device send out ssdp:update messages announcing the given
+    /// boot instance to the given address.
+    pub fn send_update(&self, dest: SocketAddr, next_boot_id: u32) {
+        self.send_notify(dest, |n| n.update_packets(next_boot_id));
+    }
+    
+    fn send_notify<F>(&self, dest: SocketAddr, packets: F) where F: Fn(&Advertisement) -> Vec<Packet> {
+        let mut rng = rand::thread_rng();
+        let mut scheduled = self.scheduled.lock().unwrap();
+        
+        for device in self.devices.lock().unwrap().iter() {
+            schedule(&mut scheduled, &packets(&device.advert)[..], device.conditions, dest, &mut rng);
+        }
+    }
+}
+
+impl Drop for LoopbackNetwork {
+    fn drop(&mut self) {
+        self.kill_flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Queue up the packets under the given conditions.
+fn schedule<R>(scheduled: &mut Vec<Scheduled>, packets: &[Packet], conditions: Conditions, dest: SocketAddr,
+    rng: &mut R) where R: Rng {
+    for packet in packets.iter() {
+        if rng.gen_range(0, 100) < conditions.loss_percent {
+            continue
+        }
+        
+        let payload = if rng.gen_range(0, 100) < conditions.malformed_percent {
+            mangle(packet)
+        } else {
+            packet.to_bytes()
+        };
+        let delay = rng.gen_range(conditions.min_delay_ms, conditions.max_delay_ms + 1);
+        
+        scheduled.push(Scheduled{ created: PreciseTime::now(), delay: Duration::milliseconds(delay as i64),
+            dest: dest, payload: payload });
+    }
+}
+
+/// Keep the start line of the packet but replace its headers with a line that
+/// can not be parsed.
+fn mangle(packet: &Packet) -> Vec<u8> {
+    let bytes = packet.to_bytes();
+    let start_end = bytes.windows(2).position(|n| n == b"\r\n").map_or(bytes.len(), |n| n + 2);
+    
+    let mut mangled = bytes[..start_end].to_vec();
+    mangled.extend(MALFORMED_LINE.bytes());
+    
+    mangled
+}
+
+/// Send out every scheduled packet whose delay has passed.
+fn send_due(udp: &UdpSocket, scheduled: &mut Vec<Scheduled>) {
+    let now = PreciseTime::now();
+    
+    let (due, waiting): (Vec<_>, Vec<_>) = mem::replace(scheduled, Vec::new()).into_iter().partition(|n|
+        n.created.to(now) >= n.delay
+    );
+    *scheduled = waiting;
+    
+    for packet in due.into_iter() {
+        // Lost Packets Are Part Of The Script Anyway...
+        let _ = udp.send_to(&packet.payload[..], packet.dest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{UdpSocket};
+    
+    use time::{Duration};
+    use url::{Url};
+    
+    use net::receiver::{PacketListener};
+    use ssdp::{Policy, Strictness};
+    use ssdp::advertiser::{AdvertisedDevice, Advertisement};
+    use ssdp::header::{NTS, USN};
+    use ssdp::message::notify::{AliveMessage};
+    use ssdp::packet::{Packet};
+    use super::{Conditions, LoopbackNetwork, ScriptedDevice};
+    
+    const WAIT_MS: i64 = 500;
+    
+    fn advert() -> Advertisement {
+        let device = AdvertisedDevice::new("ae239f00-ae2b-bbad-ddf1-88ddcc00a234",
+            "schemas-upnp-org:device:Basic:1");
+        
+        Advertisement::new(device, Url::parse("http://127.0.0.1:49152/desc.xml").unwrap(),
+            "Linux/3.0 UPnP/1.1 omni/0.0.1", 1, 1)
+    }
+    
+    /// Returns every packet received within the wait time.
+    fn receive_all(listener: &PacketListener) -> Vec<Packet> {
+        let mut packets = Vec::new();
+        
+        while let Some((pckt, _)) = listener.recv_timeout(Duration::milliseconds(WAIT_MS)) {
+            if let Ok(n) = Packet::parse(&pckt[..]) {
+                packets.push(n);
+            }
+        }
+        
+        packets
+    }
+    
+    #[test]
+    fn positive_alive_byebye_round_trip() {
+        let network = LoopbackNetwork::start().unwrap();
+        network.add_device(ScriptedDevice::new(advert()));
+        
+        let udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let listener = PacketListener::new(&udp).unwrap();
+        
+        // Discover Every Target Through Its Alive Message
+        network.send_alive(udp.local_addr().unwrap());
+        let mut discovered = receive_all(&listener).into_iter().map(|n| {
+            AliveMessage::parse(n.clone(), Policy::new(Strictness::Strict)).unwrap();
+            
+            n.headers().get::<USN>().unwrap().clone()
+        }).collect::<Vec<USN>>();
+        assert_eq!(discovered.len(), advert().targets().len());
+        
+        // Every Target Discovered Should Say Byebye
+        network.send_byebye(udp.local_addr().unwrap());
+        for packet in receive_all(&listener).iter() {
+            assert_eq!(packet.headers().get::<NTS>(), Some(&NTS::ByeBye));
+            
+            let usn = packet.headers().get::<USN>().unwrap();
+            let index = discovered.iter().position(|n| n == usn).unwrap();
+            discovered.remove(index);
+        }
+        assert!(discovered.is_empty());
+    }
+    
+    #[test]
+    fn positive_byebye_delivery() {
+        let network = LoopbackNetwork::start().unwrap();
+        network.add_device(ScriptedDevice::new(advert()));
+        
+        let udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let listener = PacketListener::new(&udp).unwrap();
+        network.send_byebye(udp.local_addr().unwrap());
+        
+        let packets = receive_all(&listener);
+        assert_eq!(packets.len(), advert().byebye_packets().len());
+        assert!(packets.iter().all(|n| n.headers().get::<NTS>() == Some(&NTS::ByeBye)));
+    }
+    
+    #[test]
+    fn positive_malformed_packets() {
+        let network = LoopbackNetwork::start().unwrap();
+        let conditions = Conditions::new().with_malformed(100);
+        network.add_device(ScriptedDevice::new(advert()).with_conditions(conditions));
+        
+        let udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let listener = PacketListener::new(&udp).unwrap();
+        network.send_alive(udp.local_addr().unwrap());
+        
+        assert!(listener.recv_timeout(Duration::milliseconds(WAIT_MS)).is_some());
+        assert_eq!(receive_all(&listener).len(), 0);
+    }
+    
+    #[test]
+    fn positive_total_loss() {
+        let network = LoopbackNetwork::start().unwrap();
+        let conditions = Conditions::new().with_loss(100);
+        network.add_device(ScriptedDevice::new(advert()).with_conditions(conditions));
+        
+        let udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let listener = PacketListener::new(&udp).unwrap();
+        network.send_alive(udp.local_addr().unwrap());
+        
+        assert!(listener.recv_timeout(Duration::milliseconds(WAIT_MS)).is_none());
+    }
+}
@@ -1,22 +1,28 @@
 use hyper::http::{self};
+use hyper::header::{Header, Server};
+
+use {SSDPError, SSDPResult};
+use ssdp::{lenient, Policy, Strictness, Warning};
+use ssdp::header::{HeaderView, ProductTokens};
+use ssdp::version::{UPnPVersion, UPNP_10_VERSION};
 /*
 use {SSDPError};
 use super::notify::{NotifyMessage};
 use super::search::{SearchRequest, SearchResponse};*/
 
 pub mod notify;
+pub mod search;
 
-const MESSAGE_MULTICAST_HOST: &'static str = "239.255.255.250";
-
-const UPNP_10_VERSION_NAME: &'static str = "UPnP/1.0";
-const UPNP_11_VERSION_NAME: &'static str = "UPnP/1.1";
-const UPNP_20_VERSION_NAME: &'static str = "UPnP/2.0";
+/// Multicast address that SSDP messages are sent to.
+pub const MESSAGE_MULTICAST_HOST: &'static str = "239.255.255.250";
 
 // TODO: This is wrong, should generlize to all 2xx error codes
 const VALID_SEARCH_RESPONSE_CODE: u16 = 200;
 
-const NOTIFY_HEADER: &'static str = "NOTIFY";
-const SEARCH_HEADER: &'static str = "M-SEARCH";
+/// Method used for notify messages.
+pub const NOTIFY_HEADER: &'static str = "NOTIFY";
+/// Method used for search requests.
+pub const SEARCH_HEADER: &'static str = "M-SEARCH";
 
 /// A trait for messages that allow vendor specific information to be exposed.
 pub trait MessageExt {
@@ -24,6 +30,45 @@ pub trait MessageExt {
     /// name and returns all field-values that match that name or none.
     fn check_header(&self, name: &str) -> Option<&[Vec<u8>]>;
 }
+
+/// Returns the implemented UPnP version that the sender of a message is
+/// compatible with according to its Server header, falling back to a loose
+/// match or UPnP 1.0 if the policy allows.
+fn server_version<T>(headers: &T, policy: &Policy, warnings: &mut Vec<Warning>) -> SSDPResult<UPnPVersion>
+    where T: HeaderView {
+    let server = match headers.view::<Server>() {
+        Some(n) => &n.0[..],
+        None if policy.server_version == Strictness::Lenient => {
+            warnings.push(Warning::new(Server::header_name(), "Missing Header, Assuming UPnP 1.0"));
+            
+            return Ok(UPNP_10_VERSION)
+        },
+        None => return Err(SSDPError::MissingHeader(Server::header_name()))
+    };
+    
+    match ProductTokens::parse(server).and_then(|n| n.upnp_version().compatible()) {
+        Some(n) => return Ok(n),
+        None if policy.server_version == Strictness::Strict => {
+            return Err(SSDPError::InvalidHeader(Server::header_name(),
+                "Invalid UPnP Version In Server Header"))
+        },
+        None => ()
+    };
+    
+    match lenient::upnp_version(server).and_then(|n| n.compatible()) {
+        Some(n) => {
+            warnings.push(Warning::new(Server::header_name(), "Inexact UPnP Version"));
+            
+            Ok(n)
+        },
+        None => {
+            warnings.push(Warning::new(Server::header_name(),
+                "Unrecognized UPnP Version, Assuming UPnP 1.0"));
+            
+            Ok(UPNP_10_VERSION)
+        }
+    }
+}
 /*
 /// Enumerates the types of SSDP messages.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
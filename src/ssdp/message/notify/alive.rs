@@ -4,13 +4,16 @@ use std::fmt::{self, Debug, Formatter};
 use hyper::header::{Location, Server, CacheDirective, CacheControl, Host, 
                     Headers, Header, HeaderFormat};
 use time::{Duration, PreciseTime};
-use url::{Url};
+use url::{Url, UrlParser};
 
 use {SSDPError, SSDPResult};
 use forum::{GenericQuery, QueryType, TargetType};
-use ssdp::{FieldPair};
-use ssdp::header::{HeaderView, SearchPort, SecureLocation, BootID, NT, USN, ConfigID};
+use ssdp::{lenient, FieldPair, Policy, Warning};
+use ssdp::header::{HeaderView, SearchPort, SecureLocation, BootID, NT, NTS, USN, ConfigID, ProductTokens,
+                   ServerTokens};
+use ssdp::version::{UPnPVersion, UPNP_10_VERSION, UPNP_11_VERSION, UPNP_20_VERSION};
 use ssdp::message::{self, MessageExt};
+use ssdp::packet::{Packet, StartLine};
 
 /// Represents AliveMessage versions pertaining to different UPnP versions.
 #[derive(Copy, Clone)]
@@ -31,16 +34,35 @@ pub struct AliveMessage {
     version:  AliveVersionImpl,
     max_age:  Duration,
     target:   TargetType,
-    location: Url
+    location: Url,
+    warnings: Vec<Warning>
 }
 
 impl AliveMessage {
+    /// Create a new AliveMessage from a packet received off of the network,
+    /// putting up with the quirks that the policy allows.
+    pub fn parse(mut packet: Packet, policy: Policy) -> SSDPResult<AliveMessage> {
+        let mut warnings = Vec::new();
+        lenient::repair(&mut packet, &policy, &mut warnings);
+        
+        match *packet.start_line() {
+            StartLine::Request(ref n, _) if n == message::NOTIFY_HEADER => (),
+            StartLine::Request(ref n, _) => return Err(SSDPError::InvalidMethod(n.clone())),
+            StartLine::Response(code, _) => return Err(SSDPError::ResponseCode(code))
+        };
+        if packet.headers().get::<NTS>() != Some(&NTS::Alive) {
+            return Err(SSDPError::InvalidHeader(NTS::header_name(), "Message Is Not An Alive Message"))
+        }
+        
+        AliveMessage::new(packet.headers().clone(), policy, warnings)
+    }
+    
     /// Create a new AliveMessage from the given header.
-    fn new(headers: Headers) -> SSDPResult<AliveMessage> {
-        let (version, duration, url, target) = try!(alive_pieces(&headers));
+    fn new(headers: Headers, policy: Policy, mut warnings: Vec<Warning>) -> SSDPResult<AliveMessage> {
+        let (version, duration, url, target) = try!(alive_pieces(&headers, &policy, &mut warnings));
         
         Ok(AliveMessage{ headers: headers, created: PreciseTime::now(),
-            version: version, max_age: duration, target: target, location: url })
+            version: version, max_age: duration, target: target, location: url, warnings: warnings })
     }
     
     /// Returns whether or not the cache control set by the sender has expired.
@@ -53,11 +75,31 @@ impl AliveMessage {
         self.max_age
     }
     
+    /// Returns the location of the root device description.
+    pub fn location(&self) -> &Url {
+        &self.location
+    }
+    
     /// Returns the Server header field containing environment information.
+    ///
+    /// Returns an empty string if the header was missing from a message that
+    /// was parsed leniently.
     pub fn server_info(&self) -> &str {
-        // We Processed The Server Header Field When Looking At The Message
-        // Version. We Can Therefore Safely Unwrap It Out Of The Header.
-        &self.headers.view::<Server>().unwrap().0[..]
+        self.headers.view::<Server>().map_or("", |n| &n.0[..])
+    }
+    
+    /// Returns the product tokens parsed out of the Server header.
+    ///
+    /// Returns None if the header was missing or did not follow the
+    /// "OS/version UPnP/major.minor product/version" form.
+    pub fn server_tokens(&self) -> Option<&ProductTokens> {
+        self.headers.view::<ServerTokens>().map(|n| &n.0)
+    }
+    
+    /// Returns the implemented UPnP version that the sender is compatible with,
+    /// so a UPnP 1.2 device is reported as UPnP 1.1.
+    pub fn upnp_version(&self) -> UPnPVersion {
+        self.version.upnp_version()
     }
     
     /// Returns UPnP version information for this specific message.
@@ -69,6 +111,25 @@ impl AliveMessage {
         }
     }
     
+    /// Returns the secure location advertised by a UPnP 2.0 device, resolved
+    /// against the regular location of the device.
+    ///
+    /// Returns None if no secure location was advertised or it is not a valid url.
+    pub fn secure_location(&self) -> Option<Url> {
+        let secure_loc = match self.version {
+            AliveVersionImpl::V20(ref n) => n.secure_location(),
+            _ => None
+        };
+        
+        secure_loc.and_then(|n| UrlParser::new().base_url(&self.location).parse(n.trim()).ok())
+    }
+    
+    /// Returns the deviations from the specification that were put up with
+    /// when parsing the message.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+    
     /// Returns the query object associated with this message.
     pub fn query<'a>(&'a self) -> QueryType<'a> {
         panic!("TODO")
@@ -97,13 +158,16 @@ impl Debug for AliveMessage {
          try!(f.write_str(", location: "));
         try!(Debug::fmt(&self.location, f));
         
+        try!(f.write_str(", warnings: "));
+        try!(Debug::fmt(&self.warnings, f));
+        
         f.write_str(" }")
     }
 }
 
 /// Delegate for the creation process of an alive message.
-fn alive_pieces<T>(headers: T) -> SSDPResult<(AliveVersionImpl, Duration, Url, TargetType)>
-    where T: HeaderView {
+fn alive_pieces<T>(headers: T, policy: &Policy, warnings: &mut Vec<Warning>)
+    -> SSDPResult<(AliveVersionImpl, Duration, Url, TargetType)> where T: HeaderView {
     // Extract Required Headers
     let ref host_name = try!(try_view_header::<T, Host>(&headers)).hostname;
     let ref cache_control = try!(try_view_header::<T, CacheControl>(&headers)).0;
@@ -116,7 +180,7 @@ fn alive_pieces<T>(headers: T) -> SSDPResult<(AliveVersionImpl, Duration, Url, T
     try!(check_nt_usn_rules(notify_type, usn_uuid, usn_type));
     
     // Create Alive Pieces
-    let version = try!(AliveVersionImpl::new(&headers));
+    let version = try!(AliveVersionImpl::new(&headers, policy, warnings));
     let max_age = try!(first_max_age(&cache_control[..]));
     let url = try!(location_as_url(&location[..]));
     let target = try!(notify_as_target(notify_type));
@@ -243,24 +307,25 @@ enum AliveVersionImpl {
 
 impl AliveVersionImpl {
     /// Create a new AliveVersionImpl object.
-    fn new<T>(headers: T) -> SSDPResult<AliveVersionImpl> where T: HeaderView {
-        let server = try!(headers.view::<Server>().ok_or(
-            SSDPError::MissingHeader(Server::header_name())
-        ));
+    fn new<T>(headers: T, policy: &Policy, warnings: &mut Vec<Warning>) -> SSDPResult<AliveVersionImpl>
+        where T: HeaderView {
+        let version = try!(message::server_version(&headers, policy, warnings));
         
-        // TODO: Change this so that just the UPnP/ is matched and gather the version
-        // number to convert to some enum like UPnPVersion::1.0, etc.
-        if server.contains(message::UPNP_10_VERSION_NAME) {
-            Ok(AliveVersionImpl::V10)
-        } else if server.contains(message::UPNP_11_VERSION_NAME) {
-            let alive_ext = try!(AliveExtV11Impl::new(&headers));
-            Ok(AliveVersionImpl::V11(alive_ext))
-        } else if server.contains(message::UPNP_20_VERSION_NAME) {
-            let alive_ext = try!(AliveExtV20Impl::new(&headers));
-            Ok(AliveVersionImpl::V20(alive_ext))
+        if version >= UPNP_20_VERSION {
+            Ok(AliveVersionImpl::V20(try!(AliveExtV20Impl::new(&headers, policy, warnings))))
+        } else if version.requires_boot_id() {
+            Ok(AliveVersionImpl::V11(try!(AliveExtV11Impl::new(&headers, policy, warnings))))
         } else {
-            Err(SSDPError::InvalidHeader(Server::header_name(), 
-                                         "Invalid UPnP Version In Server Header"))
+            Ok(AliveVersionImpl::V10)
+        }
+    }
+    
+    /// Returns the UPnP version that the message was parsed for.
+    fn upnp_version(&self) -> UPnPVersion {
+        match *self {
+            AliveVersionImpl::V10    => UPNP_10_VERSION,
+            AliveVersionImpl::V11(_) => UPNP_11_VERSION,
+            AliveVersionImpl::V20(_) => UPNP_20_VERSION
         }
     }
 }
@@ -296,8 +361,12 @@ struct AliveExtV11Impl {
 }
 
 impl AliveExtV11Impl {
-    fn new<T>(headers: T) -> SSDPResult<AliveExtV11Impl> where T: HeaderView {
-        let boot_id = try!(try_view_header::<T, BootID>(&headers)).0;
+    fn new<T>(headers: T, policy: &Policy, warnings: &mut Vec<Warning>) -> SSDPResult<AliveExtV11Impl>
+        where T: HeaderView {
+        let boot_id = match lenient::boot_id(&headers, policy, warnings) {
+            Some(n) => n.0,
+            None    => return Err(SSDPError::MissingHeader(BootID::header_name()))
+        };
         let config_id = try!(try_view_header::<T, ConfigID>(&headers)).0;
         
         let port = headers.view::<SearchPort>().map(|n| n.0);
@@ -336,8 +405,9 @@ struct AliveExtV20Impl {
 }
 
 impl AliveExtV20Impl {
-    fn new<T>(header: T) -> SSDPResult<AliveExtV20Impl> where T: HeaderView {
-        let parent = try!(AliveExtV11Impl::new(&header));
+    fn new<T>(header: T, policy: &Policy, warnings: &mut Vec<Warning>) -> SSDPResult<AliveExtV20Impl>
+        where T: HeaderView {
+        let parent = try!(AliveExtV11Impl::new(&header, policy, warnings));
         
         let secure_loc = header.view::<SecureLocation>().map(|n| n.0.clone());
         
@@ -370,29 +440,43 @@ impl AliveExtV20 for AliveExtV20Impl {
 
 #[cfg(test)]
 mod tests {
+    use std::net::{UdpSocket};
+    
     use time::{Duration};
-    use hyper::header::{Host, CacheControl, Location, Server};
-
-    use forum::{QueryType};
-    use ssdp::header::{HeaderView, SearchPort, SecureLocation, BootID, NT, USN,
-                       ConfigID, NTS};
-    use ssdp::header::mock::{MockHeaderView};
-    use super::{AliveMessage, AliveVersion};
-    /*
+    use url::{Url};
+    
+    use net::receiver::{PacketListener};
+    use ssdp::{Policy, Strictness};
+    use ssdp::advertiser::{AdvertisedDevice, Advertisement};
+    use ssdp::loopback::{LoopbackNetwork, ScriptedDevice};
+    use ssdp::packet::{Packet};
+    use ssdp::version::{UPNP_11_VERSION};
+    use super::{AliveExtV11, AliveMessage, AliveVersion};
+    
     #[test]
     fn positive_alive_message() {
-        let mut headers = MockHeaderView::new();
+        let device = AdvertisedDevice::new("ae239f00-ae2b-bbad-ddf1-88ddcc00a234",
+            "schemas-upnp-org:device:Basic:1");
+        let advert = Advertisement::new(device, Url::parse("http://192.168.0.1/desc.xml").unwrap(),
+            "Windows/3.1 UPnP/1.1 omni/0.0.1", 7, 1);
+        let network = LoopbackNetwork::start().unwrap();
+        network.add_device(ScriptedDevice::new(advert));
         
-        headers.insert::<Host>("239.255.255.250:1900");
-        headers.insert::<CacheControl>("max-age=1800");
-        headers.insert::<Location>("http://192.168.0.1/desc.xml");
-        headers.insert::<NT>("upnp:rootdevice");
-        headers.insert::<NTS>("ssdp:alive");
-        headers.insert::<Server>("Windows/3.1 UPnP/1.0 omni/0.0.1");
-        headers.insert::<USN>("uuid:ae239f00-ae2b-bbad-ddf1-88ddcc00a234::upnp:rootdevice");
+        let udp = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        let listener = PacketListener::new(&udp).unwrap();
+        network.send_alive(udp.local_addr().unwrap());
         
-        let message = AliveMessage::new(headers).unwrap();
-
-        // TODO: NOT FINISHED WITH THIS TEST YET
-    }*/
+        let (pckt, _) = listener.recv_timeout(Duration::milliseconds(500)).unwrap();
+        let packet = Packet::parse(&pckt[..]).unwrap();
+        let message = AliveMessage::parse(packet, Policy::new(Strictness::Strict)).unwrap();
+        
+        assert_eq!(message.max_age(), Duration::seconds(1800));
+        assert_eq!(message.location().serialize(), "http://192.168.0.1/desc.xml");
+        assert_eq!(message.server_info(), "Windows/3.1 UPnP/1.1 omni/0.0.1");
+        assert_eq!(message.upnp_version(), UPNP_11_VERSION);
+        match message.version() {
+            AliveVersion::V11(n) => assert_eq!((n.boot_id(), n.config_id()), (7, 1)),
+            _ => panic!("Alive Message Was Not Parsed As UPnP 1.1")
+        }
+    }
 }
\ No newline at end of file
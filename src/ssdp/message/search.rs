@@ -0,0 +1,538 @@
+use std::net::{SocketAddr};
+
+use hyper::header::{CacheControl, CacheDirective, Header, HeaderFormat, Headers, Host, Location, Server};
+use time::{Duration};
+use url::{Url, UrlParser};
+
+use {SSDPError, SSDPResult};
+use ssdp::{lenient, Policy, Strictness, Warning};
+use ssdp::header::{BootID, ConfigID, HeaderView, Man, MX, ProductTokens, SearchPort, SecureLocation,
+                   ServerTokens, ST, UserAgentTokens, USN};
+use ssdp::message::{self, MessageExt};
+use ssdp::packet::{Packet, StartLine};
+use ssdp::version::{UPnPVersion, UPNP_10_VERSION};
+
+/// Port that devices listen on for search requests unless they advertise
+/// another port with a SEARCHPORT.UPNP.ORG header.
+pub const DEFAULT_SEARCH_PORT: u16 = 1900;
+
+const STAR_URI: &'static str = "*";
+
+/// Request sent out to find devices and services on the network.
+///
+/// Multicast requests are answered by every matching device after a random
+/// delay of up to MX seconds, whereas unicast requests are sent straight to a
+/// single device which answers right away.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SearchRequest {
+    target:     ST,
+    mx:         Option<MX>,
+    host:       SocketAddr,
+    user_agent: Option<ProductTokens>,
+    warnings:   Vec<Warning>
+}
+
+impl SearchRequest {
+    /// Create a new SearchRequest from a packet received off of the network.
+    ///
+    /// Multicast requests must carry an MX header, unicast requests may not.
+    pub fn new(packet: &Packet) -> SSDPResult<SearchRequest> {
+        SearchRequest::parse(packet, Policy::new(Strictness::Strict))
+    }
+    
+    /// Create a new SearchRequest from a packet received off of the network,
+    /// putting up with the quirks that the policy allows.
+    pub fn parse(packet: &Packet, policy: Policy) -> SSDPResult<SearchRequest> {
+        match *packet.start_line() {
+            StartLine::Request(ref n, _) if n != message::SEARCH_HEADER => {
+                return Err(SSDPError::InvalidMethod(n.clone()))
+            },
+            StartLine::Request(_, ref n) if n != STAR_URI => return Err(SSDPError::InvalidUri(n.clone())),
+            StartLine::Request(..) => (),
+            StartLine::Response(code, _) => return Err(SSDPError::ResponseCode(code))
+        };
+        let headers = packet.headers();
+        let mut warnings = Vec::new();
+        
+        if lenient::man(headers, &policy, &mut warnings).is_none() {
+            return Err(SSDPError::MissingHeader(Man::header_name()))
+        }
+        let target = try!(try_view_header::<ST>(headers)).clone();
+        let host = try!(host_as_socket_addr(try!(try_view_header::<Host>(headers))));
+        
+        let mx = headers.view::<MX>().map(|n| *n);
+        if mx.is_none() && host_ip(&host) == message::MESSAGE_MULTICAST_HOST {
+            return Err(SSDPError::MissingHeader(MX::header_name()))
+        }
+        
+        let user_agent = headers.view::<UserAgentTokens>().map(|n| n.0.clone());
+        
+        Ok(SearchRequest{ target: target, mx: mx, host: host, user_agent: user_agent, warnings: warnings })
+    }
+    
+    /// Create a new SearchRequest to be multicast to all devices.
+    pub fn multicast(target: ST, mx: MX) -> SearchRequest {
+        let host = (message::MESSAGE_MULTICAST_HOST, DEFAULT_SEARCH_PORT);
+        
+        SearchRequest{ target: target, mx: Some(mx), host: to_socket_addr(host), user_agent: None,
+            warnings: Vec::new() }
+    }
+    
+    /// Create a new SearchRequest to be sent to the device at the given address.
+    pub fn unicast(target: ST, device: SocketAddr) -> SearchRequest {
+        SearchRequest{ target: target, mx: None, host: device, user_agent: None, warnings: Vec::new() }
+    }
+    
+    /// Send a UPnP 1.1 USER-AGENT header describing the control point along
+    /// with the request.
+    pub fn with_user_agent(mut self, user_agent: ProductTokens) -> SearchRequest {
+        self.user_agent = Some(user_agent);
+        
+        self
+    }
+    
+    /// Returns the search target of the request.
+    pub fn target(&self) -> &ST {
+        &self.target
+    }
+    
+    /// Returns the maximum wait time of the request, which is only present for
+    /// multicast requests.
+    pub fn mx(&self) -> Option<MX> {
+        self.mx
+    }
+    
+    /// Returns the address that the request is destined for.
+    pub fn host(&self) -> SocketAddr {
+        self.host
+    }
+    
+    /// Returns the product tokens describing the control point, sent by UPnP 1.1
+    /// control points and later.
+    pub fn user_agent(&self) -> Option<&ProductTokens> {
+        self.user_agent.as_ref()
+    }
+    
+    /// Returns the UPnP version advertised by the control point, which is
+    /// UPnP 1.0 if no user agent was sent.
+    pub fn upnp_version(&self) -> UPnPVersion {
+        self.user_agent.as_ref().map_or(UPNP_10_VERSION, |n| n.upnp_version())
+    }
+    
+    /// Returns the deviations from the specification that were put up with
+    /// when parsing the request.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+    
+    /// Returns true if the request was sent to the multicast address.
+    pub fn is_multicast(&self) -> bool {
+        host_ip(&self.host) == message::MESSAGE_MULTICAST_HOST
+    }
+    
+    /// Build the packet for the request.
+    pub fn to_packet(&self) -> Packet {
+        let mut packet = Packet::request(message::SEARCH_HEADER);
+        
+        packet.set(Host{ hostname: host_ip(&self.host), port: Some(host_port(&self.host)) });
+        packet.set(Man);
+        if let Some(mx) = self.mx {
+            packet.set(mx);
+        }
+        packet.set(self.target.clone());
+        if let Some(ref user_agent) = self.user_agent {
+            packet.set(UserAgentTokens(user_agent.clone()));
+        }
+        
+        packet
+    }
+}
+
+/// Response sent back by a device that matched a search request.
+#[derive(Clone, Debug)]
+pub struct SearchResponse {
+    headers:  Headers,
+    remote:   SocketAddr,
+    max_age:  Duration,
+    location: Url,
+    target:   ST,
+    usn:      USN,
+    version:  UPnPVersion,
+    boot_id:  Option<u32>,
+    warnings: Vec<Warning>
+}
+
+impl SearchResponse {
+    /// Create a new SearchResponse from a packet received from the given address.
+    pub fn new(packet: Packet, remote: SocketAddr) -> SSDPResult<SearchResponse> {
+        SearchResponse::parse(packet, remote, Policy::new(Strictness::Strict))
+    }
+    
+    /// Create a new SearchResponse from a packet received from the given
+    /// address, putting up with the quirks that the policy allows.
+    pub fn parse(mut packet: Packet, remote: SocketAddr, policy: Policy) -> SSDPResult<SearchResponse> {
+        let mut warnings = Vec::new();
+        lenient::repair(&mut packet, &policy, &mut warnings);
+        
+        match *packet.start_line() {
+            StartLine::Response(code, _) if code == message::VALID_SEARCH_RESPONSE_CODE => (),
+            StartLine::Response(code, _) => return Err(SSDPError::ResponseCode(code)),
+            StartLine::Request(ref n, _) => return Err(SSDPError::InvalidMethod(n.clone()))
+        };
+        let headers = packet.headers().clone();
+        
+        let max_age = try!(first_max_age(&try!(try_view_header::<CacheControl>(&headers)).0[..]));
+        let location = try!(location_as_url(&try!(try_view_header::<Location>(&headers)).0[..]));
+        let target = try!(try_view_header::<ST>(&headers)).clone();
+        let usn = try!(try_view_header::<USN>(&headers)).clone();
+        
+        let version = try!(message::server_version(&headers, &policy, &mut warnings));
+        let boot_id = lenient::boot_id(&headers, &policy, &mut warnings).map(|n| n.0);
+        
+        // Devices Are Required To Identify Their Boot Instance From UPnP 1.1 On
+        if version.requires_boot_id() {
+            try!(boot_id.ok_or(SSDPError::MissingHeader(BootID::header_name())));
+            try!(try_view_header::<ConfigID>(&headers));
+        }
+        
+        Ok(SearchResponse{ headers: headers, remote: remote, max_age: Duration::seconds(max_age as i64),
+            location: location, target: target, usn: usn, version: version, boot_id: boot_id,
+            warnings: warnings })
+    }
+    
+    /// Returns the address that the response was received from.
+    pub fn remote(&self) -> SocketAddr {
+        self.remote
+    }
+    
+    /// Returns the max-age directive attached to the response.
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+    
+    /// Returns the location of the root device description.
+    pub fn location(&self) -> &Url {
+        &self.location
+    }
+    
+    /// Returns the search target that the device matched.
+    pub fn target(&self) -> &ST {
+        &self.target
+    }
+    
+    /// Returns the unique service name of the device or service that responded.
+    pub fn usn(&self) -> &USN {
+        &self.usn
+    }
+    
+    /// Returns the implemented UPnP version that the device is compatible with.
+    pub fn upnp_version(&self) -> UPnPVersion {
+        self.version
+    }
+    
+    /// Returns the Server header field containing environment information.
+    pub fn server_info(&self) -> Option<&str> {
+        self.headers.view::<Server>().map(|n| &n.0[..])
+    }
+    
+    /// Returns the product tokens parsed out of the Server header.
+    ///
+    /// Returns None if the header was missing or did not follow the
+    /// "OS/version UPnP/major.minor product/version" form.
+    pub fn server_tokens(&self) -> Option<&ProductTokens> {
+        self.headers.view::<ServerTokens>().map(|n| &n.0)
+    }
+    
+    /// Returns the boot instance of the device, sent by UPnP 1.1 devices and later.
+    pub fn boot_id(&self) -> Option<u32> {
+        self.boot_id
+    }
+    
+    /// Returns the configuration number of the device, sent by UPnP 1.1 devices
+    /// and later.
+    pub fn config_id(&self) -> Option<u32> {
+        self.headers.view::<ConfigID>().map(|n| n.0)
+    }
+    
+    /// Returns the port that the device answers unicast search requests on.
+    pub fn search_port(&self) -> u16 {
+        self.headers.view::<SearchPort>().map(|n| n.0).unwrap_or(DEFAULT_SEARCH_PORT)
+    }
+    
+    /// Returns the secure location advertised by a UPnP 2.0 device, resolved
+    /// against the regular location of the device.
+    pub fn secure_location(&self) -> Option<Url> {
+        self.headers.view::<SecureLocation>().and_then(|n|
+            UrlParser::new().base_url(&self.location).parse(n.0.trim()).ok()
+        )
+    }
+    
+    /// Returns the deviations from the specification that were put up with
+    /// when parsing the response.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+}
+
+impl MessageExt for SearchResponse {
+    fn check_header(&self, name: &str) -> Option<&[Vec<u8>]> {
+        self.headers.view_raw(name)
+    }
+}
+
+/// Attempts to get a reference to a header value H.
+fn try_view_header<H>(headers: &Headers) -> SSDPResult<&H> where H: Header + HeaderFormat {
+    headers.view::<H>().ok_or(SSDPError::MissingHeader(H::header_name()))
+}
+
+/// Returns the first max-age directive found in the list.
+fn first_max_age(directives: &[CacheDirective]) -> SSDPResult<u32> {
+    for i in directives.iter() {
+        if let &CacheDirective::MaxAge(n) = i {
+            return Ok(n)
+        }
+    }
+    
+    Err(SSDPError::InvalidHeader(CacheControl::header_name(), "No Max-Age Found"))
+}
+
+/// Returns the location as a Url object.
+fn location_as_url(location: &str) -> SSDPResult<Url> {
+    Url::parse(location.trim()).map_err(|_|
+        SSDPError::InvalidHeader(Location::header_name(), "Could Not Parse Location As A Url")
+    )
+}
+
+/// Returns the host as a SocketAddr, using the default search port if none was given.
+fn host_as_socket_addr(host: &Host) -> SSDPResult<SocketAddr> {
+    let port = host.port.unwrap_or(DEFAULT_SEARCH_PORT);
+    
+    format!("{}:{}", host.hostname, port).parse().map_err(|_|
+        SSDPError::InvalidHeader(Host::header_name(), "Host Is Not An IP Address")
+    )
+}
+
+fn to_socket_addr(addr: (&str, u16)) -> SocketAddr {
+    format!("{}:{}", addr.0, addr.1).parse().unwrap()
+}
+
+fn host_ip(addr: &SocketAddr) -> String {
+    match *addr {
+        SocketAddr::V4(ref n) => n.ip().to_string(),
+        SocketAddr::V6(ref n) => format!("[{}]", n.ip())
+    }
+}
+
+fn host_port(addr: &SocketAddr) -> u16 {
+    match *addr {
+        SocketAddr::V4(ref n) => n.port(),
+        SocketAddr::V6(ref n) => n.port()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{SocketAddr};
+    
+    use hyper::header::{Host};
+    
+    use ssdp::{FieldPair, Policy, Strictness};
+    use ssdp::header::{MX, ProductTokens, ST};
+    use ssdp::packet::{Packet};
+    use ssdp::version::{UPnPVersion, UPNP_11_VERSION};
+    use super::{SearchRequest, SearchResponse};
+    
+    const RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+        CACHE-CONTROL: max-age=1800\r\n\
+        EXT:\r\n\
+        LOCATION: http://192.168.1.5:49152/desc.xml\r\n\
+        SERVER: Linux/3.2 UPnP/1.1 omni/0.0.1\r\n\
+        ST: upnp:rootdevice\r\n\
+        USN: uuid:ae239f00-ae2b-bbad-ddf1-88ddcc00a234::upnp:rootdevice\r\n\
+        BOOTID.UPNP.ORG: 7\r\n\
+        CONFIGID.UPNP.ORG: 3\r\n\
+        SEARCHPORT.UPNP.ORG: 49200\r\n\r\n";
+    const SERVER_LINE:       &'static str = "SERVER: Linux/3.2 UPnP/1.1 omni/0.0.1\r\n";
+    const BOOT_ID_LINE:      &'static str = "BOOTID.UPNP.ORG: 7\r\n";
+    const WIDE_BOOT_ID_LINE: &'static str = "BOOTID.UPNP.ORG: 3000000000\r\n";
+    const CONFIG_ID_LINE:    &'static str = "CONFIGID.UPNP.ORG: 3\r\n";
+    
+    fn remote() -> SocketAddr {
+        "192.168.1.5:1900".parse().unwrap()
+    }
+    
+    #[test]
+    fn positive_multicast_request() {
+        let request = SearchRequest::multicast(ST::All, MX(2)).to_packet();
+        
+        assert_eq!(request.headers().get::<MX>(), Some(&MX(2)));
+        assert_eq!(request.headers().get::<Host>().unwrap().hostname, "239.255.255.250");
+    }
+    
+    #[test]
+    fn positive_unicast_request() {
+        let request = SearchRequest::unicast(ST::All, remote()).to_packet();
+        
+        assert!(request.headers().get::<MX>().is_none());
+        assert_eq!(request.headers().get::<Host>().unwrap().hostname, "192.168.1.5");
+    }
+    
+    #[test]
+    fn positive_parse_request() {
+        let packet = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\n\
+            MX: 3\r\nST: ssdp:all\r\n\r\n";
+        let request = SearchRequest::new(&Packet::parse(packet).unwrap()).unwrap();
+        
+        assert!(request.is_multicast());
+        assert_eq!(request.mx(), Some(MX(3)));
+        assert_eq!(*request.target(), ST::All);
+    }
+    
+    #[test]
+    fn positive_parse_request_round_trip() {
+        let packet = SearchRequest::unicast(ST::All, remote()).to_packet();
+        let request = SearchRequest::new(&Packet::parse(&packet.to_bytes()[..]).unwrap()).unwrap();
+        
+        assert!(!request.is_multicast());
+        assert_eq!(request.host(), remote());
+    }
+    
+    #[test]
+    fn positive_user_agent_round_trip() {
+        let user_agent = ProductTokens::new("Linux", "3.2", UPnPVersion::new(1, 2), "omni", "0.0.1");
+        let packet = SearchRequest::multicast(ST::All, MX(2)).with_user_agent(user_agent.clone()).to_packet();
+        let request = SearchRequest::new(&Packet::parse(&packet.to_bytes()[..]).unwrap()).unwrap();
+        
+        assert_eq!(request.user_agent(), Some(&user_agent));
+        assert_eq!(request.upnp_version().compatible(), Some(UPNP_11_VERSION));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_multicast_missing_mx() {
+        let packet = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\n\
+            ST: ssdp:all\r\n\r\n";
+        
+        SearchRequest::new(&Packet::parse(packet).unwrap()).unwrap();
+    }
+    
+    #[test]
+    fn positive_lenient_man_case() {
+        let packet = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"SSDP:discover\"\r\n\
+            MX: 3\r\nST: ssdp:all\r\n\r\n";
+        let policy = Policy::new(Strictness::Lenient);
+        let request = SearchRequest::parse(&Packet::parse(packet).unwrap(), policy).unwrap();
+        
+        assert_eq!(request.warnings()[0].header(), "MAN");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_strict_man_case() {
+        let packet = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"SSDP:discover\"\r\n\
+            MX: 3\r\nST: ssdp:all\r\n\r\n";
+        
+        SearchRequest::new(&Packet::parse(packet).unwrap()).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_man() {
+        let packet = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMX: 3\r\nST: ssdp:all\r\n\r\n";
+        
+        SearchRequest::new(&Packet::parse(packet).unwrap()).unwrap();
+    }
+    
+    #[test]
+    fn positive_search_response() {
+        let response = SearchResponse::new(Packet::parse(RESPONSE).unwrap(), remote()).unwrap();
+        
+        assert_eq!(response.location().serialize(), "http://192.168.1.5:49152/desc.xml");
+        assert_eq!(*response.target(), ST::Target(FieldPair::UPnP(b"rootdevice".to_vec())));
+        assert_eq!(response.boot_id(), Some(7));
+        assert_eq!(response.config_id(), Some(3));
+        assert_eq!(response.upnp_version(), UPNP_11_VERSION);
+        assert_eq!(response.search_port(), 49200);
+        assert_eq!(response.server_tokens().unwrap().product(), "omni");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_location() {
+        let packet = b"HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nST: ssdp:all\r\n\
+            USN: uuid:ae239f00-ae2b-bbad-ddf1-88ddcc00a234\r\n\r\n";
+        
+        SearchResponse::new(Packet::parse(packet).unwrap(), remote()).unwrap();
+    }
+    
+    #[test]
+    fn positive_lenient_missing_server() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(SERVER_LINE, "");
+        let response = SearchResponse::parse(Packet::parse(packet.as_bytes()).unwrap(), remote(),
+            Policy::new(Strictness::Lenient)).unwrap();
+        
+        assert_eq!(response.server_info(), None);
+        assert_eq!(response.warnings()[0].header(), "Server");
+    }
+    
+    #[test]
+    fn positive_lenient_wide_boot_id() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(BOOT_ID_LINE, WIDE_BOOT_ID_LINE);
+        let policy = Policy::new(Strictness::Strict).with_boot_id_range(Strictness::Lenient);
+        let response = SearchResponse::parse(Packet::parse(packet.as_bytes()).unwrap(), remote(),
+            policy).unwrap();
+        
+        assert_eq!(response.boot_id(), Some(3000000000));
+        assert_eq!(response.warnings().len(), 1);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_strict_wide_boot_id() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(BOOT_ID_LINE, WIDE_BOOT_ID_LINE);
+        
+        SearchResponse::new(Packet::parse(packet.as_bytes()).unwrap(), remote()).unwrap();
+    }
+    
+    #[test]
+    fn positive_higher_minor_version() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace("UPnP/1.1", "UPnP/1.2");
+        let response = SearchResponse::new(Packet::parse(packet.as_bytes()).unwrap(), remote()).unwrap();
+        
+        assert_eq!(response.upnp_version(), UPNP_11_VERSION);
+        assert_eq!(response.server_tokens().unwrap().upnp_version(), UPnPVersion::new(1, 2));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_config_id() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(CONFIG_ID_LINE, "");
+        
+        SearchResponse::new(Packet::parse(packet.as_bytes()).unwrap(), remote()).unwrap();
+    }
+    
+    #[test]
+    fn positive_version_one_without_config_id() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(CONFIG_ID_LINE, "")
+            .replace("UPnP/1.1", "UPnP/1.0");
+        let response = SearchResponse::new(Packet::parse(packet.as_bytes()).unwrap(), remote()).unwrap();
+        
+        assert_eq!(response.config_id(), None);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_strict_missing_server() {
+        let packet = String::from_utf8_lossy(RESPONSE).replace(SERVER_LINE, "");
+        
+        SearchResponse::new(Packet::parse(packet.as_bytes()).unwrap(), remote()).unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_request_packet() {
+        let packet = Packet::parse(b"M-SEARCH * HTTP/1.1\r\n\r\n").unwrap();
+        
+        SearchResponse::new(packet, remote()).unwrap();
+    }
+}
@@ -3,11 +3,24 @@
 //! This module deals with interface discovery as well as HTTP extensions for
 //! accomodating SSDP.
 
+use std::error::{self};
 use std::fmt::{Display, Error, Formatter};
 use std::result::{Result};
 
+use {SSDPError};
+
+pub mod advertiser;
+#[cfg(test)]
+mod fixtures;
 pub mod header;
+mod lenient;
+#[cfg(test)]
+pub mod loopback;
 pub mod message;
+pub mod packet;
+pub mod responder;
+pub mod search;
+pub mod version;
 
 /// Separator character for a FieldPair and it's value.
 const PAIR_SEPARATOR: u8 = b':';
@@ -19,6 +32,107 @@ const UUID_PREFIX: &'static str = "uuid";
 /// Prefix for the "usn" field key.
 const URN_PREFIX:  &'static str = "urn";
 
+/// How closely packets received off of the network have to follow the UPnP
+/// Device Architecture in order to be accepted.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Strictness {
+    /// Reject any packet that does not follow the specification.
+    Strict,
+    /// Accept packets with quirks that are common among devices in the wild,
+    /// recording a Warning for each quirk that was put up with.
+    ///
+    /// Header names are matched without regard to case in either mode.
+    Lenient
+}
+
+/// Strictness applied to each of the known quirks when parsing packets.
+///
+/// Quirks that are parsed leniently are recorded as warnings on the message
+/// instead of causing it to be rejected.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Policy {
+    quirks:         Strictness,
+    man_case:       Strictness,
+    boot_id_range:  Strictness,
+    server_version: Strictness
+}
+
+impl Policy {
+    /// Create a new Policy applying the same strictness to every quirk.
+    pub fn new(strictness: Strictness) -> Policy {
+        Policy{ quirks: strictness, man_case: strictness, boot_id_range: strictness,
+            server_version: strictness }
+    }
+    
+    /// Whitespace around the "=" of a CACHE-CONTROL directive, as in
+    /// "max-age = 1800", and a LOCATION without a scheme, taken to be http.
+    pub fn with_quirks(mut self, strictness: Strictness) -> Policy {
+        self.quirks = strictness;
+        
+        self
+    }
+    
+    /// A MAN header of "ssdp:discover" in some other case.
+    pub fn with_man_case(mut self, strictness: Strictness) -> Policy {
+        self.man_case = strictness;
+        
+        self
+    }
+    
+    /// A BOOTID.UPNP.ORG header that does not fit in 31 bits.
+    pub fn with_boot_id_range(mut self, strictness: Strictness) -> Policy {
+        self.boot_id_range = strictness;
+        
+        self
+    }
+    
+    /// A SERVER header that is missing or does not contain an exact UPnP
+    /// version, as in "UPnP 1.0", in which case UPnP 1.0 is assumed unless a
+    /// close enough version was found.
+    pub fn with_server_version(mut self, strictness: Strictness) -> Policy {
+        self.server_version = strictness;
+        
+        self
+    }
+}
+
+impl Default for Policy {
+    /// Policy used by searches and responders unless told otherwise, which
+    /// puts up with every known quirk so that quirky peers are not shut out.
+    fn default() -> Policy {
+        Policy::new(Strictness::Lenient)
+    }
+}
+
+/// Deviation from the specification that was put up with when parsing a packet.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Warning {
+    header: &'static str,
+    reason: &'static str
+}
+
+impl Warning {
+    fn new(header: &'static str, reason: &'static str) -> Warning {
+        Warning{ header: header, reason: reason }
+    }
+    
+    /// Returns the name of the header that the deviation was found in.
+    pub fn header(&self) -> &'static str {
+        self.header
+    }
+    
+    /// Returns a description of the deviation.
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        f.write_fmt(format_args!("{}: {}", self.header, self.reason))
+    }
+}
+
 /// Key value pairs embedded within SSDP header fields.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum FieldPair {
@@ -101,6 +215,11 @@ fn matches_upnp_key(key: &[u8]) -> bool {
     UPNP_PREFIX.as_bytes() == key
 }
 
+/// Wraps some other error, usually from the network, as an SSDPError.
+fn to_ssdp_error<E>(err: E) -> SSDPError where E: error::Error + 'static {
+    SSDPError::Other(Box::new(err) as Box<error::Error>)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{FieldPair};
@@ -0,0 +1,173 @@
+//! HTTPU packets that carry SSDP messages.
+//!
+//! SSDP messages are HTTP messages sent within a single UDP packet, so they
+//! have no body and are parsed in one pass.
+
+use std::str;
+
+use hyper::header::{Header, HeaderFormat, Headers};
+
+use {SSDPError, SSDPResult};
+
+const HTTP_VERSION: &'static str = "HTTP/1.1";
+const HTTP_PREFIX:  &'static str = "HTTP/";
+const STAR_URI:     &'static str = "*";
+
+const LINE_TERMINATOR:  &'static str = "\r\n";
+const HEADER_SEPARATOR: char = ':';
+
+/// First line of an HTTPU packet.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum StartLine {
+    /// Request with its method and uri.
+    Request(String, String),
+    /// Response with its status code and reason phrase.
+    Response(u16, String)
+}
+
+/// HTTPU packet consisting of a start line and headers.
+#[derive(Clone, Debug)]
+pub struct Packet {
+    start:   StartLine,
+    headers: Headers
+}
+
+impl Packet {
+    /// Create a new request packet with the given method for the "*" uri.
+    pub fn request(method: &str) -> Packet {
+        Packet{ start: StartLine::Request(method.to_string(), STAR_URI.to_string()), headers: Headers::new() }
+    }
+    
+    /// Create a new response packet with the given status code and reason.
+    pub fn response(code: u16, reason: &str) -> Packet {
+        Packet{ start: StartLine::Response(code, reason.to_string()), headers: Headers::new() }
+    }
+    
+    /// Parse a packet received off of the network.
+    pub fn parse(data: &[u8]) -> SSDPResult<Packet> {
+        let text = try!(str::from_utf8(data).map_err(|_| SSDPError::InvalidHttp(data.to_vec())));
+        
+        // Headers End At The First Empty Line, Anything After Is Ignored
+        let mut lines = text.split('\n').map(|n| n.trim_right_matches('\r'));
+        
+        let start = try!(lines.next().and_then(parse_start_line).ok_or(
+            SSDPError::InvalidHttp(data.to_vec())
+        ));
+        
+        let mut headers = Headers::new();
+        for line in lines.take_while(|n| !n.is_empty()) {
+            let (name, value) = match line.find(HEADER_SEPARATOR) {
+                Some(n) => (line[..n].trim(), line[n + 1..].trim()),
+                None    => return Err(SSDPError::InvalidHttp(data.to_vec()))
+            };
+            if name.is_empty() {
+                return Err(SSDPError::InvalidHttp(data.to_vec()))
+            }
+            
+            // Repeated Headers Are Kept As Multiple Values
+            let mut values = headers.get_raw(name).map(|n| n.to_vec()).unwrap_or(Vec::new());
+            values.push(value.as_bytes().to_vec());
+            headers.set_raw(name.to_string(), values);
+        }
+        
+        Ok(Packet{ start: start, headers: headers })
+    }
+    
+    /// Returns the start line of the packet.
+    pub fn start_line(&self) -> &StartLine {
+        &self.start
+    }
+    
+    /// Returns the headers of the packet.
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+    
+    /// Returns the headers of the packet for modification.
+    pub fn headers_mut(&mut self) -> &mut Headers {
+        &mut self.headers
+    }
+    
+    /// Set a typed header on the packet.
+    pub fn set<H>(&mut self, header: H) where H: Header + HeaderFormat {
+        self.headers.set(header);
+    }
+    
+    /// Set a header on the packet from its name and textual value.
+    pub fn set_raw(&mut self, name: &str, value: &str) {
+        self.headers.set_raw(name.to_string(), vec![value.as_bytes().to_vec()]);
+    }
+    
+    /// Serialize the packet so that it can be sent out.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let start = match self.start {
+            StartLine::Request(ref method, ref uri) => format!("{} {} {}", method, uri, HTTP_VERSION),
+            StartLine::Response(code, ref reason)   => format!("{} {} {}", HTTP_VERSION, code, reason)
+        };
+        
+        format!("{}{}{}{}", start, LINE_TERMINATOR, self.headers, LINE_TERMINATOR).into_bytes()
+    }
+}
+
+/// Parse the start line of a packet.
+fn parse_start_line(line: &str) -> Option<StartLine> {
+    let mut parts = line.splitn(3, ' ');
+    
+    let (first, second, third) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(a), Some(b), Some(c)) => (a, b, c.trim()),
+        _ => return None
+    };
+    
+    if first.starts_with(HTTP_PREFIX) {
+        second.parse::<u16>().ok().map(|n| StartLine::Response(n, third.to_string()))
+    } else if third.starts_with(HTTP_PREFIX) {
+        Some(StartLine::Request(first.to_string(), second.to_string()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssdp::header::{MX};
+    use super::{Packet, StartLine};
+    
+    #[test]
+    fn positive_request() {
+        let data = b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMX: 2\r\n\r\n";
+        let packet = Packet::parse(data).unwrap();
+        
+        assert_eq!(*packet.start_line(), StartLine::Request("M-SEARCH".to_string(), "*".to_string()));
+        assert_eq!(packet.headers().get::<MX>(), Some(&MX(2)));
+    }
+    
+    #[test]
+    fn positive_response() {
+        let packet = Packet::parse(b"HTTP/1.1 200 OK\r\nst: ssdp:all\r\n\r\n").unwrap();
+        
+        assert_eq!(*packet.start_line(), StartLine::Response(200, "OK".to_string()));
+        assert_eq!(packet.headers().get_raw("ST").unwrap()[0], b"ssdp:all".to_vec());
+    }
+    
+    #[test]
+    fn positive_round_trip() {
+        let mut packet = Packet::request("M-SEARCH");
+        packet.set(MX(3));
+        
+        let parsed = Packet::parse(&packet.to_bytes()[..]).unwrap();
+        
+        assert_eq!(parsed.headers().get::<MX>(), Some(&MX(3)));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_separator() {
+        Packet::parse(b"HTTP/1.1 200 OK\r\nST ssdp:all\r\n\r\n").unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_start_line() {
+        Packet::parse(b"HELLO\r\n\r\n").unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+//! Answers search requests for a root device that we are hosting.
+//!
+//! Multicast search requests are answered after a random delay of up to MX
+//! seconds so that control points are not flooded with responses, whereas
+//! unicast search requests are answered right away.
+
+use std::cmp;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self};
+
+use rand::{self, Rng};
+use time::{Duration, PreciseTime};
+
+use {SSDPError, SSDPResult, SimpleError};
+use net::{self};
+use net::receiver::{PacketListener};
+use ssdp::{self, Policy};
+use ssdp::advertiser::{Advertisement};
+use ssdp::header::{SEARCHPORT_MIN_VALUE, SEARCHPORT_MAX_VALUE};
+use ssdp::message::search::{SearchRequest, DEFAULT_SEARCH_PORT};
+use ssdp::packet::{Packet};
+
+/// Time to sleep between checks for received requests.
+const POLL_INTERVAL_MS: u32 = 10;
+
+/// Largest MX value that devices should honor, as specified in the UPnP 1.1 standard.
+const MAX_RESPONSE_MX: u8 = 5;
+
+/// Search responses waiting for their random delay to pass.
+struct PendingResponse {
+    received: PreciseTime,
+    delay:    Duration,
+    dest:     SocketAddr,
+    packets:  Vec<Packet>
+}
+
+/// Answers search requests for an Advertisement until it is dropped.
+///
+/// If port 1900 is taken by some other process, multicast search requests are
+/// still received on it, but unicast search requests are received on a port
+/// advertised through the SEARCHPORT.UPNP.ORG header instead. Because of this,
+/// the Responder should be started before the Advertiser.
+pub struct Responder {
+    search_port: Option<u16>,
+    kill_flag:   Arc<AtomicBool>
+}
+
+impl Responder {
+    /// Start answering search requests for the given advertisement, parsing
+    /// them under the given Policy.
+    pub fn start(advert: Arc<Mutex<Advertisement>>, policy: Policy) -> SSDPResult<Responder> {
+        let (multicast, unicast) = try!(bind_sockets());
+        let search_port = match unicast {
+            Some(ref n) => Some(try!(local_port(n))),
+            None        => None
+        };
+        advert.lock().unwrap().set_search_port(search_port);
+        
+        Responder::spawn(advert, multicast, unicast, search_port, policy)
+    }
+    
+    /// Start answering search requests received on the given socket instead of
+    /// the multicast group, such as a socket bound to the loopback interface.
+    ///
+    /// No SEARCHPORT.UPNP.ORG header is advertised for the socket.
+    pub fn start_on(advert: Arc<Mutex<Advertisement>>, udp: UdpSocket, policy: Policy)
+        -> SSDPResult<Responder> {
+        advert.lock().unwrap().set_search_port(None);
+        
+        Responder::spawn(advert, udp, None, None, policy)
+    }
+    
+    fn spawn(advert: Arc<Mutex<Advertisement>>, multicast: UdpSocket, unicast: Option<UdpSocket>,
+        search_port: Option<u16>, policy: Policy) -> SSDPResult<Responder> {
+        let multicast_listener = try!(PacketListener::new(&multicast).map_err(ssdp::to_ssdp_error));
+        let unicast_listener = match unicast {
+            Some(ref n) => Some(try!(PacketListener::new(n).map_err(ssdp::to_ssdp_error))),
+            None        => None
+        };
+        let reply = try!(UdpSocket::bind(("0.0.0.0", 0)).map_err(ssdp::to_ssdp_error));
+        
+        let kill_flag = Arc::new(AtomicBool::new(false));
+        let kill_clone = kill_flag.clone();
+        thread::spawn(move || {
+            // Sockets Have To Outlive The Listeners
+            let _sockets = (multicast, unicast);
+            let mut pending = Vec::new();
+            let mut rng = rand::thread_rng();
+            
+            while !kill_clone.load(Ordering::SeqCst) {
+                let listeners = Some(&multicast_listener).into_iter().chain(unicast_listener.iter());
+                for listener in listeners {
+                    while let Some((pckt, addr)) = listener.try_recv() {
+                        if let Some(n) = answer_request(&pckt[..], addr, &advert, policy, &mut rng) {
+                            pending.push(n);
+                        }
+                    }
+                }
+                
+                send_due_responses(&reply, &mut pending);
+                thread::sleep_ms(POLL_INTERVAL_MS);
+            }
+        });
+        
+        Ok(Responder{ search_port: search_port, kill_flag: kill_flag })
+    }
+    
+    /// Returns the port that unicast search requests are being received on, if
+    /// it is not the default search port.
+    pub fn search_port(&self) -> Option<u16> {
+        self.search_port
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        self.kill_flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Bind the socket that multicast search requests are received on, along with
+/// a socket for unicast search requests if port 1900 could not be claimed.
+fn bind_sockets() -> SSDPResult<(UdpSocket, Option<UdpSocket>)> {
+    let group = IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250));
+    
+    // Port 1900 Is Ours Alone, Answer Unicast Searches On It As Well
+    if let Ok(udp) = UdpSocket::bind(("0.0.0.0", DEFAULT_SEARCH_PORT)) {
+        try!(udp.join_multicast(&group).map_err(ssdp::to_ssdp_error));
+        
+        return Ok((udp, None))
+    }
+    
+    // Share Port 1900 For Multicast Searches, Pick A SEARCHPORT For Unicast Searches
+    let multicast = try!(net::reuse_socket(("0.0.0.0", DEFAULT_SEARCH_PORT)).map_err(ssdp::to_ssdp_error));
+    try!(multicast.join_multicast(&group).map_err(ssdp::to_ssdp_error));
+    
+    // Widen The Bounds So SEARCHPORT_MAX_VALUE Is Included
+    for port in (SEARCHPORT_MIN_VALUE as u32..SEARCHPORT_MAX_VALUE as u32 + 1).map(|n| n as u16) {
+        if let Ok(unicast) = UdpSocket::bind(("0.0.0.0", port)) {
+            return Ok((multicast, Some(unicast)))
+        }
+    }
+    
+    Err(SSDPError::Other(Box::new(SimpleError::new("No Search Port Available"))))
+}
+
+/// Returns the port that the socket is bound to.
+fn local_port(udp: &UdpSocket) -> SSDPResult<u16> {
+    match try!(udp.local_addr().map_err(ssdp::to_ssdp_error)) {
+        SocketAddr::V4(n) => Ok(n.port()),
+        SocketAddr::V6(n) => Ok(n.port())
+    }
+}
+
+/// Build the responses to a search request, if it is valid under the policy
+/// and matches any of our devices or services.
+fn answer_request<R>(pckt: &[u8], addr: SocketAddr, advert: &Mutex<Advertisement>, policy: Policy,
+    rng: &mut R) -> Option<PendingResponse> where R: Rng {
+    let request = match Packet::parse(pckt).and_then(|n| SearchRequest::parse(&n, policy)) {
+        Ok(n)  => n,
+        Err(_) => return None
+    };
+    
+    let packets = advert.lock().unwrap().search_responses(request.target());
+    if packets.is_empty() {
+        return None
+    }
+    
+    // Spread Multicast Responses Out Over [0, MX] Seconds, MX Values Above 5 Are Treated As 5
+    let delay = match request.mx() {
+        Some(mx) if request.is_multicast() => {
+            let mx = cmp::min(mx.0, MAX_RESPONSE_MX);
+            
+            rng.gen_range(0, mx as i64 * 1000 + 1)
+        },
+        _ => 0
+    };
+    
+    Some(PendingResponse{ received: PreciseTime::now(), delay: Duration::milliseconds(delay), dest: addr,
+        packets: packets })
+}
+
+/// Send out every pending response whose delay has passed.
+fn send_due_responses(udp: &UdpSocket, pending: &mut Vec<PendingResponse>) {
+    let now = PreciseTime::now();
+    
+    let (due, waiting): (Vec<_>, Vec<_>) = mem::replace(pending, Vec::new()).into_iter().partition(|n|
+        n.received.to(now) >= n.delay
+    );
+    *pending = waiting;
+    
+    for response in due.into_iter() {
+        for packet in response.packets.iter() {
+            // Nothing We Can Do If This Fails...
+            let _ = udp.send_to(&packet.to_bytes()[..], response.dest);
+        }
+    }
+}
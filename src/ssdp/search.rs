@@ -0,0 +1,389 @@
+//! Sends search requests out and gathers up the responses.
+//!
+//! Multicast searches wait for MX seconds, plus a grace period to account for
+//! network delays, before returning every device that responded. Since SSDP
+//! runs over UDP, multicast requests are sent more than once to make up for
+//! packets that get lost along the way. Unicast
+//! searches are sent straight to a single device, which is a cheap way of
+//! checking that a known device is still around without flooding the network.
+//!
+//! Requests carry a USER-AGENT header advertising UPnP 1.1, which devices of
+//! a later version answer in a backward compatible way.
+
+use std::collections::{HashSet};
+use std::env::{consts};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+
+use rand::{self, Rng};
+use time::{Duration, PreciseTime};
+use url::{Url};
+
+use {SSDPResult};
+use net::receiver::{PacketListener};
+use ssdp::{self, FieldPair, Policy};
+use ssdp::header::{MX, ProductTokens, ST, USN};
+use ssdp::message::search::{SearchRequest, SearchResponse, DEFAULT_SEARCH_PORT};
+use ssdp::packet::{Packet};
+use ssdp::version::{UPnPVersion, UPNP_11_VERSION};
+
+/// Default amount of time to wait for a device to answer a unicast search.
+pub const DEFAULT_UNICAST_WAIT_MS: i64 = 1000;
+
+/// Default amount of time to keep waiting for responses after MX has elapsed.
+pub const DEFAULT_GRACE_PERIOD_MS: i64 = 500;
+
+/// Default number of copies of a multicast search request to send.
+pub const DEFAULT_SEARCH_COPIES: u8 = 2;
+
+/// UPnP version advertised by our search requests.
+pub const SEARCH_VERSION: UPnPVersion = UPNP_11_VERSION;
+
+/// OS version advertised by our search requests, which is not available in a
+/// portable way.
+const OS_VERSION: &'static str = "unknown";
+
+/// Bounds on the random delay between copies of a multicast search request.
+const MIN_RESEND_DELAY_MS: i64 = 100;
+const MAX_RESEND_DELAY_MS: i64 = 500;
+
+/// Responses collected from a multicast search.
+#[derive(Clone, Debug)]
+pub struct SearchReport {
+    responses: Vec<SearchResponse>,
+    late:      usize
+}
+
+impl SearchReport {
+    /// Returns the distinct responses that were received.
+    pub fn responses(&self) -> &[SearchResponse] {
+        &self.responses[..]
+    }
+    
+    /// Returns the number of distinct devices whose first response was received
+    /// after MX had elapsed since the first copy of the request was sent.
+    ///
+    /// A high number relative to the total is a sign of a lossy network.
+    pub fn late_responses(&self) -> usize {
+        self.late
+    }
+    
+    /// Consume the report, returning the responses.
+    pub fn into_responses(self) -> Vec<SearchResponse> {
+        self.responses
+    }
+}
+
+/// Multicast a search request and collect the responses.
+///
+/// Responses are parsed under the default Policy and de-duplicated by their USN.
+///
+/// This is a blocking operation.
+pub fn search(target: ST, mx: MX) -> SSDPResult<Vec<SearchResponse>> {
+    let grace = Duration::milliseconds(DEFAULT_GRACE_PERIOD_MS);
+    
+    search_with(target, mx, DEFAULT_SEARCH_COPIES, grace, Policy::default(), |_| ())
+        .map(|n| n.into_responses())
+}
+
+/// Multicast the given number of copies of a search request at random
+/// intervals and collect the responses, waiting for MX plus the given grace
+/// period after the last copy was sent. At least one copy is always sent.
+///
+/// Responses are parsed under the given Policy, and the callback is invoked
+/// with each distinct response as soon as it arrives.
+///
+/// This is a blocking operation.
+pub fn search_with<F>(target: ST, mx: MX, copies: u8, grace: Duration, policy: Policy, on_response: F)
+    -> SSDPResult<SearchReport> where F: FnMut(&SearchResponse) {
+    let request = SearchRequest::multicast(target, mx).with_user_agent(user_agent());
+    let dest = request.host();
+    
+    send_search(&request, dest, copies, grace, policy, on_response)
+}
+
+/// Returns the product tokens that describe us in the USER-AGENT header.
+fn user_agent() -> ProductTokens {
+    ProductTokens::new(consts::OS, OS_VERSION, SEARCH_VERSION, env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"))
+}
+
+/// Send copies of the multicast search request to the given address and
+/// collect the responses, see search_with.
+fn send_search<F>(request: &SearchRequest, dest: SocketAddr, copies: u8, grace: Duration, policy: Policy,
+    mut on_response: F) -> SSDPResult<SearchReport> where F: FnMut(&SearchResponse) {
+    let payload = request.to_packet().to_bytes();
+    let wait = Duration::seconds(request.mx().map_or(0, |n| n.0) as i64) + grace;
+    
+    let udp = try!(UdpSocket::bind(("0.0.0.0", 0)).map_err(ssdp::to_ssdp_error));
+    let listener = try!(PacketListener::new(&udp).map_err(ssdp::to_ssdp_error));
+    try!(udp.send_to(&payload[..], dest).map_err(ssdp::to_ssdp_error));
+    let deadline = PreciseTime::now() + Duration::seconds(request.mx().map_or(0, |n| n.0) as i64);
+    
+    let mut collector = ResponseCollector::new(policy);
+    collector.set_deadline(deadline);
+    let mut rng = rand::thread_rng();
+    for _ in 1..copies {
+        let delay = rng.gen_range(MIN_RESEND_DELAY_MS, MAX_RESEND_DELAY_MS + 1);
+        collector.collect(&listener, Duration::milliseconds(delay), &mut on_response);
+        
+        try!(udp.send_to(&payload[..], dest).map_err(ssdp::to_ssdp_error));
+    }
+    collector.collect(&listener, wait, &mut on_response);
+    
+    Ok(collector.into_report())
+}
+
+/// Send a unicast search request to the device at the given address.
+///
+/// If the device advertised a SEARCHPORT.UPNP.ORG header, that port should be
+/// passed in, otherwise the default search port is used. All responses received
+/// within the wait time that are valid under the given Policy are returned.
+///
+/// This is a blocking operation.
+pub fn search_device(device: Ipv4Addr, search_port: Option<u16>, target: ST, wait: Duration, policy: Policy)
+    -> SSDPResult<Vec<SearchResponse>> {
+    let remote = SocketAddr::V4(SocketAddrV4::new(device, search_port.unwrap_or(DEFAULT_SEARCH_PORT)));
+    let request = SearchRequest::unicast(target, remote).with_user_agent(user_agent());
+    
+    let udp = try!(UdpSocket::bind(("0.0.0.0", 0)).map_err(ssdp::to_ssdp_error));
+    let listener = try!(PacketListener::new(&udp).map_err(ssdp::to_ssdp_error));
+    try!(udp.send_to(&request.to_packet().to_bytes()[..], remote).map_err(ssdp::to_ssdp_error));
+    
+    let mut collector = ResponseCollector::new(policy);
+    collector.collect(&listener, wait, &mut |_| ());
+    
+    Ok(collector.into_report().into_responses())
+}
+
+/// Recover the location of the root device with the given uuid by sending a
+/// unicast search request to the address it was last seen at.
+///
+/// Returns None if the device did not answer within the default wait time.
+///
+/// This is a blocking operation.
+pub fn locate_device(device: Ipv4Addr, search_port: Option<u16>, uuid: &str) -> SSDPResult<Option<Url>> {
+    let target = ST::Target(FieldPair::UUID(uuid.as_bytes().to_vec()));
+    let wait = Duration::milliseconds(DEFAULT_UNICAST_WAIT_MS);
+    
+    let responses = try!(search_device(device, search_port, target, wait, Policy::default()));
+    
+    Ok(responses.into_iter().next().map(|n| n.location().clone()))
+}
+
+/// Gathers up valid search responses, ignoring any USN that was already seen.
+struct ResponseCollector {
+    seen:      HashSet<USN>,
+    responses: Vec<SearchResponse>,
+    policy:    Policy,
+    deadline:  Option<PreciseTime>,
+    late:      HashSet<FieldPair>
+}
+
+impl ResponseCollector {
+    fn new(policy: Policy) -> ResponseCollector {
+        ResponseCollector{ seen: HashSet::new(), responses: Vec::new(), policy: policy, deadline: None,
+            late: HashSet::new() }
+    }
+    
+    /// Count any device whose first response is received after the MX deadline as late.
+    fn set_deadline(&mut self, deadline: PreciseTime) {
+        self.deadline = Some(deadline);
+    }
+    
+    /// Receive packets from the listener until the wait time has elapsed.
+    fn collect<F>(&mut self, listener: &PacketListener, wait: Duration, on_response: &mut F)
+        where F: FnMut(&SearchResponse) {
+        let start = PreciseTime::now();
+        
+        loop {
+            let remaining = wait - start.to(PreciseTime::now());
+            if remaining <= Duration::zero() {
+                break;
+            }
+            
+            match listener.recv_timeout(remaining) {
+                Some((pckt, addr)) => { self.push(&pckt[..], addr, on_response); },
+                None => break
+            }
+        }
+    }
+    
+    /// Add the packet to the collected responses, returning true if it was a
+    /// valid response from a USN that has not been seen yet.
+    fn push<F>(&mut self, pckt: &[u8], addr: SocketAddr, on_response: &mut F) -> bool
+        where F: FnMut(&SearchResponse) {
+        // Ignore Anything That Is Not A Valid Search Response
+        let policy = self.policy;
+        let parsed = Packet::parse(pckt).and_then(|n| SearchResponse::parse(n, addr, policy));
+        let response = match parsed {
+            Ok(n)  => n,
+            Err(_) => return false
+        };
+        
+        if !self.seen.insert(response.usn().clone()) {
+            return false
+        }
+        // A Device Is Late If None Of Its Responses Made The Deadline
+        let uuid = &response.usn().0;
+        let known = self.responses.iter().any(|n| n.usn().0 == *uuid);
+        if !known && self.deadline.map_or(false, |n| n.to(PreciseTime::now()) > Duration::zero()) {
+            self.late.insert(uuid.clone());
+        }
+        on_response(&response);
+        self.responses.push(response);
+        
+        true
+    }
+    
+    fn into_report(self) -> SearchReport {
+        SearchReport{ responses: self.responses, late: self.late.len() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::thread::{self};
+    
+    use time::{Duration, PreciseTime};
+    use url::{Url};
+    
+    use ssdp::{Policy, Strictness};
+    use ssdp::advertiser::{AdvertisedDevice, Advertisement};
+    use ssdp::header::{MX, ST};
+    use ssdp::loopback::{Conditions, LoopbackNetwork, ScriptedDevice};
+    use ssdp::message::search::{SearchRequest};
+    use super::{ResponseCollector};
+    
+    const RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+        CACHE-CONTROL: max-age=1800\r\n\
+        LOCATION: http://192.168.1.5:49152/desc.xml\r\n\
+        ST: upnp:rootdevice\r\n\
+        USN: uuid:ae239f00-ae2b-bbad-ddf1-88ddcc00a234::upnp:rootdevice\r\n\r\n";
+    
+    fn remote() -> SocketAddr {
+        "192.168.1.5:1900".parse().unwrap()
+    }
+    
+    fn advert(uuid: &str) -> Advertisement {
+        let device = AdvertisedDevice::new(uuid, "schemas-upnp-org:device:Basic:1");
+        
+        Advertisement::new(device, Url::parse("http://127.0.0.1:49152/desc.xml").unwrap(),
+            "Linux/3.0 UPnP/1.1 omni/0.0.1", 1, 1)
+    }
+    
+    #[test]
+    fn positive_loopback_search() {
+        let network = LoopbackNetwork::start().unwrap();
+        let good = advert("ae239f00-ae2b-bbad-ddf1-88ddcc00a234");
+        network.add_device(ScriptedDevice::new(good.clone())
+            .with_conditions(Conditions::new().with_delay(0, 50)));
+        network.add_device(ScriptedDevice::new(advert("ae239f00-ae2b-bbad-ddf1-88ddcc00a235"))
+            .with_conditions(Conditions::new().with_loss(100)));
+        network.add_device(ScriptedDevice::new(advert("ae239f00-ae2b-bbad-ddf1-88ddcc00a236"))
+            .with_conditions(Conditions::new().with_malformed(100)));
+        
+        let request = SearchRequest::multicast(ST::All, MX(1));
+        let report = super::send_search(&request, network.search_addr(), 2, Duration::milliseconds(200),
+            Policy::default(), |_| ()).unwrap();
+        
+        assert_eq!(report.responses().len(), good.matching_targets(&ST::All).len());
+        assert_eq!(network.requests(), 2);
+    }
+    
+    #[test]
+    fn positive_loopback_locate() {
+        let network = LoopbackNetwork::start().unwrap();
+        network.add_device(ScriptedDevice::new(advert("ae239f00-ae2b-bbad-ddf1-88ddcc00a234")));
+        
+        let location = super::locate_device(Ipv4Addr::new(127, 0, 0, 1), Some(network.search_port()),
+            "ae239f00-ae2b-bbad-ddf1-88ddcc00a234").unwrap();
+        
+        assert_eq!(location, Some(Url::parse("http://127.0.0.1:49152/desc.xml").unwrap()));
+    }
+    
+    #[test]
+    fn positive_loopback_slow_device() {
+        let network = LoopbackNetwork::start().unwrap();
+        let conditions = Conditions::new().with_delay(1000, 1000);
+        network.add_device(ScriptedDevice::new(advert("ae239f00-ae2b-bbad-ddf1-88ddcc00a234"))
+            .with_conditions(conditions));
+        
+        let responses = super::search_device(Ipv4Addr::new(127, 0, 0, 1), Some(network.search_port()),
+            ST::All, Duration::milliseconds(200), Policy::default()).unwrap();
+        
+        assert_eq!(responses.len(), 0);
+    }
+    
+    #[test]
+    fn positive_duplicate_usn() {
+        let mut collector = ResponseCollector::new(Policy::default());
+        let mut streamed = 0;
+        
+        assert!(collector.push(RESPONSE, remote(), &mut |_| streamed += 1));
+        assert!(!collector.push(RESPONSE, remote(), &mut |_| streamed += 1));
+        
+        assert_eq!(streamed, 1);
+        assert_eq!(collector.into_report().responses().len(), 1);
+    }
+    
+    #[test]
+    fn positive_late_response() {
+        let other = String::from_utf8_lossy(RESPONSE).replace("ae239f00", "ae239f01");
+        let mut collector = ResponseCollector::new(Policy::default());
+        
+        collector.set_deadline(PreciseTime::now() + Duration::milliseconds(200));
+        collector.push(RESPONSE, remote(), &mut |_| ());
+        thread::sleep_ms(250);
+        collector.push(RESPONSE, remote(), &mut |_| ());
+        collector.push(other.as_bytes(), remote(), &mut |_| ());
+        
+        let report = collector.into_report();
+        assert_eq!(report.responses().len(), 2);
+        assert_eq!(report.late_responses(), 1);
+    }
+    
+    #[test]
+    fn positive_late_device_counted_once() {
+        let service = String::from_utf8_lossy(RESPONSE).replace("upnp:rootdevice",
+            "urn:schemas-upnp-org:service:WANIPConnection:1");
+        let mut collector = ResponseCollector::new(Policy::default());
+        
+        collector.set_deadline(PreciseTime::now());
+        thread::sleep_ms(10);
+        collector.push(RESPONSE, remote(), &mut |_| ());
+        collector.push(service.as_bytes(), remote(), &mut |_| ());
+        
+        let report = collector.into_report();
+        assert_eq!(report.responses().len(), 2);
+        assert_eq!(report.late_responses(), 1);
+    }
+    
+    #[test]
+    fn positive_large_response() {
+        let mut packet = RESPONSE[..RESPONSE.len() - 2].to_vec();
+        for i in 0..200 {
+            packet.extend(format!("X-VENDOR-{}.EXAMPLE.COM: padding padding padding\r\n", i).bytes());
+        }
+        packet.extend(b"\r\n".iter().cloned());
+        let mut collector = ResponseCollector::new(Policy::default());
+        
+        assert!(packet.len() > 1000);
+        assert!(collector.push(&packet[..], remote(), &mut |_| ()));
+    }
+    
+    #[test]
+    fn negative_strict_policy() {
+        let quirky = String::from_utf8_lossy(RESPONSE).replace("max-age=1800", "max-age = 1800");
+        let mut collector = ResponseCollector::new(Policy::new(Strictness::Strict));
+        
+        assert!(!collector.push(quirky.as_bytes(), remote(), &mut |_| ()));
+    }
+    
+    #[test]
+    fn negative_invalid_response() {
+        let mut collector = ResponseCollector::new(Policy::default());
+        
+        assert!(!collector.push(b"HTTP/1.1 200 OK\r\n\r\n", remote(), &mut |_| ()));
+    }
+}
@@ -0,0 +1,120 @@
+//! Versions of the UPnP Device Architecture and how they relate to each other.
+//!
+//! Minor versions are backward compatible within a major version, so a device
+//! that speaks UPnP 1.2 is treated as a UPnP 1.1 device.
+
+use std::fmt::{Display, Error, Formatter};
+
+/// UPnP Device Architecture 1.0.
+pub const UPNP_10_VERSION: UPnPVersion = UPnPVersion{ major: 1, minor: 0 };
+/// UPnP Device Architecture 1.1, which introduced the BOOTID.UPNP.ORG and
+/// CONFIGID.UPNP.ORG headers.
+pub const UPNP_11_VERSION: UPnPVersion = UPnPVersion{ major: 1, minor: 1 };
+/// UPnP Device Architecture 2.0.
+pub const UPNP_20_VERSION: UPnPVersion = UPnPVersion{ major: 2, minor: 0 };
+
+/// Versions that messages can be parsed for.
+const IMPLEMENTED_VERSIONS: &'static [UPnPVersion] = &[UPNP_10_VERSION, UPNP_11_VERSION, UPNP_20_VERSION];
+
+const MINOR_SEPARATOR: char = '.';
+
+/// Version of the UPnP Device Architecture, ordered by major and then minor
+/// version.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct UPnPVersion {
+    major: u32,
+    minor: u32
+}
+
+impl UPnPVersion {
+    /// Create a new UPnPVersion object.
+    pub fn new(major: u32, minor: u32) -> UPnPVersion {
+        UPnPVersion{ major: major, minor: minor }
+    }
+    
+    /// Parse a version of the form "major.minor".
+    pub fn parse(value: &str) -> Option<UPnPVersion> {
+        let mut parts = value.split(MINOR_SEPARATOR);
+        
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(major), Some(minor), None) => match (major.parse().ok(), minor.parse().ok()) {
+                (Some(major), Some(minor)) => Some(UPnPVersion::new(major, minor)),
+                _ => None
+            },
+            _ => None
+        }
+    }
+    
+    /// Returns the major version.
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+    
+    /// Returns the minor version.
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+    
+    /// Returns the highest implemented version that this version is backward
+    /// compatible with.
+    ///
+    /// Returns None if the major version is not implemented.
+    pub fn compatible(&self) -> Option<UPnPVersion> {
+        IMPLEMENTED_VERSIONS.iter().filter(|n| n.major == self.major && n.minor <= self.minor)
+            .max().map(|n| *n)
+    }
+    
+    /// Returns true if messages of this version have to carry the
+    /// BOOTID.UPNP.ORG and CONFIGID.UPNP.ORG headers.
+    pub fn requires_boot_id(&self) -> bool {
+        *self >= UPNP_11_VERSION
+    }
+}
+
+impl Display for UPnPVersion {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        f.write_fmt(format_args!("{}{}{}", self.major, MINOR_SEPARATOR, self.minor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UPnPVersion, UPNP_10_VERSION, UPNP_11_VERSION, UPNP_20_VERSION};
+    
+    #[test]
+    fn positive_ordering() {
+        assert!(UPNP_10_VERSION < UPNP_11_VERSION);
+        assert!(UPNP_11_VERSION < UPNP_20_VERSION);
+        assert!(UPnPVersion::new(1, 10) < UPNP_20_VERSION);
+    }
+    
+    #[test]
+    fn positive_parse() {
+        assert_eq!(UPnPVersion::parse("1.1"), Some(UPNP_11_VERSION));
+        assert_eq!(UPNP_20_VERSION.to_string(), "2.0");
+    }
+    
+    #[test]
+    fn positive_higher_minor_compatible() {
+        assert_eq!(UPnPVersion::new(1, 2).compatible(), Some(UPNP_11_VERSION));
+        assert_eq!(UPnPVersion::new(2, 1).compatible(), Some(UPNP_20_VERSION));
+    }
+    
+    #[test]
+    fn positive_requires_boot_id() {
+        assert!(!UPNP_10_VERSION.requires_boot_id());
+        assert!(UPnPVersion::new(1, 2).requires_boot_id());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_unimplemented_major() {
+        UPnPVersion::new(3, 0).compatible().unwrap();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn negative_missing_minor() {
+        UPnPVersion::parse("1").unwrap();
+    }
+}
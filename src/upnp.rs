@@ -1,5 +1,7 @@
 use util;
+use forum::fetcher::{DescriptionFetcher};
 use regex::{Regex};
+use url::{Url, UrlParser};
 use std::old_path::{BytesContainer};
 use std::borrow::{ToOwned};
 use std::old_io::net::tcp::{TcpStream};
@@ -18,11 +20,6 @@ Man: \"ssdp:discover\"\r
 MX: {1}\r
 ST: {2}\r\n\r\n";
 
-// {1} = Path, {2} = IP Address:Port
-static GENERIC_HTTP_REQUEST: &'static str = "GET {1} HTTP/1.1\r
-Host: {2}\r
-Connection: close\r\n\r\n";
-
 // {1} = Control URL, {2} = IP Address:Port, {3} = Bytes In Payload,
 // {4} = Search Target Of Service, {5} = Action Name, {6} = Action Parameters
 static GENERIC_SOAP_REQUEST: &'static str = "POST {1} HTTP/1.1\r
@@ -93,16 +90,16 @@ impl ServiceDesc {
         let state_vars_regex: Regex = regex!(r"<serviceStateTable>(?:.|\n)+?</serviceStateTable>");
         
         // Setup Data To Query Device Description Page
-        let path = try!(util::extract_path(location));
         let sock_addr = try!(util::lookup_url(location));
-        let request = String::from_str(GENERIC_HTTP_REQUEST).replace("{1}", path)
-            .replace("{2}", sock_addr.to_string().as_slice());
-
-        // Query Device Description Page
-        let mut tcp_sock = try!(TcpStream::connect(sock_addr));
+        let location = try!(Url::parse(location).or_else( |_|
+            Err(util::simple_ioerror(InvalidInput, "Could Not Parse Location"))
+        ));
+        let fetcher = DescriptionFetcher::new();
 
-        try!(tcp_sock.write_str(request.as_slice()));
-        let response = try!(tcp_sock.read_to_string());
+        // Query Device Description Page, Bounded By The Timeouts And Size Limit Of The Fetcher
+        let (response, location) = try!(fetcher.fetch(&location).or_else( |_|
+            Err(util::simple_ioerror(InvalidInput, "Could Not Retrieve Device Description"))
+        ));
         
         let (tag_begin, tag_end) = try!(service_regex.find(response.as_slice()).ok_or(
             util::simple_ioerror(InvalidInput, "Could Not Find Service Tag")
@@ -123,12 +120,14 @@ impl ServiceDesc {
         ));
 
         // Query Service Description
-        tcp_sock = try!(TcpStream::connect(sock_addr));
-        let request = request.replace(path.as_slice(), scpd_path);
-
-        try!(tcp_sock.write_str(request.as_slice()));
+        let scpd_url = try!(UrlParser::new().base_url(&location).parse(scpd_path).or_else( |_|
+            Err(util::simple_ioerror(InvalidInput, "Could Not Parse SCPD Path"))
+        ));
+        let (response, _) = try!(fetcher.fetch(&scpd_url).or_else( |_|
+            Err(util::simple_ioerror(InvalidInput, "Could Not Retrieve Service Description"))
+        ));
         // We Will Be Returning response Slices To Users, So We Format The Response
-        let response = try!(tcp_sock.read_to_string()).replace("\t", " ");
+        let response = response.replace("\t", " ");
 
         // Pull Out Actions And State Variable Table Locations
         let actions = action_regex.find_iter(response.as_slice()).collect::<Vec<StrPos>>();
@@ -1,7 +1,9 @@
 //! Utility functions and constants used throughout the crate.
 
 use std::io::{ErrorKind, Error, Result};
-use std::net::{self, Ipv4Addr, UdpSocket, SocketAddr};
+use std::net::{self, Ipv4Addr, UdpSocket, SocketAddr, ToSocketAddrs};
+
+use rand::{self, Rng};
 
 const UNUSED_PORT_START: u16 = 1024;
 const UNUSED_PORT_END: u16 = 49151;
@@ -20,6 +22,19 @@ pub fn ipv4_net_addrs() -> Result<Vec<Ipv4Addr>> {
     Ok(ipv4_list)
 }
 
+/// Returns the local IPv4 address that is used to reach the given remote address.
+///
+/// Connecting a UDP socket only picks a route, so no packets are sent.
+pub fn local_ipv4_for<A: ToSocketAddrs>(remote: A) -> Result<Ipv4Addr> {
+    let udp_sock = try!(UdpSocket::bind((Ipv4Addr::new(0, 0, 0, 0), 0)));
+    try!(udp_sock.connect(remote));
+    
+    match try!(udp_sock.local_addr()) {
+        SocketAddr::V4(n) => Ok(*n.ip()),
+        SocketAddr::V6(_) => Err(Error::new(ErrorKind::Other, "Local Address Is Not IPv4"))
+    }
+}
+
 /// Try to bind to a UDP port within the minimum and maximum port range.
 pub fn try_bind_udp(ip: Ipv4Addr) -> Result<UdpSocket> {
     try_range_udp(ip, UNUSED_PORT_START, UNUSED_PORT_END).map_err( |_|
@@ -42,4 +57,17 @@ pub fn try_range_udp(ip: Ipv4Addr, start: u16, end: u16) -> Result<UdpSocket> {
     }
     
     Err(Error::new(ErrorKind::Other, "Could Not Bind To A Port Within The Range Specified"))
-}
\ No newline at end of file
+}
+
+/// Generate a random (version 4) uuid, without the "uuid:" prefix.
+pub fn random_uuid() -> String {
+    let mut rng = rand::thread_rng();
+    let mut bytes = (0..16).map(|_| rng.gen::<u8>()).collect::<Vec<u8>>();
+    
+    // Mark The Version And Variant
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    let hex = bytes.iter().map(|n| format!("{:02x}", n)).collect::<String>();
+    
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
@@ -7,7 +7,10 @@ pub const VERSION_4: u8 = 4;
 pub const VERSION_5: u8 = 5;
 
 /// Version numbers for devices and services.
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+///
+/// Versions are ordered, later versions are backwards compatible with earlier
+/// versions of the same device or service.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Version {
     V1,
     V2,
@@ -36,4 +39,15 @@ impl Version {
             _         => None
         }
     }
+    
+    /// Convert a Version to its number representation.
+    pub fn to_u8(&self) -> u8 {
+        match *self {
+            Version::V1 => VERSION_1,
+            Version::V2 => VERSION_2,
+            Version::V3 => VERSION_3,
+            Version::V4 => VERSION_4,
+            Version::V5 => VERSION_5
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,353 @@
+//! Minimal XML scanning used for description documents and SOAP payloads.
+//!
+//! This is not a general purpose XML parser. It only supports locating elements
+//! by their local name (namespace prefixes are ignored) and pulling out their
+//! raw contents, which is all that the UPnP documents we deal with require.
+
+/// Character entities that must be escaped within element contents.
+const XML_ENTITIES: [(char, &'static str); 5] = [('&', "&amp;"), ('<', "&lt;"),
+    ('>', "&gt;"), ('"', "&quot;"), ('\'', "&apos;")];
+
+/// Returns the contents of the first element with the given local name.
+pub fn element<'a>(doc: &'a str, name: &str) -> Option<&'a str> {
+    Elements::new(doc, name).next()
+}
+
+/// Returns the byte offset of the start tag of the first element with the
+/// given local name.
+pub fn element_start(doc: &str, name: &str) -> Option<usize> {
+    find_start(doc, name, 0).map(|(start, _, _)| start)
+}
+
+/// Returns the local name and contents of the first child element found.
+pub fn first_child<'a>(doc: &'a str) -> Option<(&'a str, &'a str)> {
+    let mut pos = 0;
+
+    while let Some((tag_start, tag_end)) = next_tag(doc, pos) {
+        let tag = &doc[tag_start + 1..tag_end];
+        pos = tag_end + 1;
+
+        if is_start_tag(tag) {
+            let name = local_name(tag_name(tag));
+
+            return element(&doc[tag_start..], name).map(|inner| (name, inner))
+        }
+    }
+
+    None
+}
+
+/// Iterator over the contents of all non-overlapping elements with some local
+/// name, in document order.
+pub struct Elements<'a, 'b> {
+    nodes: Nodes<'a, 'b>
+}
+
+impl<'a, 'b> Elements<'a, 'b> {
+    pub fn new(doc: &'a str, name: &'b str) -> Elements<'a, 'b> {
+        Elements{ nodes: Nodes::new(doc, name) }
+    }
+}
+
+impl<'a, 'b> Iterator for Elements<'a, 'b> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.nodes.next().map(|n| n.content())
+    }
+}
+
+/// Element found within a document, along with the attributes of its start tag.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Node<'a> {
+    tag:     &'a str,
+    content: &'a str
+}
+
+impl<'a> Node<'a> {
+    /// Returns the contents of the element.
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+
+    /// Returns the unescaped value of the attribute with the given local name.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        let mut rest = &self.tag[tag_name(self.tag).len()..];
+
+        while let Some(eq_index) = rest.find('=') {
+            let attr_name = rest[..eq_index].trim();
+            let value = rest[eq_index + 1..].trim_left();
+
+            let quote = match value.chars().next() {
+                Some(n) if n == '"' || n == '\'' => n,
+                _ => return None
+            };
+            let value_end = match value[1..].find(quote) {
+                Some(n) => n + 1,
+                None    => return None
+            };
+
+            if local_name(attr_name) == name {
+                return Some(unescape(&value[1..value_end]))
+            }
+            rest = &value[value_end + 1..];
+        }
+
+        None
+    }
+}
+
+/// Iterator over all non-overlapping elements with some local name, in
+/// document order.
+pub struct Nodes<'a, 'b> {
+    doc:  &'a str,
+    name: &'b str,
+    pos:  usize
+}
+
+impl<'a, 'b> Nodes<'a, 'b> {
+    pub fn new(doc: &'a str, name: &'b str) -> Nodes<'a, 'b> {
+        Nodes{ doc: doc, name: name, pos: 0 }
+    }
+}
+
+impl<'a, 'b> Iterator for Nodes<'a, 'b> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Node<'a>> {
+        let (tag_start, content_start, self_closing) = match find_start(self.doc, self.name, self.pos) {
+            Some(n) => n,
+            None    => return None
+        };
+        let tag = self.doc[tag_start + 1..content_start - 1].trim_right_matches('/');
+
+        if self_closing {
+            self.pos = content_start;
+
+            return Some(Node{ tag: tag, content: "" })
+        }
+
+        match find_end(self.doc, self.name, content_start) {
+            Some((end_start, end_end)) => {
+                self.pos = end_end;
+
+                Some(Node{ tag: tag, content: &self.doc[content_start..end_start] })
+            },
+            None => {
+                self.pos = self.doc.len();
+
+                None
+            }
+        }
+    }
+}
+
+/// Escape the given text so that it can be placed within an element.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match XML_ENTITIES.iter().find(|&&(c, _)| c == ch) {
+            Some(&(_, entity)) => escaped.push_str(entity),
+            None               => escaped.push(ch)
+        }
+    }
+
+    escaped
+}
+
+/// Unescape the contents of an element.
+///
+/// Unknown or malformed entities are left as is.
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp_index) = rest.find('&') {
+        unescaped.push_str(&rest[..amp_index]);
+        rest = &rest[amp_index..];
+
+        let semi_index = match rest.find(';') {
+            Some(n) => n,
+            None    => break
+        };
+
+        match entity_char(&rest[..semi_index + 1]) {
+            Some(ch) => {
+                unescaped.push(ch);
+                rest = &rest[semi_index + 1..];
+            },
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+
+    unescaped
+}
+
+/// Returns the character for a named or numeric entity.
+fn entity_char(entity: &str) -> Option<char> {
+    if let Some(&(ch, _)) = XML_ENTITIES.iter().find(|&&(_, e)| e == entity) {
+        return Some(ch)
+    }
+
+    let number = &entity[1..entity.len() - 1];
+    let code = if number.starts_with("#x") || number.starts_with("#X") {
+        u32::from_str_radix(&number[2..], 16).ok()
+    } else if number.starts_with("#") {
+        u32::from_str_radix(&number[1..], 10).ok()
+    } else {
+        None
+    };
+
+    code.and_then(|n| ::std::char::from_u32(n))
+}
+
+/// Finds the next start tag with the given local name at or after from.
+///
+/// Returns the start of the tag, the start of the contents, and whether or not
+/// the element is self closing.
+fn find_start(doc: &str, name: &str, from: usize) -> Option<(usize, usize, bool)> {
+    let mut pos = from;
+
+    while let Some((tag_start, tag_end)) = next_tag(doc, pos) {
+        let tag = &doc[tag_start + 1..tag_end];
+        pos = tag_end + 1;
+
+        if is_start_tag(tag) && local_name(tag_name(tag)) == name {
+            return Some((tag_start, tag_end + 1, tag.ends_with("/")))
+        }
+    }
+
+    None
+}
+
+/// Finds the end tag matching an element with the given local name whose
+/// contents start at from.
+///
+/// Returns the start and end of the end tag.
+fn find_end(doc: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let mut depth = 0usize;
+    let mut pos = from;
+
+    while let Some((tag_start, tag_end)) = next_tag(doc, pos) {
+        let tag = &doc[tag_start + 1..tag_end];
+        pos = tag_end + 1;
+
+        if tag.starts_with("/") {
+            if local_name(tag[1..].trim()) == name {
+                if depth == 0 {
+                    return Some((tag_start, tag_end + 1))
+                }
+                depth -= 1;
+            }
+        } else if is_start_tag(tag) && !tag.ends_with("/") && local_name(tag_name(tag)) == name {
+            depth += 1;
+        }
+    }
+
+    None
+}
+
+/// Returns the positions of the next '<' and its matching '>' at or after from.
+fn next_tag(doc: &str, from: usize) -> Option<(usize, usize)> {
+    let tag_start = match doc[from..].find('<') {
+        Some(n) => from + n,
+        None    => return None
+    };
+
+    doc[tag_start..].find('>').map(|n| (tag_start, tag_start + n))
+}
+
+/// Returns true if the tag (without brackets) is a start or empty element tag.
+fn is_start_tag(tag: &str) -> bool {
+    !(tag.starts_with("/") || tag.starts_with("?") || tag.starts_with("!"))
+}
+
+/// Returns the qualified name of a tag (without brackets).
+fn tag_name(tag: &str) -> &str {
+    tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("")
+}
+
+/// Returns the name with any namespace prefix removed.
+fn local_name(name: &str) -> &str {
+    match name.rfind(':') {
+        Some(n) => &name[n + 1..],
+        None    => name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Elements, Nodes};
+
+    #[test]
+    fn positive_prefixed_element() {
+        let doc = "<s:Body><u:GetStatus xmlns:u=\"urn\">1</u:GetStatus></s:Body>";
+
+        assert_eq!(super::element(doc, "GetStatus").unwrap(), "1");
+    }
+
+    #[test]
+    fn positive_nested_same_name() {
+        let doc = "<device><UDN>a</UDN><deviceList><device><UDN>b</UDN></device></deviceList></device>";
+        let outer = super::element(doc, "device").unwrap();
+
+        assert_eq!(outer, "<UDN>a</UDN><deviceList><device><UDN>b</UDN></device></deviceList>");
+    }
+
+    #[test]
+    fn positive_multiple_elements() {
+        let doc = "<list><item>1</item><item/><item>3</item></list>";
+        let items = Elements::new(doc, "item").collect::<Vec<&str>>();
+
+        assert_eq!(items, vec!["1", "", "3"]);
+    }
+
+    #[test]
+    fn positive_node_attributes() {
+        let doc = "<field name=\"Temp\" encoding='ascii'>21.5</field><field name=\"a&amp;b\"/>";
+        let nodes = Nodes::new(doc, "field").collect::<Vec<_>>();
+
+        assert_eq!(nodes[0].attribute("name").unwrap(), "Temp");
+        assert_eq!(nodes[0].attribute("encoding").unwrap(), "ascii");
+        assert_eq!(nodes[0].content(), "21.5");
+        assert_eq!(nodes[1].attribute("name").unwrap(), "a&b");
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_missing_attribute() {
+        let doc = "<field name=\"Temp\">21.5</field>";
+
+        Nodes::new(doc, "field").next().unwrap().attribute("type").unwrap();
+    }
+
+    #[test]
+    fn positive_first_child() {
+        let doc = "\n  <e:property><Status>0</Status></e:property>";
+
+        assert_eq!(super::first_child(doc).unwrap(), ("property", "<Status>0</Status>"));
+    }
+
+    #[test]
+    fn positive_escape_round_trip() {
+        let text = "<a href=\"x\">&'</a>";
+
+        assert_eq!(super::unescape(&super::escape(text)), text);
+    }
+
+    #[test]
+    fn positive_unescape_numeric() {
+        assert_eq!(super::unescape("&#65;&#x42;&unknown; &"), "AB&unknown; &");
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_unterminated_element() {
+        super::element("<a><b>text</a>", "b").unwrap();
+    }
+}